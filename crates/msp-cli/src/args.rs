@@ -0,0 +1,161 @@
+//! Arguments shared by several commands
+
+use clap::Args;
+use msp::categories::{Category, SubCategory};
+use msp::config::FeeSchedule;
+use msp::constants::*;
+use msp::schedule::SchedulePoint;
+
+pub fn parse_treasury_type(value: &str) -> Result<u8, String> {
+    match value {
+        "open" => Ok(TREASURY_TYPE_OPEN),
+        "locked" => Ok(TREASURY_TYPE_LOCKED),
+        _ => Err("expected `open` or `locked`".to_string()),
+    }
+}
+
+pub fn parse_accrual_mode(value: &str) -> Result<u8, String> {
+    match value {
+        "linear" => Ok(STREAM_ACCRUAL_MODE_LINEAR),
+        "step" => Ok(STREAM_ACCRUAL_MODE_STEP),
+        _ => Err("expected `linear` or `step`".to_string()),
+    }
+}
+
+pub fn parse_split_mode(value: &str) -> Result<u8, String> {
+    match value {
+        "pro-rata" => Ok(ALLOCATION_SPLIT_MODE_PRO_RATA),
+        "even" => Ok(ALLOCATION_SPLIT_MODE_EVEN),
+        _ => Err("expected `pro-rata` or `even`".to_string()),
+    }
+}
+
+pub fn parse_category(value: &str) -> Result<Category, String> {
+    match value {
+        "default" => Ok(Category::Default),
+        "vesting" => Ok(Category::Vesting),
+        _ => Err("expected `default` or `vesting`".to_string()),
+    }
+}
+
+pub fn parse_sub_category(value: &str) -> Result<SubCategory, String> {
+    match value {
+        "default" => Ok(SubCategory::Default),
+        "advisor" => Ok(SubCategory::Advisor),
+        "development" => Ok(SubCategory::Development),
+        "foundation" => Ok(SubCategory::Foundation),
+        "investor" => Ok(SubCategory::Investor),
+        "marketing" => Ok(SubCategory::Marketing),
+        "partnership" => Ok(SubCategory::Partnership),
+        "seed" => Ok(SubCategory::Seed),
+        "team" => Ok(SubCategory::Team),
+        "community" => Ok(SubCategory::Community),
+        _ => Err(format!("invalid sub category `{}`", value)),
+    }
+}
+
+/// Parses a schedule point given as `<timestamp>:<cumulative units>`
+pub fn parse_schedule_point(value: &str) -> Result<SchedulePoint, String> {
+    let (timestamp, cumulative_units) = value
+        .split_once(':')
+        .ok_or_else(|| "expected `<timestamp>:<cumulative units>`".to_string())?;
+    Ok(SchedulePoint {
+        timestamp: timestamp.parse().map_err(|_| "invalid timestamp")?,
+        cumulative_units: cumulative_units
+            .parse()
+            .map_err(|_| "invalid cumulative units")?,
+    })
+}
+
+/// The template of the streams of a treasury
+#[derive(Args)]
+pub struct TemplateArgs {
+    /// Start timestamp in seconds of the streams (defaults to now)
+    #[clap(long)]
+    pub start_utc: Option<u64>,
+    #[clap(long)]
+    pub rate_interval_in_seconds: u64,
+    /// Number of intervals of the streams
+    #[clap(long)]
+    pub duration_number_of_units: u64,
+    /// Cliff percent relative to 1_000_000
+    #[clap(long, default_value = "0")]
+    pub cliff_vest_percent: u64,
+    #[clap(long)]
+    pub fee_payed_by_treasurer: bool,
+    /// `linear` or `step`
+    #[clap(long, default_value = "linear", parse(try_from_str = parse_accrual_mode))]
+    pub accrual_mode: u8,
+    /// Cliff date in seconds (defaults to the start)
+    #[clap(long, default_value = "0")]
+    pub cliff_utc: u64,
+}
+
+/// The protocol fees. Omitted fees keep their current value
+#[derive(Args)]
+pub struct FeeArgs {
+    /// Lamports
+    #[clap(long)]
+    pub create_treasury_flat_fee: Option<u64>,
+    /// Lamports
+    #[clap(long)]
+    pub create_stream_flat_fee: Option<u64>,
+    /// Lamports
+    #[clap(long)]
+    pub add_funds_flat_fee: Option<u64>,
+    /// Relative to 1_000_000
+    #[clap(long)]
+    pub withdraw_percent_fee: Option<u64>,
+    /// Lamports
+    #[clap(long)]
+    pub close_stream_flat_fee: Option<u64>,
+    /// Relative to 1_000_000
+    #[clap(long)]
+    pub close_stream_percent_fee: Option<u64>,
+    /// Lamports
+    #[clap(long)]
+    pub close_treasury_flat_fee: Option<u64>,
+    /// Lamports
+    #[clap(long)]
+    pub transfer_stream_flat_fee: Option<u64>,
+    /// Relative to 1_000_000
+    #[clap(long)]
+    pub treasury_withdraw_percent_fee: Option<u64>,
+    /// Relative to 1_000_000
+    #[clap(long)]
+    pub referrer_fee_share: Option<u64>,
+}
+
+impl FeeArgs {
+    /// Applies the given fees over `fees`
+    pub fn apply(&self, fees: FeeSchedule) -> FeeSchedule {
+        FeeSchedule {
+            create_treasury_flat_fee: self
+                .create_treasury_flat_fee
+                .unwrap_or(fees.create_treasury_flat_fee),
+            create_stream_flat_fee: self
+                .create_stream_flat_fee
+                .unwrap_or(fees.create_stream_flat_fee),
+            add_funds_flat_fee: self.add_funds_flat_fee.unwrap_or(fees.add_funds_flat_fee),
+            withdraw_percent_fee: self
+                .withdraw_percent_fee
+                .unwrap_or(fees.withdraw_percent_fee),
+            close_stream_flat_fee: self
+                .close_stream_flat_fee
+                .unwrap_or(fees.close_stream_flat_fee),
+            close_stream_percent_fee: self
+                .close_stream_percent_fee
+                .unwrap_or(fees.close_stream_percent_fee),
+            close_treasury_flat_fee: self
+                .close_treasury_flat_fee
+                .unwrap_or(fees.close_treasury_flat_fee),
+            transfer_stream_flat_fee: self
+                .transfer_stream_flat_fee
+                .unwrap_or(fees.transfer_stream_flat_fee),
+            treasury_withdraw_percent_fee: self
+                .treasury_withdraw_percent_fee
+                .unwrap_or(fees.treasury_withdraw_percent_fee),
+            referrer_fee_share: self.referrer_fee_share.unwrap_or(fees.referrer_fee_share),
+        }
+    }
+}
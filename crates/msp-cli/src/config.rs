@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_program;
+use clap::Subcommand;
+use msp::config::FeeSchedule;
+use msp_client::instruction;
+use msp_client::pda::find_fee_override_address;
+
+use crate::args::FeeArgs;
+use crate::context::{Context, Result};
+use crate::display;
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Create the protocol config (`init_config`). Omitted fees take the
+    /// default value
+    Init {
+        #[clap(long)]
+        admin: Pubkey,
+        #[clap(long)]
+        fee_treasury: Pubkey,
+        #[clap(flatten)]
+        fees: FeeArgs,
+    },
+    /// Update the protocol config as the admin (`update_config`)
+    Update {
+        #[clap(long)]
+        admin: Option<Pubkey>,
+        #[clap(long)]
+        fee_treasury: Option<Pubkey>,
+        #[clap(flatten)]
+        fees: FeeArgs,
+    },
+    /// Set the fees of a treasury or treasurer (`set_fee_override`). Omitted
+    /// fees take the config value
+    SetFeeOverride {
+        subject: Pubkey,
+        #[clap(long, default_value = "0")]
+        tier: u8,
+        #[clap(flatten)]
+        fees: FeeArgs,
+    },
+    /// Remove the fee override of a treasury or treasurer
+    /// (`remove_fee_override`)
+    RemoveFeeOverride { subject: Pubkey },
+    /// Print the protocol config
+    Show,
+}
+
+pub fn run(ctx: &Context, command: ConfigCommand) -> Result<()> {
+    match command {
+        ConfigCommand::Init {
+            admin,
+            fee_treasury,
+            fees,
+        } => {
+            let ix = instruction::init_config(
+                msp::accounts::InitConfigAccounts {
+                    payer: ctx.wallet(),
+                    authority: ctx.wallet(),
+                    config: ctx.config_address(),
+                    system_program: system_program::ID,
+                },
+                admin,
+                fee_treasury,
+                fees.apply(FeeSchedule::default()),
+            );
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        ConfigCommand::Update {
+            admin,
+            fee_treasury,
+            fees,
+        } => {
+            let ix = instruction::update_config(
+                msp::accounts::UpdateConfigAccounts {
+                    admin: ctx.wallet(),
+                    config: ctx.config_address(),
+                },
+                admin.unwrap_or(ctx.config.admin_address),
+                fee_treasury.unwrap_or(ctx.config.fee_treasury_address),
+                fees.apply(ctx.config.fees),
+            );
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        ConfigCommand::SetFeeOverride {
+            subject,
+            tier,
+            fees,
+        } => {
+            let ix = instruction::set_fee_override(
+                msp::accounts::SetFeeOverrideAccounts {
+                    payer: ctx.wallet(),
+                    admin: ctx.wallet(),
+                    config: ctx.config_address(),
+                    fee_override: find_fee_override_address(&subject).0,
+                    system_program: system_program::ID,
+                },
+                subject,
+                tier,
+                fees.apply(ctx.config.fees),
+            );
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        ConfigCommand::RemoveFeeOverride { subject } => {
+            let ix = instruction::remove_fee_override(msp::accounts::RemoveFeeOverrideAccounts {
+                admin: ctx.wallet(),
+                config: ctx.config_address(),
+                fee_override: find_fee_override_address(&subject).0,
+            });
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        ConfigCommand::Show => {
+            display::config(&ctx.config);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_program;
+use anchor_spl::associated_token::get_associated_token_address;
+use msp::config::Config;
+use msp::stream::Stream;
+use msp::treasury::{NativeSolTreasury, Treasury, TreasuryReferrer};
+use msp_client::instruction::fee_referrer_accounts;
+use msp_client::pda::{find_config_address, find_fee_override_address};
+use msp_client::state;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// The RPC connection and the wallet that signs and pays the transactions
+pub struct Context {
+    pub rpc: RpcClient,
+    pub wallet: Keypair,
+    pub config: Config,
+}
+
+/// A treasury and the accounts derived from it
+pub struct TreasuryInfo {
+    pub address: Pubkey,
+    pub treasury: Treasury,
+    pub mint: Pubkey,
+    pub treasury_token: Pubkey,
+    pub is_native: bool,
+    pub referrer: Option<Pubkey>,
+}
+
+impl Context {
+    pub fn new(url: &str, wallet: Keypair) -> Result<Context> {
+        let rpc = RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed());
+        let config = state::fetch_config(&rpc)?;
+        Ok(Context {
+            rpc,
+            wallet,
+            config,
+        })
+    }
+
+    pub fn wallet(&self) -> Pubkey {
+        self.wallet.pubkey()
+    }
+
+    /// Signs the instructions with the wallet (and the extra signers) and
+    /// sends them in one transaction
+    pub fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<Signature> {
+        let mut all_signers = vec![&self.wallet];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.wallet()),
+            &all_signers,
+            self.rpc.get_latest_blockhash()?,
+        );
+        Ok(self.rpc.send_and_confirm_transaction(&transaction)?)
+    }
+
+    /// Gets the cluster time
+    pub fn now(&self) -> Result<u64> {
+        let slot = self.rpc.get_slot()?;
+        Ok(self.rpc.get_block_time(slot)? as u64)
+    }
+
+    pub fn account_exists(&self, address: &Pubkey) -> Result<bool> {
+        Ok(self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())?
+            .value
+            .is_some())
+    }
+
+    pub fn fetch_treasury(&self, address: &Pubkey) -> Result<TreasuryInfo> {
+        let data = self.rpc.get_account_data(address)?;
+        let treasury = state::decode_treasury(&data)?;
+        let is_native = state::decode_treasury_extension::<NativeSolTreasury>(&data)?.is_some();
+        let referrer = state::decode_treasury_extension::<TreasuryReferrer>(&data)?
+            .map(|referrer| referrer.referrer_address);
+        let mint = treasury.associated_token_address;
+        Ok(TreasuryInfo {
+            address: *address,
+            treasury_token: get_associated_token_address(address, &mint),
+            treasury,
+            mint,
+            is_native,
+            referrer,
+        })
+    }
+
+    pub fn fetch_stream(&self, address: &Pubkey) -> Result<Stream> {
+        Ok(state::fetch_stream(&self.rpc, address)?)
+    }
+
+    pub fn config_address(&self) -> Pubkey {
+        find_config_address().0
+    }
+
+    pub fn fee_treasury(&self) -> Pubkey {
+        self.config.fee_treasury_address
+    }
+
+    pub fn fee_treasury_token(&self, mint: &Pubkey) -> Pubkey {
+        get_associated_token_address(&self.config.fee_treasury_address, mint)
+    }
+
+    /// Gets the fee override of the treasury (or else of its authority). The
+    /// system program is passed when there is none
+    pub fn fee_override(&self, treasury: &TreasuryInfo) -> Result<Pubkey> {
+        for subject in [treasury.address, treasury.treasury.authority()] {
+            let fee_override = find_fee_override_address(&subject).0;
+            if self.account_exists(&fee_override)? {
+                return Ok(fee_override);
+            }
+        }
+        Ok(system_program::ID)
+    }
+
+    /// Gets the `referrer` and `referrer_token` accounts of the instructions
+    /// charging fees on the treasury (the fee treasury ones if it isn't
+    /// referred)
+    pub fn fee_referrer(&self, treasury: &TreasuryInfo) -> (Pubkey, Pubkey) {
+        fee_referrer_accounts(
+            treasury.referrer.as_ref(),
+            &self.config.fee_treasury_address,
+            &treasury.mint,
+        )
+    }
+}
+
+/// Gets the accounts the stream instructions look up by key: the stream
+/// schedule
+pub fn stream_remaining_accounts(stream: &Stream) -> Vec<AccountMeta> {
+    let mut accounts = Vec::new();
+    if stream.primitive_has_schedule() {
+        accounts.push(AccountMeta::new(stream.schedule_address, false));
+    }
+    accounts
+}
@@ -0,0 +1,146 @@
+//! Printing of the program accounts
+
+use anchor_lang::prelude::*;
+use msp::config::{Config, FeeSchedule};
+use msp::schedule::StreamSchedule;
+use msp::stream::Stream;
+use msp::template::StreamTemplate;
+use solana_sdk::signature::Signature;
+
+use crate::context::{Result, TreasuryInfo};
+
+pub fn signature(signature: Signature) {
+    println!("Signature: {}", signature);
+}
+
+/// Converts the space padded name of an account to a string
+fn name(name: &[u8; 32]) -> String {
+    String::from_utf8_lossy(name).trim_end().to_string()
+}
+
+pub fn treasury(treasury: &TreasuryInfo) {
+    let data = &treasury.treasury;
+    println!("Treasury: {}", treasury.address);
+    println!("  name: {}", name(&data.name));
+    println!("  version: {}", data.version);
+    println!("  treasurer: {}", data.treasurer_address);
+    println!("  authority: {}", data.authority());
+    if data.pending_authority_address != Pubkey::default() {
+        println!("  pending authority: {}", data.pending_authority_address);
+    }
+    if treasury.is_native {
+        println!("  mint: native SOL");
+    } else {
+        println!("  mint: {}", treasury.mint);
+        println!("  token account: {}", treasury.treasury_token);
+    }
+    println!("  type: {}", if data.treasury_type == 0 { "open" } else { "locked" });
+    println!("  balance: {}", data.last_known_balance_units);
+    println!("  allocation assigned: {}", data.allocation_assigned_units);
+    println!("  total withdrawals: {}", data.total_withdrawals_units);
+    println!("  total streams: {}", data.total_streams);
+    if let Some(referrer) = treasury.referrer {
+        println!("  referrer: {}", referrer);
+    }
+}
+
+pub fn template(address: &Pubkey, template: &StreamTemplate) {
+    println!("Template: {}", address);
+    println!("  start: {}", template.start_utc_in_seconds);
+    println!("  rate interval: {}s", template.rate_interval_in_seconds);
+    println!("  duration: {} intervals", template.duration_number_of_units);
+    println!("  cliff percent: {}", template.cliff_vest_percent);
+    println!("  accrual mode: {}", accrual_mode(template.accrual_mode));
+    println!("  cliff date: {}", template.cliff_utc_in_seconds);
+}
+
+fn accrual_mode(accrual_mode: u8) -> &'static str {
+    if accrual_mode == msp::constants::STREAM_ACCRUAL_MODE_STEP {
+        "step"
+    } else {
+        "linear"
+    }
+}
+
+/// Prints the stream along with its status and withdrawable amount at `now`
+pub fn stream(
+    address: &Pubkey,
+    stream: &Stream,
+    schedule: Option<&StreamSchedule>,
+    now: u64,
+) -> Result<()> {
+    let (status, withdrawable) = match schedule {
+        Some(schedule) => (
+            stream.get_scheduled_status(schedule, now)?,
+            stream.get_scheduled_withdrawable_amount(schedule, now)?,
+        ),
+        None => (
+            stream.get_status(now)?,
+            stream.get_beneficiary_withdrawable_amount(now)?,
+        ),
+    };
+    println!("Stream: {}", address);
+    println!("  name: {}", name(&stream.name));
+    println!("  version: {}", stream.version);
+    println!("  status: {:?}", status);
+    println!("  withdrawable: {}", withdrawable);
+    println!("  treasury: {}", stream.treasury_address);
+    println!("  beneficiary: {}", stream.beneficiary_address);
+    if stream.pending_beneficiary_address != Pubkey::default() {
+        println!(
+            "  pending beneficiary: {}",
+            stream.pending_beneficiary_address
+        );
+    }
+    println!("  start: {}", stream.get_start_utc()?);
+    match schedule {
+        Some(schedule) => {
+            println!("  schedule: {}", stream.schedule_address);
+            for point in &schedule.points {
+                println!("    {}: {}", point.timestamp, point.cumulative_units);
+            }
+        }
+        None => {
+            println!(
+                "  rate: {} every {}s ({})",
+                stream.rate_amount_units,
+                stream.rate_interval_in_seconds,
+                accrual_mode(stream.accrual_mode)
+            );
+            println!("  cliff: {}", stream.primitive_get_cliff_units()?);
+            if stream.cliff_utc_in_seconds > 0 {
+                println!("  cliff date: {}", stream.cliff_utc_in_seconds);
+            }
+            println!(
+                "  estimated depletion: {}",
+                stream.get_est_depletion_blocktime(now)?
+            );
+        }
+    }
+    println!("  allocation assigned: {}", stream.allocation_assigned_units);
+    println!("  total withdrawals: {}", stream.total_withdrawals_units);
+    Ok(())
+}
+
+pub fn config(config: &Config) {
+    println!("Config: {}", msp_client::pda::find_config_address().0);
+    println!("  admin: {}", config.admin_address);
+    println!("  fee treasury: {}", config.fee_treasury_address);
+    fees(&config.fees);
+}
+
+pub fn fees(fees: &FeeSchedule) {
+    println!("  create treasury flat fee: {}", fees.create_treasury_flat_fee);
+    println!("  create stream flat fee: {}", fees.create_stream_flat_fee);
+    println!("  add funds flat fee: {}", fees.add_funds_flat_fee);
+    println!("  withdraw percent fee: {}", fees.withdraw_percent_fee);
+    println!("  close stream flat fee: {}", fees.close_stream_flat_fee);
+    println!("  close stream percent fee: {}", fees.close_stream_percent_fee);
+    println!("  close treasury flat fee: {}", fees.close_treasury_flat_fee);
+    println!("  transfer stream flat fee: {}", fees.transfer_stream_flat_fee);
+    println!(
+        "  treasury withdraw percent fee: {}",
+        fees.treasury_withdraw_percent_fee
+    );
+    println!("  referrer fee share: {}", fees.referrer_fee_share);
+}
@@ -0,0 +1,80 @@
+//! `msp` command-line tool
+
+mod args;
+mod config;
+mod context;
+mod display;
+mod stream;
+mod treasury;
+
+use clap::{Parser, Subcommand};
+use solana_sdk::signature::read_keypair_file;
+
+use crate::config::ConfigCommand;
+use crate::context::{Context, Result};
+use crate::stream::StreamCommand;
+use crate::treasury::TreasuryCommand;
+
+#[derive(Parser)]
+#[clap(name = "msp", about = "Money Streaming Program command-line tool")]
+struct Cli {
+    /// RPC URL or moniker (`mainnet-beta`, `devnet`, `testnet` or `localhost`)
+    #[clap(short, long, global = true, default_value = "localhost")]
+    url: String,
+    /// Keypair file of the wallet signing and paying the transactions
+    #[clap(short, long, global = true, default_value = "~/.config/solana/id.json")]
+    keypair: String,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Treasury instructions
+    #[clap(subcommand)]
+    Treasury(TreasuryCommand),
+    /// Stream instructions
+    #[clap(subcommand)]
+    Stream(StreamCommand),
+    /// Protocol config instructions
+    #[clap(subcommand)]
+    Config(ConfigCommand),
+}
+
+/// Resolves the cluster monikers to their RPC URL
+fn rpc_url(url: &str) -> &str {
+    match url {
+        "mainnet-beta" | "m" => "https://api.mainnet-beta.solana.com",
+        "devnet" | "d" => "https://api.devnet.solana.com",
+        "testnet" | "t" => "https://api.testnet.solana.com",
+        "localhost" | "l" => "http://localhost:8899",
+        url => url,
+    }
+}
+
+/// Expands the leading `~` of the path to the home directory
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+        _ => path.to_string(),
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let keypair_path = expand_home(&cli.keypair);
+    let wallet = read_keypair_file(&keypair_path)
+        .map_err(|err| format!("cannot read keypair {}: {}", keypair_path, err))?;
+    let ctx = Context::new(rpc_url(&cli.url), wallet)?;
+    match cli.command {
+        Command::Treasury(command) => treasury::run(&ctx, command),
+        Command::Stream(command) => stream::run(&ctx, command),
+        Command::Config(command) => config::run(&ctx, command),
+    }
+}
+
+fn main() {
+    if let Err(err) = run(Cli::parse()) {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,913 @@
+use std::path::PathBuf;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{system_program, sysvar};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token;
+use clap::Subcommand;
+use msp::schedule::SchedulePoint;
+use msp::stream::{Stream, StreamBatchEntry};
+use msp_client::instruction::{self, AllocatedStream, WithdrawnStream};
+use msp_client::pda::{find_schedule_address, find_stream_address, find_template_address};
+use msp_client::state;
+use serde::Deserialize;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::args::*;
+use crate::context::{stream_remaining_accounts, Context, Result, TreasuryInfo};
+use crate::display;
+
+#[derive(Subcommand)]
+pub enum StreamCommand {
+    /// Create a stream (`create_stream`, `create_stream_pda` or
+    /// `create_stream_native`)
+    Create {
+        #[clap(long)]
+        treasury: Pubkey,
+        #[clap(long)]
+        beneficiary: Pubkey,
+        #[clap(long)]
+        name: String,
+        /// Start timestamp in seconds (defaults to now)
+        #[clap(long)]
+        start_utc: Option<u64>,
+        #[clap(long, default_value = "0")]
+        rate_amount_units: u64,
+        #[clap(long, default_value = "0")]
+        rate_interval_in_seconds: u64,
+        #[clap(long)]
+        allocation_assigned_units: u64,
+        #[clap(long, default_value = "0")]
+        cliff_vest_amount_units: u64,
+        /// Cliff percent relative to 1_000_000
+        #[clap(long, default_value = "0")]
+        cliff_vest_percent: u64,
+        #[clap(long)]
+        fee_payed_by_treasurer: bool,
+        /// `linear` or `step`
+        #[clap(long, default_value = "linear", parse(try_from_str = parse_accrual_mode))]
+        accrual_mode: u8,
+        /// Cliff date in seconds (defaults to the start)
+        #[clap(long, default_value = "0")]
+        cliff_utc: u64,
+        /// Create the stream as a PDA of the treasury
+        #[clap(long)]
+        pda: bool,
+    },
+    /// Create streams in bulk from a CSV file (`create_streams_batch`). The
+    /// file columns are `beneficiary`, `allocation_assigned_units`,
+    /// `rate_amount_units`, `rate_interval_in_seconds` and
+    /// `cliff_vest_amount_units`
+    CreateBatch {
+        #[clap(long)]
+        treasury: Pubkey,
+        file: PathBuf,
+        /// Name of the streams
+        #[clap(long)]
+        name: String,
+        /// Start timestamp in seconds (defaults to now)
+        #[clap(long)]
+        start_utc: Option<u64>,
+        #[clap(long)]
+        fee_payed_by_treasurer: bool,
+        /// `linear` or `step`
+        #[clap(long, default_value = "linear", parse(try_from_str = parse_accrual_mode))]
+        accrual_mode: u8,
+        /// Cliff date in seconds (defaults to the start)
+        #[clap(long, default_value = "0")]
+        cliff_utc: u64,
+        /// Number of streams created per transaction
+        #[clap(long, default_value = "5")]
+        batch_size: usize,
+    },
+    /// Create a stream unlocking units at the given points
+    /// (`create_stream_with_schedule`)
+    CreateWithSchedule {
+        #[clap(long)]
+        treasury: Pubkey,
+        #[clap(long)]
+        beneficiary: Pubkey,
+        #[clap(long)]
+        name: String,
+        #[clap(long)]
+        allocation_assigned_units: u64,
+        #[clap(long)]
+        fee_payed_by_treasurer: bool,
+        /// Unlock point as `<timestamp>:<cumulative units>`
+        #[clap(long = "point", required = true, parse(try_from_str = parse_schedule_point))]
+        points: Vec<SchedulePoint>,
+    },
+    /// Create a stream with the treasury template (`create_stream_with_template`
+    /// or `create_stream_pda_with_template`)
+    CreateWithTemplate {
+        #[clap(long)]
+        treasury: Pubkey,
+        #[clap(long)]
+        beneficiary: Pubkey,
+        #[clap(long)]
+        name: String,
+        #[clap(long)]
+        allocation_assigned_units: u64,
+        /// Create the stream as a PDA of the treasury
+        #[clap(long)]
+        pda: bool,
+    },
+    /// Withdraw as the beneficiary (`withdraw`, `withdraw_to_destination` or
+    /// `withdraw_native`)
+    Withdraw {
+        stream: Pubkey,
+        amount: u64,
+        /// Token account receiving the funds (defaults to the beneficiary
+        /// associated token account)
+        #[clap(long)]
+        destination: Option<Pubkey>,
+    },
+    /// Withdraw all the withdrawable amount to the beneficiary (`push_withdraw`)
+    PushWithdraw { stream: Pubkey },
+    /// Withdraw from several streams of the signer (`withdraw_many`)
+    WithdrawMany {
+        #[clap(required = true)]
+        streams: Vec<Pubkey>,
+    },
+    /// Pause the stream (`pause_stream`)
+    Pause { stream: Pubkey },
+    /// Resume the stream (`resume_stream`)
+    Resume { stream: Pubkey },
+    /// Update the stream rate (`update_stream_rate`)
+    UpdateRate {
+        stream: Pubkey,
+        rate_amount_units: u64,
+        rate_interval_in_seconds: u64,
+    },
+    /// Transfer the stream to a new beneficiary (`transfer_stream`)
+    Transfer {
+        stream: Pubkey,
+        new_beneficiary: Pubkey,
+    },
+    /// Propose a new beneficiary (`propose_stream_transfer`)
+    ProposeTransfer {
+        stream: Pubkey,
+        new_beneficiary: Pubkey,
+    },
+    /// Cancel the proposed transfer (`cancel_stream_transfer`)
+    CancelTransfer { stream: Pubkey },
+    /// Accept the stream as the proposed beneficiary (`accept_stream_transfer`)
+    AcceptTransfer { stream: Pubkey },
+    /// Allocate treasury funds to the stream (`allocate`)
+    Allocate { stream: Pubkey, amount: u64 },
+    /// Allocate treasury funds to the given streams (`allocate_to_all_streams`)
+    AllocateToAll {
+        #[clap(long)]
+        treasury: Pubkey,
+        amount: u64,
+        /// `pro-rata` (by stream rate) or `even`
+        #[clap(long, default_value = "pro-rata", parse(try_from_str = parse_split_mode))]
+        split_mode: u8,
+        #[clap(required = true)]
+        streams: Vec<Pubkey>,
+    },
+    /// Close the stream as the treasurer (`close_stream` or `close_stream_native`)
+    Close { stream: Pubkey },
+    /// Close the stream as the beneficiary (`renounce_stream`)
+    Renounce { stream: Pubkey },
+    /// Migrate the stream to the latest layout (`migrate_stream`)
+    Migrate { stream: Pubkey },
+    /// Emit the stream data event (`get_stream`)
+    Emit { stream: Pubkey },
+    /// Print the stream status and withdrawable amount
+    Show { stream: Pubkey },
+}
+
+/// A row of the `create-batch` CSV file
+#[derive(Deserialize)]
+struct BatchRow {
+    beneficiary: String,
+    allocation_assigned_units: u64,
+    rate_amount_units: u64,
+    rate_interval_in_seconds: u64,
+    cliff_vest_amount_units: u64,
+}
+
+/// The accounts shared by the stream instructions
+struct StreamAccounts {
+    address: Pubkey,
+    stream: Stream,
+    treasury: TreasuryInfo,
+    fee_override: Pubkey,
+    referrer: Pubkey,
+    referrer_token: Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+}
+
+impl StreamAccounts {
+    fn fetch(ctx: &Context, address: &Pubkey) -> Result<StreamAccounts> {
+        let stream = ctx.fetch_stream(address)?;
+        let treasury = ctx.fetch_treasury(&stream.treasury_address)?;
+        let (referrer, referrer_token) = ctx.fee_referrer(&treasury);
+        Ok(StreamAccounts {
+            address: *address,
+            fee_override: ctx.fee_override(&treasury)?,
+            referrer,
+            referrer_token,
+            remaining_accounts: stream_remaining_accounts(&stream),
+            stream,
+            treasury,
+        })
+    }
+
+    fn beneficiary_token(&self) -> Pubkey {
+        get_associated_token_address(&self.stream.beneficiary_address, &self.treasury.mint)
+    }
+}
+
+pub fn run(ctx: &Context, command: StreamCommand) -> Result<()> {
+    match command {
+        StreamCommand::Create {
+            treasury,
+            beneficiary,
+            name,
+            start_utc,
+            rate_amount_units,
+            rate_interval_in_seconds,
+            allocation_assigned_units,
+            cliff_vest_amount_units,
+            cliff_vest_percent,
+            fee_payed_by_treasurer,
+            accrual_mode,
+            cliff_utc,
+            pda,
+        } => {
+            let treasury = ctx.fetch_treasury(&treasury)?;
+            let fee_override = ctx.fee_override(&treasury)?;
+            let (referrer, referrer_token) = ctx.fee_referrer(&treasury);
+            let start_utc = start_utc.map_or_else(|| ctx.now(), Ok)?;
+            let stream_key = Keypair::new();
+            let (ix, stream, signers) = if treasury.is_native {
+                let ix = instruction::create_stream_native(
+                    msp::accounts::CreateStreamNativeAccounts {
+                        payer: ctx.wallet(),
+                        treasurer: ctx.wallet(),
+                        treasury: treasury.address,
+                        beneficiary,
+                        stream: stream_key.pubkey(),
+                        config: ctx.config_address(),
+                        fee_override,
+                        referrer,
+                        fee_treasury: ctx.fee_treasury(),
+                        system_program: system_program::ID,
+                    },
+                    name,
+                    start_utc,
+                    rate_amount_units,
+                    rate_interval_in_seconds,
+                    allocation_assigned_units,
+                    cliff_vest_amount_units,
+                    fee_payed_by_treasurer,
+                    accrual_mode,
+                    cliff_utc,
+                );
+                (ix, stream_key.pubkey(), vec![&stream_key])
+            } else if pda {
+                let stream_pda_seed = Keypair::new().pubkey();
+                let stream = find_stream_address(&treasury.address, &stream_pda_seed).0;
+                let ix = instruction::create_stream_pda(
+                    msp::accounts::CreateStreamPdaAccounts {
+                        payer: ctx.wallet(),
+                        treasurer: ctx.wallet(),
+                        treasury: treasury.address,
+                        treasury_token: treasury.treasury_token,
+                        associated_token: treasury.mint,
+                        beneficiary,
+                        stream,
+                        config: ctx.config_address(),
+                        fee_override,
+                        referrer,
+                        referrer_token,
+                        fee_treasury: ctx.fee_treasury(),
+                        fee_treasury_token: ctx.fee_treasury_token(&treasury.mint),
+                        associated_token_program: associated_token::ID,
+                        token_program: token::ID,
+                        system_program: system_program::ID,
+                        rent: sysvar::rent::ID,
+                    },
+                    name,
+                    start_utc,
+                    rate_amount_units,
+                    rate_interval_in_seconds,
+                    allocation_assigned_units,
+                    cliff_vest_amount_units,
+                    cliff_vest_percent,
+                    fee_payed_by_treasurer,
+                    stream_pda_seed,
+                    accrual_mode,
+                    cliff_utc,
+                );
+                (ix, stream, vec![])
+            } else {
+                let ix = instruction::create_stream(
+                    msp::accounts::CreateStreamAccounts {
+                        payer: ctx.wallet(),
+                        treasurer: ctx.wallet(),
+                        treasury: treasury.address,
+                        treasury_token: treasury.treasury_token,
+                        associated_token: treasury.mint,
+                        beneficiary,
+                        stream: stream_key.pubkey(),
+                        config: ctx.config_address(),
+                        fee_override,
+                        referrer,
+                        referrer_token,
+                        fee_treasury: ctx.fee_treasury(),
+                        fee_treasury_token: ctx.fee_treasury_token(&treasury.mint),
+                        associated_token_program: associated_token::ID,
+                        token_program: token::ID,
+                        system_program: system_program::ID,
+                        rent: sysvar::rent::ID,
+                    },
+                    name,
+                    start_utc,
+                    rate_amount_units,
+                    rate_interval_in_seconds,
+                    allocation_assigned_units,
+                    cliff_vest_amount_units,
+                    cliff_vest_percent,
+                    fee_payed_by_treasurer,
+                    accrual_mode,
+                    cliff_utc,
+                );
+                (ix, stream_key.pubkey(), vec![&stream_key])
+            };
+            display::signature(ctx.send(&[ix], &signers)?);
+            println!("Stream: {}", stream);
+            Ok(())
+        }
+        StreamCommand::CreateBatch {
+            treasury,
+            file,
+            name,
+            start_utc,
+            fee_payed_by_treasurer,
+            accrual_mode,
+            cliff_utc,
+            batch_size,
+        } => {
+            let treasury = ctx.fetch_treasury(&treasury)?;
+            let fee_override = ctx.fee_override(&treasury)?;
+            let (referrer, referrer_token) = ctx.fee_referrer(&treasury);
+            let start_utc = start_utc.map_or_else(|| ctx.now(), Ok)?;
+            let entries = read_batch_file(&file)?;
+            for chunk in entries.chunks(batch_size.max(1)) {
+                let ix = instruction::create_streams_batch(
+                    msp::accounts::CreateStreamsBatchAccounts {
+                        payer: ctx.wallet(),
+                        treasurer: ctx.wallet(),
+                        treasury: treasury.address,
+                        treasury_token: treasury.treasury_token,
+                        associated_token: treasury.mint,
+                        config: ctx.config_address(),
+                        fee_override,
+                        referrer,
+                        referrer_token,
+                        fee_treasury: ctx.fee_treasury(),
+                        fee_treasury_token: ctx.fee_treasury_token(&treasury.mint),
+                        associated_token_program: associated_token::ID,
+                        token_program: token::ID,
+                        system_program: system_program::ID,
+                        rent: sysvar::rent::ID,
+                    },
+                    name.clone(),
+                    start_utc,
+                    fee_payed_by_treasurer,
+                    accrual_mode,
+                    cliff_utc,
+                    chunk.to_vec(),
+                );
+                display::signature(ctx.send(&[ix], &[])?);
+                for entry in chunk {
+                    println!(
+                        "Stream: {} (beneficiary {})",
+                        find_stream_address(&treasury.address, &entry.stream_pda_seed).0,
+                        entry.beneficiary
+                    );
+                }
+            }
+            Ok(())
+        }
+        StreamCommand::CreateWithSchedule {
+            treasury,
+            beneficiary,
+            name,
+            allocation_assigned_units,
+            fee_payed_by_treasurer,
+            points,
+        } => {
+            let treasury = ctx.fetch_treasury(&treasury)?;
+            let (referrer, referrer_token) = ctx.fee_referrer(&treasury);
+            let stream_key = Keypair::new();
+            let ix = instruction::create_stream_with_schedule(
+                msp::accounts::CreateStreamWithScheduleAccounts {
+                    payer: ctx.wallet(),
+                    treasurer: ctx.wallet(),
+                    treasury: treasury.address,
+                    treasury_token: treasury.treasury_token,
+                    associated_token: treasury.mint,
+                    beneficiary,
+                    stream: stream_key.pubkey(),
+                    schedule: find_schedule_address(&stream_key.pubkey()).0,
+                    config: ctx.config_address(),
+                    fee_override: ctx.fee_override(&treasury)?,
+                    referrer,
+                    referrer_token,
+                    fee_treasury: ctx.fee_treasury(),
+                    fee_treasury_token: ctx.fee_treasury_token(&treasury.mint),
+                    associated_token_program: associated_token::ID,
+                    token_program: token::ID,
+                    system_program: system_program::ID,
+                    rent: sysvar::rent::ID,
+                },
+                name,
+                allocation_assigned_units,
+                fee_payed_by_treasurer,
+                points,
+            );
+            display::signature(ctx.send(&[ix], &[&stream_key])?);
+            println!("Stream: {}", stream_key.pubkey());
+            Ok(())
+        }
+        StreamCommand::CreateWithTemplate {
+            treasury,
+            beneficiary,
+            name,
+            allocation_assigned_units,
+            pda,
+        } => {
+            let treasury = ctx.fetch_treasury(&treasury)?;
+            let fee_override = ctx.fee_override(&treasury)?;
+            let (referrer, referrer_token) = ctx.fee_referrer(&treasury);
+            let template = find_template_address(&treasury.address).0;
+            let (ix, stream, signers) = if pda {
+                let stream_pda_seed = Keypair::new().pubkey();
+                let stream = find_stream_address(&treasury.address, &stream_pda_seed).0;
+                let ix = instruction::create_stream_pda_with_template(
+                    msp::accounts::CreateStreamPdaWithTemplateAccounts {
+                        payer: ctx.wallet(),
+                        treasurer: ctx.wallet(),
+                        treasury: treasury.address,
+                        treasury_token: treasury.treasury_token,
+                        associated_token: treasury.mint,
+                        beneficiary,
+                        template,
+                        stream,
+                        config: ctx.config_address(),
+                        fee_override,
+                        referrer,
+                        referrer_token,
+                        fee_treasury: ctx.fee_treasury(),
+                        fee_treasury_token: ctx.fee_treasury_token(&treasury.mint),
+                        associated_token_program: associated_token::ID,
+                        token_program: token::ID,
+                        system_program: system_program::ID,
+                        rent: sysvar::rent::ID,
+                    },
+                    name,
+                    allocation_assigned_units,
+                    stream_pda_seed,
+                );
+                (ix, stream, None)
+            } else {
+                let stream_key = Keypair::new();
+                let ix = instruction::create_stream_with_template(
+                    msp::accounts::CreateStreamWithTemplateAccounts {
+                        payer: ctx.wallet(),
+                        treasurer: ctx.wallet(),
+                        treasury: treasury.address,
+                        treasury_token: treasury.treasury_token,
+                        associated_token: treasury.mint,
+                        beneficiary,
+                        template,
+                        stream: stream_key.pubkey(),
+                        config: ctx.config_address(),
+                        fee_override,
+                        referrer,
+                        referrer_token,
+                        fee_treasury: ctx.fee_treasury(),
+                        fee_treasury_token: ctx.fee_treasury_token(&treasury.mint),
+                        associated_token_program: associated_token::ID,
+                        token_program: token::ID,
+                        system_program: system_program::ID,
+                        rent: sysvar::rent::ID,
+                    },
+                    name,
+                    allocation_assigned_units,
+                );
+                (ix, stream_key.pubkey(), Some(stream_key))
+            };
+            let signers: Vec<&Keypair> = signers.iter().collect();
+            display::signature(ctx.send(&[ix], &signers)?);
+            println!("Stream: {}", stream);
+            Ok(())
+        }
+        StreamCommand::Withdraw {
+            stream,
+            amount,
+            destination,
+        } => withdraw(ctx, &stream, amount, destination),
+        StreamCommand::PushWithdraw { stream } => {
+            let accounts = StreamAccounts::fetch(ctx, &stream)?;
+            let mint = accounts.treasury.mint;
+            let mut ix = instruction::push_withdraw(msp::accounts::PushWithdrawAccounts {
+                payer: ctx.wallet(),
+                beneficiary: accounts.stream.beneficiary_address,
+                beneficiary_token: accounts.beneficiary_token(),
+                associated_token: mint,
+                treasury: accounts.treasury.address,
+                treasury_token: accounts.treasury.treasury_token,
+                stream: accounts.address,
+                config: ctx.config_address(),
+                fee_override: accounts.fee_override,
+                referrer: accounts.referrer,
+                referrer_token: accounts.referrer_token,
+                fee_treasury: ctx.fee_treasury(),
+                fee_treasury_token: ctx.fee_treasury_token(&mint),
+                associated_token_program: associated_token::ID,
+                token_program: token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            });
+            ix.accounts.extend(accounts.remaining_accounts);
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        StreamCommand::WithdrawMany { streams } => withdraw_many(ctx, &streams),
+        StreamCommand::Pause { stream } | StreamCommand::Resume { stream } => {
+            let resume = matches!(command, StreamCommand::Resume { .. });
+            let stream_data = ctx.fetch_stream(&stream)?;
+            let accounts = msp::accounts::PauseOrResumeStreamAccounts {
+                initializer: ctx.wallet(),
+                treasury: stream_data.treasury_address,
+                stream,
+            };
+            let ix = if resume {
+                instruction::resume_stream(accounts)
+            } else {
+                instruction::pause_stream(accounts)
+            };
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        StreamCommand::UpdateRate {
+            stream,
+            rate_amount_units,
+            rate_interval_in_seconds,
+        } => {
+            let stream_data = ctx.fetch_stream(&stream)?;
+            let ix = instruction::update_stream_rate(
+                msp::accounts::UpdateStreamRateAccounts {
+                    treasurer: ctx.wallet(),
+                    treasury: stream_data.treasury_address,
+                    stream,
+                },
+                rate_amount_units,
+                rate_interval_in_seconds,
+            );
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        StreamCommand::Transfer {
+            stream,
+            new_beneficiary,
+        }
+        | StreamCommand::ProposeTransfer {
+            stream,
+            new_beneficiary,
+        } => {
+            let accounts = msp::accounts::TransferStreamAccounts {
+                beneficiary: ctx.wallet(),
+                stream,
+                config: ctx.config_address(),
+                fee_treasury: ctx.fee_treasury(),
+                system_program: system_program::ID,
+            };
+            let ix = if matches!(command, StreamCommand::Transfer { .. }) {
+                instruction::transfer_stream(accounts, new_beneficiary)
+            } else {
+                instruction::propose_stream_transfer(accounts, new_beneficiary)
+            };
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        StreamCommand::CancelTransfer { stream } => {
+            let ix = instruction::cancel_stream_transfer(
+                msp::accounts::CancelStreamTransferAccounts {
+                    beneficiary: ctx.wallet(),
+                    stream,
+                },
+            );
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        StreamCommand::AcceptTransfer { stream } => {
+            let ix = instruction::accept_stream_transfer(
+                msp::accounts::AcceptStreamTransferAccounts {
+                    new_beneficiary: ctx.wallet(),
+                    stream,
+                },
+            );
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        StreamCommand::Allocate { stream, amount } => {
+            let accounts = StreamAccounts::fetch(ctx, &stream)?;
+            let mint = accounts.treasury.mint;
+            let mut ix = instruction::allocate(
+                msp::accounts::AllocateAccounts {
+                    payer: ctx.wallet(),
+                    treasurer: ctx.wallet(),
+                    treasury: accounts.treasury.address,
+                    treasury_token: accounts.treasury.treasury_token,
+                    associated_token: mint,
+                    stream: accounts.address,
+                    config: ctx.config_address(),
+                    fee_override: accounts.fee_override,
+                    referrer: accounts.referrer,
+                    referrer_token: accounts.referrer_token,
+                    fee_treasury: ctx.fee_treasury(),
+                    fee_treasury_token: ctx.fee_treasury_token(&mint),
+                    associated_token_program: associated_token::ID,
+                    token_program: token::ID,
+                    system_program: system_program::ID,
+                    rent: sysvar::rent::ID,
+                },
+                amount,
+            );
+            ix.accounts.extend(accounts.remaining_accounts);
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        StreamCommand::AllocateToAll {
+            treasury,
+            amount,
+            split_mode,
+            streams,
+        } => {
+            let treasury = ctx.fetch_treasury(&treasury)?;
+            let (referrer, referrer_token) = ctx.fee_referrer(&treasury);
+            let streams = streams
+                .iter()
+                .map(|address| {
+                    Ok(AllocatedStream {
+                        stream: *address,
+                        has_schedule: ctx.fetch_stream(address)?.primitive_has_schedule(),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let ix = instruction::allocate_to_all_streams(
+                msp::accounts::AllocateToAllStreamsAccounts {
+                    payer: ctx.wallet(),
+                    treasurer: ctx.wallet(),
+                    treasury: treasury.address,
+                    treasury_token: treasury.treasury_token,
+                    associated_token: treasury.mint,
+                    config: ctx.config_address(),
+                    fee_override: ctx.fee_override(&treasury)?,
+                    referrer,
+                    referrer_token,
+                    fee_treasury: ctx.fee_treasury(),
+                    fee_treasury_token: ctx.fee_treasury_token(&treasury.mint),
+                    associated_token_program: associated_token::ID,
+                    token_program: token::ID,
+                    system_program: system_program::ID,
+                    rent: sysvar::rent::ID,
+                },
+                amount,
+                split_mode,
+                &streams,
+            );
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        StreamCommand::Close { stream } => close(ctx, &stream),
+        StreamCommand::Renounce { stream } => {
+            let accounts = StreamAccounts::fetch(ctx, &stream)?;
+            let mint = accounts.treasury.mint;
+            let mut ix = instruction::renounce_stream(msp::accounts::RenounceStreamAccounts {
+                payer: ctx.wallet(),
+                beneficiary: ctx.wallet(),
+                beneficiary_token: accounts.beneficiary_token(),
+                associated_token: mint,
+                treasurer: accounts.treasury.treasury.authority(),
+                treasury: accounts.treasury.address,
+                treasury_token: accounts.treasury.treasury_token,
+                stream: accounts.address,
+                config: ctx.config_address(),
+                fee_override: accounts.fee_override,
+                referrer: accounts.referrer,
+                referrer_token: accounts.referrer_token,
+                fee_treasury: ctx.fee_treasury(),
+                fee_treasury_token: ctx.fee_treasury_token(&mint),
+                associated_token_program: associated_token::ID,
+                token_program: token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            });
+            ix.accounts.extend(accounts.remaining_accounts);
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        StreamCommand::Migrate { stream } => {
+            let stream_data = ctx.fetch_stream(&stream)?;
+            let ix = instruction::migrate_stream(msp::accounts::MigrateStreamAccounts {
+                authority: ctx.wallet(),
+                treasury: stream_data.treasury_address,
+                stream,
+                system_program: system_program::ID,
+            });
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        StreamCommand::Emit { stream } => {
+            let stream_data = ctx.fetch_stream(&stream)?;
+            let mut ix = instruction::get_stream(msp::accounts::GetStreamAccounts { stream });
+            if stream_data.primitive_has_schedule() {
+                ix.accounts
+                    .push(AccountMeta::new_readonly(stream_data.schedule_address, false));
+            }
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        StreamCommand::Show { stream } => {
+            let stream_data = ctx.fetch_stream(&stream)?;
+            let schedule = if stream_data.primitive_has_schedule() {
+                Some(state::fetch_stream_schedule(
+                    &ctx.rpc,
+                    &stream_data.schedule_address,
+                )?)
+            } else {
+                None
+            };
+            display::stream(&stream, &stream_data, schedule.as_ref(), ctx.now()?)
+        }
+    }
+}
+
+fn read_batch_file(file: &PathBuf) -> Result<Vec<StreamBatchEntry>> {
+    let mut reader = csv::Reader::from_path(file)?;
+    let mut entries = Vec::new();
+    for row in reader.deserialize() {
+        let row: BatchRow = row?;
+        entries.push(StreamBatchEntry {
+            beneficiary: row.beneficiary.parse()?,
+            stream_pda_seed: Keypair::new().pubkey(),
+            allocation_assigned_units: row.allocation_assigned_units,
+            rate_amount_units: row.rate_amount_units,
+            rate_interval_in_seconds: row.rate_interval_in_seconds,
+            cliff_vest_amount_units: row.cliff_vest_amount_units,
+        });
+    }
+    Ok(entries)
+}
+
+fn withdraw(ctx: &Context, stream: &Pubkey, amount: u64, destination: Option<Pubkey>) -> Result<()> {
+    let accounts = StreamAccounts::fetch(ctx, stream)?;
+    let mint = accounts.treasury.mint;
+    let mut ix = if accounts.treasury.is_native {
+        instruction::withdraw_native(
+            msp::accounts::WithdrawNativeAccounts {
+                beneficiary: ctx.wallet(),
+                treasury: accounts.treasury.address,
+                stream: accounts.address,
+                config: ctx.config_address(),
+                fee_override: accounts.fee_override,
+                referrer: accounts.referrer,
+                fee_treasury: ctx.fee_treasury(),
+                system_program: system_program::ID,
+            },
+            amount,
+        )
+    } else if let Some(destination_token) = destination {
+        instruction::withdraw_to_destination(
+            msp::accounts::WithdrawToDestinationAccounts {
+                beneficiary: ctx.wallet(),
+                destination_token,
+                associated_token: mint,
+                treasury: accounts.treasury.address,
+                treasury_token: accounts.treasury.treasury_token,
+                stream: accounts.address,
+                config: ctx.config_address(),
+                fee_override: accounts.fee_override,
+                referrer: accounts.referrer,
+                referrer_token: accounts.referrer_token,
+                fee_treasury: ctx.fee_treasury(),
+                fee_treasury_token: ctx.fee_treasury_token(&mint),
+                token_program: token::ID,
+            },
+            amount,
+        )
+    } else {
+        instruction::withdraw(
+            msp::accounts::WithdrawAccounts {
+                payer: ctx.wallet(),
+                beneficiary: ctx.wallet(),
+                beneficiary_token: accounts.beneficiary_token(),
+                associated_token: mint,
+                treasury: accounts.treasury.address,
+                treasury_token: accounts.treasury.treasury_token,
+                stream: accounts.address,
+                config: ctx.config_address(),
+                fee_override: accounts.fee_override,
+                referrer: accounts.referrer,
+                referrer_token: accounts.referrer_token,
+                fee_treasury: ctx.fee_treasury(),
+                fee_treasury_token: ctx.fee_treasury_token(&mint),
+                associated_token_program: associated_token::ID,
+                token_program: token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            amount,
+        )
+    };
+    ix.accounts.extend(accounts.remaining_accounts);
+    display::signature(ctx.send(&[ix], &[])?);
+    Ok(())
+}
+
+fn withdraw_many(ctx: &Context, streams: &[Pubkey]) -> Result<()> {
+    let withdrawn_streams = streams
+        .iter()
+        .map(|address| {
+            let stream = ctx.fetch_stream(address)?;
+            Ok(WithdrawnStream {
+                treasury: stream.treasury_address,
+                stream: *address,
+                has_schedule: stream.primitive_has_schedule(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let treasury = ctx.fetch_treasury(&withdrawn_streams[0].treasury)?;
+    let (referrer, referrer_token) = ctx.fee_referrer(&treasury);
+    let ix = instruction::withdraw_many(
+        msp::accounts::WithdrawManyAccounts {
+            payer: ctx.wallet(),
+            beneficiary: ctx.wallet(),
+            beneficiary_token: get_associated_token_address(&ctx.wallet(), &treasury.mint),
+            associated_token: treasury.mint,
+            config: ctx.config_address(),
+            fee_override: ctx.fee_override(&treasury)?,
+            referrer,
+            referrer_token,
+            fee_treasury: ctx.fee_treasury(),
+            fee_treasury_token: ctx.fee_treasury_token(&treasury.mint),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        &withdrawn_streams,
+    );
+    display::signature(ctx.send(&[ix], &[])?);
+    Ok(())
+}
+
+fn close(ctx: &Context, stream: &Pubkey) -> Result<()> {
+    let accounts = StreamAccounts::fetch(ctx, stream)?;
+    let mint = accounts.treasury.mint;
+    let mut ix = if accounts.treasury.is_native {
+        instruction::close_stream_native(msp::accounts::CloseStreamNativeAccounts {
+            payer: ctx.wallet(),
+            treasurer: ctx.wallet(),
+            beneficiary: accounts.stream.beneficiary_address,
+            treasury: accounts.treasury.address,
+            stream: accounts.address,
+            config: ctx.config_address(),
+            fee_override: accounts.fee_override,
+            referrer: accounts.referrer,
+            fee_treasury: ctx.fee_treasury(),
+            system_program: system_program::ID,
+        })
+    } else {
+        instruction::close_stream(msp::accounts::CloseStreamAccounts {
+            payer: ctx.wallet(),
+            treasurer: ctx.wallet(),
+            beneficiary: accounts.stream.beneficiary_address,
+            beneficiary_token: accounts.beneficiary_token(),
+            associated_token: mint,
+            treasury: accounts.treasury.address,
+            treasury_token: accounts.treasury.treasury_token,
+            stream: accounts.address,
+            config: ctx.config_address(),
+            fee_override: accounts.fee_override,
+            referrer: accounts.referrer,
+            referrer_token: accounts.referrer_token,
+            fee_treasury: ctx.fee_treasury(),
+            fee_treasury_token: ctx.fee_treasury_token(&mint),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        })
+    };
+    ix.accounts.extend(accounts.remaining_accounts);
+    display::signature(ctx.send(&[ix], &[])?);
+    Ok(())
+}
@@ -0,0 +1,526 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{system_program, sysvar};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token;
+use clap::Subcommand;
+use msp::categories::{Category, SubCategory};
+use msp_client::instruction::{self, ClosedStream};
+use msp_client::pda::{find_template_address, find_treasury_address};
+use msp_client::state;
+
+use crate::args::*;
+use crate::context::{Context, Result};
+use crate::display;
+
+#[derive(Subcommand)]
+pub enum TreasuryCommand {
+    /// Create a token treasury (`create_treasury`)
+    Create {
+        /// The treasury mint
+        #[clap(long)]
+        mint: Pubkey,
+        #[clap(long)]
+        name: String,
+        /// The treasury pays the SOL fees of its streams
+        #[clap(long)]
+        sol_fee_payed_by_treasury: bool,
+        #[clap(flatten)]
+        options: TreasuryOptions,
+    },
+    /// Create a token treasury and the template of its streams
+    /// (`create_treasury_and_template`)
+    CreateWithTemplate {
+        #[clap(long)]
+        mint: Pubkey,
+        #[clap(long)]
+        name: String,
+        #[clap(long)]
+        sol_fee_payed_by_treasury: bool,
+        #[clap(flatten)]
+        options: TreasuryOptions,
+        #[clap(flatten)]
+        template: TemplateArgs,
+    },
+    /// Create a native SOL treasury (`create_native_treasury`)
+    CreateNative {
+        #[clap(long)]
+        name: String,
+        #[clap(flatten)]
+        options: TreasuryOptions,
+    },
+    /// Deposit funds into the treasury (`add_funds` or `add_funds_native`)
+    AddFunds {
+        treasury: Pubkey,
+        /// Amount in token units (lamports for native SOL treasuries)
+        amount: u64,
+    },
+    /// Withdraw unallocated funds (`treasury_withdraw` or
+    /// `treasury_withdraw_native`)
+    Withdraw {
+        treasury: Pubkey,
+        amount: u64,
+        /// The wallet receiving the funds (defaults to the signer)
+        #[clap(long)]
+        destination: Option<Pubkey>,
+    },
+    /// Sync the treasury balance with its token account (`refresh_treasury_data`)
+    Refresh { treasury: Pubkey },
+    /// Propose a new treasury authority (`propose_treasury_authority`)
+    ProposeAuthority {
+        treasury: Pubkey,
+        new_authority: Pubkey,
+    },
+    /// Accept the treasury authority as the signer (`accept_treasury_authority`)
+    AcceptAuthority { treasury: Pubkey },
+    /// Create the template of the treasury streams (`create_stream_template`)
+    CreateTemplate {
+        treasury: Pubkey,
+        #[clap(flatten)]
+        template: TemplateArgs,
+    },
+    /// Modify the template of the treasury streams (`modify_stream_template`)
+    ModifyTemplate {
+        treasury: Pubkey,
+        #[clap(flatten)]
+        template: TemplateArgs,
+    },
+    /// Close the treasury (`close_treasury` or `close_treasury_native`), or
+    /// the treasury and the given streams (`close_treasury_and_streams`)
+    Close {
+        treasury: Pubkey,
+        /// The wallet receiving the remaining funds (defaults to the signer)
+        #[clap(long)]
+        destination: Option<Pubkey>,
+        /// Streams to close along with the treasury
+        #[clap(long = "stream")]
+        streams: Vec<Pubkey>,
+    },
+    /// Migrate the treasury to the latest layout (`migrate_treasury`)
+    Migrate { treasury: Pubkey },
+    /// Print the treasury
+    Show { treasury: Pubkey },
+}
+
+#[derive(clap::Args)]
+pub struct TreasuryOptions {
+    /// Slot used to derive the treasury address (defaults to the current slot)
+    #[clap(long)]
+    slot: Option<u64>,
+    /// `open` or `locked`
+    #[clap(long = "type", default_value = "open", parse(try_from_str = parse_treasury_type))]
+    treasury_type: u8,
+    #[clap(long)]
+    auto_close: bool,
+    #[clap(long, default_value = "default", parse(try_from_str = parse_category))]
+    category: Category,
+    #[clap(long, default_value = "default", parse(try_from_str = parse_sub_category))]
+    sub_category: SubCategory,
+    /// Wallet of the integrator that referred the treasury
+    #[clap(long)]
+    referrer: Option<Pubkey>,
+}
+
+pub fn run(ctx: &Context, command: TreasuryCommand) -> Result<()> {
+    match command {
+        TreasuryCommand::Create {
+            mint,
+            name,
+            sol_fee_payed_by_treasury,
+            options,
+        } => create(ctx, mint, name, sol_fee_payed_by_treasury, options, None),
+        TreasuryCommand::CreateWithTemplate {
+            mint,
+            name,
+            sol_fee_payed_by_treasury,
+            options,
+            template,
+        } => create(
+            ctx,
+            mint,
+            name,
+            sol_fee_payed_by_treasury,
+            options,
+            Some(template),
+        ),
+        TreasuryCommand::CreateNative { name, options } => create_native(ctx, name, options),
+        TreasuryCommand::AddFunds { treasury, amount } => add_funds(ctx, &treasury, amount),
+        TreasuryCommand::Withdraw {
+            treasury,
+            amount,
+            destination,
+        } => withdraw(ctx, &treasury, amount, destination),
+        TreasuryCommand::Refresh { treasury } => {
+            let treasury = ctx.fetch_treasury(&treasury)?;
+            let ix = instruction::refresh_treasury_data(msp::accounts::RefreshTreasuryDataAccounts {
+                associated_token: treasury.mint,
+                treasury: treasury.address,
+                treasury_token: treasury.treasury_token,
+            });
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        TreasuryCommand::ProposeAuthority {
+            treasury,
+            new_authority,
+        } => {
+            let ix = instruction::propose_treasury_authority(
+                msp::accounts::ProposeTreasuryAuthorityAccounts {
+                    authority: ctx.wallet(),
+                    treasury,
+                },
+                new_authority,
+            );
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        TreasuryCommand::AcceptAuthority { treasury } => {
+            let ix = instruction::accept_treasury_authority(
+                msp::accounts::AcceptTreasuryAuthorityAccounts {
+                    new_authority: ctx.wallet(),
+                    treasury,
+                },
+            );
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        TreasuryCommand::CreateTemplate { treasury, template } => {
+            let ix = instruction::create_stream_template(
+                msp::accounts::CreateStreamTemplateAccounts {
+                    payer: ctx.wallet(),
+                    treasurer: ctx.wallet(),
+                    treasury,
+                    template: find_template_address(&treasury).0,
+                    system_program: system_program::ID,
+                },
+                template.start_utc.map_or_else(|| ctx.now(), Ok)?,
+                template.rate_interval_in_seconds,
+                template.duration_number_of_units,
+                template.cliff_vest_percent,
+                template.fee_payed_by_treasurer,
+                template.accrual_mode,
+                template.cliff_utc,
+            );
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        TreasuryCommand::ModifyTemplate { treasury, template } => {
+            let ix = instruction::modify_stream_template(
+                msp::accounts::ModifyStreamTemplateAccounts {
+                    payer: ctx.wallet(),
+                    treasurer: ctx.wallet(),
+                    treasury,
+                    template: find_template_address(&treasury).0,
+                },
+                template.start_utc.map_or_else(|| ctx.now(), Ok)?,
+                template.rate_interval_in_seconds,
+                template.duration_number_of_units,
+                template.cliff_vest_percent,
+                template.fee_payed_by_treasurer,
+                template.accrual_mode,
+                template.cliff_utc,
+            );
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        TreasuryCommand::Close {
+            treasury,
+            destination,
+            streams,
+        } => close(ctx, &treasury, destination, &streams),
+        TreasuryCommand::Migrate { treasury } => {
+            let ix = instruction::migrate_treasury(msp::accounts::MigrateTreasuryAccounts {
+                authority: ctx.wallet(),
+                treasury,
+                system_program: system_program::ID,
+            });
+            display::signature(ctx.send(&[ix], &[])?);
+            Ok(())
+        }
+        TreasuryCommand::Show { treasury } => {
+            let treasury = ctx.fetch_treasury(&treasury)?;
+            display::treasury(&treasury);
+            let template = find_template_address(&treasury.address).0;
+            if ctx.account_exists(&template)? {
+                display::template(&template, &state::fetch_stream_template(&ctx.rpc, &template)?);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Gets the treasury address for the given slot (the current one by default)
+fn treasury_address(ctx: &Context, slot: Option<u64>) -> Result<(Pubkey, u64)> {
+    let slot = match slot {
+        Some(slot) => slot,
+        None => ctx.rpc.get_slot()?,
+    };
+    Ok((find_treasury_address(&ctx.wallet(), slot).0, slot))
+}
+
+fn create(
+    ctx: &Context,
+    mint: Pubkey,
+    name: String,
+    sol_fee_payed_by_treasury: bool,
+    options: TreasuryOptions,
+    template: Option<TemplateArgs>,
+) -> Result<()> {
+    if template.is_some() && options.referrer.is_some() {
+        return Err("treasuries created with a template can't have a referrer".into());
+    }
+    let (treasury, slot) = treasury_address(ctx, options.slot)?;
+    let treasury_token = get_associated_token_address(&treasury, &mint);
+    let ix = match template {
+        Some(template) => instruction::create_treasury_and_template(
+            msp::accounts::CreateTreasuryAndTemplateAccounts {
+                payer: ctx.wallet(),
+                treasurer: ctx.wallet(),
+                treasury,
+                treasury_token,
+                template: find_template_address(&treasury).0,
+                associated_token: mint,
+                config: ctx.config_address(),
+                fee_treasury: ctx.fee_treasury(),
+                associated_token_program: associated_token::ID,
+                token_program: token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            name,
+            options.treasury_type,
+            options.auto_close,
+            sol_fee_payed_by_treasury,
+            options.category,
+            options.sub_category,
+            template.start_utc.map_or_else(|| ctx.now(), Ok)?,
+            template.rate_interval_in_seconds,
+            template.duration_number_of_units,
+            template.cliff_vest_percent,
+            template.fee_payed_by_treasurer,
+            slot,
+            template.accrual_mode,
+            template.cliff_utc,
+        ),
+        None => instruction::create_treasury(
+            msp::accounts::CreateTreasuryAccounts {
+                payer: ctx.wallet(),
+                treasurer: ctx.wallet(),
+                treasury,
+                treasury_token,
+                associated_token: mint,
+                config: ctx.config_address(),
+                fee_treasury: ctx.fee_treasury(),
+                associated_token_program: associated_token::ID,
+                token_program: token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            slot,
+            name,
+            options.treasury_type,
+            options.auto_close,
+            sol_fee_payed_by_treasury,
+            options.category,
+            options.sub_category,
+            options.referrer,
+        ),
+    };
+    display::signature(ctx.send(&[ix], &[])?);
+    println!("Treasury: {}", treasury);
+    Ok(())
+}
+
+fn create_native(ctx: &Context, name: String, options: TreasuryOptions) -> Result<()> {
+    let (treasury, slot) = treasury_address(ctx, options.slot)?;
+    let ix = instruction::create_native_treasury(
+        msp::accounts::CreateNativeTreasuryAccounts {
+            payer: ctx.wallet(),
+            treasurer: ctx.wallet(),
+            treasury,
+            config: ctx.config_address(),
+            fee_treasury: ctx.fee_treasury(),
+            system_program: system_program::ID,
+        },
+        slot,
+        name,
+        options.treasury_type,
+        options.auto_close,
+        options.category,
+        options.sub_category,
+        options.referrer,
+    );
+    display::signature(ctx.send(&[ix], &[])?);
+    println!("Treasury: {}", treasury);
+    Ok(())
+}
+
+fn add_funds(ctx: &Context, treasury: &Pubkey, amount: u64) -> Result<()> {
+    let treasury = ctx.fetch_treasury(treasury)?;
+    let ix = if treasury.is_native {
+        instruction::add_funds_native(
+            msp::accounts::AddFundsNativeAccounts {
+                payer: ctx.wallet(),
+                contributor: ctx.wallet(),
+                treasury: treasury.address,
+                config: ctx.config_address(),
+                fee_treasury: ctx.fee_treasury(),
+                system_program: system_program::ID,
+            },
+            amount,
+        )
+    } else {
+        instruction::add_funds(
+            msp::accounts::AddFundsAccounts {
+                payer: ctx.wallet(),
+                contributor: ctx.wallet(),
+                contributor_token: get_associated_token_address(&ctx.wallet(), &treasury.mint),
+                treasury: treasury.address,
+                treasury_token: treasury.treasury_token,
+                associated_token: treasury.mint,
+                config: ctx.config_address(),
+                fee_treasury: ctx.fee_treasury(),
+                fee_treasury_token: ctx.fee_treasury_token(&treasury.mint),
+                associated_token_program: associated_token::ID,
+                token_program: token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            amount,
+        )
+    };
+    display::signature(ctx.send(&[ix], &[])?);
+    Ok(())
+}
+
+fn withdraw(
+    ctx: &Context,
+    treasury: &Pubkey,
+    amount: u64,
+    destination: Option<Pubkey>,
+) -> Result<()> {
+    let treasury = ctx.fetch_treasury(treasury)?;
+    let destination = destination.unwrap_or_else(|| ctx.wallet());
+    let (referrer, referrer_token) = ctx.fee_referrer(&treasury);
+    let ix = if treasury.is_native {
+        instruction::treasury_withdraw_native(
+            msp::accounts::TreasuryWithdrawNativeAccounts {
+                treasurer: ctx.wallet(),
+                destination_authority: destination,
+                treasury: treasury.address,
+                config: ctx.config_address(),
+                fee_override: ctx.fee_override(&treasury)?,
+                referrer,
+                fee_treasury: ctx.fee_treasury(),
+                system_program: system_program::ID,
+            },
+            amount,
+        )
+    } else {
+        instruction::treasury_withdraw(
+            msp::accounts::TreasuryWithdrawAccounts {
+                payer: ctx.wallet(),
+                treasurer: ctx.wallet(),
+                destination_authority: destination,
+                destination_token_account: get_associated_token_address(
+                    &destination,
+                    &treasury.mint,
+                ),
+                associated_token: treasury.mint,
+                treasury: treasury.address,
+                treasury_token: treasury.treasury_token,
+                config: ctx.config_address(),
+                fee_override: ctx.fee_override(&treasury)?,
+                referrer,
+                referrer_token,
+                fee_treasury: ctx.fee_treasury(),
+                fee_treasury_token: ctx.fee_treasury_token(&treasury.mint),
+                associated_token_program: associated_token::ID,
+                token_program: token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            amount,
+        )
+    };
+    display::signature(ctx.send(&[ix], &[])?);
+    Ok(())
+}
+
+fn close(
+    ctx: &Context,
+    treasury: &Pubkey,
+    destination: Option<Pubkey>,
+    streams: &[Pubkey],
+) -> Result<()> {
+    let treasury = ctx.fetch_treasury(treasury)?;
+    let destination = destination.unwrap_or_else(|| ctx.wallet());
+    let destination_token_account = get_associated_token_address(&destination, &treasury.mint);
+    let (referrer, referrer_token) = ctx.fee_referrer(&treasury);
+    let ix = if treasury.is_native {
+        if !streams.is_empty() {
+            return Err("the streams of native SOL treasuries have to be closed first".into());
+        }
+        instruction::close_treasury_native(msp::accounts::CloseTreasuryNativeAccounts {
+            payer: ctx.wallet(),
+            treasurer: ctx.wallet(),
+            destination_authority: destination,
+            treasury: treasury.address,
+            config: ctx.config_address(),
+            fee_treasury: ctx.fee_treasury(),
+            system_program: system_program::ID,
+        })
+    } else if streams.is_empty() {
+        instruction::close_treasury(msp::accounts::CloseTreasuryAccounts {
+            payer: ctx.wallet(),
+            treasurer: ctx.wallet(),
+            destination_authority: destination,
+            destination_token_account,
+            associated_token: treasury.mint,
+            treasury: treasury.address,
+            treasury_token: treasury.treasury_token,
+            config: ctx.config_address(),
+            fee_treasury: ctx.fee_treasury(),
+            fee_treasury_token: ctx.fee_treasury_token(&treasury.mint),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        })
+    } else {
+        let closed_streams = streams
+            .iter()
+            .map(|address| {
+                let stream = ctx.fetch_stream(address)?;
+                Ok(ClosedStream {
+                    stream: *address,
+                    beneficiary: stream.beneficiary_address,
+                    has_schedule: stream.primitive_has_schedule(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        instruction::close_treasury_and_streams(
+            msp::accounts::CloseTreasuryAndStreamsAccounts {
+                payer: ctx.wallet(),
+                treasurer: ctx.wallet(),
+                destination_authority: destination,
+                destination_token_account,
+                associated_token: treasury.mint,
+                treasury: treasury.address,
+                treasury_token: treasury.treasury_token,
+                config: ctx.config_address(),
+                fee_override: ctx.fee_override(&treasury)?,
+                referrer,
+                referrer_token,
+                fee_treasury: ctx.fee_treasury(),
+                fee_treasury_token: ctx.fee_treasury_token(&treasury.mint),
+                associated_token_program: associated_token::ID,
+                token_program: token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            &closed_streams,
+        )
+    };
+    display::signature(ctx.send(&[ix], &[])?);
+    Ok(())
+}
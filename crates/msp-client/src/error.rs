@@ -0,0 +1,38 @@
+use anchor_lang::prelude::Pubkey;
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// The RPC request failed
+    Rpc(Box<solana_client::client_error::ClientError>),
+    /// The account is not owned by the program
+    InvalidOwner(Pubkey),
+    /// The account data can't be decoded
+    Decode(Box<anchor_lang::error::Error>),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Rpc(error) => write!(f, "RPC error: {}", error),
+            ClientError::InvalidOwner(owner) => write!(f, "Invalid account owner: {}", owner),
+            ClientError::Decode(error) => write!(f, "Invalid account data: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<solana_client::client_error::ClientError> for ClientError {
+    fn from(error: solana_client::client_error::ClientError) -> Self {
+        ClientError::Rpc(Box::new(error))
+    }
+}
+
+impl From<anchor_lang::error::Error> for ClientError {
+    fn from(error: anchor_lang::error::Error) -> Self {
+        ClientError::Decode(Box::new(error))
+    }
+}
@@ -0,0 +1,868 @@
+//! Typed builders for the program instructions. Each builder takes the
+//! accounts struct generated by Anchor and the instruction arguments, and
+//! fills in the current `IDL_FILE_VERSION`
+#![allow(clippy::too_many_arguments)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address;
+use msp::categories::{Category, SubCategory};
+use msp::config::FeeSchedule;
+use msp::constants::IDL_FILE_VERSION;
+use msp::schedule::SchedulePoint;
+use msp::stream::StreamBatchEntry;
+
+use crate::pda::{find_schedule_address, find_stream_address};
+
+/// A stream closed by `close_treasury_and_streams`
+#[derive(Clone, Copy, Debug)]
+pub struct ClosedStream {
+    pub stream: Pubkey,
+    pub beneficiary: Pubkey,
+    /// Whether the stream unlocks units following a `StreamSchedule`
+    pub has_schedule: bool,
+}
+
+/// A stream withdrawn from by `withdraw_many`
+#[derive(Clone, Copy, Debug)]
+pub struct WithdrawnStream {
+    pub treasury: Pubkey,
+    pub stream: Pubkey,
+    /// Whether the stream unlocks units following a `StreamSchedule`
+    pub has_schedule: bool,
+}
+
+/// A stream passed to `allocate_to_all_streams`
+#[derive(Clone, Copy, Debug)]
+pub struct AllocatedStream {
+    pub stream: Pubkey,
+    /// Whether the stream unlocks units following a `StreamSchedule`
+    pub has_schedule: bool,
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: msp::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Gets the `referrer` and `referrer_token` accounts of the instructions that
+/// charge fees: the treasury referrer wallet and its token account of the
+/// treasury mint or, when the treasury has no referrer, the fee treasury and
+/// its token account
+pub fn fee_referrer_accounts(
+    referrer: Option<&Pubkey>,
+    fee_treasury: &Pubkey,
+    associated_token: &Pubkey,
+) -> (Pubkey, Pubkey) {
+    let wallet = referrer.unwrap_or(fee_treasury);
+    (
+        *wallet,
+        get_associated_token_address(wallet, associated_token),
+    )
+}
+
+/// Builds a `create_treasury` instruction. The optional `referrer` wallet
+/// gets a share of the fees charged for the treasury
+pub fn create_treasury(
+    accounts: msp::accounts::CreateTreasuryAccounts,
+    slot: u64,
+    name: String,
+    treasury_type: u8,
+    auto_close: bool,
+    sol_fee_payed_by_treasury: bool,
+    category: Category,
+    sub_category: SubCategory,
+    referrer: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::CreateTreasury {
+            _idl_file_version: IDL_FILE_VERSION,
+            slot,
+            name,
+            treasury_type,
+            auto_close,
+            sol_fee_payed_by_treasury,
+            category,
+            sub_category,
+            referrer,
+        },
+    )
+}
+
+/// Builds a `create_stream` instruction
+pub fn create_stream(
+    accounts: msp::accounts::CreateStreamAccounts,
+    name: String,
+    start_utc: u64,
+    rate_amount_units: u64,
+    rate_interval_in_seconds: u64,
+    allocation_assigned_units: u64,
+    cliff_vest_amount_units: u64,
+    cliff_vest_percent: u64,
+    fee_payed_by_treasurer: bool,
+    accrual_mode: u8,
+    cliff_utc: u64,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::CreateStream {
+            _idl_file_version: IDL_FILE_VERSION,
+            name,
+            start_utc,
+            rate_amount_units,
+            rate_interval_in_seconds,
+            allocation_assigned_units,
+            cliff_vest_amount_units,
+            cliff_vest_percent,
+            fee_payed_by_treasurer,
+            accrual_mode,
+            cliff_utc,
+        },
+    )
+}
+
+/// Builds a `create_stream_pda` instruction
+pub fn create_stream_pda(
+    accounts: msp::accounts::CreateStreamPdaAccounts,
+    name: String,
+    start_utc: u64,
+    rate_amount_units: u64,
+    rate_interval_in_seconds: u64,
+    allocation_assigned_units: u64,
+    cliff_vest_amount_units: u64,
+    cliff_vest_percent: u64,
+    fee_payed_by_treasurer: bool,
+    stream_pda_seed: Pubkey,
+    accrual_mode: u8,
+    cliff_utc: u64,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::CreateStreamPda {
+            _idl_file_version: IDL_FILE_VERSION,
+            name,
+            start_utc,
+            rate_amount_units,
+            rate_interval_in_seconds,
+            allocation_assigned_units,
+            cliff_vest_amount_units,
+            cliff_vest_percent,
+            fee_payed_by_treasurer,
+            _stream_pda_seed: stream_pda_seed,
+            accrual_mode,
+            cliff_utc,
+        },
+    )
+}
+
+/// Builds a `create_streams_batch` instruction. The stream PDAs are
+/// derived from the treasury and the entries seeds
+pub fn create_streams_batch(
+    accounts: msp::accounts::CreateStreamsBatchAccounts,
+    name: String,
+    start_utc: u64,
+    fee_payed_by_treasurer: bool,
+    accrual_mode: u8,
+    cliff_utc: u64,
+    entries: Vec<StreamBatchEntry>,
+) -> Instruction {
+    let treasury = accounts.treasury;
+    let streams: Vec<AccountMeta> = entries
+        .iter()
+        .map(|entry| {
+            AccountMeta::new(
+                find_stream_address(&treasury, &entry.stream_pda_seed).0,
+                false,
+            )
+        })
+        .collect();
+    let mut instruction = build(
+        accounts,
+        msp::instruction::CreateStreamsBatch {
+            _idl_file_version: IDL_FILE_VERSION,
+            name,
+            start_utc,
+            fee_payed_by_treasurer,
+            accrual_mode,
+            cliff_utc,
+            entries,
+        },
+    );
+    instruction.accounts.extend(streams);
+    instruction
+}
+
+/// Builds a `create_stream_with_schedule` instruction
+pub fn create_stream_with_schedule(
+    accounts: msp::accounts::CreateStreamWithScheduleAccounts,
+    name: String,
+    allocation_assigned_units: u64,
+    fee_payed_by_treasurer: bool,
+    points: Vec<SchedulePoint>,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::CreateStreamWithSchedule {
+            _idl_file_version: IDL_FILE_VERSION,
+            name,
+            allocation_assigned_units,
+            fee_payed_by_treasurer,
+            points,
+        },
+    )
+}
+
+/// Builds a `create_stream_template` instruction
+pub fn create_stream_template(
+    accounts: msp::accounts::CreateStreamTemplateAccounts,
+    start_utc: u64,
+    rate_interval_in_seconds: u64,
+    duration_number_of_units: u64,
+    cliff_vest_percent: u64,
+    fee_payed_by_treasurer: bool,
+    accrual_mode: u8,
+    cliff_utc: u64,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::CreateStreamTemplate {
+            _idl_file_version: IDL_FILE_VERSION,
+            start_utc,
+            rate_interval_in_seconds,
+            duration_number_of_units,
+            cliff_vest_percent,
+            fee_payed_by_treasurer,
+            accrual_mode,
+            cliff_utc,
+        },
+    )
+}
+
+/// Builds a `modify_stream_template` instruction
+pub fn modify_stream_template(
+    accounts: msp::accounts::ModifyStreamTemplateAccounts,
+    start_utc: u64,
+    rate_interval_in_seconds: u64,
+    duration_number_of_units: u64,
+    cliff_vest_percent: u64,
+    fee_payed_by_treasurer: bool,
+    accrual_mode: u8,
+    cliff_utc: u64,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::ModifyStreamTemplate {
+            _idl_file_version: IDL_FILE_VERSION,
+            start_utc,
+            rate_interval_in_seconds,
+            duration_number_of_units,
+            cliff_vest_percent,
+            fee_payed_by_treasurer,
+            accrual_mode,
+            cliff_utc,
+        },
+    )
+}
+
+/// Builds a `create_treasury_and_template` instruction
+pub fn create_treasury_and_template(
+    accounts: msp::accounts::CreateTreasuryAndTemplateAccounts,
+    name: String,
+    treasury_type: u8,
+    auto_close: bool,
+    sol_fee_payed_by_treasury: bool,
+    category: Category,
+    sub_category: SubCategory,
+    start_utc: u64,
+    rate_interval_in_seconds: u64,
+    duration_number_of_units: u64,
+    cliff_vest_percent: u64,
+    fee_payed_by_treasurer: bool,
+    slot: u64,
+    accrual_mode: u8,
+    cliff_utc: u64,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::CreateTreasuryAndTemplate {
+            _idl_file_version: IDL_FILE_VERSION,
+            name,
+            treasury_type,
+            auto_close,
+            sol_fee_payed_by_treasury,
+            category,
+            sub_category,
+            start_utc,
+            rate_interval_in_seconds,
+            duration_number_of_units,
+            cliff_vest_percent,
+            fee_payed_by_treasurer,
+            slot,
+            accrual_mode,
+            cliff_utc,
+        },
+    )
+}
+
+/// Builds a `create_stream_with_template` instruction
+pub fn create_stream_with_template(
+    accounts: msp::accounts::CreateStreamWithTemplateAccounts,
+    name: String,
+    allocation_assigned_units: u64,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::CreateStreamWithTemplate {
+            _idl_file_version: IDL_FILE_VERSION,
+            name,
+            allocation_assigned_units,
+        },
+    )
+}
+
+/// Builds a `create_stream_pda_with_template` instruction
+pub fn create_stream_pda_with_template(
+    accounts: msp::accounts::CreateStreamPdaWithTemplateAccounts,
+    name: String,
+    allocation_assigned_units: u64,
+    stream_pda_seed: Pubkey,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::CreateStreamPdaWithTemplate {
+            _idl_file_version: IDL_FILE_VERSION,
+            name,
+            allocation_assigned_units,
+            _stream_pda_seed: stream_pda_seed,
+        },
+    )
+}
+
+/// Builds a `withdraw` instruction
+pub fn withdraw(accounts: msp::accounts::WithdrawAccounts, amount: u64) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::Withdraw {
+            _idl_file_version: IDL_FILE_VERSION,
+            amount,
+        },
+    )
+}
+
+/// Builds a `withdraw_to_destination` instruction
+pub fn withdraw_to_destination(
+    accounts: msp::accounts::WithdrawToDestinationAccounts,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::WithdrawToDestination {
+            _idl_file_version: IDL_FILE_VERSION,
+            amount,
+        },
+    )
+}
+
+/// Builds a `push_withdraw` instruction
+pub fn push_withdraw(accounts: msp::accounts::PushWithdrawAccounts) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::PushWithdraw {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds a `withdraw_many` instruction for the given streams. The treasury
+/// token accounts are derived from `associated_token`
+pub fn withdraw_many(
+    accounts: msp::accounts::WithdrawManyAccounts,
+    streams: &[WithdrawnStream],
+) -> Instruction {
+    let associated_token = accounts.associated_token;
+    let mut instruction = build(
+        accounts,
+        msp::instruction::WithdrawMany {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    );
+    for withdrawn_stream in streams {
+        instruction.accounts.extend([
+            AccountMeta::new(withdrawn_stream.treasury, false),
+            AccountMeta::new(
+                get_associated_token_address(&withdrawn_stream.treasury, &associated_token),
+                false,
+            ),
+            AccountMeta::new(withdrawn_stream.stream, false),
+        ]);
+        if withdrawn_stream.has_schedule {
+            let schedule = find_schedule_address(&withdrawn_stream.stream).0;
+            instruction.accounts.push(AccountMeta::new(schedule, false));
+        }
+    }
+    instruction
+}
+
+/// Builds a `pause_stream` instruction
+pub fn pause_stream(accounts: msp::accounts::PauseOrResumeStreamAccounts) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::PauseStream {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds a `resume_stream` instruction
+pub fn resume_stream(accounts: msp::accounts::PauseOrResumeStreamAccounts) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::ResumeStream {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds an `update_stream_rate` instruction
+pub fn update_stream_rate(
+    accounts: msp::accounts::UpdateStreamRateAccounts,
+    rate_amount_units: u64,
+    rate_interval_in_seconds: u64,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::UpdateStreamRate {
+            _idl_file_version: IDL_FILE_VERSION,
+            rate_amount_units,
+            rate_interval_in_seconds,
+        },
+    )
+}
+
+/// Builds a `propose_treasury_authority` instruction
+pub fn propose_treasury_authority(
+    accounts: msp::accounts::ProposeTreasuryAuthorityAccounts,
+    new_authority: Pubkey,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::ProposeTreasuryAuthority {
+            _idl_file_version: IDL_FILE_VERSION,
+            new_authority,
+        },
+    )
+}
+
+/// Builds an `accept_treasury_authority` instruction
+pub fn accept_treasury_authority(
+    accounts: msp::accounts::AcceptTreasuryAuthorityAccounts,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::AcceptTreasuryAuthority {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds a `refresh_treasury_data` instruction
+pub fn refresh_treasury_data(accounts: msp::accounts::RefreshTreasuryDataAccounts) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::RefreshTreasuryData {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds a `transfer_stream` instruction
+pub fn transfer_stream(
+    accounts: msp::accounts::TransferStreamAccounts,
+    new_beneficiary: Pubkey,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::TransferStream {
+            _idl_file_version: IDL_FILE_VERSION,
+            new_beneficiary,
+        },
+    )
+}
+
+/// Builds a `propose_stream_transfer` instruction
+pub fn propose_stream_transfer(
+    accounts: msp::accounts::TransferStreamAccounts,
+    new_beneficiary: Pubkey,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::ProposeStreamTransfer {
+            _idl_file_version: IDL_FILE_VERSION,
+            new_beneficiary,
+        },
+    )
+}
+
+/// Builds a `cancel_stream_transfer` instruction
+pub fn cancel_stream_transfer(
+    accounts: msp::accounts::CancelStreamTransferAccounts,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::CancelStreamTransfer {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds an `accept_stream_transfer` instruction
+pub fn accept_stream_transfer(
+    accounts: msp::accounts::AcceptStreamTransferAccounts,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::AcceptStreamTransfer {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds a `get_stream` instruction
+pub fn get_stream(accounts: msp::accounts::GetStreamAccounts) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::GetStream {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds an `add_funds` instruction
+pub fn add_funds(accounts: msp::accounts::AddFundsAccounts, amount: u64) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::AddFunds {
+            _idl_file_version: IDL_FILE_VERSION,
+            amount,
+        },
+    )
+}
+
+/// Builds an `allocate` instruction
+pub fn allocate(accounts: msp::accounts::AllocateAccounts, amount: u64) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::Allocate {
+            _idl_file_version: IDL_FILE_VERSION,
+            amount,
+        },
+    )
+}
+
+/// Builds an `allocate_to_all_streams` instruction for the given streams
+pub fn allocate_to_all_streams(
+    accounts: msp::accounts::AllocateToAllStreamsAccounts,
+    amount: u64,
+    split_mode: u8,
+    streams: &[AllocatedStream],
+) -> Instruction {
+    let mut instruction = build(
+        accounts,
+        msp::instruction::AllocateToAllStreams {
+            _idl_file_version: IDL_FILE_VERSION,
+            amount,
+            split_mode,
+        },
+    );
+    for allocated_stream in streams {
+        instruction
+            .accounts
+            .push(AccountMeta::new(allocated_stream.stream, false));
+        if allocated_stream.has_schedule {
+            let schedule = find_schedule_address(&allocated_stream.stream).0;
+            instruction.accounts.push(AccountMeta::new(schedule, false));
+        }
+    }
+    instruction
+}
+
+/// Builds a `close_stream` instruction
+pub fn close_stream(accounts: msp::accounts::CloseStreamAccounts) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::CloseStream {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds a `renounce_stream` instruction
+pub fn renounce_stream(accounts: msp::accounts::RenounceStreamAccounts) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::RenounceStream {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds a `close_treasury` instruction
+pub fn close_treasury(accounts: msp::accounts::CloseTreasuryAccounts) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::CloseTreasury {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds a `close_treasury_and_streams` instruction for the given streams.
+/// The beneficiary token accounts are derived from `associated_token`
+pub fn close_treasury_and_streams(
+    accounts: msp::accounts::CloseTreasuryAndStreamsAccounts,
+    streams: &[ClosedStream],
+) -> Instruction {
+    let associated_token = accounts.associated_token;
+    let mut instruction = build(
+        accounts,
+        msp::instruction::CloseTreasuryAndStreams {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    );
+    for closed_stream in streams {
+        instruction.accounts.extend([
+            AccountMeta::new(closed_stream.stream, false),
+            AccountMeta::new(
+                get_associated_token_address(&closed_stream.beneficiary, &associated_token),
+                false,
+            ),
+        ]);
+        if closed_stream.has_schedule {
+            let schedule = find_schedule_address(&closed_stream.stream).0;
+            instruction.accounts.push(AccountMeta::new(schedule, false));
+        }
+    }
+    instruction
+}
+
+/// Builds a `migrate_stream` instruction
+pub fn migrate_stream(accounts: msp::accounts::MigrateStreamAccounts) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::MigrateStream {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds a `migrate_treasury` instruction
+pub fn migrate_treasury(accounts: msp::accounts::MigrateTreasuryAccounts) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::MigrateTreasury {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds a `create_native_treasury` instruction. The optional `referrer`
+/// wallet gets a share of the fees charged for the treasury
+pub fn create_native_treasury(
+    accounts: msp::accounts::CreateNativeTreasuryAccounts,
+    slot: u64,
+    name: String,
+    treasury_type: u8,
+    auto_close: bool,
+    category: Category,
+    sub_category: SubCategory,
+    referrer: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::CreateNativeTreasury {
+            _idl_file_version: IDL_FILE_VERSION,
+            slot,
+            name,
+            treasury_type,
+            auto_close,
+            category,
+            sub_category,
+            referrer,
+        },
+    )
+}
+
+/// Builds an `add_funds_native` instruction
+pub fn add_funds_native(
+    accounts: msp::accounts::AddFundsNativeAccounts,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::AddFundsNative {
+            _idl_file_version: IDL_FILE_VERSION,
+            amount,
+        },
+    )
+}
+
+/// Builds a `create_stream_native` instruction
+pub fn create_stream_native(
+    accounts: msp::accounts::CreateStreamNativeAccounts,
+    name: String,
+    start_utc: u64,
+    rate_amount_units: u64,
+    rate_interval_in_seconds: u64,
+    allocation_assigned_units: u64,
+    cliff_vest_amount_units: u64,
+    fee_payed_by_treasurer: bool,
+    accrual_mode: u8,
+    cliff_utc: u64,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::CreateStreamNative {
+            _idl_file_version: IDL_FILE_VERSION,
+            name,
+            start_utc,
+            rate_amount_units,
+            rate_interval_in_seconds,
+            allocation_assigned_units,
+            cliff_vest_amount_units,
+            fee_payed_by_treasurer,
+            accrual_mode,
+            cliff_utc,
+        },
+    )
+}
+
+/// Builds a `withdraw_native` instruction
+pub fn withdraw_native(
+    accounts: msp::accounts::WithdrawNativeAccounts,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::WithdrawNative {
+            _idl_file_version: IDL_FILE_VERSION,
+            amount,
+        },
+    )
+}
+
+/// Builds a `close_stream_native` instruction
+pub fn close_stream_native(accounts: msp::accounts::CloseStreamNativeAccounts) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::CloseStreamNative {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds a `treasury_withdraw_native` instruction
+pub fn treasury_withdraw_native(
+    accounts: msp::accounts::TreasuryWithdrawNativeAccounts,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::TreasuryWithdrawNative {
+            _idl_file_version: IDL_FILE_VERSION,
+            amount,
+        },
+    )
+}
+
+/// Builds a `close_treasury_native` instruction
+pub fn close_treasury_native(accounts: msp::accounts::CloseTreasuryNativeAccounts) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::CloseTreasuryNative {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds an `init_config` instruction
+pub fn init_config(
+    accounts: msp::accounts::InitConfigAccounts,
+    admin: Pubkey,
+    fee_treasury: Pubkey,
+    fees: FeeSchedule,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::InitConfig {
+            _idl_file_version: IDL_FILE_VERSION,
+            admin,
+            fee_treasury,
+            fees,
+        },
+    )
+}
+
+/// Builds an `update_config` instruction
+pub fn update_config(
+    accounts: msp::accounts::UpdateConfigAccounts,
+    admin: Pubkey,
+    fee_treasury: Pubkey,
+    fees: FeeSchedule,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::UpdateConfig {
+            _idl_file_version: IDL_FILE_VERSION,
+            admin,
+            fee_treasury,
+            fees,
+        },
+    )
+}
+
+/// Builds a `set_fee_override` instruction
+pub fn set_fee_override(
+    accounts: msp::accounts::SetFeeOverrideAccounts,
+    subject: Pubkey,
+    tier: u8,
+    fees: FeeSchedule,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::SetFeeOverride {
+            _idl_file_version: IDL_FILE_VERSION,
+            subject,
+            tier,
+            fees,
+        },
+    )
+}
+
+/// Builds a `remove_fee_override` instruction
+pub fn remove_fee_override(accounts: msp::accounts::RemoveFeeOverrideAccounts) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::RemoveFeeOverride {
+            _idl_file_version: IDL_FILE_VERSION,
+        },
+    )
+}
+
+/// Builds a `treasury_withdraw` instruction
+pub fn treasury_withdraw(
+    accounts: msp::accounts::TreasuryWithdrawAccounts,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts,
+        msp::instruction::TreasuryWithdraw {
+            _idl_file_version: IDL_FILE_VERSION,
+            amount,
+        },
+    )
+}
@@ -0,0 +1,12 @@
+//! Rust client of the Money Streaming Program: instruction builders, PDA
+//! derivation and account fetch/decode helpers.
+//!
+//! The `msp` crate is re-exported for the account structs generated by
+//! Anchor (`msp::accounts`) and the program types.
+
+pub mod error;
+pub mod instruction;
+pub mod pda;
+pub mod state;
+
+pub use msp;
@@ -0,0 +1,37 @@
+//! Derivation of the program PDAs
+
+use anchor_lang::prelude::*;
+
+/// Finds the treasury PDA of `treasurer` for the given slot
+pub fn find_treasury_address(treasurer: &Pubkey, slot: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[treasurer.as_ref(), &slot.to_le_bytes()], &msp::ID)
+}
+
+/// Finds the stream template PDA of `treasury`
+pub fn find_template_address(treasury: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"template", treasury.as_ref()], &msp::ID)
+}
+
+/// Finds the PDA of a stream created with `stream_pda_seed` by the
+/// `*_pda*` and batch instructions
+pub fn find_stream_address(treasury: &Pubkey, stream_pda_seed: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"stream", treasury.as_ref(), stream_pda_seed.as_ref()],
+        &msp::ID,
+    )
+}
+
+/// Finds the `StreamSchedule` PDA of `stream`
+pub fn find_schedule_address(stream: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"schedule", stream.as_ref()], &msp::ID)
+}
+
+/// Finds the config PDA
+pub fn find_config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &msp::ID)
+}
+
+/// Finds the fee override PDA of a treasury or treasury authority
+pub fn find_fee_override_address(subject: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"fee_override", subject.as_ref()], &msp::ID)
+}
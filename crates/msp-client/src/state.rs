@@ -0,0 +1,86 @@
+//! Fetching and decoding of the program accounts
+
+use anchor_lang::prelude::*;
+use anchor_lang::AccountDeserialize;
+use msp::config::Config;
+use msp::schedule::StreamSchedule;
+use msp::stream::Stream;
+use msp::template::StreamTemplate;
+use msp::tlv::{get_data_extension, AccountExtension};
+use msp::treasury::Treasury;
+use solana_client::rpc_client::RpcClient;
+
+use crate::error::{ClientError, Result};
+use crate::pda::find_config_address;
+
+/// Decodes a stream account (v2 and v3 layouts)
+pub fn decode_stream(data: &[u8]) -> Result<Stream> {
+    Ok(Stream::try_deserialize(&mut &data[..])?)
+}
+
+/// Decodes a treasury account (v2 and v3 layouts). The extensions are read
+/// with `decode_treasury_extension`
+pub fn decode_treasury(data: &[u8]) -> Result<Treasury> {
+    Ok(Treasury::try_deserialize(&mut &data[..])?)
+}
+
+/// Gets the extension of type `E` attached to a treasury account
+pub fn decode_treasury_extension<E: AccountExtension>(data: &[u8]) -> Result<Option<E>> {
+    Ok(get_data_extension::<Treasury, E>(data)?)
+}
+
+/// Decodes a stream template account
+pub fn decode_stream_template(data: &[u8]) -> Result<StreamTemplate> {
+    Ok(StreamTemplate::try_deserialize(&mut &data[..])?)
+}
+
+/// Decodes a stream schedule account
+pub fn decode_stream_schedule(data: &[u8]) -> Result<StreamSchedule> {
+    Ok(StreamSchedule::try_deserialize(&mut &data[..])?)
+}
+
+/// Decodes the config account
+pub fn decode_config(data: &[u8]) -> Result<Config> {
+    Ok(Config::try_deserialize(&mut &data[..])?)
+}
+
+fn fetch<T>(rpc: &RpcClient, address: &Pubkey, decode: fn(&[u8]) -> Result<T>) -> Result<T> {
+    let account = rpc.get_account(address)?;
+    if account.owner != msp::ID {
+        return Err(ClientError::InvalidOwner(account.owner));
+    }
+    decode(&account.data)
+}
+
+/// Fetches and decodes a stream account
+pub fn fetch_stream(rpc: &RpcClient, address: &Pubkey) -> Result<Stream> {
+    fetch(rpc, address, decode_stream)
+}
+
+/// Fetches and decodes a treasury account
+pub fn fetch_treasury(rpc: &RpcClient, address: &Pubkey) -> Result<Treasury> {
+    fetch(rpc, address, decode_treasury)
+}
+
+/// Fetches and decodes a stream template account
+pub fn fetch_stream_template(rpc: &RpcClient, address: &Pubkey) -> Result<StreamTemplate> {
+    fetch(rpc, address, decode_stream_template)
+}
+
+/// Fetches and decodes a stream schedule account
+pub fn fetch_stream_schedule(rpc: &RpcClient, address: &Pubkey) -> Result<StreamSchedule> {
+    fetch(rpc, address, decode_stream_schedule)
+}
+
+/// Fetches and decodes the config account. Until the config is initialized,
+/// the program uses the default config (see `Config::load`)
+pub fn fetch_config(rpc: &RpcClient) -> Result<Config> {
+    let address = find_config_address().0;
+    match rpc
+        .get_account_with_commitment(&address, rpc.commitment())?
+        .value
+    {
+        Some(account) if account.owner == msp::ID => decode_config(&account.data),
+        _ => Ok(Config::default()),
+    }
+}
@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash::hash, system_program, sysvar};
+use anchor_lang::AnchorSerialize;
+use anchor_spl::{associated_token, token};
+use msp::categories::{Category, SubCategory};
+use msp_client::instruction::{self, ClosedStream};
+use msp_client::pda::*;
+
+#[test]
+fn create_treasury_instruction_test() {
+    let treasurer = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let (treasury, _) = find_treasury_address(&treasurer, 7);
+    let treasury_token = associated_token::get_associated_token_address(&treasury, &mint);
+    let referrer = Pubkey::new_unique();
+
+    let ix = instruction::create_treasury(
+        msp::accounts::CreateTreasuryAccounts {
+            payer: treasurer,
+            treasurer,
+            treasury,
+            treasury_token,
+            associated_token: mint,
+            config: find_config_address().0,
+            fee_treasury: msp::instructions::fee_treasury::ID,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        7,
+        "payroll".to_string(),
+        0,
+        false,
+        false,
+        Category::Default,
+        SubCategory::Default,
+        Some(referrer),
+    );
+
+    assert_eq!(ix.program_id, msp::ID);
+    assert_eq!(ix.accounts.len(), 11);
+    assert_eq!(ix.accounts[2].pubkey, treasury);
+    assert!(ix.accounts[2].is_writable);
+    assert_eq!(
+        &ix.data[..8],
+        &hash(b"global:create_treasury").to_bytes()[..8]
+    );
+    // the IDL file version goes right after the discriminator
+    assert_eq!(ix.data[8], msp::constants::IDL_FILE_VERSION);
+    assert_eq!(&ix.data[9..17], &7u64.to_le_bytes());
+    assert_eq!(&ix.data[17..], {
+        let mut rest = "payroll".to_string().try_to_vec().unwrap();
+        rest.extend([0, 0, 0]);
+        rest.extend(Category::Default.try_to_vec().unwrap());
+        rest.extend(SubCategory::Default.try_to_vec().unwrap());
+        rest.extend(Some(referrer).try_to_vec().unwrap());
+        rest
+    });
+}
+
+#[test]
+fn close_treasury_and_streams_remaining_accounts_test() {
+    let treasurer = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let (treasury, _) = find_treasury_address(&treasurer, 0);
+    let streams = [
+        ClosedStream {
+            stream: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            has_schedule: false,
+        },
+        ClosedStream {
+            stream: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            has_schedule: true,
+        },
+    ];
+    let (referrer, referrer_token) =
+        instruction::fee_referrer_accounts(None, &msp::instructions::fee_treasury::ID, &mint);
+
+    let ix = instruction::close_treasury_and_streams(
+        msp::accounts::CloseTreasuryAndStreamsAccounts {
+            payer: treasurer,
+            treasurer,
+            destination_authority: treasurer,
+            destination_token_account: Pubkey::new_unique(),
+            associated_token: mint,
+            treasury,
+            treasury_token: Pubkey::new_unique(),
+            config: find_config_address().0,
+            fee_override: system_program::ID,
+            referrer,
+            referrer_token,
+            fee_treasury: msp::instructions::fee_treasury::ID,
+            fee_treasury_token: Pubkey::new_unique(),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        &streams,
+    );
+
+    let remaining = &ix.accounts[ix.accounts.len() - 5..];
+    assert_eq!(remaining[0].pubkey, streams[0].stream);
+    assert_eq!(
+        remaining[1].pubkey,
+        associated_token::get_associated_token_address(&streams[0].beneficiary, &mint)
+    );
+    assert_eq!(remaining[2].pubkey, streams[1].stream);
+    assert_eq!(
+        remaining[4].pubkey,
+        find_schedule_address(&streams[1].stream).0
+    );
+    assert!(remaining.iter().all(|account| account.is_writable));
+}
+
+#[test]
+fn fee_referrer_accounts_test() {
+    let referrer = Pubkey::new_unique();
+    let fee_treasury = msp::instructions::fee_treasury::ID;
+    let mint = Pubkey::new_unique();
+
+    assert_eq!(
+        instruction::fee_referrer_accounts(Some(&referrer), &fee_treasury, &mint),
+        (
+            referrer,
+            associated_token::get_associated_token_address(&referrer, &mint)
+        )
+    );
+    // the fee treasury accounts when the treasury has no referrer
+    assert_eq!(
+        instruction::fee_referrer_accounts(None, &fee_treasury, &mint),
+        (
+            fee_treasury,
+            associated_token::get_associated_token_address(&fee_treasury, &mint)
+        )
+    );
+}
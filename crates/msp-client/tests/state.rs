@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_lang::{AccountDeserialize, AccountSerialize};
+use msp::constants::*;
+use msp::stream::Stream;
+use msp::tlv::set_extension;
+use msp::treasury::{Treasury, TreasuryReferrer};
+use msp_client::state::*;
+
+fn empty_stream(version: u8) -> Stream {
+    let mut stream = Stream::deserialize(&mut &[0u8; STREAM_V3_SIZE][..]).unwrap();
+    stream.version = version;
+    stream.initialized = true;
+    stream.beneficiary_address = Pubkey::new_unique();
+    stream.allocation_assigned_units = 1_000_000;
+    stream.rate_amount_units = 100;
+    stream.rate_interval_in_seconds = 60;
+    stream.start_utc = 1_700_000_000;
+    stream
+}
+
+fn empty_treasury(version: u8) -> Treasury {
+    let mut treasury = Treasury::deserialize(&mut &[0u8; TREASURY_V3_SIZE][..]).unwrap();
+    treasury.version = version;
+    treasury.initialized = true;
+    treasury.treasurer_address = Pubkey::new_unique();
+    treasury.allocation_assigned_units = 500;
+    treasury
+}
+
+fn serialize<T: AccountSerialize>(account: &T, size: usize) -> Vec<u8> {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    data.resize(size, 0);
+    data
+}
+
+#[test]
+fn decode_v2_stream_test() {
+    let mut data = serialize(&empty_stream(2), STREAM_V2_SIZE);
+
+    // a v2 stream with a 10% cliff and its start in milliseconds
+    data[98..106].copy_from_slice(&0u64.to_le_bytes()); // cliff_vest_amount_units
+    data[106..114].copy_from_slice(&100_000u64.to_le_bytes()); // cliff_vest_percent
+    data[90..98].copy_from_slice(&1_700_000_000_000u64.to_le_bytes()); // start_utc
+    data[323..331].copy_from_slice(&0u64.to_le_bytes()); // start_utc_in_seconds
+    data[218..226].copy_from_slice(&7u64.to_le_bytes()); // allocation_reserved_units
+
+    let stream = decode_stream(&data).unwrap();
+    assert_eq!(stream.version, 2);
+    assert_eq!(stream.cliff_vest_amount_units, 100_000);
+    assert_eq!(stream.start_utc, 1_700_000_000);
+    assert_eq!(stream.rate_amount_units, 100);
+    assert_eq!(stream.allocation_assigned_units, 1_000_000);
+
+    // written back with the v2 layout and the replaced deprecated fields
+    let mut written = Vec::new();
+    stream.try_serialize(&mut written).unwrap();
+    assert_eq!(written.len(), STREAM_V2_EXTENSIONS_OFFSET);
+    assert_eq!(&written[98..106], &100_000u64.to_le_bytes());
+    assert_eq!(&written[106..114], &0u64.to_le_bytes());
+    assert_eq!(&written[90..98], &1_700_000_000u64.to_le_bytes());
+    assert_eq!(&written[323..331], &1_700_000_000u64.to_le_bytes());
+    // and the ones nothing replaces as they were read
+    assert_eq!(&written[218..226], &7u64.to_le_bytes());
+}
+
+#[test]
+fn decode_v3_stream_test() {
+    let stream = empty_stream(3);
+    let data = serialize(&stream, STREAM_V3_SIZE);
+    let mut written = Vec::new();
+    stream.try_serialize(&mut written).unwrap();
+    assert_eq!(written.len(), STREAM_V3_SIZE);
+
+    let decoded = decode_stream(&data).unwrap();
+    assert_eq!(decoded.version, 3);
+    assert_eq!(decoded.beneficiary_address, stream.beneficiary_address);
+    assert_eq!(decoded.start_utc, stream.start_utc);
+    assert_eq!(decoded.allocation_assigned_units, 1_000_000);
+
+    // a treasury is not a stream
+    let treasury_data = serialize(&empty_treasury(3), TREASURY_V3_SIZE);
+    assert!(Stream::try_deserialize(&mut &treasury_data[..]).is_err());
+}
+
+#[test]
+fn decode_treasury_extension_test() {
+    let referrer = TreasuryReferrer {
+        referrer_address: Pubkey::new_unique(),
+    };
+
+    // v2 treasuries keep the extensions after the v2 fields (the account is
+    // reallocated when the padding is not enough)
+    let mut v2_data = serialize(&empty_treasury(2), TREASURY_V2_EXTENSIONS_OFFSET + 36);
+    set_extension(&mut v2_data[TREASURY_V2_EXTENSIONS_OFFSET..], &referrer).unwrap();
+    let treasury = decode_treasury(&v2_data).unwrap();
+    assert_eq!(treasury.version, 2);
+    assert_eq!(treasury.allocation_assigned_units, 500);
+    let extension = decode_treasury_extension::<TreasuryReferrer>(&v2_data).unwrap();
+    assert_eq!(extension.unwrap().referrer_address, referrer.referrer_address);
+
+    // v3 treasuries keep them right after the v3 fields
+    let mut v3_data = serialize(&empty_treasury(3), TREASURY_V3_SIZE + 36);
+    set_extension(&mut v3_data[TREASURY_V3_SIZE..], &referrer).unwrap();
+    let treasury = decode_treasury(&v3_data).unwrap();
+    assert_eq!(treasury.version, 3);
+    assert_eq!(treasury.treasurer_address, treasury.authority());
+    let extension = decode_treasury_extension::<TreasuryReferrer>(&v3_data).unwrap();
+    assert_eq!(extension.unwrap().referrer_address, referrer.referrer_address);
+
+    // without extensions
+    let v3_data = serialize(&empty_treasury(3), TREASURY_V3_SIZE);
+    assert!(decode_treasury_extension::<TreasuryReferrer>(&v3_data)
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,33 @@
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, DecodeError>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodeError {
+    /// The event data is not valid base64
+    InvalidBase64,
+    /// The event data is shorter than the discriminator
+    TooShort,
+    /// The discriminator doesn't match any event of the program
+    UnknownDiscriminator([u8; 8]),
+    /// The event fields can't be deserialized
+    InvalidData(&'static str),
+    /// The event marker is not followed by the event data
+    MissingData,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidBase64 => write!(f, "Invalid base64 event data"),
+            DecodeError::TooShort => write!(f, "Event data too short"),
+            DecodeError::UnknownDiscriminator(discriminator) => {
+                write!(f, "Unknown event discriminator: {:?}", discriminator)
+            }
+            DecodeError::InvalidData(name) => write!(f, "Invalid {} data", name),
+            DecodeError::MissingData => write!(f, "Event marker without event data"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
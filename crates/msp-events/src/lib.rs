@@ -0,0 +1,98 @@
+//! Decoder of the events logged by the Money Streaming Program.
+//!
+//! Every event goes through `mean_emit!`, which logs
+//! `msp::constants::EVENT_LOG_MARKER` followed by the Anchor event data
+//! (the event discriminator and the borsh serialized event). `parse_logs`
+//! turns the log messages of a transaction into typed `MspEvent`s.
+
+pub mod error;
+pub mod logs;
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use msp::events::*;
+
+pub use crate::error::{DecodeError, Result};
+pub use crate::logs::{parse_logs, LogEvents};
+pub use msp::constants::EVENT_LOG_MARKER;
+
+macro_rules! msp_events {
+    ($($variant:ident($event:ident),)*) => {
+        /// An event of the program
+        #[allow(clippy::large_enum_variant)] // `StreamEvent` is only logged by `get_stream`
+        pub enum MspEvent {
+            $($variant($event),)*
+        }
+
+        impl MspEvent {
+            /// Decodes the event data (the discriminator followed by the
+            /// serialized event). Trailing bytes are ignored so the events
+            /// logged after fields are appended can still be decoded
+            pub fn decode(data: &[u8]) -> Result<MspEvent> {
+                if data.len() < 8 {
+                    return Err(DecodeError::TooShort);
+                }
+                let (discriminator, mut fields) = data.split_at(8);
+                $(
+                    if discriminator == $event::discriminator() {
+                        return $event::deserialize(&mut fields)
+                            .map(MspEvent::$variant)
+                            .map_err(|_| DecodeError::InvalidData(stringify!($event)));
+                    }
+                )*
+                let mut unknown = [0u8; 8];
+                unknown.copy_from_slice(discriminator);
+                Err(DecodeError::UnknownDiscriminator(unknown))
+            }
+
+            /// Gets the name of the event struct
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(MspEvent::$variant(_) => stringify!($event),)*
+                }
+            }
+
+            pub fn discriminator(&self) -> [u8; 8] {
+                match self {
+                    $(MspEvent::$variant(_) => $event::discriminator(),)*
+                }
+            }
+        }
+
+        /// Gets the name and discriminator of every event struct
+        pub fn event_discriminators() -> Vec<(&'static str, [u8; 8])> {
+            vec![$((stringify!($event), $event::discriminator()),)*]
+        }
+    };
+}
+
+msp_events! {
+    Stream(StreamEvent),
+    CreateTreasury(CreateTreasuryEvent),
+    CreateStream(CreateStreamEvent),
+    StreamWithdraw(StreamWithdrawEvent),
+    StreamPushWithdraw(StreamPushWithdrawEvent),
+    StreamPause(StreamPauseEvent),
+    StreamResume(StreamResumeEvent),
+    TreasuryAuthorityPropose(TreasuryAuthorityProposeEvent),
+    TreasuryAuthorityTransfer(TreasuryAuthorityTransferEvent),
+    TreasuryRefresh(TreasuryRefreshEvent),
+    StreamTransfer(StreamTransferEvent),
+    StreamTransferPropose(StreamTransferProposeEvent),
+    StreamTransferCancel(StreamTransferCancelEvent),
+    TreasuryAddFunds(TreasuryAddFundsEvent),
+    StreamAllocate(StreamAllocateEvent),
+    StreamRateUpdate(StreamRateUpdateEvent),
+    CloseStream(CloseStreamEvent),
+    CloseTreasury(CloseTreasuryEvent),
+    TreasuryWithdraw(TreasuryWithdrawEvent),
+    ConfigUpdate(ConfigUpdateEvent),
+    FeeOverrideUpdate(FeeOverrideUpdateEvent),
+}
+
+/// Gets the name of the event struct with the given discriminator
+pub fn event_name(discriminator: &[u8; 8]) -> Option<&'static str> {
+    event_discriminators()
+        .into_iter()
+        .find(|(_, event_discriminator)| event_discriminator == discriminator)
+        .map(|(name, _)| name)
+}
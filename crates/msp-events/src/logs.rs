@@ -0,0 +1,133 @@
+//! Extraction of the events from the log messages of a transaction
+
+use msp::constants::EVENT_LOG_MARKER;
+
+use crate::error::DecodeError;
+use crate::MspEvent;
+
+/// Logged by the runtime in place of the messages past the log limit
+pub const LOG_TRUNCATED: &str = "Log truncated";
+
+const PROGRAM_LOG: &str = "Program log: ";
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// The events found in the log messages of a transaction
+#[derive(Default)]
+pub struct LogEvents {
+    /// The decoded events, in the order they were logged
+    pub events: Vec<MspEvent>,
+    /// The events that couldn't be decoded
+    pub errors: Vec<DecodeError>,
+    /// The logs were truncated, so events past the log limit are missing
+    pub truncated: bool,
+}
+
+enum LogLine<'a> {
+    Invoke(&'a str),
+    Exit,
+    Log(&'a str),
+    Data(&'a str),
+    Truncated,
+    Other,
+}
+
+impl<'a> LogLine<'a> {
+    fn parse(line: &'a str) -> LogLine<'a> {
+        if line == LOG_TRUNCATED {
+            return LogLine::Truncated;
+        }
+        if let Some(message) = line.strip_prefix(PROGRAM_LOG) {
+            return LogLine::Log(message);
+        }
+        if let Some(data) = line.strip_prefix(PROGRAM_DATA) {
+            return LogLine::Data(data);
+        }
+        let rest = match line.strip_prefix("Program ") {
+            Some(rest) => rest,
+            None => return LogLine::Other,
+        };
+        match rest.split_once(' ') {
+            Some((program_id, status)) if status.starts_with("invoke [") => {
+                LogLine::Invoke(program_id)
+            }
+            Some((_, status)) if status == "success" || status.starts_with("failed") => {
+                LogLine::Exit
+            }
+            _ => LogLine::Other,
+        }
+    }
+}
+
+/// Decodes the events logged by `mean_emit!`. Only the messages logged by
+/// the program are considered (the marker logged by another program is
+/// ignored), unless the logs don't include the program invocations.
+///
+/// Anchor logs the event data as `Program data: <base64>` (older program
+/// versions logged it as `Program log: <base64>`). A `StreamEvent` (logged
+/// by `get_stream` without the marker) can be decoded with
+/// `MspEvent::decode`.
+///
+/// Note the logs of a failed transaction include the events logged before
+/// the failure, although their changes were reverted.
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> LogEvents {
+    let program_id = msp::ID.to_string();
+    let mut result = LogEvents::default();
+    let mut invocations: Vec<&str> = Vec::new();
+    let mut expecting_event = false;
+
+    for line in logs {
+        let line = LogLine::parse(line.as_ref());
+        if let LogLine::Truncated = line {
+            result.truncated = true;
+            return result;
+        }
+        if let LogLine::Invoke(invoked) = line {
+            invocations.push(invoked);
+            continue;
+        }
+        if let LogLine::Exit = line {
+            if expecting_event {
+                result.errors.push(DecodeError::MissingData);
+                expecting_event = false;
+            }
+            invocations.pop();
+            continue;
+        }
+        if matches!(invocations.last(), Some(invoked) if *invoked != program_id) {
+            continue;
+        }
+
+        let payload = match line {
+            LogLine::Log(EVENT_LOG_MARKER) => {
+                if expecting_event {
+                    result.errors.push(DecodeError::MissingData);
+                }
+                expecting_event = true;
+                continue;
+            }
+            LogLine::Data(data) | LogLine::Log(data) if expecting_event => data,
+            _ => {
+                if expecting_event {
+                    result.errors.push(DecodeError::MissingData);
+                    expecting_event = false;
+                }
+                continue;
+            }
+        };
+        expecting_event = false;
+
+        match base64::decode(payload)
+            .map_err(|_| DecodeError::InvalidBase64)
+            .and_then(|data| MspEvent::decode(&data))
+        {
+            Ok(event) => result.events.push(event),
+            Err(error) => result.errors.push(error),
+        }
+    }
+
+    // The logs stopped between the marker and the event data
+    if expecting_event {
+        result.truncated = true;
+    }
+    result
+}
@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Event;
+use msp::constants::EVENT_LOG_MARKER;
+use msp::events::*;
+use msp_events::*;
+
+fn pause_event(timestamp: u64) -> StreamPauseEvent {
+    StreamPauseEvent {
+        timestamp,
+        sol_fee_charged: 0,
+        token_fee_charged: 0,
+        stream_last_manual_stop_withdrawable_after: 100,
+        stream: Pubkey::new_unique(),
+        treasury: Pubkey::new_unique(),
+    }
+}
+
+fn refresh_event(treasury_balance_after: u64) -> TreasuryRefreshEvent {
+    TreasuryRefreshEvent {
+        timestamp: 1_000,
+        sol_fee_charged: 0,
+        token_fee_charged: 0,
+        treasury_balance_after,
+        treasury: Pubkey::new_unique(),
+    }
+}
+
+fn data_line(event: &impl Event) -> String {
+    format!("Program data: {}", base64::encode(event.data()))
+}
+
+fn marker_line() -> String {
+    format!("Program log: {}", EVENT_LOG_MARKER)
+}
+
+#[test]
+fn parse_logs_test() {
+    let program = msp::ID.to_string();
+    let other_program = Pubkey::new_unique().to_string();
+    let logs = vec![
+        format!("Program {} invoke [1]", program),
+        "Program log: Instruction: PauseStream".to_string(),
+        marker_line(),
+        data_line(&pause_event(1_000)),
+        // another program logging the marker is ignored
+        format!("Program {} invoke [2]", other_program),
+        marker_line(),
+        data_line(&refresh_event(5)),
+        format!("Program {} success", other_program),
+        marker_line(),
+        data_line(&refresh_event(10)),
+        format!("Program {} consumed 20000 of 200000 compute units", program),
+        format!("Program {} success", program),
+    ];
+
+    let result = parse_logs(&logs);
+    assert!(!result.truncated);
+    assert!(result.errors.is_empty());
+    assert_eq!(result.events.len(), 2);
+    match &result.events[0] {
+        MspEvent::StreamPause(event) => assert_eq!(event.timestamp, 1_000),
+        event => panic!("unexpected {}", event.name()),
+    }
+    match &result.events[1] {
+        MspEvent::TreasuryRefresh(event) => assert_eq!(event.treasury_balance_after, 10),
+        event => panic!("unexpected {}", event.name()),
+    }
+}
+
+#[test]
+fn parse_truncated_logs_test() {
+    let logs = vec![
+        marker_line(),
+        data_line(&pause_event(1_000)),
+        marker_line(),
+        "Log truncated".to_string(),
+    ];
+    let result = parse_logs(&logs);
+    assert!(result.truncated);
+    assert!(result.errors.is_empty());
+    assert_eq!(result.events.len(), 1);
+
+    // the logs end between the marker and the event data
+    let logs = vec![marker_line()];
+    let result = parse_logs(&logs);
+    assert!(result.truncated);
+    assert!(result.events.is_empty());
+}
+
+#[test]
+fn parse_invalid_logs_test() {
+    let mut unknown = vec![0u8; 8];
+    unknown.extend_from_slice(&[1, 2, 3]);
+    let logs = vec![
+        marker_line(),
+        "Program data: not base64!".to_string(),
+        marker_line(),
+        format!("Program data: {}", base64::encode(&unknown)),
+        marker_line(),
+        format!("Program data: {}", base64::encode(&pause_event(1).data()[..12])),
+        // a marker without event data
+        marker_line(),
+        // the legacy `Program log:` event data
+        marker_line(),
+        format!("Program log: {}", base64::encode(pause_event(7).data())),
+    ];
+    let result = parse_logs(&logs);
+    assert_eq!(
+        result.errors,
+        vec![
+            DecodeError::InvalidBase64,
+            DecodeError::UnknownDiscriminator([0; 8]),
+            DecodeError::InvalidData("StreamPauseEvent"),
+            DecodeError::MissingData,
+        ]
+    );
+    assert_eq!(result.events.len(), 1);
+    assert_eq!(result.events[0].name(), "StreamPauseEvent");
+}
+
+#[test]
+fn event_discriminators_test() {
+    let discriminators = event_discriminators();
+    for (i, (name, discriminator)) in discriminators.iter().enumerate() {
+        assert_eq!(event_name(discriminator), Some(*name));
+        assert!(discriminators[i + 1..]
+            .iter()
+            .all(|(_, other)| other != discriminator));
+    }
+
+    // every event struct of the program is covered
+    let events = include_str!("../../../programs/msp/src/events.rs");
+    let names: Vec<&str> = events
+        .lines()
+        .filter_map(|line| line.strip_prefix("pub struct "))
+        .map(|line| line.trim_end_matches(" {"))
+        .collect();
+    assert_eq!(names.len(), discriminators.len());
+    for name in names {
+        assert!(
+            discriminators.iter().any(|(event, _)| *event == name),
+            "{} is not decoded",
+            name
+        );
+    }
+}
@@ -0,0 +1,426 @@
+//! SQLite storage of the event history and of the reconstructed treasury
+//! accounting
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use msp_events::parse_logs;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::ledger::{event_effect, Change};
+use crate::source::Transaction;
+use crate::Result;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT PRIMARY KEY,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    truncated INTEGER NOT NULL,
+    decode_errors INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS treasury_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    treasury TEXT NOT NULL,
+    stream TEXT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    event_index INTEGER NOT NULL,
+    event TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    amount INTEGER NOT NULL,
+    token_fee_charged INTEGER NOT NULL,
+    sol_fee_charged INTEGER NOT NULL,
+    allocation_after INTEGER,
+    balance_after INTEGER
+);
+CREATE INDEX IF NOT EXISTS treasury_history_treasury
+    ON treasury_history (treasury, timestamp);
+CREATE TABLE IF NOT EXISTS stream_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    stream TEXT NOT NULL,
+    treasury TEXT NOT NULL,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    event_index INTEGER NOT NULL,
+    event TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    amount INTEGER NOT NULL,
+    token_fee_charged INTEGER NOT NULL,
+    allocation_after INTEGER,
+    total_withdrawals_after INTEGER
+);
+CREATE INDEX IF NOT EXISTS stream_history_stream
+    ON stream_history (stream, timestamp);
+CREATE TABLE IF NOT EXISTS treasuries (
+    address TEXT PRIMARY KEY,
+    allocation_assigned_units INTEGER,
+    last_known_balance_units INTEGER,
+    closed INTEGER NOT NULL,
+    last_signature TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS streams (
+    address TEXT PRIMARY KEY,
+    treasury TEXT NOT NULL,
+    allocation_assigned_units INTEGER,
+    total_withdrawals_units INTEGER,
+    closed INTEGER NOT NULL,
+    last_signature TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS mismatches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    treasury TEXT NOT NULL,
+    signature TEXT NOT NULL,
+    field TEXT NOT NULL,
+    reconstructed INTEGER NOT NULL,
+    reported INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS cursors (
+    address TEXT PRIMARY KEY,
+    signature TEXT NOT NULL
+);
+";
+
+/// The treasury accounting reconstructed from the events. Fields are unknown
+/// until an event sets or reports them (e.g. for treasuries created before
+/// the indexed history)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TreasuryState {
+    pub allocation_assigned_units: Option<u64>,
+    pub last_known_balance_units: Option<u64>,
+    pub closed: bool,
+}
+
+/// A reconstructed treasury field that differs from the value reported by
+/// the events of a transaction
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mismatch {
+    pub treasury: Pubkey,
+    pub signature: String,
+    pub field: String,
+    pub reconstructed: u64,
+    pub reported: u64,
+}
+
+/// The `*_after` values last reported in a transaction
+#[derive(Default)]
+struct Reported {
+    allocation: Option<u64>,
+    balance: Option<u64>,
+}
+
+// SQLite integers are signed, u64 values are stored with the same bits
+fn to_sql(value: u64) -> i64 {
+    value as i64
+}
+
+fn from_sql(value: i64) -> u64 {
+    value as u64
+}
+
+// range bounds compared with the stored timestamps are clamped instead
+fn bound_to_sql(value: u64) -> i64 {
+    value.min(i64::MAX as u64) as i64
+}
+
+pub struct Indexer {
+    conn: Connection,
+}
+
+impl Indexer {
+    pub fn open(path: &Path) -> Result<Indexer> {
+        Indexer::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Indexer> {
+        Indexer::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Indexer> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Indexer { conn })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Records the events of the transaction and updates the reconstructed
+    /// treasury accounting. At the end of the transaction the reconstructed
+    /// fields are checked against the `*_after` fields of its events (and
+    /// synced to them). Failed and already indexed transactions are skipped.
+    /// Returns the number of events recorded
+    pub fn ingest(&mut self, transaction: &Transaction) -> Result<usize> {
+        if transaction.failed {
+            return Ok(0);
+        }
+        let logs = parse_logs(&transaction.logs);
+        if logs.events.is_empty() && logs.errors.is_empty() {
+            return Ok(0);
+        }
+
+        let db = self.conn.transaction()?;
+        let indexed = db
+            .query_row(
+                "SELECT 1 FROM transactions WHERE signature = ?1",
+                params![transaction.signature],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if indexed {
+            return Ok(0);
+        }
+        db.execute(
+            "INSERT INTO transactions (signature, slot, block_time, truncated, decode_errors)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                transaction.signature,
+                to_sql(transaction.slot),
+                transaction.block_time,
+                logs.truncated,
+                logs.errors.len() as i64,
+            ],
+        )?;
+
+        let mut states: HashMap<Pubkey, TreasuryState> = HashMap::new();
+        let mut reported: HashMap<Pubkey, Reported> = HashMap::new();
+        let mut recorded = 0;
+        for (index, event) in logs.events.iter().enumerate() {
+            let effect = match event_effect(event) {
+                Some(effect) => effect,
+                None => continue,
+            };
+            let treasury = effect.treasury.to_string();
+            db.execute(
+                "INSERT INTO treasury_history (treasury, stream, signature, slot, event_index,
+                    event, timestamp, amount, token_fee_charged, sol_fee_charged,
+                    allocation_after, balance_after)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    treasury,
+                    effect.stream.map(|stream| stream.to_string()),
+                    transaction.signature,
+                    to_sql(transaction.slot),
+                    index as i64,
+                    event.name(),
+                    to_sql(effect.timestamp),
+                    to_sql(effect.amount),
+                    to_sql(effect.token_fee_charged),
+                    to_sql(effect.sol_fee_charged),
+                    effect.allocation_after.map(to_sql),
+                    effect.balance_after.map(to_sql),
+                ],
+            )?;
+
+            if let Some(stream) = effect.stream {
+                let stream = stream.to_string();
+                db.execute(
+                    "INSERT INTO stream_history (stream, treasury, signature, slot, event_index,
+                        event, timestamp, amount, token_fee_charged, allocation_after,
+                        total_withdrawals_after)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                    params![
+                        stream,
+                        treasury,
+                        transaction.signature,
+                        to_sql(transaction.slot),
+                        index as i64,
+                        event.name(),
+                        to_sql(effect.timestamp),
+                        to_sql(effect.amount),
+                        to_sql(effect.token_fee_charged),
+                        effect.stream_allocation_after.map(to_sql),
+                        effect.stream_total_withdrawals_after.map(to_sql),
+                    ],
+                )?;
+                db.execute(
+                    "INSERT INTO streams (address, treasury, allocation_assigned_units,
+                        total_withdrawals_units, closed, last_signature)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                     ON CONFLICT (address) DO UPDATE SET
+                        allocation_assigned_units =
+                            COALESCE(?3, allocation_assigned_units),
+                        total_withdrawals_units = COALESCE(?4, total_withdrawals_units),
+                        closed = closed OR ?5,
+                        last_signature = ?6",
+                    params![
+                        stream,
+                        treasury,
+                        effect.stream_allocation_after.map(to_sql),
+                        effect.stream_total_withdrawals_after.map(to_sql),
+                        effect.closes_stream,
+                        transaction.signature,
+                    ],
+                )?;
+            }
+
+            let state = match states.entry(effect.treasury) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    entry.insert(load_treasury_state(&db, &effect.treasury)?.unwrap_or_default())
+                }
+            };
+            state.allocation_assigned_units =
+                effect.allocation.apply(state.allocation_assigned_units);
+            state.last_known_balance_units = effect.balance.apply(state.last_known_balance_units);
+            state.closed |= effect.closes_treasury;
+
+            // a reset field supersedes the values reported before it
+            let reported = reported.entry(effect.treasury).or_default();
+            if let Change::Set(_) = effect.allocation {
+                reported.allocation = None;
+            }
+            if let Change::Set(_) = effect.balance {
+                reported.balance = None;
+            }
+            reported.allocation = effect.allocation_after.or(reported.allocation);
+            reported.balance = effect.balance_after.or(reported.balance);
+            recorded += 1;
+        }
+
+        for (treasury, state) in states.iter_mut() {
+            let reported = &reported[treasury];
+            for (field, value, reported) in [
+                (
+                    "allocation_assigned_units",
+                    &mut state.allocation_assigned_units,
+                    reported.allocation,
+                ),
+                (
+                    "last_known_balance_units",
+                    &mut state.last_known_balance_units,
+                    reported.balance,
+                ),
+            ] {
+                let reported = match reported {
+                    Some(reported) => reported,
+                    None => continue,
+                };
+                match *value {
+                    Some(reconstructed) if reconstructed != reported => {
+                        db.execute(
+                            "INSERT INTO mismatches (treasury, signature, field, reconstructed,
+                                reported)
+                             VALUES (?1, ?2, ?3, ?4, ?5)",
+                            params![
+                                treasury.to_string(),
+                                transaction.signature,
+                                field,
+                                to_sql(reconstructed),
+                                to_sql(reported),
+                            ],
+                        )?;
+                    }
+                    _ => {}
+                }
+                *value = Some(reported);
+            }
+            db.execute(
+                "INSERT INTO treasuries (address, allocation_assigned_units,
+                    last_known_balance_units, closed, last_signature)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT (address) DO UPDATE SET
+                    allocation_assigned_units = ?2,
+                    last_known_balance_units = ?3,
+                    closed = ?4,
+                    last_signature = ?5",
+                params![
+                    treasury.to_string(),
+                    state.allocation_assigned_units.map(to_sql),
+                    state.last_known_balance_units.map(to_sql),
+                    state.closed,
+                    transaction.signature,
+                ],
+            )?;
+        }
+
+        db.commit()?;
+        Ok(recorded)
+    }
+
+    pub fn treasury_state(&self, treasury: &Pubkey) -> Result<Option<TreasuryState>> {
+        load_treasury_state(&self.conn, treasury)
+    }
+
+    pub fn mismatches(&self) -> Result<Vec<Mismatch>> {
+        let mut statement = self.conn.prepare(
+            "SELECT treasury, signature, field, reconstructed, reported
+             FROM mismatches ORDER BY id",
+        )?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })?;
+        let mut mismatches = Vec::new();
+        for row in rows {
+            let (treasury, signature, field, reconstructed, reported) = row?;
+            mismatches.push(Mismatch {
+                treasury: treasury.parse()?,
+                signature,
+                field,
+                reconstructed: from_sql(reconstructed),
+                reported: from_sql(reported),
+            });
+        }
+        Ok(mismatches)
+    }
+
+    /// Gets the units the treasury paid out to beneficiaries (withdrawals
+    /// and stream closings, excluding fees) between the given timestamps
+    pub fn payouts(&self, treasury: &Pubkey, from: u64, to: u64) -> Result<u64> {
+        let total: i64 = self.conn.query_row(
+            "SELECT COALESCE(SUM(amount), 0) FROM treasury_history
+             WHERE treasury = ?1 AND timestamp >= ?2 AND timestamp < ?3
+                AND event IN ('StreamWithdrawEvent', 'CloseStreamEvent')",
+            params![treasury.to_string(), bound_to_sql(from), bound_to_sql(to)],
+            |row| row.get(0),
+        )?;
+        Ok(from_sql(total))
+    }
+
+    /// Gets the last signature indexed from the history of `address`
+    pub fn cursor(&self, address: &Pubkey) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT signature FROM cursors WHERE address = ?1",
+                params![address.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn set_cursor(&self, address: &Pubkey, signature: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO cursors (address, signature) VALUES (?1, ?2)
+             ON CONFLICT (address) DO UPDATE SET signature = ?2",
+            params![address.to_string(), signature],
+        )?;
+        Ok(())
+    }
+}
+
+fn load_treasury_state(conn: &Connection, treasury: &Pubkey) -> Result<Option<TreasuryState>> {
+    Ok(conn
+        .query_row(
+            "SELECT allocation_assigned_units, last_known_balance_units, closed
+             FROM treasuries WHERE address = ?1",
+            params![treasury.to_string()],
+            |row| {
+                Ok(TreasuryState {
+                    allocation_assigned_units: row.get::<_, Option<i64>>(0)?.map(from_sql),
+                    last_known_balance_units: row.get::<_, Option<i64>>(1)?.map(from_sql),
+                    closed: row.get(2)?,
+                })
+            },
+        )
+        .optional()?)
+}
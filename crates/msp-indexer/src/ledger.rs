@@ -0,0 +1,296 @@
+//! Effect of the events on the treasury accounting, mirroring how the
+//! program updates `Treasury.allocation_assigned_units` and
+//! `Treasury.last_known_balance_units`
+
+use anchor_lang::prelude::Pubkey;
+use msp_events::MspEvent;
+
+/// A change of a treasury field
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Change {
+    None,
+    Add(u64),
+    Sub(u64),
+    /// The field is reset (not checked against the reported value)
+    Set(u64),
+}
+
+impl Change {
+    /// Applies the change to a field. Unknown fields stay unknown until they
+    /// are set or reported by an event
+    pub fn apply(self, value: Option<u64>) -> Option<u64> {
+        match self {
+            Change::None => value,
+            Change::Add(amount) => value.map(|value| value.saturating_add(amount)),
+            // the program saturates when closing streams
+            Change::Sub(amount) => value.map(|value| value.saturating_sub(amount)),
+            Change::Set(value) => Some(value),
+        }
+    }
+}
+
+/// What an event records about a treasury and its stream
+pub struct Effect {
+    pub treasury: Pubkey,
+    pub stream: Option<Pubkey>,
+    pub timestamp: u64,
+    /// Token units moved by the instruction (e.g. sent to the beneficiary)
+    pub amount: u64,
+    pub token_fee_charged: u64,
+    pub sol_fee_charged: u64,
+    pub allocation: Change,
+    pub balance: Change,
+    pub allocation_after: Option<u64>,
+    pub balance_after: Option<u64>,
+    pub stream_allocation_after: Option<u64>,
+    pub stream_total_withdrawals_after: Option<u64>,
+    pub closes_treasury: bool,
+    pub closes_stream: bool,
+}
+
+impl Effect {
+    fn new(
+        treasury: Pubkey,
+        timestamp: u64,
+        sol_fee_charged: u64,
+        token_fee_charged: u64,
+    ) -> Effect {
+        Effect {
+            treasury,
+            stream: None,
+            timestamp,
+            amount: 0,
+            token_fee_charged,
+            sol_fee_charged,
+            allocation: Change::None,
+            balance: Change::None,
+            allocation_after: None,
+            balance_after: None,
+            stream_allocation_after: None,
+            stream_total_withdrawals_after: None,
+            closes_treasury: false,
+            closes_stream: false,
+        }
+    }
+
+    fn with_stream(
+        treasury: Pubkey,
+        stream: Pubkey,
+        timestamp: u64,
+        sol_fee_charged: u64,
+        token_fee_charged: u64,
+    ) -> Effect {
+        Effect {
+            stream: Some(stream),
+            ..Effect::new(treasury, timestamp, sol_fee_charged, token_fee_charged)
+        }
+    }
+}
+
+/// Gets the effect of the event on its treasury (none for the config events
+/// and the `StreamEvent` logged by `get_stream`)
+pub fn event_effect(event: &MspEvent) -> Option<Effect> {
+    let effect = match event {
+        MspEvent::Stream(_) | MspEvent::ConfigUpdate(_) | MspEvent::FeeOverrideUpdate(_) => {
+            return None
+        }
+        MspEvent::CreateTreasury(e) => Effect {
+            allocation: Change::Set(0),
+            balance: Change::Set(0),
+            ..Effect::new(
+                e.treasury,
+                e.timestamp,
+                e.sol_fee_charged,
+                e.token_fee_charged,
+            )
+        },
+        MspEvent::CreateStream(e) => Effect {
+            amount: e.stream_allocation,
+            // the withdraw fee payed by the treasurer leaves the treasury
+            allocation: Change::Add(e.stream_allocation),
+            balance: Change::Sub(e.token_fee_charged),
+            allocation_after: Some(e.treasury_allocation_after),
+            balance_after: Some(e.treasury_balance_after),
+            stream_allocation_after: Some(e.stream_allocation),
+            stream_total_withdrawals_after: Some(0),
+            ..Effect::with_stream(
+                e.treasury,
+                e.stream,
+                e.timestamp,
+                e.sol_fee_charged,
+                e.token_fee_charged,
+            )
+        },
+        MspEvent::StreamWithdraw(e) => {
+            let withdrawn = e
+                .token_amount_sent_to_beneficiary
+                .saturating_add(e.token_fee_charged);
+            Effect {
+                amount: e.token_amount_sent_to_beneficiary,
+                allocation: Change::Sub(withdrawn),
+                balance: Change::Sub(withdrawn),
+                allocation_after: Some(e.treasury_allocation_after),
+                balance_after: Some(e.treasury_balance_after),
+                stream_allocation_after: Some(e.stream_allocation_after),
+                stream_total_withdrawals_after: Some(e.stream_total_withdrawals_after),
+                ..Effect::with_stream(
+                    e.treasury,
+                    e.stream,
+                    e.timestamp,
+                    e.sol_fee_charged,
+                    e.token_fee_charged,
+                )
+            }
+        }
+        // followed by the `StreamWithdrawEvent` of the same withdrawal
+        MspEvent::StreamPushWithdraw(e) => Effect {
+            amount: e.token_amount_sent_to_beneficiary,
+            stream_total_withdrawals_after: Some(e.stream_total_withdrawals_after),
+            ..Effect::with_stream(
+                e.treasury,
+                e.stream,
+                e.timestamp,
+                e.sol_fee_charged,
+                e.token_fee_charged,
+            )
+        },
+        MspEvent::StreamPause(e) => Effect::with_stream(
+            e.treasury,
+            e.stream,
+            e.timestamp,
+            e.sol_fee_charged,
+            e.token_fee_charged,
+        ),
+        MspEvent::StreamResume(e) => Effect::with_stream(
+            e.treasury,
+            e.stream,
+            e.timestamp,
+            e.sol_fee_charged,
+            e.token_fee_charged,
+        ),
+        MspEvent::StreamRateUpdate(e) => Effect::with_stream(
+            e.treasury,
+            e.stream,
+            e.timestamp,
+            e.sol_fee_charged,
+            e.token_fee_charged,
+        ),
+        MspEvent::StreamTransfer(e) => Effect::with_stream(
+            e.treasury,
+            e.stream,
+            e.timestamp,
+            e.sol_fee_charged,
+            e.token_fee_charged,
+        ),
+        MspEvent::StreamTransferPropose(e) => Effect::with_stream(
+            e.treasury,
+            e.stream,
+            e.timestamp,
+            e.sol_fee_charged,
+            e.token_fee_charged,
+        ),
+        MspEvent::StreamTransferCancel(e) => Effect::with_stream(
+            e.treasury,
+            e.stream,
+            e.timestamp,
+            e.sol_fee_charged,
+            e.token_fee_charged,
+        ),
+        MspEvent::TreasuryAuthorityPropose(e) => Effect::new(
+            e.treasury,
+            e.timestamp,
+            e.sol_fee_charged,
+            e.token_fee_charged,
+        ),
+        MspEvent::TreasuryAuthorityTransfer(e) => Effect::new(
+            e.treasury,
+            e.timestamp,
+            e.sol_fee_charged,
+            e.token_fee_charged,
+        ),
+        // the balance is synced with the treasury token account, which picks
+        // up the tokens sent to the treasury outside of the program
+        MspEvent::TreasuryRefresh(e) => Effect {
+            balance: Change::Set(e.treasury_balance_after),
+            ..Effect::new(
+                e.treasury,
+                e.timestamp,
+                e.sol_fee_charged,
+                e.token_fee_charged,
+            )
+        },
+        MspEvent::TreasuryAddFunds(e) => Effect {
+            amount: e.amount,
+            balance: Change::Add(e.amount),
+            balance_after: Some(e.treasury_balance_after),
+            ..Effect::new(
+                e.treasury,
+                e.timestamp,
+                e.sol_fee_charged,
+                e.token_fee_charged,
+            )
+        },
+        MspEvent::StreamAllocate(e) => Effect {
+            amount: e.amount,
+            allocation: Change::Add(e.amount),
+            balance: Change::Sub(e.token_fee_charged),
+            allocation_after: Some(e.treasury_allocation_after),
+            balance_after: Some(e.treasury_balance_after),
+            stream_allocation_after: Some(e.stream_allocation_after),
+            ..Effect::with_stream(
+                e.treasury,
+                e.stream,
+                e.timestamp,
+                e.sol_fee_charged,
+                e.token_fee_charged,
+            )
+        },
+        MspEvent::CloseStream(e) => Effect {
+            amount: e.token_amount_sent_to_beneficiary,
+            // the unused allocation is released and the withdrawable amount
+            // (plus fee) leaves the treasury
+            allocation: Change::Sub(
+                e.stream_allocation_before
+                    .saturating_sub(e.stream_total_withdrawals_before),
+            ),
+            balance: Change::Sub(
+                e.token_amount_sent_to_beneficiary
+                    .saturating_add(e.token_fee_charged),
+            ),
+            allocation_after: Some(e.treasury_allocation_after),
+            balance_after: Some(e.treasury_balance_after),
+            closes_stream: true,
+            ..Effect::with_stream(
+                e.treasury,
+                e.stream,
+                e.timestamp,
+                e.sol_fee_charged,
+                e.token_fee_charged,
+            )
+        },
+        MspEvent::CloseTreasury(e) => Effect {
+            amount: e.token_amount_sent_to_destination,
+            allocation: Change::Set(0),
+            balance: Change::Set(0),
+            closes_treasury: true,
+            ..Effect::new(
+                e.treasury,
+                e.timestamp,
+                e.sol_fee_charged,
+                e.token_fee_charged,
+            )
+        },
+        MspEvent::TreasuryWithdraw(e) => Effect {
+            amount: e.token_amount_sent_to_destination,
+            balance: Change::Sub(e.amount),
+            balance_after: Some(e.treasury_balance_after),
+            ..Effect::new(
+                e.treasury,
+                e.timestamp,
+                e.sol_fee_charged,
+                e.token_fee_charged,
+            )
+        },
+    };
+    Some(effect)
+}
@@ -0,0 +1,17 @@
+//! Event indexer of the Money Streaming Program.
+//!
+//! Transactions are ingested from a JSON-RPC endpoint or from exported block
+//! files, their `mean_emit!` events are decoded with `msp-events` and
+//! recorded in per-treasury and per-stream history tables of a SQLite
+//! database. The treasury `allocation_assigned_units` and
+//! `last_known_balance_units` are reconstructed from the events and checked
+//! against the `*_after` fields the events report.
+
+pub mod indexer;
+pub mod ledger;
+pub mod source;
+
+pub use crate::indexer::{Indexer, Mismatch, TreasuryState};
+pub use crate::source::Transaction;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
@@ -0,0 +1,103 @@
+//! `msp-indexer` command-line tool
+
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use clap::{Parser, Subcommand};
+use msp_indexer::{source, Indexer, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+#[derive(Parser)]
+#[clap(name = "msp-indexer", about = "Money Streaming Program event indexer")]
+struct Cli {
+    /// SQLite database file
+    #[clap(long, global = true, default_value = "msp-index.sqlite")]
+    db: PathBuf,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Ingest the transactions of an address (the program by default) newer
+    /// than the last indexed one
+    Rpc {
+        #[clap(short, long, default_value = "http://localhost:8899")]
+        url: String,
+        #[clap(long)]
+        address: Option<Pubkey>,
+    },
+    /// Ingest the transactions of `getBlock` JSON files
+    Blocks {
+        #[clap(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Print the units paid out by a treasury between two timestamps
+    Payouts {
+        treasury: Pubkey,
+        #[clap(long, default_value = "0")]
+        from: u64,
+        #[clap(long, default_value = "18446744073709551615")]
+        to: u64,
+    },
+    /// Print the reconstructed treasury fields that differ from the values
+    /// reported by the events
+    Mismatches,
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let mut indexer = Indexer::open(&cli.db)?;
+    match cli.command {
+        Command::Rpc { url, address } => {
+            let rpc = RpcClient::new_with_commitment(url, CommitmentConfig::confirmed());
+            let address = address.unwrap_or(msp::ID);
+            let until = indexer
+                .cursor(&address)?
+                .map(|signature| signature.parse())
+                .transpose()?;
+            let signatures = source::fetch_signatures(&rpc, &address, until)?;
+            let mut events = 0;
+            for signature in &signatures {
+                events += indexer.ingest(&source::fetch_transaction(&rpc, signature)?)?;
+                indexer.set_cursor(&address, signature)?;
+            }
+            println!("{} transactions, {} events", signatures.len(), events);
+        }
+        Command::Blocks { files } => {
+            let mut blocks = files
+                .iter()
+                .map(|file| source::read_block_file(file))
+                .collect::<Result<Vec<_>>>()?;
+            blocks.sort_by_key(|transactions| transactions.first().map(|t| t.slot));
+            let mut events = 0;
+            for transaction in blocks.iter().flatten() {
+                events += indexer.ingest(transaction)?;
+            }
+            println!("{} events", events);
+        }
+        Command::Payouts { treasury, from, to } => {
+            println!("{}", indexer.payouts(&treasury, from, to)?);
+        }
+        Command::Mismatches => {
+            for mismatch in indexer.mismatches()? {
+                println!(
+                    "{} {} {}: reconstructed {}, reported {}",
+                    mismatch.signature,
+                    mismatch.treasury,
+                    mismatch.field,
+                    mismatch.reconstructed,
+                    mismatch.reported
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(err) = run(Cli::parse()) {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
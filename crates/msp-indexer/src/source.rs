@@ -0,0 +1,116 @@
+//! Transactions ingested from a JSON-RPC endpoint or from exported block
+//! files
+
+use std::fs;
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use serde_json::{json, Value};
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::signature::Signature;
+
+use crate::Result;
+
+/// The part of a confirmed transaction the indexer needs
+pub struct Transaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub failed: bool,
+    pub logs: Vec<String>,
+}
+
+impl Transaction {
+    /// Parses a transaction in the JSON encoding (as returned by
+    /// `getTransaction` or listed by `getBlock`). The slot is only present
+    /// in the `getTransaction` result, so the one of the block is passed
+    pub fn from_json(value: &Value, slot: u64, block_time: Option<i64>) -> Option<Transaction> {
+        let meta = value.get("meta")?;
+        Some(Transaction {
+            signature: value["transaction"]["signatures"][0].as_str()?.to_string(),
+            slot: value["slot"].as_u64().unwrap_or(slot),
+            block_time: value["blockTime"].as_i64().or(block_time),
+            failed: !meta["err"].is_null(),
+            logs: meta["logMessages"]
+                .as_array()
+                .map(|logs| {
+                    logs.iter()
+                        .filter_map(|log| log.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Reads the transactions of a block exported as the JSON `getBlock` result
+/// (with full transaction details), either bare or wrapped in the JSON-RPC
+/// response. The slot is read from the `slot` field or else from the file
+/// name (e.g. `123456.json`)
+pub fn read_block_file(path: &Path) -> Result<Vec<Transaction>> {
+    let value: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let block = if value.get("result").is_some() {
+        &value["result"]
+    } else {
+        &value
+    };
+    let slot = match block["slot"].as_u64() {
+        Some(slot) => slot,
+        None => path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse().ok())
+            .ok_or_else(|| format!("unknown slot of block file {}", path.display()))?,
+    };
+    let block_time = block["blockTime"].as_i64();
+    Ok(block["transactions"]
+        .as_array()
+        .ok_or_else(|| format!("no transactions in block file {}", path.display()))?
+        .iter()
+        .filter_map(|transaction| Transaction::from_json(transaction, slot, block_time))
+        .collect())
+}
+
+/// Gets the signatures of the transactions of `address` newer than `until`,
+/// oldest first
+pub fn fetch_signatures(
+    rpc: &RpcClient,
+    address: &Pubkey,
+    until: Option<Signature>,
+) -> Result<Vec<String>> {
+    let mut signatures = Vec::new();
+    let mut before = None;
+    loop {
+        let page = rpc.get_signatures_for_address_with_config(
+            address,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: None,
+                commitment: Some(rpc.commitment()),
+            },
+        )?;
+        match page.last() {
+            Some(last) => before = Some(last.signature.parse()?),
+            None => break,
+        }
+        signatures.extend(page.into_iter().map(|status| status.signature));
+    }
+    signatures.reverse();
+    Ok(signatures)
+}
+
+/// Fetches a confirmed transaction
+pub fn fetch_transaction(rpc: &RpcClient, signature: &str) -> Result<Transaction> {
+    let value: Value = rpc.send(
+        RpcRequest::GetTransaction,
+        json!([signature, {
+            "encoding": "json",
+            "commitment": rpc.commitment().commitment,
+            "maxSupportedTransactionVersion": 0,
+        }]),
+    )?;
+    Transaction::from_json(&value, 0, None)
+        .ok_or_else(|| format!("transaction {} not found", signature).into())
+}
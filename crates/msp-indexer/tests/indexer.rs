@@ -0,0 +1,261 @@
+use std::fs;
+
+use anchor_lang::prelude::*;
+use anchor_lang::Event;
+use msp::constants::EVENT_LOG_MARKER;
+use msp::events::*;
+use msp_indexer::{source, Indexer, Mismatch, Transaction, TreasuryState};
+use serde_json::json;
+
+fn logs(events: &[Vec<u8>]) -> Vec<String> {
+    let mut logs = vec![format!("Program {} invoke [1]", msp::ID)];
+    for event in events {
+        logs.push(format!("Program log: {}", EVENT_LOG_MARKER));
+        logs.push(format!("Program data: {}", base64::encode(event)));
+    }
+    logs.push(format!("Program {} success", msp::ID));
+    logs
+}
+
+fn transaction(signature: &str, slot: u64, logs: Vec<String>) -> Transaction {
+    Transaction {
+        signature: signature.to_string(),
+        slot,
+        block_time: Some(slot as i64),
+        failed: false,
+        logs,
+    }
+}
+
+fn create_treasury(treasury: Pubkey) -> CreateTreasuryEvent {
+    CreateTreasuryEvent {
+        timestamp: 1_000,
+        sol_fee_charged: 0,
+        token_fee_charged: 0,
+        sol_deposited_for_fees: 0,
+        treasury_is_sol_fee_payed_by_treasury: false,
+        treasury_type: 0,
+        treasury_is_auto_close: false,
+        treasury,
+    }
+}
+
+fn add_funds(treasury: Pubkey, amount: u64, treasury_balance_after: u64) -> TreasuryAddFundsEvent {
+    TreasuryAddFundsEvent {
+        timestamp: 1_100,
+        sol_fee_charged: 0,
+        token_fee_charged: 0,
+        amount,
+        treasury_is_sol_fee_payed_by_treasury: false,
+        treasury_balance_after,
+        treasury,
+    }
+}
+
+fn create_stream(treasury: Pubkey, stream: Pubkey) -> CreateStreamEvent {
+    CreateStreamEvent {
+        timestamp: 1_200,
+        sol_fee_charged: 0,
+        token_fee_charged: 6,
+        fee_tier: 0,
+        stream_start_ts: 1_200,
+        stream_rate_amount: 10,
+        stream_rate_interval: 1,
+        stream_allocation: 600,
+        stream_cliff: 0,
+        stream_is_token_withdraw_fee_payed_by_treasury: true,
+        treasury_is_sol_fee_payed_by_treasury: false,
+        treasury_allocation_after: 600,
+        treasury_balance_after: 994,
+        stream,
+        treasury,
+    }
+}
+
+fn withdraw(treasury: Pubkey, stream: Pubkey) -> StreamWithdrawEvent {
+    StreamWithdrawEvent {
+        timestamp: 2_000,
+        sol_fee_charged: 0,
+        token_fee_charged: 1,
+        fee_tier: 0,
+        amount: u64::MAX,
+        token_amount_sent_to_beneficiary: 99,
+        stream_withdrawable_before: 100,
+        stream_is_manually_paused: false,
+        stream_allocation_after: 600,
+        stream_total_withdrawals_after: 100,
+        stream_is_token_withdraw_fee_payed_by_treasury: false,
+        treasury_is_sol_fee_payed_by_treasury: false,
+        treasury_allocation_after: 500,
+        treasury_balance_after: 894,
+        treasury_total_withdrawals_after: 100,
+        stream,
+        treasury,
+        destination_token: Pubkey::new_unique(),
+    }
+}
+
+fn close_stream(treasury: Pubkey, stream: Pubkey, treasury_balance_after: u64) -> CloseStreamEvent {
+    CloseStreamEvent {
+        timestamp: 3_000,
+        sol_fee_charged: 0,
+        token_fee_charged: 3,
+        fee_tier: 0,
+        token_amount_sent_to_beneficiary: 297,
+        stream_is_token_withdraw_fee_payed_by_treasury: false,
+        stream_allocation_before: 600,
+        stream_total_withdrawals_before: 100,
+        treasury_is_sol_fee_payed_by_treasury: false,
+        treasury_allocation_after: 0,
+        treasury_balance_after,
+        treasury_total_streams_after: 0,
+        stream,
+        treasury,
+    }
+}
+
+#[test]
+fn reconstruct_treasury_test() {
+    let treasury = Pubkey::new_unique();
+    let stream = Pubkey::new_unique();
+    let mut indexer = Indexer::open_in_memory().unwrap();
+
+    let transactions = vec![
+        transaction("tx1", 1, logs(&[create_treasury(treasury).data()])),
+        transaction("tx2", 2, logs(&[add_funds(treasury, 1_000, 1_000).data()])),
+        transaction("tx3", 3, logs(&[create_stream(treasury, stream).data()])),
+        transaction("tx4", 4, logs(&[withdraw(treasury, stream).data()])),
+    ];
+    for transaction in &transactions {
+        assert_eq!(indexer.ingest(transaction).unwrap(), 1);
+    }
+    // already indexed
+    assert_eq!(indexer.ingest(&transactions[3]).unwrap(), 0);
+    // failed transactions are skipped
+    let mut failed = transaction("tx5", 5, logs(&[add_funds(treasury, 7, 901).data()]));
+    failed.failed = true;
+    assert_eq!(indexer.ingest(&failed).unwrap(), 0);
+
+    assert!(indexer.mismatches().unwrap().is_empty());
+    assert_eq!(
+        indexer.treasury_state(&treasury).unwrap(),
+        Some(TreasuryState {
+            allocation_assigned_units: Some(500),
+            last_known_balance_units: Some(894),
+            closed: false,
+        })
+    );
+
+    // 106 units were sent to the treasury token account outside of the
+    // program and picked up when closing the stream
+    let close = transaction(
+        "tx6",
+        6,
+        logs(&[close_stream(treasury, stream, 700).data()]),
+    );
+    assert_eq!(indexer.ingest(&close).unwrap(), 1);
+    assert_eq!(
+        indexer.mismatches().unwrap(),
+        vec![Mismatch {
+            treasury,
+            signature: "tx6".to_string(),
+            field: "last_known_balance_units".to_string(),
+            reconstructed: 594,
+            reported: 700,
+        }]
+    );
+    // the reconstruction is synced to the reported values
+    assert_eq!(
+        indexer.treasury_state(&treasury).unwrap(),
+        Some(TreasuryState {
+            allocation_assigned_units: Some(0),
+            last_known_balance_units: Some(700),
+            closed: false,
+        })
+    );
+
+    assert_eq!(indexer.payouts(&treasury, 0, u64::MAX).unwrap(), 99 + 297);
+    assert_eq!(indexer.payouts(&treasury, 0, 2_500).unwrap(), 99);
+
+    let stream_events: i64 = indexer
+        .connection()
+        .query_row(
+            "SELECT COUNT(*) FROM stream_history WHERE stream = ?1",
+            [stream.to_string()],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(stream_events, 3);
+}
+
+#[test]
+fn unknown_treasury_test() {
+    // a treasury created before the indexed history is seeded from the
+    // reported values
+    let treasury = Pubkey::new_unique();
+    let stream = Pubkey::new_unique();
+    let mut indexer = Indexer::open_in_memory().unwrap();
+    indexer
+        .ingest(&transaction(
+            "tx1",
+            1,
+            logs(&[withdraw(treasury, stream).data()]),
+        ))
+        .unwrap();
+    indexer
+        .ingest(&transaction(
+            "tx2",
+            2,
+            logs(&[close_stream(treasury, stream, 594).data()]),
+        ))
+        .unwrap();
+    assert!(indexer.mismatches().unwrap().is_empty());
+    assert_eq!(
+        indexer.treasury_state(&treasury).unwrap(),
+        Some(TreasuryState {
+            allocation_assigned_units: Some(0),
+            last_known_balance_units: Some(594),
+            closed: false,
+        })
+    );
+}
+
+#[test]
+fn read_block_file_test() {
+    let treasury = Pubkey::new_unique();
+    let block = json!({
+        "blockTime": 1_650_000_000,
+        "transactions": [
+            {
+                "transaction": { "signatures": ["tx1"] },
+                "meta": { "err": null, "logMessages": logs(&[create_treasury(treasury).data()]) },
+            },
+            {
+                "transaction": { "signatures": ["tx2"] },
+                "meta": { "err": { "InstructionError": [0, "InvalidArgument"] }, "logMessages": [] },
+            },
+        ],
+    });
+    let path = std::env::temp_dir().join(format!("{}.json", 123_456));
+    fs::write(&path, block.to_string()).unwrap();
+    let transactions = source::read_block_file(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(transactions.len(), 2);
+    assert_eq!(transactions[0].signature, "tx1");
+    assert_eq!(transactions[0].slot, 123_456);
+    assert_eq!(transactions[0].block_time, Some(1_650_000_000));
+    assert!(!transactions[0].failed);
+    assert!(transactions[1].failed);
+
+    let mut indexer = Indexer::open_in_memory().unwrap();
+    assert_eq!(indexer.ingest(&transactions[0]).unwrap(), 1);
+    assert_eq!(
+        indexer.treasury_state(&treasury).unwrap(),
+        Some(TreasuryState {
+            allocation_assigned_units: Some(0),
+            last_known_balance_units: Some(0),
+            closed: false,
+        })
+    );
+}
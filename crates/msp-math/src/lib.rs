@@ -0,0 +1,377 @@
+//! Stream accrual math of the Money Streaming Program.
+//!
+//! The functions take the stream fields in a plain [`StreamState`] and an
+//! explicit timestamp, so the same calculations the program does on-chain
+//! can be done off-chain. The crate has no dependencies and is `no_std`.
+#![no_std]
+
+use core::cmp;
+use core::convert::TryFrom;
+
+pub const PERCENT_DENOMINATOR: u64 = 1_000_000;
+
+// Stream Accrual Modes
+pub const STREAM_ACCRUAL_MODE_LINEAR: u8 = 0;
+pub const STREAM_ACCRUAL_MODE_STEP: u8 = 1;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MathError {
+    Overflow,
+    InvalidArgument,
+}
+
+pub type Result<T> = core::result::Result<T, MathError>;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum StreamStatus {
+    Scheduled = 0,
+    Running = 1,
+    Paused = 2,
+}
+
+/// The stream fields used by the accrual calculations. Timestamps are in
+/// seconds except for `start_utc` which is in milliseconds in old streams
+/// (when `start_utc_in_seconds` is zero)
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct StreamState {
+    pub rate_amount_units: u64,
+    pub rate_interval_in_seconds: u64,
+    pub start_utc: u64,
+    pub start_utc_in_seconds: u64,
+    pub cliff_vest_amount_units: u64,
+    pub cliff_vest_percent: u64,
+    pub allocation_assigned_units: u64,
+    pub total_withdrawals_units: u64,
+    pub last_manual_stop_withdrawable_units_snap: u64,
+    pub last_manual_stop_block_time: u64,
+    pub last_manual_resume_block_time: u64,
+    pub last_known_total_seconds_in_paused_status: u64,
+    pub last_auto_stop_block_time: u64,
+    pub accrual_mode: u8,
+    pub cliff_utc_in_seconds: u64,
+}
+
+impl StreamState {
+    /// Calculates the cliff amount
+    pub fn get_cliff_units(&self) -> Result<u64> {
+        // calculate effective cliff units as an absolute amount. We will not store %
+        let cliff_units = if self.cliff_vest_percent > 0 {
+            u64::try_from(
+                (self.cliff_vest_percent as u128)
+                    .checked_mul(self.allocation_assigned_units as u128)
+                    .ok_or(MathError::Overflow)?
+                    .checked_div(PERCENT_DENOMINATOR as u128)
+                    .ok_or(MathError::Overflow)?,
+            )
+            .unwrap()
+        } else {
+            self.cliff_vest_amount_units
+        };
+
+        Ok(cliff_units)
+    }
+
+    /// Check is the stream was manually paused
+    pub fn is_manually_paused(&self) -> bool {
+        if self.last_manual_stop_block_time == 0 {
+            return false;
+        }
+        self.last_manual_stop_block_time > self.last_manual_resume_block_time
+    }
+
+    /// Check if the stream unlocks units in discrete steps at the end of each
+    /// interval instead of every second
+    pub fn is_step_accrual(&self) -> bool {
+        self.accrual_mode == STREAM_ACCRUAL_MODE_STEP
+    }
+
+    /// Calculates the number of seconds needed to stream the given units.
+    ///
+    /// In step mode the last tranche is unlocked at the end of its interval
+    /// even if it is smaller than `rate_amount_units`.
+    pub fn get_streaming_seconds(&self, streamable_units: u64) -> Result<u64> {
+        if self.is_step_accrual() {
+            let streaming_intervals = (streamable_units as u128)
+                .checked_add(self.rate_amount_units as u128)
+                .ok_or(MathError::Overflow)?
+                .checked_sub(1)
+                .ok_or(MathError::Overflow)?
+                .checked_div(self.rate_amount_units as u128)
+                .ok_or(MathError::Overflow)?;
+            let streaming_seconds = u64::try_from(
+                streaming_intervals
+                    .checked_mul(self.rate_interval_in_seconds as u128)
+                    .ok_or(MathError::Overflow)?,
+            )
+            .unwrap();
+            return Ok(streaming_seconds);
+        }
+
+        let streaming_seconds = u64::try_from(
+            (streamable_units as u128)
+                .checked_mul(self.rate_interval_in_seconds as u128)
+                .ok_or(MathError::Overflow)?
+                .checked_div(self.rate_amount_units as u128)
+                .ok_or(MathError::Overflow)?,
+        )
+        .unwrap();
+
+        Ok(streaming_seconds)
+    }
+
+    /// Check if the given blocktime is before the stream cliff date
+    pub fn is_before_cliff_date(&self, timestamp: u64) -> bool {
+        timestamp < self.cliff_utc_in_seconds
+    }
+
+    /// Gets the last known blocktime where the stream was paused (auto or manual)
+    pub fn get_last_known_stop_block_time(&self) -> u64 {
+        cmp::max(
+            self.last_auto_stop_block_time,
+            self.last_manual_stop_block_time,
+        )
+    }
+
+    /// Calculates the amount of units streamed units during the given seconds
+    ///
+    /// This takes into account if there are enough remaining allocated
+    /// units to fully stream for this number of seconds.
+    /// Also, the returned value does not include cliff.
+    ///
+    /// In step mode, only the intervals fully elapsed in the given seconds
+    /// are counted.
+    pub fn get_streamed_units(&self, seconds: u64) -> Result<u64> {
+        if self.rate_interval_in_seconds == 0 {
+            return Ok(0_u64);
+        }
+
+        let cliff_units = self.get_cliff_units()?;
+        let streamable_units = self
+            .allocation_assigned_units
+            .checked_sub(cliff_units)
+            .ok_or(MathError::Overflow)?;
+        let streaming_seconds = self.get_streaming_seconds(streamable_units)?;
+
+        if seconds >= streaming_seconds {
+            return Ok(streamable_units);
+        }
+
+        let unlocked_seconds = if self.is_step_accrual() {
+            seconds
+                .checked_sub(seconds % self.rate_interval_in_seconds)
+                .ok_or(MathError::Overflow)?
+        } else {
+            seconds
+        };
+
+        let streamable_units_in_given_seconds = u64::try_from(
+            (self.rate_amount_units as u128)
+                .checked_mul(unlocked_seconds as u128)
+                .unwrap()
+                .checked_div(self.rate_interval_in_seconds as u128)
+                .ok_or(MathError::Overflow)?,
+        )
+        .unwrap();
+
+        Ok(streamable_units_in_given_seconds)
+    }
+
+    /// Gets the seconds streamed in the current interval of a step stream,
+    /// which are not unlocked until the interval ends. Zero in linear mode
+    pub fn get_step_interval_elapsed_seconds(&self, timestamp: u64) -> Result<u64> {
+        if !self.is_step_accrual() || self.rate_interval_in_seconds == 0 {
+            return Ok(0);
+        }
+
+        let seconds_since_start = timestamp
+            .checked_sub(self.get_start_utc()?)
+            .ok_or(MathError::Overflow)?;
+        let streamed_seconds = seconds_since_start
+            .checked_sub(self.last_known_total_seconds_in_paused_status)
+            .ok_or(MathError::Overflow)?;
+
+        Ok(streamed_seconds % self.rate_interval_in_seconds)
+    }
+
+    /// Gets the start utc seconds amount
+    pub fn get_start_utc(&self) -> Result<u64> {
+        if self.start_utc_in_seconds > 0 {
+            return Ok(self.start_utc);
+        }
+        let start_utc_seconds = self
+            .start_utc
+            .checked_div(1000u64)
+            .ok_or(MathError::Overflow)?;
+        Ok(start_utc_seconds)
+    }
+
+    /// Gets the stream status in the given blocktime
+    pub fn get_status(&self, timestamp: u64) -> Result<StreamStatus> {
+        let start_utc_seconds = self.get_start_utc()?;
+
+        // scheduled
+        if start_utc_seconds > timestamp {
+            return Ok(StreamStatus::Scheduled);
+        }
+
+        // manually paused
+        if self.is_manually_paused() {
+            return Ok(StreamStatus::Paused);
+        }
+
+        // running (the stream keeps accruing until the cliff date even if
+        // the allocation is fully earned, since nothing was unlocked yet)
+        if self.is_before_cliff_date(timestamp) {
+            return Ok(StreamStatus::Running);
+        }
+
+        // running or automatically paused (ran out of funds)
+        let cliff_units = self.get_cliff_units()?;
+        let seconds_since_start = timestamp
+            .checked_sub(start_utc_seconds)
+            .ok_or(MathError::Overflow)?;
+
+        let not_stop_streamed_units_since_started = self.get_streamed_units(seconds_since_start)?;
+        let non_stop_earning_units = cliff_units
+            .checked_add(not_stop_streamed_units_since_started)
+            .ok_or(MathError::Overflow)?;
+
+        let actual_streamed_seconds = seconds_since_start
+            .checked_sub(self.last_known_total_seconds_in_paused_status) // TODO: check
+            .ok_or(MathError::Overflow)?;
+        let actual_streamed_units = self.get_streamed_units(actual_streamed_seconds)?;
+        let actual_earned_units = cliff_units
+            .checked_add(actual_streamed_units)
+            .ok_or(MathError::Overflow)?;
+
+        assert!(
+            non_stop_earning_units >= actual_earned_units,
+            "non_stop vs actual earned units invariant violated"
+        );
+
+        // running
+        if self.allocation_assigned_units > actual_earned_units {
+            return Ok(StreamStatus::Running);
+        }
+
+        // automatically paused (ran out of funds)
+        Ok(StreamStatus::Paused)
+    }
+
+    /// Calculates the stream estimated depletion blocktime. The calculation
+    /// has into account the periods of time in which the stream was in
+    /// paused status. One time payments are depleted at `timestamp`
+    pub fn get_est_depletion_blocktime(&self, timestamp: u64) -> Result<u64> {
+        if self.rate_interval_in_seconds == 0 {
+            return Ok(timestamp);
+        }
+        let cliff_units = self.get_cliff_units()?;
+
+        let streamable_units = self
+            .allocation_assigned_units
+            .checked_sub(cliff_units)
+            .ok_or(MathError::Overflow)?;
+
+        let streaming_seconds = self.get_streaming_seconds(streamable_units)?;
+
+        let duration_span_seconds = streaming_seconds
+            .checked_add(self.last_known_total_seconds_in_paused_status)
+            .ok_or(MathError::Overflow)?;
+
+        let start_utc_seconds = self.get_start_utc()?;
+        let est_depletion_time = start_utc_seconds
+            .checked_add(duration_span_seconds)
+            .ok_or(MathError::Overflow)?;
+        Ok(est_depletion_time)
+    }
+
+    /// Gets the remaining allocation in the stream
+    pub fn get_remaining_allocation(&self) -> Result<u64> {
+        self.allocation_assigned_units
+            .checked_sub(self.total_withdrawals_units)
+            .ok_or(MathError::Overflow)
+    }
+
+    /// Gets the total funds sent to beneficiary (withdrawable + withdrawn)
+    pub fn get_funds_sent_to_beneficiary(&self, timestamp: u64) -> Result<u64> {
+        let withdrawable = self.get_beneficiary_withdrawable_amount(timestamp)?;
+        self.total_withdrawals_units
+            .checked_add(withdrawable)
+            .ok_or(MathError::Overflow)
+    }
+
+    /// Gets the funds that have not been withdrew
+    pub fn get_funds_left_in_account(&self, timestamp: u64) -> Result<u64> {
+        let withdrawable = self.get_beneficiary_withdrawable_amount(timestamp)?;
+        self.get_remaining_allocation()?
+            .checked_sub(withdrawable)
+            .ok_or(MathError::Overflow)
+    }
+
+    /// Gets the beneficiary withdrawable amount in the given blocktime
+    pub fn get_beneficiary_withdrawable_amount(&self, timestamp: u64) -> Result<u64> {
+        // nothing is withdrawable before the cliff date
+        if self.is_before_cliff_date(timestamp) {
+            return Ok(0);
+        }
+
+        self.get_beneficiary_vested_amount(timestamp)
+    }
+
+    /// Gets the amount earned by the beneficiary and not withdrawn yet in the
+    /// given blocktime, regardless of the cliff date
+    pub fn get_beneficiary_vested_amount(&self, timestamp: u64) -> Result<u64> {
+        let remaining_allocation = self.get_remaining_allocation()?;
+
+        if remaining_allocation == 0 {
+            return Ok(0);
+        }
+
+        let status = self.get_status(timestamp)?;
+
+        // Check if SCHEDULED
+        if status == StreamStatus::Scheduled {
+            return Ok(0);
+        }
+
+        // Check if PAUSED
+        if status == StreamStatus::Paused {
+            let withdrawable_while_paused = if self.is_manually_paused() {
+                self.last_manual_stop_withdrawable_units_snap
+            } else {
+                remaining_allocation
+            };
+            return Ok(withdrawable_while_paused);
+        }
+
+        // Check if RUNNING
+        if self.rate_interval_in_seconds == 0 || self.rate_amount_units == 0 {
+            return Err(MathError::InvalidArgument);
+        }
+
+        let cliff_units = self.get_cliff_units()?;
+        let start_utc_seconds = self.get_start_utc()?;
+        let seconds_since_start = timestamp
+            .checked_sub(start_utc_seconds)
+            .ok_or(MathError::Overflow)?;
+
+        let actual_streamed_seconds = seconds_since_start
+            .checked_sub(self.last_known_total_seconds_in_paused_status) // TODO: check
+            .ok_or(MathError::Overflow)?;
+        let actual_streamed_units = self.get_streamed_units(actual_streamed_seconds)?;
+        let mut actual_earned_units = cliff_units
+            .checked_add(actual_streamed_units)
+            .ok_or(MathError::Overflow)?;
+
+        // TODO: this is a work around the issue of not having a better way for calculating earned units after auto-PAUSED streams
+        actual_earned_units = cmp::max(actual_earned_units, self.total_withdrawals_units);
+
+        let withdrawable_units_while_running = actual_earned_units
+            .checked_sub(self.total_withdrawals_units)
+            .ok_or(MathError::Overflow)?;
+
+        let withdrawable = cmp::min(remaining_allocation, withdrawable_units_while_running); // TODO: these two shuold be equal by now
+
+        Ok(withdrawable)
+    }
+}
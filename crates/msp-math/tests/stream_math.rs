@@ -0,0 +1,105 @@
+use msp_math::{MathError, StreamState, StreamStatus, STREAM_ACCRUAL_MODE_STEP};
+
+fn stream() -> StreamState {
+    StreamState {
+        rate_amount_units: 100,
+        rate_interval_in_seconds: 10,
+        start_utc: 1_000,
+        start_utc_in_seconds: 1_000,
+        allocation_assigned_units: 1_000,
+        ..StreamState::default()
+    }
+}
+
+#[test]
+fn linear_stream_test() {
+    let stream = stream();
+
+    assert_eq!(stream.get_status(999), Ok(StreamStatus::Scheduled));
+    assert_eq!(stream.get_beneficiary_withdrawable_amount(999), Ok(0));
+
+    assert_eq!(stream.get_status(1_015), Ok(StreamStatus::Running));
+    assert_eq!(stream.get_beneficiary_withdrawable_amount(1_015), Ok(150));
+    assert_eq!(stream.get_funds_left_in_account(1_015), Ok(850));
+
+    assert_eq!(stream.get_est_depletion_blocktime(1_015), Ok(1_100));
+    assert_eq!(stream.get_status(1_100), Ok(StreamStatus::Paused));
+    assert_eq!(stream.get_beneficiary_withdrawable_amount(1_200), Ok(1_000));
+}
+
+#[test]
+fn step_stream_test() {
+    let stream = StreamState {
+        accrual_mode: STREAM_ACCRUAL_MODE_STEP,
+        allocation_assigned_units: 950,
+        ..stream()
+    };
+
+    assert_eq!(stream.get_beneficiary_withdrawable_amount(1_019), Ok(100));
+    assert_eq!(stream.get_beneficiary_withdrawable_amount(1_020), Ok(200));
+    // the last 50 units are unlocked at the end of the tenth interval
+    assert_eq!(stream.get_est_depletion_blocktime(1_000), Ok(1_100));
+    assert_eq!(stream.get_beneficiary_withdrawable_amount(1_099), Ok(900));
+}
+
+#[test]
+fn step_interval_elapsed_seconds_test() {
+    let step_stream = StreamState {
+        accrual_mode: STREAM_ACCRUAL_MODE_STEP,
+        ..stream()
+    };
+
+    assert_eq!(step_stream.get_step_interval_elapsed_seconds(1_000), Ok(0));
+    assert_eq!(step_stream.get_step_interval_elapsed_seconds(1_017), Ok(7));
+    assert_eq!(step_stream.get_step_interval_elapsed_seconds(1_020), Ok(0));
+
+    // the seconds in paused status are not streamed
+    let resumed = StreamState {
+        last_known_total_seconds_in_paused_status: 5,
+        ..step_stream
+    };
+    assert_eq!(resumed.get_step_interval_elapsed_seconds(1_017), Ok(2));
+
+    // linear streams unlock every second
+    assert_eq!(stream().get_step_interval_elapsed_seconds(1_017), Ok(0));
+}
+
+#[test]
+fn cliff_test() {
+    let stream = StreamState {
+        cliff_vest_amount_units: 200,
+        cliff_utc_in_seconds: 1_030,
+        ..stream()
+    };
+
+    // units accrue from the start but unlock at the cliff date
+    assert_eq!(stream.get_beneficiary_vested_amount(1_020), Ok(400));
+    assert_eq!(stream.get_beneficiary_withdrawable_amount(1_020), Ok(0));
+    assert_eq!(stream.get_beneficiary_withdrawable_amount(1_030), Ok(500));
+    assert_eq!(stream.get_est_depletion_blocktime(1_030), Ok(1_080));
+}
+
+#[test]
+fn manually_paused_stream_test() {
+    let stream = StreamState {
+        last_manual_stop_block_time: 1_030,
+        last_manual_stop_withdrawable_units_snap: 300,
+        ..stream()
+    };
+
+    assert_eq!(stream.get_status(1_050), Ok(StreamStatus::Paused));
+    assert_eq!(stream.get_beneficiary_withdrawable_amount(1_050), Ok(300));
+}
+
+#[test]
+fn invalid_rate_test() {
+    let stream = StreamState {
+        rate_interval_in_seconds: 0,
+        ..stream()
+    };
+
+    assert_eq!(
+        stream.get_beneficiary_withdrawable_amount(1_015),
+        Err(MathError::InvalidArgument)
+    );
+}
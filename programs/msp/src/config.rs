@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::constants::*;
+use crate::errors::ErrorCode;
+use crate::instructions::fee_treasury;
+use crate::treasury::Treasury;
+
+/// The protocol fees. Flat fees are in lamports and percent fees (and the
+/// referrer share) are relative to `PERCENT_DENOMINATOR`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct FeeSchedule {
+    pub create_treasury_flat_fee: u64,
+    pub create_stream_flat_fee: u64,
+    pub add_funds_flat_fee: u64,
+    pub withdraw_percent_fee: u64,
+    pub close_stream_flat_fee: u64,
+    pub close_stream_percent_fee: u64,
+    pub close_treasury_flat_fee: u64,
+    pub transfer_stream_flat_fee: u64,
+    pub treasury_withdraw_percent_fee: u64,
+    /// Share of the fees charged for a referred treasury that goes to the
+    /// referrer
+    pub referrer_fee_share: u64,
+}
+
+impl Default for FeeSchedule {
+    fn default() -> Self {
+        FeeSchedule {
+            create_treasury_flat_fee: CREATE_TREASURY_FLAT_FEE,
+            create_stream_flat_fee: CREATE_STREAM_FLAT_FEE,
+            add_funds_flat_fee: ADD_FUNDS_FLAT_FEE,
+            withdraw_percent_fee: WITHDRAW_PERCENT_FEE,
+            close_stream_flat_fee: CLOSE_STREAM_FLAT_FEE,
+            close_stream_percent_fee: CLOSE_STREAM_PERCENT_FEE,
+            close_treasury_flat_fee: CLOSE_TREASURY_FLAT_FEE,
+            transfer_stream_flat_fee: TRANSFER_STREAM_FLAT_FEE,
+            treasury_withdraw_percent_fee: TREASURY_WITHDRAW_PERCENT_FEE,
+            referrer_fee_share: REFERRER_FEE_SHARE,
+        }
+    }
+}
+
+impl FeeSchedule {
+    /// Checks the percent fees and the referrer share are not greater than 100%
+    pub fn validate(&self) -> Result<()> {
+        if self.withdraw_percent_fee > PERCENT_DENOMINATOR
+            || self.close_stream_percent_fee > PERCENT_DENOMINATOR
+            || self.treasury_withdraw_percent_fee > PERCENT_DENOMINATOR
+            || self.referrer_fee_share > PERCENT_DENOMINATOR
+        {
+            return Err(ErrorCode::InvalidFeeSchedule.into());
+        }
+        Ok(())
+    }
+}
+
+/// Program-wide settings (PDA with seeds `[b"config"]`)
+#[account]
+pub struct Config {
+    pub version: u8, // offset: 8
+    pub bump: u8,
+    /// The wallet allowed to update this account
+    pub admin_address: Pubkey,
+    /// The wallet receiving the protocol fees
+    pub fee_treasury_address: Pubkey,
+    pub fees: FeeSchedule,
+    // total bytes: 154
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            version: 1,
+            bump: 0,
+            admin_address: Pubkey::default(),
+            fee_treasury_address: fee_treasury::ID,
+            fees: FeeSchedule::default(),
+        }
+    }
+}
+
+impl Config {
+    pub const SIZE: usize = 8 + 1 + 1 + 32 + 32 + 10 * 8;
+
+    /// Loads the config PDA. Until the config is initialized, the fee
+    /// constants and the `fee_treasury` wallet are used
+    pub fn load(config: &AccountInfo) -> Result<Config> {
+        if config.owner != &crate::ID || config.data_is_empty() {
+            return Ok(Config::default());
+        }
+        let data = config.try_borrow_data()?;
+        Config::try_deserialize(&mut &data[..])
+    }
+}
+
+/// Fee schedule assigned by the admin to a treasury or to a treasury
+/// authority (PDA with seeds `[b"fee_override", subject_address]`). It
+/// replaces the config fees in the stream create, withdraw, allocate and close
+/// instructions and in `treasury_withdraw`
+#[account]
+pub struct FeeOverride {
+    pub version: u8, // offset: 8
+    pub bump: u8,
+    /// The treasury or treasury authority the fees apply to
+    pub subject_address: Pubkey,
+    /// The tier reported in the events. Zero is reserved for the config fees
+    pub tier: u8,
+    pub fees: FeeSchedule,
+    // total bytes: 123
+}
+
+impl FeeOverride {
+    pub const SIZE: usize = 8 + 1 + 1 + 32 + 1 + 10 * 8;
+
+    /// Gets the fees and the fee tier charged to `treasury`. These are the
+    /// `fee_override` fees when it belongs to the treasury or to its
+    /// authority, and the config fees (tier zero) otherwise. Any other
+    /// account (e.g. the system program) can be passed when there is no
+    /// override
+    pub fn resolve<'info>(
+        fee_override: &AccountInfo<'info>,
+        config: &Config,
+        treasury: &Account<'info, Treasury>,
+    ) -> Result<(FeeSchedule, u8)> {
+        if fee_override.owner != &crate::ID {
+            return Ok((config.fees, 0));
+        }
+        let data = fee_override.try_borrow_data()?;
+        if data.len() < 8 || data[..8] != FeeOverride::discriminator() {
+            return Ok((config.fees, 0));
+        }
+        let fee_override = FeeOverride::try_deserialize(&mut &data[..])?;
+        if fee_override.subject_address != treasury.key()
+            && fee_override.subject_address != treasury.authority()
+        {
+            return Ok((config.fees, 0));
+        }
+        Ok((fee_override.fees, fee_override.tier))
+    }
+}
@@ -10,6 +10,13 @@ pub const CLOSE_STREAM_PERCENT_FEE: u64 = 2_500;
 pub const CLOSE_TREASURY_FLAT_FEE: u64 = 10_000;
 pub const TRANSFER_STREAM_FLAT_FEE: u64 = 10_000;
 pub const TREASURY_WITHDRAW_PERCENT_FEE: u64 = 2_500;
+// Share of the fees charged for a referred treasury that goes to the referrer
+pub const REFERRER_FEE_SHARE: u64 = 100_000;
+// Payed by the treasury (if it pays the SOL fees) to the cranker of `push_withdraw`
+pub const PUSH_WITHDRAW_CRANKER_REWARD: u64 = 5_000;
+// Minimum seconds since the previous stream withdrawal for the cranker of
+// `push_withdraw` to be rewarded
+pub const PUSH_WITHDRAW_CRANKER_REWARD_MIN_INTERVAL: u64 = 86_400;
 
 pub const PERCENT_DENOMINATOR: u64 = 1_000_000;
 
@@ -17,14 +24,40 @@ pub const PERCENT_DENOMINATOR: u64 = 1_000_000;
 pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
 
 // Stream Allocation Types
-// pub const ALLOCATION_TYPE_ASSIGN_TO_ALL_STREAMS: u8 = 0; // NOT IMPLEMENTED YET
+pub const ALLOCATION_TYPE_ASSIGN_TO_ALL_STREAMS: u8 = 0;
 pub const ALLOCATION_TYPE_ASSIGN_TO_SPECIFIC_STREAM: u8 = 1;
 pub const ALLOCATION_TYPE_LEAVE_UNALLOCATED: u8 = 2;
 pub const TREASURY_TYPE_OPEN: u8 = 0;
 pub const TREASURY_TYPE_LOCKED: u8 = 1;
 
+// Allocation Split Modes (assign to all streams)
+pub const ALLOCATION_SPLIT_MODE_PRO_RATA: u8 = 0; // by stream rate
+pub const ALLOCATION_SPLIT_MODE_EVEN: u8 = 1;
+
+// Stream Accrual Modes
+pub const STREAM_ACCRUAL_MODE_LINEAR: u8 = 0;
+pub const STREAM_ACCRUAL_MODE_STEP: u8 = 1;
+
+// Stream Schedules
+pub const MAX_SCHEDULE_POINTS: usize = 64;
+
+// Events
+/// Logged by `mean_emit!` right before the event data
+pub const EVENT_LOG_MARKER: &str = "mean-log-msp2";
+
+// Account Layouts
+// v2 accounts were allocated with room to append fields and their extensions
+// start right after the last v2 field. v3 accounts drop the deprecated fields
+// and are allocated with the exact size of the fields (plus extensions)
+pub const STREAM_V2_SIZE: usize = 500;
+pub const STREAM_V2_EXTENSIONS_OFFSET: usize = 414;
+pub const STREAM_V3_SIZE: usize = 390;
+pub const TREASURY_V2_SIZE: usize = 300;
+pub const TREASURY_V2_EXTENSIONS_OFFSET: usize = 284;
+pub const TREASURY_V3_SIZE: usize = 240;
+
 /// Current version of the IDL file. Not to confuse with the program version
 /// written into the IDL file. Every time a new breaking change is done to
 /// the IDL (i.e. added or removed instruction argument or account, argument
 /// type changed, etc.), then this value is increased.
-pub const IDL_FILE_VERSION: u8 = 5;
+pub const IDL_FILE_VERSION: u8 = 10;
@@ -1,9 +1,4 @@
-#[derive(Debug, PartialEq, Clone, Copy)]
-pub enum StreamStatus {
-    Scheduled = 0,
-    Running = 1,
-    Paused = 2,
-}
+pub use msp_math::StreamStatus;
 
 #[derive(Debug, PartialEq)]
 pub enum TreasuryType {
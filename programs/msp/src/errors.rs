@@ -106,4 +106,51 @@ pub enum ErrorCode {
     NumberOfIntervalsMustBePossitive,
     #[msg("The configuration of this template yields a zero rate amount per interval")]
     ZeroRateAmountTemplateConfiguration,
+    #[msg("Invalid stream accrual mode")]
+    InvalidAccrualMode,
+    #[msg("Invalid unlock schedule")]
+    InvalidSchedule,
+    #[msg("The unlock schedule total does not match the stream allocation")]
+    InvalidScheduleTotal,
+    #[msg("Invalid stream schedule account")]
+    InvalidScheduleAccount,
+    #[msg("This operation is not allowed on streams with an unlock schedule")]
+    NotAllowedOnScheduledStream,
+    #[msg("Can not update the rate of a stream from a locked treasury")]
+    UpdateRateNotAllowedOnLockedStreams,
+    #[msg("Can not update the rate of a manually paused stream")]
+    UpdateRateNotAllowedWhilePaused,
+    #[msg("The stream has no pending transfer")]
+    NoPendingStreamTransfer,
+    #[msg("Invalid treasury authority")]
+    InvalidTreasuryAuthority,
+    #[msg("The treasury has no pending authority handover")]
+    NoPendingTreasuryAuthority,
+    #[msg("Invalid stream accounts")]
+    InvalidStreamAccounts,
+    #[msg("Invalid allocation split mode")]
+    InvalidAllocationSplitMode,
+    #[msg("Invalid account extension")]
+    InvalidAccountExtension,
+    #[msg("The treasury is not a native SOL treasury")]
+    NotNativeSolTreasury,
+    #[msg("This operation is not allowed on native SOL treasuries")]
+    NotAllowedOnNativeSolTreasury,
+    #[msg("Invalid fee schedule")]
+    InvalidFeeSchedule,
+    #[msg("Invalid config account")]
+    InvalidConfigAccount,
+    #[msg("Invalid fee tier")]
+    InvalidFeeTier,
+    #[msg("Invalid referrer account")]
+    InvalidReferrerAccount,
+}
+
+impl From<msp_math::MathError> for ErrorCode {
+    fn from(error: msp_math::MathError) -> Self {
+        match error {
+            msp_math::MathError::Overflow => ErrorCode::Overflow,
+            msp_math::MathError::InvalidArgument => ErrorCode::InvalidArgument,
+        }
+    }
 }
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::config::FeeSchedule;
+
 #[event]
 pub struct StreamEvent {
     // state data
@@ -53,6 +55,10 @@ pub struct StreamEvent {
     pub created_on_utc: u64,
     pub category: u8,
     pub sub_category: u8,
+    pub accrual_mode: u8,
+    pub schedule_address: Pubkey,
+    pub cliff_utc_in_seconds: u64,
+    pub pending_beneficiary_address: Pubkey,
 }
 
 #[event]
@@ -73,6 +79,7 @@ pub struct CreateStreamEvent {
     pub timestamp: u64,
     pub sol_fee_charged: u64,
     pub token_fee_charged: u64,
+    pub fee_tier: u8,
     pub stream_start_ts: u64,
     pub stream_rate_amount: u64,
     pub stream_rate_interval: u64,
@@ -93,6 +100,7 @@ pub struct StreamWithdrawEvent {
     pub timestamp: u64,
     pub sol_fee_charged: u64,
     pub token_fee_charged: u64,
+    pub fee_tier: u8,
     pub amount: u64,
     pub token_amount_sent_to_beneficiary: u64,
     pub stream_withdrawable_before: u64,
@@ -108,6 +116,25 @@ pub struct StreamWithdrawEvent {
     pub stream: Pubkey,
     #[index]
     pub treasury: Pubkey,
+    pub destination_token: Pubkey,
+}
+
+#[event]
+pub struct StreamPushWithdrawEvent {
+    pub timestamp: u64,
+    pub sol_fee_charged: u64,
+    pub token_fee_charged: u64,
+    pub fee_tier: u8,
+    pub token_amount_sent_to_beneficiary: u64,
+    pub stream_total_withdrawals_after: u64,
+    /// Lamports payed by the treasury to the cranker
+    pub cranker_reward: u64,
+    pub cranker: Pubkey,
+    pub beneficiary: Pubkey,
+    #[index]
+    pub stream: Pubkey,
+    #[index]
+    pub treasury: Pubkey,
 }
 
 #[event]
@@ -134,6 +161,28 @@ pub struct StreamResumeEvent {
     pub treasury: Pubkey,
 }
 
+#[event]
+pub struct TreasuryAuthorityProposeEvent {
+    pub timestamp: u64,
+    pub sol_fee_charged: u64,
+    pub token_fee_charged: u64,
+    pub authority: Pubkey,
+    pub proposed_authority: Pubkey,
+    #[index]
+    pub treasury: Pubkey,
+}
+
+#[event]
+pub struct TreasuryAuthorityTransferEvent {
+    pub timestamp: u64,
+    pub sol_fee_charged: u64,
+    pub token_fee_charged: u64,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    #[index]
+    pub treasury: Pubkey,
+}
+
 #[event]
 pub struct TreasuryRefreshEvent {
     pub timestamp: u64,
@@ -157,6 +206,32 @@ pub struct StreamTransferEvent {
     pub new_beneficiary: Pubkey,
 }
 
+#[event]
+pub struct StreamTransferProposeEvent {
+    pub timestamp: u64,
+    pub sol_fee_charged: u64,
+    pub token_fee_charged: u64,
+    #[index]
+    pub stream: Pubkey,
+    #[index]
+    pub treasury: Pubkey,
+    pub beneficiary: Pubkey,
+    pub proposed_beneficiary: Pubkey,
+}
+
+#[event]
+pub struct StreamTransferCancelEvent {
+    pub timestamp: u64,
+    pub sol_fee_charged: u64,
+    pub token_fee_charged: u64,
+    #[index]
+    pub stream: Pubkey,
+    #[index]
+    pub treasury: Pubkey,
+    pub beneficiary: Pubkey,
+    pub cancelled_beneficiary: Pubkey,
+}
+
 #[event]
 pub struct TreasuryAddFundsEvent {
     pub timestamp: u64,
@@ -174,6 +249,7 @@ pub struct StreamAllocateEvent {
     pub timestamp: u64,
     pub sol_fee_charged: u64,
     pub token_fee_charged: u64,
+    pub fee_tier: u8,
     pub amount: u64,
     pub stream_status_before: u32,
     pub stream_was_manually_paused_before: bool,
@@ -190,11 +266,32 @@ pub struct StreamAllocateEvent {
     pub treasury: Pubkey,
 }
 
+#[event]
+pub struct StreamRateUpdateEvent {
+    pub timestamp: u64,
+    pub sol_fee_charged: u64,
+    pub token_fee_charged: u64,
+    pub stream_status_before: u32,
+    pub stream_rate_amount_before: u64,
+    pub stream_rate_interval_before: u64,
+    pub stream_rate_amount_after: u64,
+    pub stream_rate_interval_after: u64,
+    /// Units earned at the previous rate (including withdrawals)
+    pub stream_earned_units_before: u64,
+    pub stream_start_ts_after: u64,
+    pub stream_total_seconds_in_paused_status_before: u64,
+    #[index]
+    pub stream: Pubkey,
+    #[index]
+    pub treasury: Pubkey,
+}
+
 #[event]
 pub struct CloseStreamEvent {
     pub timestamp: u64,
     pub sol_fee_charged: u64,
     pub token_fee_charged: u64,
+    pub fee_tier: u8,
     pub token_amount_sent_to_beneficiary: u64,
     pub stream_is_token_withdraw_fee_payed_by_treasury: bool,
     pub stream_allocation_before: u64,
@@ -225,6 +322,7 @@ pub struct TreasuryWithdrawEvent {
     pub timestamp: u64,
     pub sol_fee_charged: u64,
     pub token_fee_charged: u64,
+    pub fee_tier: u8,
     pub amount: u64,
     pub token_amount_sent_to_destination: u64,
     pub treasury_is_sol_fee_payed_by_treasury: bool,
@@ -232,3 +330,20 @@ pub struct TreasuryWithdrawEvent {
     #[index]
     pub treasury: Pubkey,
 }
+
+#[event]
+pub struct ConfigUpdateEvent {
+    pub timestamp: u64,
+    pub admin: Pubkey,
+    pub fee_treasury: Pubkey,
+    pub fees: FeeSchedule,
+}
+
+#[event]
+pub struct FeeOverrideUpdateEvent {
+    pub timestamp: u64,
+    pub tier: u8,
+    pub fees: FeeSchedule,
+    #[index]
+    pub subject: Pubkey,
+}
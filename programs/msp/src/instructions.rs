@@ -5,10 +5,13 @@ use anchor_spl::token::*;
 use crate::constants::*;
 use crate::enums::*;
 use crate::errors::ErrorCode;
+use crate::schedule::*;
 use crate::stream::*;
 use crate::template::*;
 use crate::treasury::*;
+use crate::utils::is_native_sol_treasury;
 use crate::categories::*;
+use crate::config::*;
 
 pub mod fee_treasury {
     anchor_lang::declare_id!("3TD6SWY9M1mLY2kZWJNavPLhwXvcRsWdnZLRaMzERJBw");
@@ -34,7 +37,7 @@ pub struct CreateTreasuryAccounts<'info> {
         payer = payer,
         seeds = [treasurer.key().as_ref(), &slot.to_le_bytes()],
         bump,
-        space = 300,
+        space = TREASURY_V3_SIZE,
         constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
     )]
     pub treasury: Account<'info, Treasury>,
@@ -48,9 +51,12 @@ pub struct CreateTreasuryAccounts<'info> {
     pub treasury_token: Box<Account<'info, TokenAccount>>,
     pub associated_token: Box<Account<'info, Mint>>,
 
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
     #[account(
         mut,
-        constraint = fee_treasury.key() == fee_treasury::ID @ ErrorCode::InvalidFeeTreasuryAccount
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
     )]
     pub fee_treasury: SystemAccount<'info>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -74,15 +80,15 @@ pub struct CreateTreasuryAccounts<'info> {
 pub struct CreateStreamAccounts<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
-    #[account(constraint = treasurer.key() == treasury.treasurer_address @ ErrorCode::NotAuthorized)]
+    #[account(constraint = treasurer.key() == treasury.authority() @ ErrorCode::NotAuthorized)]
     pub treasurer: Signer<'info>,
     #[account(
         mut,
-        seeds = [treasurer.key().as_ref(), &treasury.slot.to_le_bytes()],
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
         bump = treasury.bump,
-        constraint = treasury.version == 2 @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
         constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
-        constraint = treasury.to_account_info().data_len() == 300 @ ErrorCode::InvalidTreasurySize,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
         constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
     )]
     pub treasury: Account<'info, Treasury>,
@@ -93,6 +99,7 @@ pub struct CreateStreamAccounts<'info> {
     )]
     pub treasury_token: Box<Account<'info, TokenAccount>>,
     #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury,
         constraint = associated_token.key() == treasury.associated_token_address @ ErrorCode::InvalidAssociatedToken
     )]
     pub associated_token: Box<Account<'info, Mint>>,
@@ -101,7 +108,7 @@ pub struct CreateStreamAccounts<'info> {
     #[account(
         init,
         payer = payer,
-        space = 500,
+        space = STREAM_V3_SIZE,
         // rate_amount_units and rate_interval_in_seconds are allowed to be
         // equal to zero to support one time payments (OTP)
         // Here, because we are forcing cliff_vest_amount_units to be positive,
@@ -119,9 +126,20 @@ pub struct CreateStreamAccounts<'info> {
         constraint = (cliff_vest_amount_units == 0 || cliff_vest_percent == 0) @ ErrorCode::InvalidCliff,
     )]
     pub stream: Account<'info, Stream>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
     #[account(
         mut,
-        constraint = fee_treasury.key() == fee_treasury::ID @ ErrorCode::InvalidFeeTreasuryAccount
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
     )]
     pub fee_treasury: SystemAccount<'info>,
     #[account(
@@ -154,15 +172,15 @@ pub struct CreateStreamAccounts<'info> {
 pub struct CreateStreamPdaAccounts<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
-    #[account(constraint = treasurer.key() == treasury.treasurer_address @ ErrorCode::NotAuthorized)]
+    #[account(constraint = treasurer.key() == treasury.authority() @ ErrorCode::NotAuthorized)]
     pub treasurer: Signer<'info>,
     #[account(
         mut,
-        seeds = [treasurer.key().as_ref(), &treasury.slot.to_le_bytes()],
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
         bump = treasury.bump,
-        constraint = treasury.version == 2 @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
         constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
-        constraint = treasury.to_account_info().data_len() == 300 @ ErrorCode::InvalidTreasurySize,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
         constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
     )]
     pub treasury: Account<'info, Treasury>,
@@ -173,6 +191,7 @@ pub struct CreateStreamPdaAccounts<'info> {
     )]
     pub treasury_token: Box<Account<'info, TokenAccount>>,
     #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury,
         constraint = associated_token.key() == treasury.associated_token_address @ ErrorCode::InvalidAssociatedToken
     )]
     pub associated_token: Box<Account<'info, Mint>>,
@@ -187,7 +206,7 @@ pub struct CreateStreamPdaAccounts<'info> {
         ],
         bump,
         payer = payer,
-        space = 500,
+        space = STREAM_V3_SIZE,
         // rate_amount_units and rate_interval_in_seconds are allowed to be
         // equal to zero to support one time payments (OTP)
         // Here, because we are forcing cliff_vest_amount_units to be positive,
@@ -205,9 +224,163 @@ pub struct CreateStreamPdaAccounts<'info> {
         constraint = (cliff_vest_amount_units == 0 || cliff_vest_percent == 0) @ ErrorCode::InvalidCliff,
     )]
     pub stream: Account<'info, Stream>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = fee_treasury
+    )]
+    pub fee_treasury_token: Box<Account<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Create Streams Batch
+/// The stream PDAs are passed in the remaining accounts in the same order as
+/// the entries
+#[derive(Accounts)]
+#[instruction(
+    idl_file_version: u8,
+)]
+pub struct CreateStreamsBatchAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(constraint = treasurer.key() == treasury.authority() @ ErrorCode::NotAuthorized)]
+    pub treasurer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        associated_token::mint = associated_token,
+        associated_token::authority = treasury
+    )]
+    pub treasury_token: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury,
+        constraint = associated_token.key() == treasury.associated_token_address @ ErrorCode::InvalidAssociatedToken
+    )]
+    pub associated_token: Box<Account<'info, Mint>>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = fee_treasury
+    )]
+    pub fee_treasury_token: Box<Account<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Create Stream With Schedule
+#[derive(Accounts, Clone)]
+#[instruction(
+    idl_file_version: u8,
+    name: String,
+    allocation_assigned_units: u64,
+    fee_payed_by_treasurer: bool,
+    points: Vec<SchedulePoint>,
+)]
+pub struct CreateStreamWithScheduleAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(constraint = treasurer.key() == treasury.authority() @ ErrorCode::NotAuthorized)]
+    pub treasurer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        associated_token::mint = associated_token,
+        associated_token::authority = treasury
+    )]
+    pub treasury_token: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury,
+        constraint = associated_token.key() == treasury.associated_token_address @ ErrorCode::InvalidAssociatedToken
+    )]
+    pub associated_token: Box<Account<'info, Mint>>,
+    #[account(constraint = beneficiary.key() != treasurer.key() @ ErrorCode::InvalidBeneficiary)]
+    pub beneficiary: SystemAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = STREAM_V3_SIZE,
+        constraint = allocation_assigned_units > 0 @ ErrorCode::InvalidRequestedStreamAllocation,
+    )]
+    pub stream: Account<'info, Stream>,
+    #[account(
+        init,
+        seeds = [b"schedule", stream.key().as_ref()],
+        bump,
+        payer = payer,
+        space = StreamSchedule::space(points.len()),
+    )]
+    pub schedule: Account<'info, StreamSchedule>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
     #[account(
         mut,
-        constraint = fee_treasury.key() == fee_treasury::ID @ ErrorCode::InvalidFeeTreasuryAccount
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
     )]
     pub fee_treasury: SystemAccount<'info>,
     #[account(
@@ -250,7 +423,7 @@ pub struct CreateTreasuryAndTemplateAccounts<'info> {
         payer = payer,
         seeds = [treasurer.key().as_ref(), &slot.to_le_bytes()],
         bump,
-        space = 300,
+        space = TREASURY_V3_SIZE,
         constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
     )]
     pub treasury: Account<'info, Treasury>,
@@ -277,9 +450,12 @@ pub struct CreateTreasuryAndTemplateAccounts<'info> {
 
     pub associated_token: Box<Account<'info, Mint>>,
 
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
     #[account(
         mut,
-        constraint = fee_treasury.key() == fee_treasury::ID @ ErrorCode::InvalidFeeTreasuryAccount
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
     )]
     pub fee_treasury: SystemAccount<'info>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -301,16 +477,16 @@ pub struct CreateStreamTemplateAccounts<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    #[account(constraint = treasurer.key() == treasury.treasurer_address @ ErrorCode::NotAuthorized)]
+    #[account(constraint = treasurer.key() == treasury.authority() @ ErrorCode::NotAuthorized)]
     pub treasurer: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [treasurer.key().as_ref(), &treasury.slot.to_le_bytes()],
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
         bump = treasury.bump,
-        constraint = treasury.version == 2 @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
         constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
-        constraint = treasury.to_account_info().data_len() == 300 @ ErrorCode::InvalidTreasurySize,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
         constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
     )]
     pub treasury: Box<Account<'info, Treasury>>,
@@ -342,16 +518,16 @@ pub struct ModifyStreamTemplateAccounts<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    #[account(constraint = treasurer.key() == treasury.treasurer_address @ ErrorCode::NotAuthorized)]
+    #[account(constraint = treasurer.key() == treasury.authority() @ ErrorCode::NotAuthorized)]
     pub treasurer: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [treasurer.key().as_ref(), &treasury.slot.to_le_bytes()],
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
         bump = treasury.bump,
-        constraint = treasury.version == 2 @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
         constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
-        constraint = treasury.to_account_info().data_len() == 300 @ ErrorCode::InvalidTreasurySize,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
         constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
     )]
     pub treasury: Box<Account<'info, Treasury>>,
@@ -377,15 +553,15 @@ pub struct ModifyStreamTemplateAccounts<'info> {
 pub struct CreateStreamWithTemplateAccounts<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
-    #[account(constraint = treasurer.key() == treasury.treasurer_address @ ErrorCode::NotAuthorized)]
+    #[account(constraint = treasurer.key() == treasury.authority() @ ErrorCode::NotAuthorized)]
     pub treasurer: Signer<'info>,
     #[account(
         mut,
-        seeds = [treasurer.key().as_ref(), &treasury.slot.to_le_bytes()],
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
         bump = treasury.bump,
-        constraint = treasury.version == 2 @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
         constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
-        constraint = treasury.to_account_info().data_len() == 300 @ ErrorCode::InvalidTreasurySize,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
         constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion
     )]
     pub treasury: Box<Account<'info, Treasury>>,
@@ -396,6 +572,7 @@ pub struct CreateStreamWithTemplateAccounts<'info> {
     )]
     pub treasury_token: Box<Account<'info, TokenAccount>>,
     #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury,
         constraint = associated_token.key() == treasury.associated_token_address @ ErrorCode::InvalidAssociatedToken
     )]
     pub associated_token: Box<Account<'info, Mint>>,
@@ -413,13 +590,24 @@ pub struct CreateStreamWithTemplateAccounts<'info> {
     #[account(
         init,
         payer = payer,
-        space = 500,
+        space = STREAM_V3_SIZE,
         // rate_interval_in_seconds > 0 is checked when creating stream template (create_stream_template)
     )]
     pub stream: Box<Account<'info, Stream>>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
     #[account(
         mut,
-        constraint = fee_treasury.key() == fee_treasury::ID @ ErrorCode::InvalidFeeTreasuryAccount
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
     )]
     pub fee_treasury: SystemAccount<'info>,
     #[account(
@@ -446,15 +634,15 @@ pub struct CreateStreamWithTemplateAccounts<'info> {
 pub struct CreateStreamPdaWithTemplateAccounts<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
-    #[account(constraint = treasurer.key() == treasury.treasurer_address @ ErrorCode::NotAuthorized)]
+    #[account(constraint = treasurer.key() == treasury.authority() @ ErrorCode::NotAuthorized)]
     pub treasurer: Signer<'info>,
     #[account(
         mut,
-        seeds = [treasurer.key().as_ref(), &treasury.slot.to_le_bytes()],
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
         bump = treasury.bump,
-        constraint = treasury.version == 2 @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
         constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
-        constraint = treasury.to_account_info().data_len() == 300 @ ErrorCode::InvalidTreasurySize,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
         constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion
     )]
     pub treasury: Box<Account<'info, Treasury>>,
@@ -465,6 +653,7 @@ pub struct CreateStreamPdaWithTemplateAccounts<'info> {
     )]
     pub treasury_token: Box<Account<'info, TokenAccount>>,
     #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury,
         constraint = associated_token.key() == treasury.associated_token_address @ ErrorCode::InvalidAssociatedToken
     )]
     pub associated_token: Box<Account<'info, Mint>>,
@@ -488,13 +677,24 @@ pub struct CreateStreamPdaWithTemplateAccounts<'info> {
         ],
         bump,
         payer = payer,
-        space = 500,
+        space = STREAM_V3_SIZE,
         // rate_interval_in_seconds > 0 is checked when creating stream template (create_stream_template)
     )]
     pub stream: Box<Account<'info, Stream>>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
     #[account(
         mut,
-        constraint = fee_treasury.key() == fee_treasury::ID @ ErrorCode::InvalidFeeTreasuryAccount
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
     )]
     pub fee_treasury: SystemAccount<'info>,
     #[account(
@@ -533,6 +733,7 @@ pub struct WithdrawAccounts<'info> {
     )]
     pub beneficiary_token: Box<Account<'info, TokenAccount>>,
     #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury,
         constraint = (
             associated_token.key() == treasury.associated_token_address &&
             associated_token.key() == stream.beneficiary_associated_token
@@ -543,9 +744,9 @@ pub struct WithdrawAccounts<'info> {
         mut,
         seeds = [stream.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
         bump = treasury.bump,
-        constraint = treasury.version == 2 @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
         constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
-        constraint = treasury.to_account_info().data_len() == 300 @ ErrorCode::InvalidTreasurySize,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
     )]
     pub treasury: Account<'info, Treasury>,
     #[account(
@@ -557,15 +758,26 @@ pub struct WithdrawAccounts<'info> {
     #[account(
         mut,
         constraint = stream.treasury_address == treasury.key() @ ErrorCode::InvalidTreasury,
-        constraint = stream.version == 2 @ ErrorCode::InvalidStreamVersion,
+        constraint = stream.primitive_is_supported_version() @ ErrorCode::InvalidStreamVersion,
         constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
-        constraint = stream.to_account_info().data_len() == 500 @ ErrorCode::InvalidStreamSize,
+        constraint = stream.primitive_has_valid_size(stream.to_account_info().data_len()) @ ErrorCode::InvalidStreamSize,
         constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
     )]
     pub stream: Account<'info, Stream>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
     #[account(
         mut,
-        constraint = fee_treasury.key() == fee_treasury::ID @ ErrorCode::InvalidFeeTreasuryAccount
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
     )]
     pub fee_treasury: SystemAccount<'info>,
     #[account(
@@ -581,52 +793,39 @@ pub struct WithdrawAccounts<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
-/// Pause or Resume Stream
+/// Withdraw To Destination
 #[derive(Accounts)]
-#[instruction(idl_file_version: u8)]
-pub struct PauseOrResumeStreamAccounts<'info> {
+#[instruction(
+    idl_file_version: u8,
+    amount: u64,
+)]
+pub struct WithdrawToDestinationAccounts<'info> {
     #[account(
-        constraint = (
-            initializer.key() == stream.treasurer_address
-        ) @ ErrorCode::NotAuthorized
+        constraint = amount > 0 @ ErrorCode::ZeroWithdrawalAmount,
+        constraint = beneficiary.key() == stream.beneficiary_address @ ErrorCode::InvalidBeneficiary,
     )]
-    pub initializer: Signer<'info>,
+    pub beneficiary: Signer<'info>,
+    /// Any token account of the stream mint
     #[account(
         mut,
-        seeds = [stream.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
-        bump = treasury.bump,
-        constraint = treasury.version == 2 @ ErrorCode::InvalidTreasuryVersion,
-        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
-        constraint = treasury.to_account_info().data_len() == 300 @ ErrorCode::InvalidTreasurySize,
-        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+        constraint = destination_token.mint == associated_token.key() @ ErrorCode::InvalidAssociatedToken,
     )]
-    pub treasury: Account<'info, Treasury>,
+    pub destination_token: Box<Account<'info, TokenAccount>>,
     #[account(
-        mut,
-        constraint = stream.treasury_address == treasury.key() @ ErrorCode::InvalidTreasury,
-        constraint = stream.version == 2 @ ErrorCode::InvalidStreamVersion,
-        constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
-        constraint = stream.to_account_info().data_len() == 500 @ ErrorCode::InvalidStreamSize,
-        constraint = treasury.treasury_type != TREASURY_TYPE_LOCKED @ ErrorCode::PauseOrResumeLockedStreamNotAllowed,
-        constraint = stream.beneficiary_associated_token == treasury.associated_token_address @ ErrorCode::InvalidAssociatedToken,
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury,
+        constraint = (
+            associated_token.key() == treasury.associated_token_address &&
+            associated_token.key() == stream.beneficiary_associated_token
+        ) @ ErrorCode::InvalidAssociatedToken
     )]
-    pub stream: Account<'info, Stream>,
-}
-
-/// Refresh Treasury Data
-#[derive(Accounts)]
-#[instruction(idl_file_version: u8)]
-pub struct RefreshTreasuryDataAccounts<'info> {
-    #[account(constraint = associated_token.key() == treasury.associated_token_address @ ErrorCode::InvalidAssociatedToken)]
-    pub associated_token: Account<'info, Mint>,
+    pub associated_token: Box<Account<'info, Mint>>,
     #[account(
         mut,
-        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        seeds = [stream.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
         bump = treasury.bump,
-        constraint = treasury.version == 2 @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
         constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
-        constraint = treasury.to_account_info().data_len() == 300 @ ErrorCode::InvalidTreasurySize,
-        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
     )]
     pub treasury: Account<'info, Treasury>,
     #[account(
@@ -634,91 +833,105 @@ pub struct RefreshTreasuryDataAccounts<'info> {
         associated_token::mint = associated_token,
         associated_token::authority = treasury
     )]
-    pub treasury_token: Account<'info, TokenAccount>,
-}
-
-/// Transfer Stream
-#[derive(Accounts)]
-#[instruction(
-    idl_file_version: u8,
-    new_beneficiary: Pubkey,
-)]
-pub struct TransferStreamAccounts<'info> {
-    #[account(
-        mut,
-        constraint = beneficiary.key() == stream.beneficiary_address @ ErrorCode::NotAuthorized
-    )]
-    pub beneficiary: Signer<'info>,
+    pub treasury_token: Box<Account<'info, TokenAccount>>,
     #[account(
         mut,
-        constraint = stream.version == 2 @ ErrorCode::InvalidStreamVersion,
+        constraint = stream.treasury_address == treasury.key() @ ErrorCode::InvalidTreasury,
+        constraint = stream.primitive_is_supported_version() @ ErrorCode::InvalidStreamVersion,
         constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
-        constraint = stream.to_account_info().data_len() == 500 @ ErrorCode::InvalidStreamSize,
+        constraint = stream.primitive_has_valid_size(stream.to_account_info().data_len()) @ ErrorCode::InvalidStreamSize,
         constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
     )]
     pub stream: Account<'info, Stream>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
     #[account(
         mut,
-        constraint = fee_treasury.key() == fee_treasury::ID @ ErrorCode::InvalidFeeTreasuryAccount
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
     )]
     pub fee_treasury: SystemAccount<'info>,
-    pub system_program: Program<'info, System>,
-}
-
-/// Get Stream
-#[derive(Accounts)]
-pub struct GetStreamAccounts<'info> {
     #[account(
-        constraint = stream.version == 2 @ ErrorCode::InvalidStreamVersion,
-        constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
-        constraint = stream.to_account_info().data_len() == 500 @ ErrorCode::InvalidStreamSize
+        mut,
+        associated_token::mint = associated_token,
+        associated_token::authority = fee_treasury
     )]
-    pub stream: Account<'info, Stream>,
+    pub fee_treasury_token: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
 }
 
+/// Push Withdraw
 #[derive(Accounts)]
-#[instruction(
-    idl_file_version: u8,
-    amount: u64,
-)]
-pub struct AddFundsAccounts<'info> {
+#[instruction(idl_file_version: u8)]
+pub struct PushWithdrawAccounts<'info> {
+    /// Anyone can crank the withdrawal on behalf of the beneficiary
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
-        mut,
-        constraint = amount > 0 @ ErrorCode::ZeroContributionAmount
+        constraint = beneficiary.key() == stream.beneficiary_address @ ErrorCode::InvalidBeneficiary,
     )]
-    pub contributor: Signer<'info>,
+    pub beneficiary: SystemAccount<'info>,
     #[account(
-        mut,
+        init_if_needed,
+        payer = payer,
         associated_token::mint = associated_token,
-        associated_token::authority = contributor,
-        constraint = contributor_token.amount >= amount @ ErrorCode::InsufficientFunds,
+        associated_token::authority = beneficiary
     )]
-    pub contributor_token: Box<Account<'info, TokenAccount>>,
+    pub beneficiary_token: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury,
+        constraint = (
+            associated_token.key() == treasury.associated_token_address &&
+            associated_token.key() == stream.beneficiary_associated_token
+        ) @ ErrorCode::InvalidAssociatedToken
+    )]
+    pub associated_token: Box<Account<'info, Mint>>,
     #[account(
         mut,
-        constraint = treasury.version == 2 @ ErrorCode::InvalidTreasuryVersion,
+        seeds = [stream.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
         constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
-        constraint = treasury.to_account_info().data_len() == 300 @ ErrorCode::InvalidTreasurySize,
-        constraint = (
-            treasury.associated_token_address == Pubkey::default() ||
-            treasury.associated_token_address == associated_token.key()
-        ) @ ErrorCode::InvalidAssociatedToken,
-        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
     )]
     pub treasury: Account<'info, Treasury>,
     #[account(
-        init_if_needed,
-        payer = payer,
+        mut,
         associated_token::mint = associated_token,
         associated_token::authority = treasury
     )]
     pub treasury_token: Box<Account<'info, TokenAccount>>,
-    pub associated_token: Box<Account<'info, Mint>>,
     #[account(
         mut,
-        constraint = fee_treasury.key() == fee_treasury::ID @ ErrorCode::InvalidFeeTreasuryAccount
+        constraint = stream.treasury_address == treasury.key() @ ErrorCode::InvalidTreasury,
+        constraint = stream.primitive_is_supported_version() @ ErrorCode::InvalidStreamVersion,
+        constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
+        constraint = stream.primitive_has_valid_size(stream.to_account_info().data_len()) @ ErrorCode::InvalidStreamSize,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub stream: Account<'info, Stream>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
     )]
     pub fee_treasury: SystemAccount<'info>,
     #[account(
@@ -734,208 +947,1259 @@ pub struct AddFundsAccounts<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+/// Withdraw Many
+/// The (treasury, treasury_token, stream) triples to withdraw from are passed
+/// in the remaining accounts, each followed by the stream schedule when the
+/// stream has one
 #[derive(Accounts)]
-#[instruction(
-    idl_file_version: u8,
-    amount: u64,
-)]
-pub struct AllocateAccounts<'info> {
+#[instruction(idl_file_version: u8)]
+pub struct WithdrawManyAccounts<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
-    #[account()]
-    pub treasurer: Signer<'info>,
+    #[account(constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion)]
+    pub beneficiary: Signer<'info>,
     #[account(
-        mut,
-        seeds = [stream.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
-        bump = treasury.bump,
-
-        constraint = treasury.version == 2 @ ErrorCode::InvalidTreasuryVersion,
-        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
-        constraint = treasury.to_account_info().data_len() == 300 @ ErrorCode::InvalidTreasurySize,
-        constraint = treasury.associated_token_address == associated_token.key() @ ErrorCode::InvalidAssociatedToken,
-        constraint = treasury.treasury_type != TREASURY_TYPE_LOCKED @ ErrorCode::AllocateNotAllowedOnLockedStreams,
-        constraint = treasury.treasurer_address == treasurer.key() @ ErrorCode::InvalidTreasurer,
-        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = beneficiary
+    )]
+    pub beneficiary_token: Box<Account<'info, TokenAccount>>,
+    pub associated_token: Box<Account<'info, Mint>>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = fee_treasury
+    )]
+    pub fee_treasury_token: Box<Account<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Pause or Resume Stream
+#[derive(Accounts)]
+#[instruction(idl_file_version: u8)]
+pub struct PauseOrResumeStreamAccounts<'info> {
+    #[account(
+        constraint = (
+            initializer.key() == treasury.authority()
+        ) @ ErrorCode::NotAuthorized
+    )]
+    pub initializer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [stream.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        constraint = stream.treasury_address == treasury.key() @ ErrorCode::InvalidTreasury,
+        constraint = stream.primitive_is_supported_version() @ ErrorCode::InvalidStreamVersion,
+        constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
+        constraint = stream.primitive_has_valid_size(stream.to_account_info().data_len()) @ ErrorCode::InvalidStreamSize,
+        constraint = treasury.treasury_type != TREASURY_TYPE_LOCKED @ ErrorCode::PauseOrResumeLockedStreamNotAllowed,
+        constraint = !stream.primitive_has_schedule() @ ErrorCode::NotAllowedOnScheduledStream,
+        constraint = stream.beneficiary_associated_token == treasury.associated_token_address @ ErrorCode::InvalidAssociatedToken,
+    )]
+    pub stream: Account<'info, Stream>,
+}
+
+/// Update Stream Rate
+#[derive(Accounts)]
+#[instruction(
+    idl_file_version: u8,
+    rate_amount_units: u64,
+    rate_interval_in_seconds: u64,
+)]
+pub struct UpdateStreamRateAccounts<'info> {
+    #[account(
+        constraint = (
+            stream.treasurer_address == treasury.treasurer_address &&
+            treasurer.key() == treasury.authority()
+        ) @ ErrorCode::InvalidTreasurer
+    )]
+    pub treasurer: Signer<'info>,
+    #[account(
+        seeds = [stream.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = treasury.treasury_type != TREASURY_TYPE_LOCKED @ ErrorCode::UpdateRateNotAllowedOnLockedStreams,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        constraint = stream.treasury_address == treasury.key() @ ErrorCode::InvalidTreasury,
+        constraint = stream.primitive_is_supported_version() @ ErrorCode::InvalidStreamVersion,
+        constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
+        constraint = stream.primitive_has_valid_size(stream.to_account_info().data_len()) @ ErrorCode::InvalidStreamSize,
+        constraint = !stream.primitive_has_schedule() @ ErrorCode::NotAllowedOnScheduledStream,
+        constraint = rate_amount_units > 0 && rate_interval_in_seconds > 0 @ ErrorCode::InvalidStreamRate,
+    )]
+    pub stream: Account<'info, Stream>,
+}
+
+/// Propose Treasury Authority
+#[derive(Accounts)]
+#[instruction(
+    idl_file_version: u8,
+    new_authority: Pubkey,
+)]
+pub struct ProposeTreasuryAuthorityAccounts<'info> {
+    #[account(constraint = authority.key() == treasury.authority() @ ErrorCode::NotAuthorized)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+}
+
+/// Accept Treasury Authority
+#[derive(Accounts)]
+#[instruction(idl_file_version: u8)]
+pub struct AcceptTreasuryAuthorityAccounts<'info> {
+    #[account(constraint = new_authority.key() == treasury.pending_authority_address @ ErrorCode::NotAuthorized)]
+    pub new_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = treasury.pending_authority_address != Pubkey::default() @ ErrorCode::NoPendingTreasuryAuthority,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+}
+
+/// Refresh Treasury Data
+#[derive(Accounts)]
+#[instruction(idl_file_version: u8)]
+pub struct RefreshTreasuryDataAccounts<'info> {
+    #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury,
+        constraint = associated_token.key() == treasury.associated_token_address @ ErrorCode::InvalidAssociatedToken
+    )]
+    pub associated_token: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        associated_token::mint = associated_token,
+        associated_token::authority = treasury
+    )]
+    pub treasury_token: Account<'info, TokenAccount>,
+}
+
+/// Transfer Stream
+#[derive(Accounts)]
+#[instruction(
+    idl_file_version: u8,
+    new_beneficiary: Pubkey,
+)]
+pub struct TransferStreamAccounts<'info> {
+    #[account(
+        mut,
+        constraint = beneficiary.key() == stream.beneficiary_address @ ErrorCode::NotAuthorized
+    )]
+    pub beneficiary: Signer<'info>,
+    #[account(
+        mut,
+        constraint = stream.primitive_is_supported_version() @ ErrorCode::InvalidStreamVersion,
+        constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
+        constraint = stream.primitive_has_valid_size(stream.to_account_info().data_len()) @ ErrorCode::InvalidStreamSize,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub stream: Account<'info, Stream>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Cancel Stream Transfer
+#[derive(Accounts)]
+#[instruction(idl_file_version: u8)]
+pub struct CancelStreamTransferAccounts<'info> {
+    #[account(
+        constraint = beneficiary.key() == stream.beneficiary_address @ ErrorCode::NotAuthorized
+    )]
+    pub beneficiary: Signer<'info>,
+    #[account(
+        mut,
+        constraint = stream.primitive_is_supported_version() @ ErrorCode::InvalidStreamVersion,
+        constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
+        constraint = stream.primitive_has_valid_size(stream.to_account_info().data_len()) @ ErrorCode::InvalidStreamSize,
+        constraint = stream.pending_beneficiary_address != Pubkey::default() @ ErrorCode::NoPendingStreamTransfer,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub stream: Account<'info, Stream>,
+}
+
+/// Accept Stream Transfer
+#[derive(Accounts)]
+#[instruction(idl_file_version: u8)]
+pub struct AcceptStreamTransferAccounts<'info> {
+    #[account(
+        constraint = new_beneficiary.key() == stream.pending_beneficiary_address @ ErrorCode::NotAuthorized
+    )]
+    pub new_beneficiary: Signer<'info>,
+    #[account(
+        mut,
+        constraint = stream.primitive_is_supported_version() @ ErrorCode::InvalidStreamVersion,
+        constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
+        constraint = stream.primitive_has_valid_size(stream.to_account_info().data_len()) @ ErrorCode::InvalidStreamSize,
+        constraint = stream.pending_beneficiary_address != Pubkey::default() @ ErrorCode::NoPendingStreamTransfer,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub stream: Account<'info, Stream>,
+}
+
+/// Get Stream
+#[derive(Accounts)]
+pub struct GetStreamAccounts<'info> {
+    #[account(
+        constraint = stream.primitive_is_supported_version() @ ErrorCode::InvalidStreamVersion,
+        constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
+        constraint = stream.primitive_has_valid_size(stream.to_account_info().data_len()) @ ErrorCode::InvalidStreamSize
+    )]
+    pub stream: Account<'info, Stream>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    idl_file_version: u8,
+    amount: u64,
+)]
+pub struct AddFundsAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        constraint = amount > 0 @ ErrorCode::ZeroContributionAmount
+    )]
+    pub contributor: Signer<'info>,
+    #[account(
+        mut,
+        associated_token::mint = associated_token,
+        associated_token::authority = contributor,
+        constraint = contributor_token.amount >= amount @ ErrorCode::InsufficientFunds,
+    )]
+    pub contributor_token: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = (
+            treasury.associated_token_address == Pubkey::default() ||
+            treasury.associated_token_address == associated_token.key()
+        ) @ ErrorCode::InvalidAssociatedToken,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = treasury
+    )]
+    pub treasury_token: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury
+    )]
+    pub associated_token: Box<Account<'info, Mint>>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = fee_treasury
+    )]
+    pub fee_treasury_token: Box<Account<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    idl_file_version: u8,
+    amount: u64,
+)]
+pub struct AllocateAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account()]
+    pub treasurer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [stream.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = treasury.associated_token_address == associated_token.key() @ ErrorCode::InvalidAssociatedToken,
+        constraint = treasury.treasury_type != TREASURY_TYPE_LOCKED @ ErrorCode::AllocateNotAllowedOnLockedStreams,
+        constraint = treasury.authority() == treasurer.key() @ ErrorCode::InvalidTreasurer,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        associated_token::mint = associated_token,
+        associated_token::authority = treasury
+    )]
+    pub treasury_token: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury
+    )]
+    pub associated_token: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        constraint = stream.treasury_address == treasury.key() @ ErrorCode::InvalidTreasury,
+        constraint = stream.primitive_is_supported_version() @ ErrorCode::InvalidStreamVersion,
+        constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
+        constraint = stream.primitive_has_valid_size(stream.to_account_info().data_len()) @ ErrorCode::InvalidStreamSize,
+        constraint = stream.treasurer_address == treasury.treasurer_address @ ErrorCode::InvalidTreasurer,
+        constraint = stream.beneficiary_associated_token == associated_token.key() @ ErrorCode::InvalidAssociatedToken,
+        constraint = amount > 0 @ ErrorCode::ZeroContributionAmount,
+        constraint = !stream.primitive_has_schedule() @ ErrorCode::NotAllowedOnScheduledStream,
+        constraint = stream.rate_amount_units > 0 && stream.rate_interval_in_seconds > 0 @ ErrorCode::InvalidStreamRate,
+    )]
+    pub stream: Account<'info, Stream>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = fee_treasury
+    )]
+    pub fee_treasury_token: Box<Account<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Allocate To All Streams
+/// The streams to allocate to are passed in the remaining accounts, each
+/// followed by the stream schedule when the stream has one
+#[derive(Accounts)]
+#[instruction(
+    idl_file_version: u8,
+    amount: u64,
+    split_mode: u8,
+)]
+pub struct AllocateToAllStreamsAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account()]
+    pub treasurer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = treasury.associated_token_address == associated_token.key() @ ErrorCode::InvalidAssociatedToken,
+        constraint = treasury.treasury_type != TREASURY_TYPE_LOCKED @ ErrorCode::AllocateNotAllowedOnLockedStreams,
+        constraint = treasury.authority() == treasurer.key() @ ErrorCode::InvalidTreasurer,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+        constraint = amount > 0 @ ErrorCode::ZeroContributionAmount,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        associated_token::mint = associated_token,
+        associated_token::authority = treasury
+    )]
+    pub treasury_token: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury
+    )]
+    pub associated_token: Box<Account<'info, Mint>>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = fee_treasury
+    )]
+    pub fee_treasury_token: Box<Account<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(idl_file_version: u8)]
+pub struct CloseStreamAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        constraint = (
+            stream.treasurer_address == treasury.treasurer_address &&
+            treasurer.key() == treasury.authority()
+        ) @ ErrorCode::InvalidTreasurer
+    )]
+    pub treasurer: Signer<'info>,
+    #[account(
+        constraint = beneficiary.key() == stream.beneficiary_address @ ErrorCode::InvalidBeneficiary
+    )]
+    pub beneficiary: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = beneficiary
+    )]
+    pub beneficiary_token: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury,
+        constraint = (
+            associated_token.key() == stream.beneficiary_associated_token &&
+            associated_token.key() == treasury.associated_token_address
+        ) @ ErrorCode::InvalidAssociatedToken,
+    )]
+    pub associated_token: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [stream.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        associated_token::mint = associated_token,
+        associated_token::authority = treasury
+    )]
+    pub treasury_token: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        close = payer,
+        constraint = stream.treasury_address == treasury.key() @ ErrorCode::InvalidTreasury,
+        constraint = stream.primitive_is_supported_version() @ ErrorCode::InvalidStreamVersion,
+        constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
+        constraint = stream.primitive_has_valid_size(stream.to_account_info().data_len()) @ ErrorCode::InvalidStreamSize,
+        // locked streams with a schedule are checked against it in the handler
+        constraint = (
+            treasury.treasury_type != TREASURY_TYPE_LOCKED 
+            || stream.primitive_has_schedule()
+            || stream.get_status(Clock::get()?.unix_timestamp as u64)? == StreamStatus::Paused
+            || stream.get_status(Clock::get()?.unix_timestamp as u64)? == StreamStatus::Scheduled
+        ) @ ErrorCode::CloseLockedStreamNotAllowedWhileRunning
+    )]
+    pub stream: Account<'info, Stream>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = fee_treasury
+    )]
+    pub fee_treasury_token: Box<Account<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Renounce Stream
+#[derive(Accounts)]
+#[instruction(idl_file_version: u8)]
+pub struct RenounceStreamAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        constraint = beneficiary.key() == stream.beneficiary_address @ ErrorCode::InvalidBeneficiary
+    )]
+    pub beneficiary: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = beneficiary
+    )]
+    pub beneficiary_token: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury,
+        constraint = (
+            associated_token.key() == stream.beneficiary_associated_token &&
+            associated_token.key() == treasury.associated_token_address
+        ) @ ErrorCode::InvalidAssociatedToken,
+    )]
+    pub associated_token: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        constraint = treasurer.key() == treasury.authority() @ ErrorCode::InvalidTreasurer
+    )]
+    pub treasurer: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [stream.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        associated_token::mint = associated_token,
+        associated_token::authority = treasury
+    )]
+    pub treasury_token: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        close = treasurer,
+        constraint = stream.treasury_address == treasury.key() @ ErrorCode::InvalidTreasury,
+        constraint = stream.primitive_is_supported_version() @ ErrorCode::InvalidStreamVersion,
+        constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
+        constraint = stream.primitive_has_valid_size(stream.to_account_info().data_len()) @ ErrorCode::InvalidStreamSize,
+    )]
+    pub stream: Account<'info, Stream>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = fee_treasury
+    )]
+    pub fee_treasury_token: Box<Account<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(idl_file_version: u8)]
+pub struct CloseTreasuryAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        constraint = treasurer.key() == treasury.authority() @ ErrorCode::InvalidTreasurer
+    )]
+    pub treasurer: Signer<'info>,
+    #[account(mut)]
+    //#[soteria(ignore)]
+    pub destination_authority: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = destination_authority,
+    )]
+    pub destination_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury,
+        constraint = (
+            treasury.associated_token_address == Pubkey::default() ||
+            associated_token.key() == treasury.associated_token_address
+         ) @ ErrorCode::InvalidAssociatedToken
+    )]
+    pub associated_token: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        close = destination_authority,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = treasury.total_streams == 0 @ ErrorCode::TreasuryContainsStreams,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = treasury
+    )]
+    pub treasury_token: Box<Account<'info, TokenAccount>>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = fee_treasury
+    )]
+    pub fee_treasury_token: Box<Account<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Close Treasury And Streams
+/// The treasury streams are passed in the remaining accounts as
+/// (stream, beneficiary_token) pairs, followed by the stream schedule when the
+/// stream has one
+#[derive(Accounts)]
+#[instruction(idl_file_version: u8)]
+pub struct CloseTreasuryAndStreamsAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        constraint = treasurer.key() == treasury.authority() @ ErrorCode::InvalidTreasurer
+    )]
+    pub treasurer: Signer<'info>,
+    #[account(mut)]
+    pub destination_authority: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = destination_authority,
+    )]
+    pub destination_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury,
+        constraint = (
+            treasury.associated_token_address == Pubkey::default() ||
+            associated_token.key() == treasury.associated_token_address
+         ) @ ErrorCode::InvalidAssociatedToken
+    )]
+    pub associated_token: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        close = destination_authority,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = treasury
+    )]
+    pub treasury_token: Box<Account<'info, TokenAccount>>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = associated_token,
+        associated_token::authority = fee_treasury
+    )]
+    pub fee_treasury_token: Box<Account<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Migrate Stream
+#[derive(Accounts)]
+#[instruction(idl_file_version: u8)]
+pub struct MigrateStreamAccounts<'info> {
+    /// Gets the rent exceeding the v3 stream size back
+    #[account(
+        mut,
+        constraint = authority.key() == treasury.authority() @ ErrorCode::NotAuthorized
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        constraint = stream.treasury_address == treasury.key() @ ErrorCode::InvalidTreasury,
+        constraint = stream.primitive_needs_migration() @ ErrorCode::InvalidStreamVersion,
+        constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
+        constraint = stream.primitive_has_valid_size(stream.to_account_info().data_len()) @ ErrorCode::InvalidStreamSize,
+    )]
+    pub stream: Account<'info, Stream>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Migrate Treasury
+#[derive(Accounts)]
+#[instruction(idl_file_version: u8)]
+pub struct MigrateTreasuryAccounts<'info> {
+    /// Gets the rent exceeding the v3 treasury size back
+    #[account(
+        mut,
+        constraint = authority.key() == treasury.authority() @ ErrorCode::NotAuthorized
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.needs_migration() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Create Native Treasury
+/// Native SOL treasuries hold their balance as lamports of the treasury PDA
+#[derive(Accounts)]
+#[instruction(
+    idl_file_version: u8,
+    slot: u64,
+)]
+pub struct CreateNativeTreasuryAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub treasurer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [treasurer.key().as_ref(), &slot.to_le_bytes()],
+        bump,
+        space = TREASURY_V3_SIZE,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
     )]
     pub treasury: Account<'info, Treasury>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
     #[account(
         mut,
-        associated_token::mint = associated_token,
-        associated_token::authority = treasury
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
     )]
-    pub treasury_token: Box<Account<'info, TokenAccount>>,
-    pub associated_token: Box<Account<'info, Mint>>,
+    pub fee_treasury: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Add Funds Native
+#[derive(Accounts)]
+#[instruction(
+    idl_file_version: u8,
+    amount: u64,
+)]
+pub struct AddFundsNativeAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
     #[account(
         mut,
-        constraint = stream.treasury_address == treasury.key() @ ErrorCode::InvalidTreasury,
-        constraint = stream.version == 2 @ ErrorCode::InvalidStreamVersion,
-        constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
-        constraint = stream.to_account_info().data_len() == 500 @ ErrorCode::InvalidStreamSize,
-        constraint = stream.treasurer_address == treasurer.key() @ ErrorCode::InvalidTreasurer,
-        constraint = stream.beneficiary_associated_token == associated_token.key() @ ErrorCode::InvalidAssociatedToken,
-        constraint = amount > 0 @ ErrorCode::ZeroContributionAmount,
-        constraint = stream.rate_amount_units > 0 && stream.rate_interval_in_seconds > 0 @ ErrorCode::InvalidStreamRate,
+        constraint = amount > 0 @ ErrorCode::ZeroContributionAmount
     )]
-    pub stream: Account<'info, Stream>,
+    pub contributor: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotNativeSolTreasury,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
     #[account(
         mut,
-        constraint = fee_treasury.key() == fee_treasury::ID @ ErrorCode::InvalidFeeTreasuryAccount
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
     )]
     pub fee_treasury: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Create Stream Native
+#[derive(Accounts)]
+#[instruction(
+    idl_file_version: u8,
+    name: String,
+    start_utc: u64,
+    rate_amount_units: u64,
+    rate_interval_in_seconds: u64,
+    allocation_assigned_units: u64,
+    cliff_vest_amount_units: u64,
+)]
+pub struct CreateStreamNativeAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(constraint = treasurer.key() == treasury.authority() @ ErrorCode::NotAuthorized)]
+    pub treasurer: Signer<'info>,
     #[account(
-        init_if_needed,
+        mut,
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotNativeSolTreasury,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(constraint = beneficiary.key() != treasurer.key() @ ErrorCode::InvalidBeneficiary)]
+    pub beneficiary: SystemAccount<'info>,
+    #[account(
+        init,
         payer = payer,
-        associated_token::mint = associated_token,
-        associated_token::authority = fee_treasury
+        space = STREAM_V3_SIZE,
+        // same rules as create_stream (one time payments included)
+        constraint = (
+                rate_amount_units == 0 &&
+                rate_interval_in_seconds == 0 &&
+                cliff_vest_amount_units > 0 &&
+                cliff_vest_amount_units == allocation_assigned_units) ||
+            (rate_amount_units > 0 && rate_interval_in_seconds > 0)
+            @ ErrorCode::InvalidStreamRate,
+        constraint = allocation_assigned_units >= cliff_vest_amount_units @ ErrorCode::InvalidCliff,
     )]
-    pub fee_treasury_token: Box<Account<'info, TokenAccount>>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    pub stream: Account<'info, Stream>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraw Native
+#[derive(Accounts)]
+#[instruction(
+    idl_file_version: u8,
+    amount: u64,
+)]
+pub struct WithdrawNativeAccounts<'info> {
+    #[account(
+        mut,
+        constraint = amount > 0 @ ErrorCode::ZeroWithdrawalAmount,
+        constraint = beneficiary.key() == stream.beneficiary_address @ ErrorCode::InvalidBeneficiary,
+    )]
+    pub beneficiary: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [stream.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotNativeSolTreasury,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        constraint = stream.treasury_address == treasury.key() @ ErrorCode::InvalidTreasury,
+        constraint = stream.primitive_is_supported_version() @ ErrorCode::InvalidStreamVersion,
+        constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
+        constraint = stream.primitive_has_valid_size(stream.to_account_info().data_len()) @ ErrorCode::InvalidStreamSize,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub stream: Account<'info, Stream>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
+    )]
+    pub fee_treasury: SystemAccount<'info>,
     pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
+/// Close Stream Native
 #[derive(Accounts)]
 #[instruction(idl_file_version: u8)]
-pub struct CloseStreamAccounts<'info> {
+pub struct CloseStreamNativeAccounts<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
         mut,
         constraint = (
-            treasurer.key() == stream.treasurer_address &&
-            treasurer.key() == treasury.treasurer_address
+            stream.treasurer_address == treasury.treasurer_address &&
+            treasurer.key() == treasury.authority()
         ) @ ErrorCode::InvalidTreasurer
     )]
     pub treasurer: Signer<'info>,
     #[account(
+        mut,
         constraint = beneficiary.key() == stream.beneficiary_address @ ErrorCode::InvalidBeneficiary
     )]
     pub beneficiary: SystemAccount<'info>,
-    #[account(
-        init_if_needed,
-        payer = payer,
-        associated_token::mint = associated_token,
-        associated_token::authority = beneficiary
-    )]
-    pub beneficiary_token: Box<Account<'info, TokenAccount>>,
-    #[account(
-        constraint = (
-            associated_token.key() == stream.beneficiary_associated_token &&
-            associated_token.key() == treasury.associated_token_address
-        ) @ ErrorCode::InvalidAssociatedToken,
-    )]
-    pub associated_token: Box<Account<'info, Mint>>,
     #[account(
         mut,
         seeds = [stream.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
         bump = treasury.bump,
-        constraint = treasury.version == 2 @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
         constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
-        constraint = treasury.to_account_info().data_len() == 300 @ ErrorCode::InvalidTreasurySize,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotNativeSolTreasury,
         constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
     )]
     pub treasury: Account<'info, Treasury>,
-    #[account(
-        mut,
-        associated_token::mint = associated_token,
-        associated_token::authority = treasury
-    )]
-    pub treasury_token: Box<Account<'info, TokenAccount>>,
     #[account(
         mut,
         close = payer,
         constraint = stream.treasury_address == treasury.key() @ ErrorCode::InvalidTreasury,
-        constraint = stream.version == 2 @ ErrorCode::InvalidStreamVersion,
+        constraint = stream.primitive_is_supported_version() @ ErrorCode::InvalidStreamVersion,
         constraint = stream.initialized == true @ ErrorCode::StreamNotInitialized,
-        constraint = stream.to_account_info().data_len() == 500 @ ErrorCode::InvalidStreamSize,
+        constraint = stream.primitive_has_valid_size(stream.to_account_info().data_len()) @ ErrorCode::InvalidStreamSize,
+        // locked streams with a schedule are checked against it in the handler
         constraint = (
-            treasury.treasury_type != TREASURY_TYPE_LOCKED 
+            treasury.treasury_type != TREASURY_TYPE_LOCKED
+            || stream.primitive_has_schedule()
             || stream.get_status(Clock::get()?.unix_timestamp as u64)? == StreamStatus::Paused
             || stream.get_status(Clock::get()?.unix_timestamp as u64)? == StreamStatus::Scheduled
         ) @ ErrorCode::CloseLockedStreamNotAllowedWhileRunning
     )]
     pub stream: Account<'info, Stream>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
     #[account(
         mut,
-        constraint = fee_treasury.key() == fee_treasury::ID @ ErrorCode::InvalidFeeTreasuryAccount
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
     )]
     pub fee_treasury: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Treasury Withdraw Native
+/// Withdraws unallocated lamports from a native SOL treasury. The treasury
+/// keeps the rent exempt minimum
+#[derive(Accounts)]
+#[instruction(
+    idl_file_version: u8,
+    amount: u64,
+)]
+pub struct TreasuryWithdrawNativeAccounts<'info> {
+    #[account(address = treasury.authority() @ ErrorCode::InvalidTreasurer)]
+    pub treasurer: Signer<'info>,
+    #[account(mut)]
+    //#[soteria(ignore)]
+    pub destination_authority: SystemAccount<'info>,
     #[account(
-        init_if_needed,
-        payer = payer,
-        associated_token::mint = associated_token,
-        associated_token::authority = fee_treasury
+        mut,
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
+        bump = treasury.bump,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotNativeSolTreasury,
+        constraint = amount > 0 @ ErrorCode::InvalidWithdrawalAmount,
+        constraint = treasury.last_known_unallocated_balance()? >= amount @ ErrorCode::InsufficientTreasuryBalance,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
     )]
-    pub fee_treasury_token: Box<Account<'info, TokenAccount>>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    pub treasury: Account<'info, Treasury>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
+    )]
+    pub fee_treasury: SystemAccount<'info>,
     pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
+/// Close Treasury Native
+/// The treasury lamports, rent included, go to the destination authority
 #[derive(Accounts)]
 #[instruction(idl_file_version: u8)]
-pub struct CloseTreasuryAccounts<'info> {
+pub struct CloseTreasuryNativeAccounts<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
         mut,
-        constraint = treasurer.key() == treasury.treasurer_address @ ErrorCode::InvalidTreasurer
+        constraint = treasurer.key() == treasury.authority() @ ErrorCode::InvalidTreasurer
     )]
     pub treasurer: Signer<'info>,
     #[account(mut)]
     //#[soteria(ignore)]
     pub destination_authority: SystemAccount<'info>,
-    #[account(
-        init_if_needed,
-        payer = payer,
-        associated_token::mint = associated_token,
-        associated_token::authority = destination_authority,
-    )]
-    pub destination_token_account: Box<Account<'info, TokenAccount>>,
-    #[account(
-        constraint = (
-            treasury.associated_token_address == Pubkey::default() ||
-            associated_token.key() == treasury.associated_token_address
-         ) @ ErrorCode::InvalidAssociatedToken
-    )]
-    pub associated_token: Box<Account<'info, Mint>>,
     #[account(
         mut,
-        seeds = [treasurer.key().as_ref(), &treasury.slot.to_le_bytes()],
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
         bump = treasury.bump,
         close = destination_authority,
-        constraint = treasury.version == 2 @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
         constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
-        constraint = treasury.to_account_info().data_len() == 300 @ ErrorCode::InvalidTreasurySize,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
+        constraint = is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotNativeSolTreasury,
         constraint = treasury.total_streams == 0 @ ErrorCode::TreasuryContainsStreams,
         constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
     )]
     pub treasury: Account<'info, Treasury>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
     #[account(
-        init_if_needed,
+        mut,
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Init Config
+#[derive(Accounts)]
+#[instruction(idl_file_version: u8)]
+pub struct InitConfigAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(address = maintenance_authority::ID @ ErrorCode::NotAuthorized)]
+    pub authority: Signer<'info>,
+    #[account(
+        init,
         payer = payer,
-        associated_token::mint = associated_token,
-        associated_token::authority = treasury
+        seeds = [b"config"],
+        bump,
+        space = Config::SIZE,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
     )]
-    pub treasury_token: Box<Account<'info, TokenAccount>>,
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Update Config
+#[derive(Accounts)]
+#[instruction(idl_file_version: u8)]
+pub struct UpdateConfigAccounts<'info> {
+    #[account(address = config.admin_address @ ErrorCode::NotAuthorized)]
+    pub admin: Signer<'info>,
     #[account(
         mut,
-        constraint = fee_treasury.key() == fee_treasury::ID @ ErrorCode::InvalidFeeTreasuryAccount
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == 1 @ ErrorCode::InvalidConfigAccount,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
     )]
-    pub fee_treasury: SystemAccount<'info>,
+    pub config: Account<'info, Config>,
+}
+
+/// Set Fee Override
+#[derive(Accounts)]
+#[instruction(idl_file_version: u8, subject: Pubkey)]
+pub struct SetFeeOverrideAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(address = config.admin_address @ ErrorCode::NotAuthorized)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == 1 @ ErrorCode::InvalidConfigAccount,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub config: Account<'info, Config>,
     #[account(
         init_if_needed,
         payer = payer,
-        associated_token::mint = associated_token,
-        associated_token::authority = fee_treasury
+        seeds = [b"fee_override", subject.as_ref()],
+        bump,
+        space = FeeOverride::SIZE,
     )]
-    pub fee_treasury_token: Box<Account<'info, TokenAccount>>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    pub fee_override: Account<'info, FeeOverride>,
     pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Remove Fee Override
+#[derive(Accounts)]
+#[instruction(idl_file_version: u8)]
+pub struct RemoveFeeOverrideAccounts<'info> {
+    #[account(
+        mut,
+        address = config.admin_address @ ErrorCode::NotAuthorized
+    )]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == 1 @ ErrorCode::InvalidConfigAccount,
+        constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"fee_override", fee_override.subject_address.as_ref()],
+        bump = fee_override.bump,
+        close = admin
+    )]
+    pub fee_override: Account<'info, FeeOverride>,
 }
 
 // #[derive(Accounts)]
@@ -981,7 +2245,7 @@ pub struct TreasuryWithdrawAccounts<'info> {
     pub payer: Signer<'info>,
     #[account(
         mut,
-        address = treasury.treasurer_address @ ErrorCode::InvalidTreasurer
+        address = treasury.authority() @ ErrorCode::InvalidTreasurer
     )]
     pub treasurer: Signer<'info>,
     #[account()]
@@ -995,16 +2259,17 @@ pub struct TreasuryWithdrawAccounts<'info> {
     )]
     pub destination_token_account: Box<Account<'info, TokenAccount>>,
     #[account(
+        constraint = !is_native_sol_treasury(&treasury.to_account_info())? @ ErrorCode::NotAllowedOnNativeSolTreasury,
         address = treasury.associated_token_address @ ErrorCode::InvalidAssociatedToken
     )]
     pub associated_token: Box<Account<'info, Mint>>,
     #[account(
         mut,
-        seeds = [treasurer.key().as_ref(), &treasury.slot.to_le_bytes()],
+        seeds = [treasury.treasurer_address.as_ref(), &treasury.slot.to_le_bytes()],
         bump = treasury.bump,
-        constraint = treasury.version == 2 @ ErrorCode::InvalidTreasuryVersion,
+        constraint = treasury.is_supported_version() @ ErrorCode::InvalidTreasuryVersion,
         constraint = treasury.initialized == true @ ErrorCode::TreasuryNotInitialized,
-        constraint = treasury.to_account_info().data_len() == 300 @ ErrorCode::InvalidTreasurySize,
+        constraint = treasury.has_valid_size(treasury.to_account_info().data_len()) @ ErrorCode::InvalidTreasurySize,
         constraint = amount > 0 @ ErrorCode::InvalidWithdrawalAmount,
         constraint = treasury.last_known_unallocated_balance()? >= amount @ ErrorCode::InsufficientTreasuryBalance,
         constraint = idl_file_version == IDL_FILE_VERSION @ErrorCode::InvalidIdlFileVersion,
@@ -1016,9 +2281,20 @@ pub struct TreasuryWithdrawAccounts<'info> {
         associated_token::authority = treasury
     )]
     pub treasury_token: Box<Account<'info, TokenAccount>>,
+    /// CHECK: loaded with `Config::load`
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: loaded with `FeeOverride::resolve`
+    pub fee_override: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    /// CHECK: see `load_fee_referrer`
+    #[account(mut)]
+    pub referrer_token: UncheckedAccount<'info>,
     #[account(
         mut,
-        constraint = fee_treasury.key() == fee_treasury::ID @ ErrorCode::InvalidFeeTreasuryAccount
+        constraint = fee_treasury.key() == Config::load(&config)?.fee_treasury_address @ ErrorCode::InvalidFeeTreasuryAccount
     )]
     pub fee_treasury: SystemAccount<'info>,
     #[account(
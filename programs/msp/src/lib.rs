@@ -1,23 +1,32 @@
 use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
+use anchor_spl::associated_token::get_associated_token_address;
 use anchor_spl::token::*;
 
 pub mod categories;
+pub mod config;
 pub mod constants;
 pub mod enums;
 pub mod errors;
 pub mod events;
 pub mod extensions;
 pub mod instructions;
+pub mod schedule;
 pub mod stream;
 pub mod template;
+pub mod tlv;
 pub mod treasury;
 pub mod utils;
 
+use crate::config::*;
 use crate::constants::*;
 use crate::enums::*;
 use crate::errors::ErrorCode;
-use crate::extensions::*;
 use crate::instructions::*;
+use crate::schedule::*;
+use crate::stream::*;
+use crate::tlv::*;
+use crate::treasury::*;
 use crate::utils::*;
 pub use categories::*;
 use std::convert::TryFrom;
@@ -34,6 +43,8 @@ pub mod msp {
     use super::*;
 
     /// Create Treasury
+    /// The optional `referrer` is the wallet of the integrator that referred
+    /// the treasury, which gets a share of the fees charged for it
     pub fn create_treasury(
         ctx: Context<CreateTreasuryAccounts>,
         _idl_file_version: u8,
@@ -44,7 +55,9 @@ pub mod msp {
         sol_fee_payed_by_treasury: bool,
         category: Category,
         sub_category: SubCategory,
+        referrer: Option<Pubkey>,
     ) -> Result<()> {
+        let fees = Config::load(&ctx.accounts.config)?.fees;
         // Initialize Treasury
         construct_treasury_account(
             name,
@@ -61,8 +74,24 @@ pub mod msp {
             &ctx.accounts.associated_token.to_account_info(),
             &ctx.accounts.system_program.to_account_info(),
             slot,
+            &fees,
         )?;
-        
+
+        if let Some(referrer) = referrer {
+            // treasurers can't take a share of their own fees
+            if referrer == ctx.accounts.treasurer.key() {
+                return Err(ErrorCode::InvalidReferrerAccount.into());
+            }
+            set_account_extension::<Treasury, TreasuryReferrer>(
+                &ctx.accounts.treasury.to_account_info(),
+                &TreasuryReferrer {
+                    referrer_address: referrer,
+                },
+                &ctx.accounts.payer.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+            )?;
+        }
+
         return Ok(());
     }
 
@@ -79,7 +108,19 @@ pub mod msp {
         cliff_vest_amount_units: u64,
         cliff_vest_percent: u64,
         fee_payed_by_treasurer: bool,
+        accrual_mode: u8,
+        cliff_utc: u64,
     ) -> Result<()> {
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(
+            &ctx.accounts.treasury,
+            &ctx.accounts.referrer,
+            Some(&ctx.accounts.referrer_token),
+        )?;
         // calculate effective cliff units as an absolute amount. We will not store %
         let effective_cliff_units = if cliff_vest_percent > 0 {
             u64::try_from(
@@ -102,17 +143,23 @@ pub mod msp {
             allocation_assigned_units,
             fee_payed_by_treasurer,
             effective_cliff_units,
+            accrual_mode,
+            cliff_utc,
             &mut ctx.accounts.stream,
             &mut ctx.accounts.treasury,
-            &mut ctx.accounts.treasury_token,
-            &ctx.accounts.treasurer.to_account_info(),
+            &mut TreasuryVault::Token {
+                treasury_token: &mut ctx.accounts.treasury_token,
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
             &ctx.accounts.beneficiary.to_account_info(),
             &ctx.accounts.associated_token.to_account_info(),
             &ctx.accounts.fee_treasury_token.to_account_info(),
             &ctx.accounts.fee_treasury,
             &ctx.accounts.payer,
-            &ctx.accounts.token_program,
             &ctx.accounts.system_program,
+            referrer.as_ref(),
+            &fees,
+            fee_tier,
         )?;
         Ok(())
     }
@@ -131,7 +178,19 @@ pub mod msp {
         cliff_vest_percent: u64,
         fee_payed_by_treasurer: bool,
         _stream_pda_seed: Pubkey,
+        accrual_mode: u8,
+        cliff_utc: u64,
     ) -> Result<()> {
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(
+            &ctx.accounts.treasury,
+            &ctx.accounts.referrer,
+            Some(&ctx.accounts.referrer_token),
+        )?;
         // calculate effective cliff units as an absolute amount. We will not store %
         let effective_cliff_units = if cliff_vest_percent > 0 {
             u64::try_from(
@@ -154,18 +213,266 @@ pub mod msp {
             allocation_assigned_units,
             fee_payed_by_treasurer,
             effective_cliff_units,
+            accrual_mode,
+            cliff_utc,
             &mut ctx.accounts.stream,
             &mut ctx.accounts.treasury,
-            &mut ctx.accounts.treasury_token,
-            &ctx.accounts.treasurer.to_account_info(),
+            &mut TreasuryVault::Token {
+                treasury_token: &mut ctx.accounts.treasury_token,
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            &ctx.accounts.beneficiary.to_account_info(),
+            &ctx.accounts.associated_token.to_account_info(),
+            &ctx.accounts.fee_treasury_token.to_account_info(),
+            &ctx.accounts.fee_treasury,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            referrer.as_ref(),
+            &fees,
+            fee_tier,
+        )?;
+        Ok(())
+    }
+
+    /// Create Streams Batch
+    /// Creates one stream per entry. All the streams share the name, start,
+    /// fee payer, accrual mode and cliff date
+    pub fn create_streams_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateStreamsBatchAccounts<'info>>,
+        _idl_file_version: u8,
+        name: String,
+        start_utc: u64,
+        fee_payed_by_treasurer: bool,
+        accrual_mode: u8,
+        cliff_utc: u64,
+        entries: Vec<StreamBatchEntry>,
+    ) -> Result<()> {
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(
+            &ctx.accounts.treasury,
+            &ctx.accounts.referrer,
+            Some(&ctx.accounts.referrer_token),
+        )?;
+        if entries.is_empty() || entries.len() != ctx.remaining_accounts.len() {
+            return Err(ErrorCode::InvalidStreamAccounts.into());
+        }
+
+        let clock = Clock::get()?;
+        let now_ts = clock.unix_timestamp as u64;
+        let treasurer_key = ctx.accounts.treasurer.key();
+
+        let mut total_allocation_assigned_units = 0u64;
+        let mut total_treasurer_fee_amount = 0u64;
+        let mut streams = Vec::with_capacity(entries.len());
+        let mut treasurer_fee_amounts = Vec::with_capacity(entries.len());
+
+        for (entry, stream_account_info) in entries.iter().zip(ctx.remaining_accounts.iter()) {
+            // same checks as create_stream_pda (one time payments included)
+            if !((entry.rate_amount_units == 0
+                && entry.rate_interval_in_seconds == 0
+                && entry.cliff_vest_amount_units > 0
+                && entry.cliff_vest_amount_units == entry.allocation_assigned_units)
+                || (entry.rate_amount_units > 0 && entry.rate_interval_in_seconds > 0))
+            {
+                return Err(ErrorCode::InvalidStreamRate.into());
+            }
+            if entry.allocation_assigned_units < entry.cliff_vest_amount_units {
+                return Err(ErrorCode::InvalidCliff.into());
+            }
+            if entry.beneficiary == treasurer_key {
+                return Err(ErrorCode::InvalidBeneficiary.into());
+            }
+
+            let mut stream = create_treasury_stream_pda(
+                stream_account_info,
+                &ctx.accounts.treasury,
+                entry.stream_pda_seed,
+                &ctx.accounts.payer,
+                &ctx.accounts.system_program,
+                ctx.program_id,
+            )?;
+
+            populate_stream_account(
+                name.clone(),
+                start_utc,
+                entry.rate_amount_units,
+                entry.rate_interval_in_seconds,
+                entry.allocation_assigned_units,
+                fee_payed_by_treasurer,
+                entry.cliff_vest_amount_units,
+                accrual_mode,
+                cliff_utc,
+                &mut stream,
+                &ctx.accounts.treasury,
+                entry.beneficiary,
+                ctx.accounts.associated_token.key(),
+                now_ts,
+            )?;
+
+            let treasurer_fee_amount =
+                get_treasurer_fee_amount(fee_payed_by_treasurer, entry.allocation_assigned_units, &fees)?;
+            total_allocation_assigned_units = total_allocation_assigned_units
+                .checked_add(entry.allocation_assigned_units)
+                .ok_or(ErrorCode::Overflow)?;
+            total_treasurer_fee_amount = total_treasurer_fee_amount
+                .checked_add(treasurer_fee_amount)
+                .ok_or(ErrorCode::Overflow)?;
+
+            streams.push(stream);
+            treasurer_fee_amounts.push(treasurer_fee_amount);
+        }
+
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.last_known_balance_units = ctx.accounts.treasury_token.amount;
+        let total_treasury_allocation_amount = total_allocation_assigned_units
+            .checked_add(total_treasurer_fee_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        if total_treasury_allocation_amount > treasury.last_known_unallocated_balance()? {
+            return Err(ErrorCode::InsufficientTreasuryBalance.into());
+        }
+
+        // update treasury once for the whole batch
+        treasury.allocation_assigned_units = treasury
+            .allocation_assigned_units
+            .checked_add(total_allocation_assigned_units)
+            .ok_or(ErrorCode::Overflow)?;
+        treasury.total_streams = treasury
+            .total_streams
+            .checked_add(streams.len() as u64)
+            .ok_or(ErrorCode::Overflow)?;
+
+        if total_treasurer_fee_amount > 0 {
+            // beneficiary withdraw fees payed by the treasurer
+            TreasuryVault::Token {
+                treasury_token: &mut ctx.accounts.treasury_token,
+                token_program: ctx.accounts.token_program.to_account_info(),
+            }
+            .transfer_fee(
+                treasury,
+                &ctx.accounts.fee_treasury_token.to_account_info(),
+                referrer.as_ref(),
+                total_treasurer_fee_amount,
+                &fees,
+            )?;
+
+            treasury.last_known_balance_slot = clock.slot as u64;
+            treasury.last_known_balance_block_time = now_ts;
+            treasury.last_known_balance_units = treasury
+                .last_known_balance_units
+                .checked_sub(total_treasurer_fee_amount)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+
+        let total_sol_fee = fees.create_stream_flat_fee
+            .checked_mul(streams.len() as u64)
+            .ok_or(ErrorCode::Overflow)?;
+        charge_sol_fee_with_referrer(
+            treasury,
+            &ctx.accounts.fee_treasury,
+            referrer.as_ref(),
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            total_sol_fee,
+            &fees,
+        )?;
+
+        ctx.accounts.treasury_token.reload()?;
+        let treasury = &ctx.accounts.treasury;
+        assert!(
+            ctx.accounts.treasury_token.amount >= treasury.last_known_balance_units,
+            "treasury balance units invariant violated"
+        );
+        assert!(
+            treasury.allocation_assigned_units >= total_allocation_assigned_units,
+            "treasury vs stream assigned units invariant violated"
+        );
+
+        for (stream, treasurer_fee_amount) in streams.iter().zip(treasurer_fee_amounts) {
+            stream.exit(ctx.program_id)?;
+
+            mean_emit!(CreateStreamEvent {
+                timestamp: now_ts,
+                sol_fee_charged: fees.create_stream_flat_fee,
+                token_fee_charged: treasurer_fee_amount,
+                fee_tier,
+                stream_start_ts: stream.start_utc,
+                stream_rate_amount: stream.rate_amount_units,
+                stream_rate_interval: stream.rate_interval_in_seconds,
+                stream_allocation: stream.allocation_assigned_units,
+                stream_cliff: stream.cliff_vest_amount_units,
+                stream_is_token_withdraw_fee_payed_by_treasury: fee_payed_by_treasurer,
+                treasury_is_sol_fee_payed_by_treasury: treasury.sol_fee_payed_by_treasury,
+                treasury_allocation_after: treasury.allocation_assigned_units,
+                treasury_balance_after: treasury.last_known_balance_units,
+                stream: stream.key(),
+                treasury: treasury.key(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Create Stream With Schedule
+    pub fn create_stream_with_schedule(
+        ctx: Context<CreateStreamWithScheduleAccounts>,
+        _idl_file_version: u8,
+        name: String,
+        allocation_assigned_units: u64,
+        fee_payed_by_treasurer: bool,
+        points: Vec<SchedulePoint>,
+    ) -> Result<()> {
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(
+            &ctx.accounts.treasury,
+            &ctx.accounts.referrer,
+            Some(&ctx.accounts.referrer_token),
+        )?;
+        StreamSchedule::validate_points(&points, allocation_assigned_units)?;
+
+        // the stream starts at the first schedule point. Rate and cliff are
+        // not used since the schedule defines the unlocked units
+        construct_stream_account(
+            name,
+            points[0].timestamp,
+            0,
+            0,
+            allocation_assigned_units,
+            fee_payed_by_treasurer,
+            0,
+            STREAM_ACCRUAL_MODE_LINEAR,
+            0,
+            &mut ctx.accounts.stream,
+            &mut ctx.accounts.treasury,
+            &mut TreasuryVault::Token {
+                treasury_token: &mut ctx.accounts.treasury_token,
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
             &ctx.accounts.beneficiary.to_account_info(),
             &ctx.accounts.associated_token.to_account_info(),
             &ctx.accounts.fee_treasury_token.to_account_info(),
             &ctx.accounts.fee_treasury,
             &ctx.accounts.payer,
-            &ctx.accounts.token_program,
             &ctx.accounts.system_program,
+            referrer.as_ref(),
+            &fees,
+            fee_tier,
         )?;
+
+        let schedule = &mut ctx.accounts.schedule;
+        schedule.version = 1;
+        schedule.bump = ctx.bumps["schedule"];
+        schedule.stream_address = ctx.accounts.stream.key();
+        schedule.points = points;
+
+        ctx.accounts.stream.schedule_address = schedule.key();
         Ok(())
     }
 
@@ -178,6 +485,8 @@ pub mod msp {
         duration_number_of_units: u64,
         cliff_vest_percent: u64,
         fee_payed_by_treasurer: bool,
+        accrual_mode: u8,
+        cliff_utc: u64,
     ) -> Result<()> {
         construct_stream_template(
             start_utc,
@@ -185,6 +494,8 @@ pub mod msp {
             duration_number_of_units,
             cliff_vest_percent,
             fee_payed_by_treasurer,
+            accrual_mode,
+            cliff_utc,
             &mut ctx.accounts.template,
             ctx.bumps["template"],
             2
@@ -201,6 +512,8 @@ pub mod msp {
         duration_number_of_units: u64,
         cliff_vest_percent: u64,
         fee_payed_by_treasurer: bool,
+        accrual_mode: u8,
+        cliff_utc: u64,
     ) -> Result<()> {
         let template_bump = ctx.accounts.template.bump;
         let template_version = ctx.accounts.template.version;
@@ -210,6 +523,8 @@ pub mod msp {
             duration_number_of_units,
             cliff_vest_percent,
             fee_payed_by_treasurer,
+            accrual_mode,
+            cliff_utc,
             &mut ctx.accounts.template,
             template_bump,
             template_version,
@@ -233,7 +548,10 @@ pub mod msp {
         cliff_vest_percent: u64,
         fee_payed_by_treasurer: bool,
         slot: u64,
+        accrual_mode: u8,
+        cliff_utc: u64,
     ) -> Result<()> {
+        let fees = Config::load(&ctx.accounts.config)?.fees;
         // Initialize Treasury
         construct_treasury_account(
             name,
@@ -250,6 +568,7 @@ pub mod msp {
             &ctx.accounts.associated_token.to_account_info(),
             &ctx.accounts.system_program.to_account_info(),
             slot,
+            &fees,
         )?;
         
         // Create template
@@ -259,6 +578,8 @@ pub mod msp {
             duration_number_of_units,
             cliff_vest_percent,
             fee_payed_by_treasurer,
+            accrual_mode,
+            cliff_utc,
             &mut ctx.accounts.template,
             ctx.bumps["template"],
             2
@@ -274,6 +595,16 @@ pub mod msp {
         name: String,
         allocation_assigned_units: u64,
     ) -> Result<()> {
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(
+            &ctx.accounts.treasury,
+            &ctx.accounts.referrer,
+            Some(&ctx.accounts.referrer_token),
+        )?;
         let template = &ctx.accounts.template;
 
         // calculate effective cliff units as an absolute amount. We will not store %
@@ -311,17 +642,23 @@ pub mod msp {
             allocation_assigned_units,
             template.fee_payed_by_treasurer,
             effective_cliff_units,
+            template.accrual_mode,
+            template.cliff_utc_in_seconds,
             &mut ctx.accounts.stream,
             &mut ctx.accounts.treasury,
-            &mut ctx.accounts.treasury_token,
-            &ctx.accounts.treasurer.to_account_info(),
+            &mut TreasuryVault::Token {
+                treasury_token: &mut ctx.accounts.treasury_token,
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
             &ctx.accounts.beneficiary.to_account_info(),
             &ctx.accounts.associated_token.to_account_info(),
             &ctx.accounts.fee_treasury_token.to_account_info(),
             &ctx.accounts.fee_treasury,
             &ctx.accounts.payer,
-            &ctx.accounts.token_program,
             &ctx.accounts.system_program,
+            referrer.as_ref(),
+            &fees,
+            fee_tier,
         )?;
 
         Ok(())
@@ -335,6 +672,16 @@ pub mod msp {
         allocation_assigned_units: u64,
         _stream_pda_seed: Pubkey,
     ) -> Result<()> {
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(
+            &ctx.accounts.treasury,
+            &ctx.accounts.referrer,
+            Some(&ctx.accounts.referrer_token),
+        )?;
         let template = &ctx.accounts.template;
 
         // calculate effective cliff units as an absolute amount. We will not store %
@@ -372,169 +719,287 @@ pub mod msp {
             allocation_assigned_units,
             template.fee_payed_by_treasurer,
             effective_cliff_units,
+            template.accrual_mode,
+            template.cliff_utc_in_seconds,
             &mut ctx.accounts.stream,
             &mut ctx.accounts.treasury,
-            &mut ctx.accounts.treasury_token,
-            &ctx.accounts.treasurer.to_account_info(),
+            &mut TreasuryVault::Token {
+                treasury_token: &mut ctx.accounts.treasury_token,
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
             &ctx.accounts.beneficiary.to_account_info(),
             &ctx.accounts.associated_token.to_account_info(),
             &ctx.accounts.fee_treasury_token.to_account_info(),
             &ctx.accounts.fee_treasury,
             &ctx.accounts.payer,
-            &ctx.accounts.token_program,
             &ctx.accounts.system_program,
+            referrer.as_ref(),
+            &fees,
+            fee_tier,
         )?;
 
         Ok(())
     }
 
     /// Withdraw
-    pub fn withdraw(
-        ctx: Context<WithdrawAccounts>,
+    pub fn withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawAccounts<'info>>,
         _idl_file_version: u8,
         amount: u64,
     ) -> Result<()> {
-        let clock = Clock::get()?;
-        let now_ts = clock.unix_timestamp as u64;
-
-        let treasury = &mut ctx.accounts.treasury;
-        let stream = &mut ctx.accounts.stream;
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(
+            &ctx.accounts.treasury,
+            &ctx.accounts.referrer,
+            Some(&ctx.accounts.referrer_token),
+        )?;
+        let schedule = load_stream_schedule(
+            ctx.accounts.stream.key(),
+            &ctx.accounts.stream,
+            ctx.remaining_accounts,
+        )?;
 
-        let start_utc_seconds = stream.get_start_utc()?;
-        if start_utc_seconds > now_ts {
-            return Err(ErrorCode::StreamIsScheduled.into());
-        }
+        let withdraw_event = stream_withdraw(
+            amount,
+            schedule.as_deref(),
+            &mut ctx.accounts.stream,
+            &mut ctx.accounts.treasury,
+            &mut TreasuryVault::Token {
+                treasury_token: &mut ctx.accounts.treasury_token,
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            &ctx.accounts.beneficiary_token.to_account_info(),
+            &ctx.accounts.fee_treasury_token.to_account_info(),
+            referrer.as_ref(),
+            &fees,
+            fee_tier,
+        )?;
 
-        stream.save_effective_cliff();
+        mean_emit!(withdraw_event);
 
-        let withdrawable_amount = stream.get_beneficiary_withdrawable_amount(now_ts)?;
+        Ok(())
+    }
 
-        if withdrawable_amount == 0 {
-            return Err(ErrorCode::ZeroWithdrawalAmount.into());
-        }
+    /// Withdraw to any token account of the stream mint
+    pub fn withdraw_to_destination<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawToDestinationAccounts<'info>>,
+        _idl_file_version: u8,
+        amount: u64,
+    ) -> Result<()> {
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(
+            &ctx.accounts.treasury,
+            &ctx.accounts.referrer,
+            Some(&ctx.accounts.referrer_token),
+        )?;
+        let schedule = load_stream_schedule(
+            ctx.accounts.stream.key(),
+            &ctx.accounts.stream,
+            ctx.remaining_accounts,
+        )?;
 
-        let mut user_requested_amount = amount;
+        let withdraw_event = stream_withdraw(
+            amount,
+            schedule.as_deref(),
+            &mut ctx.accounts.stream,
+            &mut ctx.accounts.treasury,
+            &mut TreasuryVault::Token {
+                treasury_token: &mut ctx.accounts.treasury_token,
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            &ctx.accounts.destination_token.to_account_info(),
+            &ctx.accounts.fee_treasury_token.to_account_info(),
+            referrer.as_ref(),
+            &fees,
+            fee_tier,
+        )?;
 
-        if user_requested_amount > withdrawable_amount {
-            user_requested_amount = withdrawable_amount;
-        }
+        mean_emit!(withdraw_event);
 
-        let fee_amount = if stream.fee_payed_by_treasurer {
-            0u64
-        } else {
-            u64::try_from(
-                (WITHDRAW_PERCENT_FEE as u128)
-                    .checked_mul(user_requested_amount as u128)
-                    .ok_or(ErrorCode::Overflow)?
-                    .checked_div(PERCENT_DENOMINATOR as u128)
-                    .ok_or(ErrorCode::Overflow)?,
-            )
-            .unwrap()
-        };
+        Ok(())
+    }
 
-        let transfer_amount = if fee_amount == 0 {
-            user_requested_amount
-        } else {
-            user_requested_amount
-                .checked_sub(fee_amount)
-                .ok_or(ErrorCode::Overflow)?
-        };
+    /// Withdraw the full withdrawable amount on behalf of the beneficiary.
+    /// Anyone can call it and, if the treasury pays the SOL fees, the cranker
+    /// gets a small reward from the treasury at most once a day per stream
+    pub fn push_withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, PushWithdrawAccounts<'info>>,
+        _idl_file_version: u8,
+    ) -> Result<()> {
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(
+            &ctx.accounts.treasury,
+            &ctx.accounts.referrer,
+            Some(&ctx.accounts.referrer_token),
+        )?;
+        let schedule = load_stream_schedule(
+            ctx.accounts.stream.key(),
+            &ctx.accounts.stream,
+            ctx.remaining_accounts,
+        )?;
+        let previous_withdrawal_block_time = ctx.accounts.stream.last_withdrawal_block_time;
 
-        // Transfer from treasury to beneficiary
-        treasury_transfer(
-            &treasury,
-            &ctx.accounts.treasury_token.to_account_info(),
+        let mut withdraw_event = stream_withdraw(
+            u64::MAX,
+            schedule.as_deref(),
+            &mut ctx.accounts.stream,
+            &mut ctx.accounts.treasury,
+            &mut TreasuryVault::Token {
+                treasury_token: &mut ctx.accounts.treasury_token,
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
             &ctx.accounts.beneficiary_token.to_account_info(),
-            &ctx.accounts.token_program.to_account_info(),
-            transfer_amount,
+            &ctx.accounts.fee_treasury_token.to_account_info(),
+            referrer.as_ref(),
+            &fees,
+            fee_tier,
         )?;
-
-        // Transfer fee
-        if fee_amount > 0 {
-            treasury_transfer(
-                &treasury,
-                &ctx.accounts.treasury_token.to_account_info(),
-                &ctx.accounts.fee_treasury_token.to_account_info(),
-                &ctx.accounts.token_program.to_account_info(),
-                fee_amount,
+        // the full withdrawable amount is always requested
+        withdraw_event.amount = withdraw_event.stream_withdrawable_before;
+
+        // cranker reward (only if the treasury can afford it and the stream
+        // was not withdrawn recently, so cranking every slot doesn't pay)
+        let treasury = &ctx.accounts.treasury;
+        let mut cranker_reward = 0u64;
+        if treasury.sol_fee_payed_by_treasury
+            && withdraw_event.timestamp
+                >= previous_withdrawal_block_time
+                    .saturating_add(PUSH_WITHDRAW_CRANKER_REWARD_MIN_INTERVAL)
+            && get_treasury_available_lamports(&treasury.to_account_info())?
+                >= PUSH_WITHDRAW_CRANKER_REWARD
+        {
+            // this call needs to be after any cpi in this ix to avoid Solana's weird CPI imbalance check hack
+            treasury_transfer_sol_amount(
+                &treasury.to_account_info(),
+                &ctx.accounts.payer.to_account_info(),
+                PUSH_WITHDRAW_CRANKER_REWARD,
             )?;
+            cranker_reward = PUSH_WITHDRAW_CRANKER_REWARD;
         }
 
-        stream.last_withdrawal_slot = clock.slot as u64;
-        stream.last_withdrawal_block_time = now_ts;
-        stream.last_withdrawal_units = user_requested_amount;
-        stream.total_withdrawals_units = stream
-            .total_withdrawals_units
-            .checked_add(user_requested_amount)
-            .ok_or(ErrorCode::Overflow)?;
-
-        let mut withdraw_event = StreamWithdrawEvent {
-            timestamp: now_ts,
+        mean_emit!(StreamPushWithdrawEvent {
+            timestamp: withdraw_event.timestamp,
             sol_fee_charged: 0,
-            token_fee_charged: fee_amount,
-            amount: amount,
-            token_amount_sent_to_beneficiary: transfer_amount,
-            stream_withdrawable_before: withdrawable_amount,
-            stream_is_manually_paused: false,
-            stream_allocation_after: 0,
-            stream_total_withdrawals_after: stream.total_withdrawals_units,
-            stream_is_token_withdraw_fee_payed_by_treasury: stream.fee_payed_by_treasurer,
-            treasury_is_sol_fee_payed_by_treasury: treasury.sol_fee_payed_by_treasury,
-            treasury_allocation_after: 0,
-            treasury_balance_after: 0,
-            treasury_total_withdrawals_after: 0,
-            stream: stream.key(),
-            treasury: treasury.key(),
-        };
+            token_fee_charged: withdraw_event.token_fee_charged,
+            fee_tier,
+            token_amount_sent_to_beneficiary: withdraw_event.token_amount_sent_to_beneficiary,
+            stream_total_withdrawals_after: withdraw_event.stream_total_withdrawals_after,
+            cranker_reward,
+            cranker: ctx.accounts.payer.key(),
+            beneficiary: ctx.accounts.beneficiary.key(),
+            stream: withdraw_event.stream,
+            treasury: withdraw_event.treasury,
+        });
+        mean_emit!(withdraw_event);
 
-        // if the stream was manually paused then deduct the user requested amount
-        // from the `last_manual_stop_withdrawable_units_snap` to update the
-        // beneficiary withdrawable amount
-        if stream.primitive_is_manually_paused() {
-            stream.last_manual_stop_withdrawable_units_snap = stream
-                .last_manual_stop_withdrawable_units_snap
-                .checked_sub(user_requested_amount)
-                .ok_or(ErrorCode::Overflow)?;
-                withdraw_event.stream_is_manually_paused = true;
+        Ok(())
+    }
+
+    /// Withdraw Many
+    /// Withdraws the full withdrawable amount of each stream passed in the
+    /// remaining accounts to the beneficiary token account. Streams with
+    /// nothing to withdraw yet are skipped
+    pub fn withdraw_many<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawManyAccounts<'info>>,
+        _idl_file_version: u8,
+    ) -> Result<()> {
+        let config = Config::load(&ctx.accounts.config)?;
+        let remaining_accounts = ctx.remaining_accounts;
+        if remaining_accounts.is_empty() {
+            return Err(ErrorCode::InvalidStreamAccounts.into());
         }
 
-        // update the start UTC to seconds if it's necesary
-        stream.update_start_utc()?;
+        let clock = Clock::get()?;
+        let now_ts = clock.unix_timestamp as u64;
+        let associated_token = ctx.accounts.associated_token.key();
 
-        // Update treasury data
-        assert!(
-            treasury.allocation_assigned_units >= user_requested_amount,
-            "treasury allocation_assigned vs withdraw amount invariant violated"
-        );
-        treasury.allocation_assigned_units = treasury
-            .allocation_assigned_units
-            .checked_sub(user_requested_amount)
-            .ok_or(ErrorCode::Overflow)?;
+        let mut i = 0;
+        while i < remaining_accounts.len() {
+            if i + 2 >= remaining_accounts.len() {
+                return Err(ErrorCode::InvalidStreamAccounts.into());
+            }
+            let accounts = &remaining_accounts[i..i + 3];
+            let mut treasury = load_treasury(&accounts[0], ctx.program_id)?;
+            if is_native_sol_treasury(&accounts[0])? {
+                return Err(ErrorCode::NotAllowedOnNativeSolTreasury.into());
+            }
+            if treasury.associated_token_address != associated_token {
+                return Err(ErrorCode::InvalidAssociatedToken.into());
+            }
 
-        treasury.last_known_balance_slot = clock.slot as u64;
-        treasury.last_known_balance_block_time = now_ts;
-        treasury.last_known_balance_units = treasury
-            .last_known_balance_units
-            .checked_sub(user_requested_amount)
-            .ok_or(ErrorCode::Overflow)?;
-        treasury.total_withdrawals_units = treasury
-            .total_withdrawals_units
-            .checked_add(user_requested_amount)
-            .ok_or(ErrorCode::Overflow)?;
+            if accounts[1].key() != get_associated_token_address(&treasury.key(), &associated_token)
+                || !accounts[1].is_writable
+            {
+                return Err(ErrorCode::InvalidAssociatedToken.into());
+            }
+            let mut treasury_token = Account::<TokenAccount>::try_from(&accounts[1])?;
 
-        // invariants
-        ctx.accounts.treasury_token.reload()?;
-        assert!(
-            ctx.accounts.treasury_token.amount >= treasury.last_known_balance_units,
-            "treasury balance units invariant violated"
-        );
+            let mut stream = load_treasury_stream(&accounts[2], &treasury)?;
+            if stream.beneficiary_address != ctx.accounts.beneficiary.key() {
+                return Err(ErrorCode::InvalidBeneficiary.into());
+            }
+            if stream.beneficiary_associated_token != associated_token {
+                return Err(ErrorCode::InvalidAssociatedToken.into());
+            }
 
-        withdraw_event.stream_allocation_after = stream.allocation_assigned_units;
-        withdraw_event.treasury_total_withdrawals_after = treasury.total_withdrawals_units;
-        withdraw_event.treasury_allocation_after = treasury.allocation_assigned_units;
-        withdraw_event.treasury_balance_after = treasury.last_known_balance_units;
+            // the stream schedule, if any, goes right after the triple
+            let schedule_accounts = remaining_accounts.get(i + 3..i + 4).unwrap_or(&[]);
+            let schedule = load_stream_schedule(stream.key(), &stream, schedule_accounts)?;
+            i += if schedule.is_some() { 4 } else { 3 };
+
+            let withdrawable_amount = match &schedule {
+                Some(schedule) => stream.get_scheduled_withdrawable_amount(schedule, now_ts)?,
+                None => stream.get_beneficiary_withdrawable_amount(now_ts)?,
+            };
+            if stream.get_start_utc()? > now_ts || withdrawable_amount == 0 {
+                continue;
+            }
 
-        mean_emit!(withdraw_event);
+            // the override only applies to the triples of its treasury (or
+            // treasury authority)
+            let (fees, fee_tier) =
+                FeeOverride::resolve(&ctx.accounts.fee_override, &config, &treasury)?;
+            // the fee share goes to the referrer accounts only for the
+            // treasuries they referred
+            let referrer = load_fee_referrer(
+                &treasury,
+                &ctx.accounts.referrer,
+                Some(&ctx.accounts.referrer_token),
+            )?;
+            let withdraw_event = stream_withdraw(
+                u64::MAX,
+                schedule.as_deref(),
+                &mut stream,
+                &mut treasury,
+                &mut TreasuryVault::Token {
+                    treasury_token: &mut treasury_token,
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+                &ctx.accounts.beneficiary_token.to_account_info(),
+                &ctx.accounts.fee_treasury_token.to_account_info(),
+                referrer.as_ref(),
+                &fees,
+                fee_tier,
+            )?;
+
+            // persist now in case the same treasury is passed again
+            stream.exit(ctx.program_id)?;
+            treasury.exit(ctx.program_id)?;
+
+            mean_emit!(withdraw_event);
+        }
 
         Ok(())
     }
@@ -549,9 +1014,9 @@ pub mod msp {
 
         let stream = &mut ctx.accounts.stream;
 
-        stream.save_effective_cliff();
-
-        let withdrawable_amount = stream.get_beneficiary_withdrawable_amount(now_ts)?;
+        // the vested amount is kept while paused so it can be withdrawn once
+        // the cliff date is reached
+        let withdrawable_amount = stream.get_beneficiary_vested_amount(now_ts)?;
         let stream_status = stream.get_status(now_ts)?;
 
         if stream_status == StreamStatus::Paused || stream_status == StreamStatus::Scheduled {
@@ -566,8 +1031,6 @@ pub mod msp {
         stream.last_manual_stop_withdrawable_units_snap = withdrawable_amount;
         stream.last_manual_stop_slot = clock.slot as u64;
         stream.last_manual_stop_block_time = now_ts;
-        // update the start UTC to seconds if it's necesary
-        stream.update_start_utc()?;
 
         mean_emit!(StreamPauseEvent {
             timestamp: now_ts,
@@ -591,8 +1054,6 @@ pub mod msp {
 
         let stream = &mut ctx.accounts.stream;
 
-        stream.save_effective_cliff();
-
         let stream_status = stream.get_status(now_ts)?;
         if stream_status == StreamStatus::Running || stream_status == StreamStatus::Scheduled {
             return Err(ErrorCode::StreamAlreadyRunning.into());
@@ -640,8 +1101,6 @@ pub mod msp {
         stream.last_manual_resume_remaining_allocation_units_snap = remaining_allocation;
         stream.last_manual_resume_slot = clock.slot as u64;
         stream.last_manual_resume_block_time = now_ts;
-        // update the start UTC to seconds if it's necesary
-        stream.update_start_utc()?;
 
         mean_emit!(StreamResumeEvent {
             timestamp: now_ts,
@@ -655,28 +1114,152 @@ pub mod msp {
         Ok(())
     }
 
-    /// Refresh Treasury Balance
-    pub fn refresh_treasury_data(
-        ctx: Context<RefreshTreasuryDataAccounts>,
+    /// Update the rate of a stream. Everything earned at the previous rate
+    /// is kept as cliff and the stream continues at the new rate from now
+    pub fn update_stream_rate(
+        ctx: Context<UpdateStreamRateAccounts>,
         _idl_file_version: u8,
+        rate_amount_units: u64,
+        rate_interval_in_seconds: u64,
     ) -> Result<()> {
         let clock = Clock::get()?;
+        let now_ts = clock.unix_timestamp as u64;
 
-        let treasury = &mut ctx.accounts.treasury;
+        let stream = &mut ctx.accounts.stream;
 
-        treasury.last_known_balance_slot = clock.slot as u64;
-        treasury.last_known_balance_block_time = clock.unix_timestamp as u64;
-        treasury.last_known_balance_units = ctx.accounts.treasury_token.amount;
+        if stream.primitive_is_manually_paused() {
+            return Err(ErrorCode::UpdateRateNotAllowedWhilePaused.into());
+        }
 
-        mean_emit!(TreasuryRefreshEvent {
-            timestamp: treasury.last_known_balance_block_time,
+        let status = stream.get_status(now_ts)?;
+        let mut rate_update_event = StreamRateUpdateEvent {
+            timestamp: now_ts,
             sol_fee_charged: 0,
             token_fee_charged: 0,
-            treasury_balance_after: treasury.last_known_balance_units,
-            treasury: treasury.key(),
-        });
-
-        Ok(())
+            stream_status_before: status as u32,
+            stream_rate_amount_before: stream.rate_amount_units,
+            stream_rate_interval_before: stream.rate_interval_in_seconds,
+            stream_rate_amount_after: rate_amount_units,
+            stream_rate_interval_after: rate_interval_in_seconds,
+            stream_earned_units_before: 0,
+            stream_start_ts_after: 0,
+            stream_total_seconds_in_paused_status_before: stream
+                .last_known_total_seconds_in_paused_status,
+            stream: stream.key(),
+            treasury: stream.treasury_address,
+        };
+
+        // checkpoint the units earned at the previous rate (withdrawn or not)
+        // as cliff and restart the stream now. Scheduled streams have not
+        // earned anything yet so they keep their start
+        if status != StreamStatus::Scheduled {
+            let earned_units = stream
+                .total_withdrawals_units
+                .checked_add(stream.get_beneficiary_vested_amount(now_ts)?)
+                .ok_or(ErrorCode::Overflow)?;
+
+            // in step mode the earned units only count whole intervals, so the
+            // restart is moved back to keep the seconds already streamed in
+            // the current interval
+            let restart_ts = if status == StreamStatus::Running {
+                now_ts
+                    .checked_sub(stream.get_step_interval_elapsed_seconds(now_ts)?)
+                    .ok_or(ErrorCode::Overflow)?
+            } else {
+                now_ts
+            };
+
+            stream.cliff_vest_amount_units = earned_units;
+            stream.start_utc = restart_ts;
+            stream.last_known_total_seconds_in_paused_status = 0;
+            stream.last_auto_stop_block_time = 0;
+
+            rate_update_event.stream_earned_units_before = earned_units;
+        }
+
+        stream.rate_amount_units = rate_amount_units;
+        stream.rate_interval_in_seconds = rate_interval_in_seconds;
+
+        rate_update_event.stream_start_ts_after = stream.get_start_utc()?;
+        mean_emit!(rate_update_event);
+
+        Ok(())
+    }
+
+    /// Propose a new treasury authority. The handover takes effect when the
+    /// proposed authority accepts it. Proposing the default pubkey cancels a
+    /// pending handover
+    pub fn propose_treasury_authority(
+        ctx: Context<ProposeTreasuryAuthorityAccounts>,
+        _idl_file_version: u8,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+
+        if new_authority == treasury.authority() {
+            return Err(ErrorCode::InvalidTreasuryAuthority.into());
+        }
+
+        treasury.pending_authority_address = new_authority;
+
+        mean_emit!(TreasuryAuthorityProposeEvent {
+            timestamp: Clock::get()?.unix_timestamp as u64,
+            sol_fee_charged: 0,
+            token_fee_charged: 0,
+            authority: treasury.authority(),
+            proposed_authority: new_authority,
+            treasury: treasury.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Accept a pending treasury authority handover. Signed by the proposed
+    /// authority
+    pub fn accept_treasury_authority(
+        ctx: Context<AcceptTreasuryAuthorityAccounts>,
+        _idl_file_version: u8,
+    ) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+
+        let previous_authority = treasury.authority();
+        treasury.authority_address = treasury.pending_authority_address;
+        treasury.pending_authority_address = Pubkey::default();
+
+        mean_emit!(TreasuryAuthorityTransferEvent {
+            timestamp: Clock::get()?.unix_timestamp as u64,
+            sol_fee_charged: 0,
+            token_fee_charged: 0,
+            previous_authority,
+            new_authority: treasury.authority_address,
+            treasury: treasury.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Refresh Treasury Balance
+    pub fn refresh_treasury_data(
+        ctx: Context<RefreshTreasuryDataAccounts>,
+        _idl_file_version: u8,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+
+        let treasury = &mut ctx.accounts.treasury;
+
+        treasury.last_known_balance_slot = clock.slot as u64;
+        treasury.last_known_balance_block_time = clock.unix_timestamp as u64;
+        treasury.last_known_balance_units = ctx.accounts.treasury_token.amount;
+
+        mean_emit!(TreasuryRefreshEvent {
+            timestamp: treasury.last_known_balance_block_time,
+            sol_fee_charged: 0,
+            token_fee_charged: 0,
+            treasury_balance_after: treasury.last_known_balance_units,
+            treasury: treasury.key(),
+        });
+
+        Ok(())
     }
 
     /// Transfer Stream
@@ -685,26 +1268,24 @@ pub mod msp {
         _idl_file_version: u8,
         new_beneficiary: Pubkey,
     ) -> Result<()> {
+        let fees = Config::load(&ctx.accounts.config)?.fees;
         let stream = &mut ctx.accounts.stream;
 
-        stream.save_effective_cliff();
-
         let previous_beneficiary = stream.beneficiary_address;
 
         stream.beneficiary_address = new_beneficiary;
-        // update the start UTC to seconds if it's necesary
-        stream.update_start_utc()?;
+        stream.pending_beneficiary_address = Pubkey::default();
         // Fee
         transfer_sol_amount(
             &ctx.accounts.beneficiary.to_account_info(),
             &ctx.accounts.fee_treasury.to_account_info(),
             &ctx.accounts.system_program.to_account_info(),
-            TRANSFER_STREAM_FLAT_FEE
+            fees.transfer_stream_flat_fee
         )?;
 
         mean_emit!(StreamTransferEvent {
             timestamp: Clock::get()?.unix_timestamp as u64,
-            sol_fee_charged: TRANSFER_STREAM_FLAT_FEE,
+            sol_fee_charged: fees.transfer_stream_flat_fee,
             token_fee_charged: 0,
             stream: stream.key(),
             treasury: stream.treasury_address,
@@ -715,9 +1296,98 @@ pub mod msp {
         return Ok(())
     }
 
+    /// Propose a new beneficiary for the stream. The transfer takes effect
+    /// when the proposed beneficiary accepts it
+    pub fn propose_stream_transfer(
+        ctx: Context<TransferStreamAccounts>,
+        _idl_file_version: u8,
+        new_beneficiary: Pubkey,
+    ) -> Result<()> {
+        let fees = Config::load(&ctx.accounts.config)?.fees;
+        if new_beneficiary == Pubkey::default() {
+            return Err(ErrorCode::InvalidBeneficiary.into());
+        }
+
+        let stream = &mut ctx.accounts.stream;
+        stream.pending_beneficiary_address = new_beneficiary;
+
+        // Fee
+        transfer_sol_amount(
+            &ctx.accounts.beneficiary.to_account_info(),
+            &ctx.accounts.fee_treasury.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            fees.transfer_stream_flat_fee
+        )?;
+
+        mean_emit!(StreamTransferProposeEvent {
+            timestamp: Clock::get()?.unix_timestamp as u64,
+            sol_fee_charged: fees.transfer_stream_flat_fee,
+            token_fee_charged: 0,
+            stream: stream.key(),
+            treasury: stream.treasury_address,
+            beneficiary: stream.beneficiary_address,
+            proposed_beneficiary: new_beneficiary,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel a pending stream transfer
+    pub fn cancel_stream_transfer(
+        ctx: Context<CancelStreamTransferAccounts>,
+        _idl_file_version: u8,
+    ) -> Result<()> {
+        let stream = &mut ctx.accounts.stream;
+
+        let cancelled_beneficiary = stream.pending_beneficiary_address;
+        stream.pending_beneficiary_address = Pubkey::default();
+
+        mean_emit!(StreamTransferCancelEvent {
+            timestamp: Clock::get()?.unix_timestamp as u64,
+            sol_fee_charged: 0,
+            token_fee_charged: 0,
+            stream: stream.key(),
+            treasury: stream.treasury_address,
+            beneficiary: stream.beneficiary_address,
+            cancelled_beneficiary,
+        });
+
+        Ok(())
+    }
+
+    /// Accept a pending stream transfer. Signed by the proposed beneficiary
+    pub fn accept_stream_transfer(
+        ctx: Context<AcceptStreamTransferAccounts>,
+        _idl_file_version: u8,
+    ) -> Result<()> {
+        let stream = &mut ctx.accounts.stream;
+
+        let previous_beneficiary = stream.beneficiary_address;
+
+        stream.beneficiary_address = stream.pending_beneficiary_address;
+        stream.pending_beneficiary_address = Pubkey::default();
+
+        mean_emit!(StreamTransferEvent {
+            timestamp: Clock::get()?.unix_timestamp as u64,
+            sol_fee_charged: 0,
+            token_fee_charged: 0,
+            stream: stream.key(),
+            treasury: stream.treasury_address,
+            previous_beneficiary: previous_beneficiary,
+            new_beneficiary: stream.beneficiary_address,
+        });
+
+        Ok(())
+    }
+
     /// Get Stream
     pub fn get_stream(ctx: Context<GetStreamAccounts>, _idl_file_version: u8) -> Result<()> {
-        emit!(get_stream_data_event(&ctx.accounts.stream)?);
+        let schedule = load_stream_schedule(
+            ctx.accounts.stream.key(),
+            &ctx.accounts.stream,
+            ctx.remaining_accounts,
+        )?;
+        emit!(get_stream_data_event(&ctx.accounts.stream, schedule.as_deref())?);
 
         Ok(())
     }
@@ -730,6 +1400,7 @@ pub mod msp {
         _idl_file_version: u8,
         amount: u64,
     ) -> Result<()> {
+        let fees = Config::load(&ctx.accounts.config)?.fees;
         let clock = Clock::get()?;
         let now_ts = clock.unix_timestamp as u64;
         let now_slot = clock.slot as u64;
@@ -737,21 +1408,21 @@ pub mod msp {
         let treasury = &mut ctx.accounts.treasury;
 
         // sol fee
-        if ctx.accounts.contributor.key().eq(&treasury.treasurer_address) && // TODO:
+        if ctx.accounts.contributor.key().eq(&treasury.authority()) && // TODO:
             treasury.sol_fee_payed_by_treasury {
             // this call needs to be after any cpi in this ix to avoid Solana's weird CPI imbalance check hack
             // REF: https://discord.com/channels/889577356681945098/889584618372734977/915190505002921994
             treasury_transfer_sol_amount(
                 &treasury.to_account_info(),
                 &ctx.accounts.fee_treasury.to_account_info(),
-                ADD_FUNDS_FLAT_FEE,
+                fees.add_funds_flat_fee,
             )?;
         } else {
             transfer_sol_amount(
                 &ctx.accounts.payer.to_account_info(),
                 &ctx.accounts.fee_treasury.to_account_info(),
                 &ctx.accounts.system_program.to_account_info(),
-                ADD_FUNDS_FLAT_FEE,
+                fees.add_funds_flat_fee,
             )?;
         }
 
@@ -781,7 +1452,7 @@ pub mod msp {
 
         mean_emit!(TreasuryAddFundsEvent {
             timestamp: now_ts,
-            sol_fee_charged: ADD_FUNDS_FLAT_FEE,
+            sol_fee_charged: fees.add_funds_flat_fee,
             token_fee_charged: 0,
             treasury_is_sol_fee_payed_by_treasury: treasury.sol_fee_payed_by_treasury,
             amount: amount,
@@ -794,460 +1465,945 @@ pub mod msp {
 
     /// Allocate units to a stream
     pub fn allocate<'info>(
-        ctx: Context<AllocateAccounts>,
+        ctx: Context<'_, '_, '_, 'info, AllocateAccounts<'info>>,
         _idl_file_version: u8,
         amount: u64,
     ) -> Result<()> {
-        let clock = Clock::get()?;
-        let now_ts = clock.unix_timestamp as u64;
-        let now_slot = clock.slot as u64;
-
-        let treasury = &mut ctx.accounts.treasury;
-        let stream = &mut ctx.accounts.stream;
-
-        stream.save_effective_cliff();
-
-        let fee_amount = if stream.fee_payed_by_treasurer {
-            u64::try_from(
-                (WITHDRAW_PERCENT_FEE as u128)
-                    .checked_mul(amount as u128)
-                    .ok_or(ErrorCode::Overflow)?
-                    .checked_div(PERCENT_DENOMINATOR as u128)
-                    .ok_or(ErrorCode::Overflow)?,
-            )
-            .unwrap()
-        } else {
-            0_u64
-        };
-
-        let funding_amount = amount.checked_add(fee_amount).ok_or(ErrorCode::Overflow)?;
-
-        // Added in case we decide not to throw error on inssuficient treasury balance
-        // if funding_amount > treasury.last_known_unallocated_balance()? {
-        //     let stream_max_allocation = treasury.last_known_unallocated_balance()?
-        //         .checked_mul(PERCENT_DENOMINATOR).unwrap()
-        //         .checked_div(
-        //             WITHDRAW_PERCENT_FEE.checked_add(PERCENT_DENOMINATOR).unwrap()
-        //         ).unwrap();
-        //     fee_amount = stream_max_allocation
-        //         .checked_mul(WITHDRAW_PERCENT_FEE).unwrap()
-        //         .checked_div(PERCENT_DENOMINATOR).unwrap();
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(
+            &ctx.accounts.treasury,
+            &ctx.accounts.referrer,
+            Some(&ctx.accounts.referrer_token),
+        )?;
+        let allocate_event = stream_allocate(
+            amount,
+            &mut ctx.accounts.stream,
+            &mut ctx.accounts.treasury,
+            &mut ctx.accounts.treasury_token,
+            &ctx.accounts.fee_treasury_token.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            referrer.as_ref(),
+            &fees,
+            fee_tier,
+        )?;
+        mean_emit!(allocate_event);
 
-        //     funding_amount = treasury.last_known_unallocated_balance()?
-        //         .checked_sub(fee_amount).unwrap();
-        // }
+        Ok(())
+    }
 
-        if funding_amount > treasury.last_known_unallocated_balance()? {
-            return Err(ErrorCode::InsufficientTreasuryBalance.into());
+    /// Split `amount` units across the treasury streams passed in the
+    /// remaining accounts, pro-rata to each stream rate or evenly. Any
+    /// rounding remainder is left unallocated and scheduled streams are
+    /// skipped
+    pub fn allocate_to_all_streams<'info>(
+        ctx: Context<'_, '_, '_, 'info, AllocateToAllStreamsAccounts<'info>>,
+        _idl_file_version: u8,
+        amount: u64,
+        split_mode: u8,
+    ) -> Result<()> {
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(
+            &ctx.accounts.treasury,
+            &ctx.accounts.referrer,
+            Some(&ctx.accounts.referrer_token),
+        )?;
+        if split_mode != ALLOCATION_SPLIT_MODE_PRO_RATA && split_mode != ALLOCATION_SPLIT_MODE_EVEN {
+            return Err(ErrorCode::InvalidAllocationSplitMode.into());
         }
-
-        if fee_amount > 0 {
-            // Transfer fee from Treasury
-            treasury_transfer(
-                treasury,
-                &ctx.accounts.treasury_token.to_account_info(),
-                &ctx.accounts.fee_treasury_token.to_account_info(),
-                &ctx.accounts.token_program.to_account_info(),
-                fee_amount,
-            )?;
+        if ctx.remaining_accounts.is_empty() {
+            return Err(ErrorCode::InvalidStreamAccounts.into());
         }
 
-        // update stream
-        let status = stream.get_status(now_ts)?;
-        let is_manual_pause = stream.primitive_is_manually_paused();
-
-        let mut allocate_event = StreamAllocateEvent {
-            timestamp: now_ts,
-            sol_fee_charged: 0,
-            token_fee_charged: fee_amount,
-            amount: amount,
-            stream_status_before: status as u32,
-            stream_was_manually_paused_before: is_manual_pause,
-            stream_last_auto_stop_block_time: 0,
-            stream_total_seconds_in_paused_status_after: 0,
-            stream_is_token_withdraw_fee_payed_by_treasury: stream.fee_payed_by_treasurer,
-            stream_allocation_after: 0,
-            treasury_is_sol_fee_payed_by_treasury: treasury.sol_fee_payed_by_treasury,
-            treasury_allocation_after: 0,
-            treasury_balance_after: 0,
-            stream: stream.key(),
-            treasury: treasury.key(),
-        };
-
-        if status == StreamStatus::Paused && !is_manual_pause {
-            let est_depletion_time = stream.get_est_depletion_blocktime()?;
-            let remaining_allocation = stream.get_remaining_allocation()?;
-
-            // record the moment the stream stopped for running out of money
-            stream.last_auto_stop_block_time = est_depletion_time;
-
-            let seconds_paused_since_last_auto_stop = now_ts
-                .checked_sub(stream.last_auto_stop_block_time)
-                .ok_or(ErrorCode::Overflow)?;
-
-            // SecondsPaused += S3
-            stream.last_known_total_seconds_in_paused_status = stream
-                .last_known_total_seconds_in_paused_status
-                .checked_add(seconds_paused_since_last_auto_stop)
-                .ok_or(ErrorCode::Overflow)?;
+        let remaining_accounts = ctx.remaining_accounts;
+        let mut streams = Vec::with_capacity(remaining_accounts.len());
+        let mut i = 0;
+        while i < remaining_accounts.len() {
+            let stream_account_info = &remaining_accounts[i];
+            // the same stream can't be allocated twice
+            if remaining_accounts[..i]
+                .iter()
+                .any(|other| other.key() == stream_account_info.key())
+            {
+                return Err(ErrorCode::InvalidStreamAccounts.into());
+            }
 
-            // Update stream data (Resume the stream)
-            stream.last_manual_resume_remaining_allocation_units_snap = remaining_allocation;
-            stream.last_manual_resume_slot = now_slot;
-            stream.last_manual_resume_block_time = now_ts;
+            let stream = load_treasury_stream(stream_account_info, &ctx.accounts.treasury)?;
+            if stream.beneficiary_associated_token != ctx.accounts.associated_token.key() {
+                return Err(ErrorCode::InvalidAssociatedToken.into());
+            }
 
-            #[cfg(feature = "test")]
-            msg!("allocate status: auto-paused, est_depletion_time: {0}, remaining_allocation: {1}, last_auto_stop_block_time: {2}, seconds_paused_since_last_auto_stop: {3}, last_known_total_seconds_in_paused_status: {4}",
-                est_depletion_time, remaining_allocation, stream.last_auto_stop_block_time, seconds_paused_since_last_auto_stop, stream.last_known_total_seconds_in_paused_status);
+            // the stream schedule, if any, goes right after the stream. A
+            // scheduled stream is fully allocated when created so it takes no
+            // share of the amount
+            let schedule_accounts = remaining_accounts.get(i + 1..i + 2).unwrap_or(&[]);
+            let schedule = load_stream_schedule(stream.key(), &stream, schedule_accounts)?;
+            i += if schedule.is_some() { 2 } else { 1 };
+            if schedule.is_some() {
+                continue;
+            }
 
-            allocate_event.stream_last_auto_stop_block_time = stream.last_auto_stop_block_time;
-            allocate_event.stream_total_seconds_in_paused_status_after = stream.last_known_total_seconds_in_paused_status;
+            if stream.rate_amount_units == 0 || stream.rate_interval_in_seconds == 0 {
+                return Err(ErrorCode::InvalidStreamRate.into());
+            }
+            streams.push(stream);
+        }
+        if streams.is_empty() {
+            return Err(ErrorCode::InvalidStreamAccounts.into());
         }
 
-        stream.allocation_assigned_units = stream
-            .allocation_assigned_units
-            .checked_add(amount)
+        // the pro-rata weight is the stream rate in units per 10^9 seconds to
+        // keep precision
+        let mut weights = Vec::with_capacity(streams.len());
+        for stream in streams.iter() {
+            let weight = if split_mode == ALLOCATION_SPLIT_MODE_PRO_RATA {
+                (stream.rate_amount_units as u128)
+                    .checked_mul(1_000_000_000u128)
+                    .ok_or(ErrorCode::Overflow)?
+                    .checked_div(stream.rate_interval_in_seconds as u128)
+                    .ok_or(ErrorCode::Overflow)?
+            } else {
+                1u128
+            };
+            weights.push(weight);
+        }
+        let total_weight = weights
+            .iter()
+            .try_fold(0u128, |total, weight| total.checked_add(*weight))
             .ok_or(ErrorCode::Overflow)?;
+        if total_weight == 0 {
+            return Err(ErrorCode::InvalidStreamRate.into());
+        }
 
-        // update the start UTC to seconds if it's necesary
-        stream.update_start_utc()?;
-
-        // update treasury
-        treasury.allocation_assigned_units = treasury
-            .allocation_assigned_units
-            .checked_add(amount)
-            .ok_or(ErrorCode::Overflow)?;
+        for (stream, weight) in streams.iter_mut().zip(weights) {
+            let stream_amount = u64::try_from(
+                (amount as u128)
+                    .checked_mul(weight)
+                    .ok_or(ErrorCode::Overflow)?
+                    .checked_div(total_weight)
+                    .ok_or(ErrorCode::Overflow)?,
+            )
+            .unwrap();
 
-        treasury.last_known_balance_slot = now_slot;
-        treasury.last_known_balance_block_time = now_ts;
-        treasury.last_known_balance_units = treasury
-            .last_known_balance_units
-            .checked_sub(fee_amount)
-            .ok_or(ErrorCode::Overflow)?;
+            if stream_amount == 0 {
+                continue;
+            }
 
-        ctx.accounts.treasury_token.reload()?;
-        assert!(
-            ctx.accounts.treasury_token.amount >= treasury.last_known_balance_units,
-            "treasury balance units invariant violated"
-        );
+            let allocate_event = stream_allocate(
+                stream_amount,
+                stream,
+                &mut ctx.accounts.treasury,
+                &mut ctx.accounts.treasury_token,
+                &ctx.accounts.fee_treasury_token.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                referrer.as_ref(),
+                &fees,
+                fee_tier,
+            )?;
+            stream.exit(ctx.program_id)?;
 
-        allocate_event.stream_allocation_after = stream.allocation_assigned_units;
-        allocate_event.treasury_allocation_after = treasury.allocation_assigned_units;
-        allocate_event.treasury_balance_after = treasury.last_known_balance_units;
-        mean_emit!(allocate_event);
+            mean_emit!(allocate_event);
+        }
 
         Ok(())
     }
 
     /// Close Stream
-    pub fn close_stream(ctx: Context<CloseStreamAccounts>, _idl_file_version: u8) -> Result<()> {
+    pub fn close_stream<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseStreamAccounts<'info>>,
+        _idl_file_version: u8,
+    ) -> Result<()> {
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(
+            &ctx.accounts.treasury,
+            &ctx.accounts.referrer,
+            Some(&ctx.accounts.referrer_token),
+        )?;
         let clock = Clock::get()?;
         let now_ts = clock.unix_timestamp as u64;
-        let now_slot = clock.slot as u64;
 
+        let schedule = load_stream_schedule(
+            ctx.accounts.stream.key(),
+            &ctx.accounts.stream,
+            ctx.remaining_accounts,
+        )?;
         let treasury = &mut ctx.accounts.treasury;
         let stream = &mut ctx.accounts.stream;
         treasury.last_known_balance_units = ctx.accounts.treasury_token.amount;
 
-        stream.save_effective_cliff();
-
-        let beneficiary_closing_amount = stream.get_beneficiary_withdrawable_amount(now_ts)?;
+        let beneficiary_closing_amount = match &schedule {
+            Some(schedule) => {
+                if treasury.treasury_type == TREASURY_TYPE_LOCKED
+                    && stream.get_scheduled_status(schedule, now_ts)? == StreamStatus::Running
+                {
+                    return Err(ErrorCode::CloseLockedStreamNotAllowedWhileRunning.into());
+                }
+                stream.get_scheduled_withdrawable_amount(schedule, now_ts)?
+            }
+            None => stream.get_beneficiary_withdrawable_amount(now_ts)?,
+        };
         #[cfg(feature = "test")]
         msg!(
             "beneficiary_closing_amount: {0}",
             beneficiary_closing_amount
         );
 
-        let closing_amount_kept_in_treasury = stream
-            .allocation_assigned_units
-            .checked_sub(stream.total_withdrawals_units)
-            .unwrap()
-            .checked_sub(beneficiary_closing_amount)
-            .ok_or(ErrorCode::Overflow)?;
-        #[cfg(feature = "test")]
-        msg!(
-            "closing_amount_kept_in_treasury: {0}",
-            closing_amount_kept_in_treasury
-        );
+        close_stream_settle(
+            beneficiary_closing_amount,
+            stream,
+            treasury,
+            &mut TreasuryVault::Token {
+                treasury_token: &mut ctx.accounts.treasury_token,
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            &ctx.accounts.beneficiary_token.to_account_info(),
+            &ctx.accounts.fee_treasury_token.to_account_info(),
+            &ctx.accounts.fee_treasury.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            referrer.as_ref(),
+            &fees,
+            fee_tier,
+            true,
+        )?;
 
-        let mut fee_amount = 0u64;
-        let mut beneficiary_closing_amount_after_deducting_fees = beneficiary_closing_amount;
+        // the schedule is closed along with the stream
+        if let Some(schedule) = schedule {
+            schedule.close(ctx.accounts.payer.to_account_info())?;
+        }
 
-        if !stream.fee_payed_by_treasurer && beneficiary_closing_amount > 0 {
-            fee_amount = u64::try_from(
-                (CLOSE_STREAM_PERCENT_FEE as u128)
-                    .checked_mul(beneficiary_closing_amount as u128)
-                    .ok_or(ErrorCode::Overflow)?
-                    .checked_div(PERCENT_DENOMINATOR as u128)
-                    .ok_or(ErrorCode::Overflow)?,
-            )
-            .unwrap();
+        Ok(())
+    }
 
-            beneficiary_closing_amount_after_deducting_fees = beneficiary_closing_amount
-                .checked_sub(fee_amount)
-                .ok_or(ErrorCode::Overflow)?;
+    /// Close a stream as the beneficiary, giving up the unvested allocation.
+    /// The withdrawable amount is paid out and the rest goes back to the
+    /// treasury unallocated balance
+    pub fn renounce_stream<'info>(
+        ctx: Context<'_, '_, '_, 'info, RenounceStreamAccounts<'info>>,
+        _idl_file_version: u8,
+    ) -> Result<()> {
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(
+            &ctx.accounts.treasury,
+            &ctx.accounts.referrer,
+            Some(&ctx.accounts.referrer_token),
+        )?;
+        let now_ts = Clock::get()?.unix_timestamp as u64;
+
+        let schedule = load_stream_schedule(
+            ctx.accounts.stream.key(),
+            &ctx.accounts.stream,
+            ctx.remaining_accounts,
+        )?;
+        let treasury = &mut ctx.accounts.treasury;
+        let stream = &mut ctx.accounts.stream;
+        treasury.last_known_balance_units = ctx.accounts.treasury_token.amount;
+
+        let beneficiary_closing_amount = match &schedule {
+            Some(schedule) => stream.get_scheduled_withdrawable_amount(schedule, now_ts)?,
+            None => stream.get_beneficiary_withdrawable_amount(now_ts)?,
+        };
+
+        close_stream_settle(
+            beneficiary_closing_amount,
+            stream,
+            treasury,
+            &mut TreasuryVault::Token {
+                treasury_token: &mut ctx.accounts.treasury_token,
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            &ctx.accounts.beneficiary_token.to_account_info(),
+            &ctx.accounts.fee_treasury_token.to_account_info(),
+            &ctx.accounts.fee_treasury.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            referrer.as_ref(),
+            &fees,
+            fee_tier,
+            true,
+        )?;
+
+        // the schedule rent goes back to the treasurer along with the stream's
+        if let Some(schedule) = schedule {
+            schedule.close(ctx.accounts.treasurer.to_account_info())?;
         }
 
-        // Transfer withdrawable amount to beneficiary and deduct fee
-        if beneficiary_closing_amount > 0 {
-            // Transfer withdrawable amount
-            treasury_transfer(
-                &treasury,
-                &ctx.accounts.treasury_token.to_account_info(),
-                &ctx.accounts.beneficiary_token.to_account_info(),
-                &ctx.accounts.token_program.to_account_info(),
-                beneficiary_closing_amount_after_deducting_fees,
-            )?;
+        Ok(())
+    }
+
+    /// Close Treasury
+    pub fn close_treasury(
+        ctx: Context<CloseTreasuryAccounts>,
+        _idl_file_version: u8,
+    ) -> Result<()> {
+        let fees = Config::load(&ctx.accounts.config)?.fees;
+        let treasury_token = &mut ctx.accounts.treasury_token;
+        close_treasury_settle(
+            &ctx.accounts.treasury,
+            treasury_token,
+            &ctx.accounts.destination_token_account.to_account_info(),
+            &ctx.accounts.destination_authority.to_account_info(),
+            &ctx.accounts.fee_treasury.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &fees,
+        )?;
+
+        Ok(())
+    }
+
+    /// Close Treasury And Streams
+    /// Closes every stream of the treasury (as close_stream does) and then
+    /// closes the treasury. All the treasury streams must be passed
+    pub fn close_treasury_and_streams<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseTreasuryAndStreamsAccounts<'info>>,
+        _idl_file_version: u8,
+    ) -> Result<()> {
+        let config = Config::load(&ctx.accounts.config)?;
+        let (fees, fee_tier) =
+            FeeOverride::resolve(&ctx.accounts.fee_override, &config, &ctx.accounts.treasury)?;
+        let referrer = load_fee_referrer(
+            &ctx.accounts.treasury,
+            &ctx.accounts.referrer,
+            Some(&ctx.accounts.referrer_token),
+        )?;
+        let clock = Clock::get()?;
+        let now_ts = clock.unix_timestamp as u64;
+
+        let remaining_accounts = ctx.remaining_accounts;
+        let associated_token = ctx.accounts.associated_token.key();
+        ctx.accounts.treasury.last_known_balance_units = ctx.accounts.treasury_token.amount;
 
-            if fee_amount > 0 {
-                // Fee
-                treasury_transfer(
-                    &treasury,
-                    &ctx.accounts.treasury_token.to_account_info(),
-                    &ctx.accounts.fee_treasury_token.to_account_info(),
-                    &ctx.accounts.token_program.to_account_info(),
-                    fee_amount,
-                )?;
+        let mut closed_streams = Vec::new();
+        let mut i = 0;
+        while i < remaining_accounts.len() {
+            if i + 1 >= remaining_accounts.len() {
+                return Err(ErrorCode::InvalidStreamAccounts.into());
             }
+            let stream = load_treasury_stream(&remaining_accounts[i], &ctx.accounts.treasury)?;
+            if stream.beneficiary_associated_token != associated_token {
+                return Err(ErrorCode::InvalidAssociatedToken.into());
+            }
+
+            let beneficiary_token_account_info = &remaining_accounts[i + 1];
+            if beneficiary_token_account_info.key()
+                != get_associated_token_address(&stream.beneficiary_address, &associated_token)
+            {
+                return Err(ErrorCode::InvalidAssociatedToken.into());
+            }
+            // the beneficiary token account needs to exist already
+            Account::<TokenAccount>::try_from(beneficiary_token_account_info)?;
+
+            // the stream schedule, if any, goes right after the pair
+            let schedule_accounts = remaining_accounts.get(i + 2..i + 3).unwrap_or(&[]);
+            let schedule = load_stream_schedule(stream.key(), &stream, schedule_accounts)?;
+            i += if schedule.is_some() { 3 } else { 2 };
+
+            // same locked treasury rules as close_stream
+            let status = match &schedule {
+                Some(schedule) => stream.get_scheduled_status(schedule, now_ts)?,
+                None => stream.get_status(now_ts)?,
+            };
+            if ctx.accounts.treasury.treasury_type == TREASURY_TYPE_LOCKED
+                && status == StreamStatus::Running
+            {
+                return Err(ErrorCode::CloseLockedStreamNotAllowedWhileRunning.into());
+            }
+
+            let beneficiary_closing_amount = match &schedule {
+                Some(schedule) => stream.get_scheduled_withdrawable_amount(schedule, now_ts)?,
+                None => stream.get_beneficiary_withdrawable_amount(now_ts)?,
+            };
+
+            close_stream_settle(
+                beneficiary_closing_amount,
+                &stream,
+                &mut ctx.accounts.treasury,
+                &mut TreasuryVault::Token {
+                    treasury_token: &mut ctx.accounts.treasury_token,
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+                beneficiary_token_account_info,
+                &ctx.accounts.fee_treasury_token.to_account_info(),
+                &ctx.accounts.fee_treasury.to_account_info(),
+                &ctx.accounts.payer.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                referrer.as_ref(),
+                &fees,
+                fee_tier,
+                false,
+            )?;
+
+            closed_streams.push((stream, schedule));
         }
 
-        // Update treasury data
-        let deallocated_units = beneficiary_closing_amount
-            .checked_add(closing_amount_kept_in_treasury)
-            .ok_or(ErrorCode::Overflow)?;
-        close_stream_update_treasury(
-            treasury,
-            beneficiary_closing_amount,
-            deallocated_units,
-            now_ts,
-            now_slot,
+        if ctx.accounts.treasury.total_streams > 0 {
+            return Err(ErrorCode::TreasuryContainsStreams.into());
+        }
+
+        close_treasury_settle(
+            &ctx.accounts.treasury,
+            &mut ctx.accounts.treasury_token,
+            &ctx.accounts.destination_token_account.to_account_info(),
+            &ctx.accounts.destination_authority.to_account_info(),
+            &ctx.accounts.fee_treasury.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &config.fees,
         )?;
 
-        // sol fee
-        // #[cfg(feature = "test")]
-        if treasury.sol_fee_payed_by_treasury {
-            treasury_transfer_sol_amount(
-                &treasury.to_account_info(),
+        // the sol fees and the stream (and its schedule) rent going to the
+        // payer need to be after any cpi in this ix to avoid Solana's weird
+        // CPI imbalance check hack
+        for (stream, schedule) in closed_streams {
+            charge_sol_fee_with_referrer(
+                &ctx.accounts.treasury,
                 &ctx.accounts.fee_treasury.to_account_info(),
-                CLOSE_STREAM_FLAT_FEE,
+                referrer.as_ref(),
+                &ctx.accounts.payer.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                fees.close_stream_flat_fee,
+                &fees,
             )?;
-        } else {
-            transfer_sol_amount(
+            stream.close(ctx.accounts.payer.to_account_info())?;
+            if let Some(schedule) = schedule {
+                schedule.close(ctx.accounts.payer.to_account_info())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Migrate Stream
+    /// Rewrites a v2 stream with the v3 layout and shrinks it to
+    /// `STREAM_V3_SIZE` (plus its extensions)
+    pub fn migrate_stream(ctx: Context<MigrateStreamAccounts>, _idl_file_version: u8) -> Result<()> {
+        let stream = &mut ctx.accounts.stream;
+
+        // the extensions are moved right after the v3 fields before the
+        // account is shrunk. The v3 fields are written when the instruction
+        // exits
+        let extensions_len =
+            migrate_account_extensions::<Stream>(&stream.to_account_info(), 3)?;
+        stream.migrate_to_v3();
+        resize_account(
+            &stream.to_account_info(),
+            STREAM_V3_SIZE + extensions_len,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Migrate Treasury
+    /// Rewrites a v2 treasury with the v3 layout and shrinks it to
+    /// `TREASURY_V3_SIZE` (plus its extensions)
+    pub fn migrate_treasury(ctx: Context<MigrateTreasuryAccounts>, _idl_file_version: u8) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+
+        // the extensions are moved right after the v3 fields before the
+        // account is shrunk. The v3 fields are written when the instruction
+        // exits
+        let extensions_len =
+            migrate_account_extensions::<Treasury>(&treasury.to_account_info(), 3)?;
+        treasury.migrate_to_v3();
+        resize_account(
+            &treasury.to_account_info(),
+            TREASURY_V3_SIZE + extensions_len,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Create Native Treasury
+    /// Creates a treasury that streams native SOL held as lamports by the
+    /// treasury PDA. Program fees are always payed by the `payer`. The
+    /// optional `referrer` is set as in `create_treasury`
+    pub fn create_native_treasury(
+        ctx: Context<CreateNativeTreasuryAccounts>,
+        _idl_file_version: u8,
+        slot: u64,
+        name: String,
+        treasury_type: u8,
+        auto_close: bool,
+        category: Category,
+        sub_category: SubCategory,
+        referrer: Option<Pubkey>,
+    ) -> Result<()> {
+        let fees = Config::load(&ctx.accounts.config)?.fees;
+        // native SOL treasuries have no mint so the associated token is set
+        // to the default pubkey (the system program address)
+        construct_treasury_account(
+            name,
+            treasury_type,
+            auto_close,
+            false,
+            category,
+            sub_category,
+            &mut ctx.accounts.treasury,
+            ctx.bumps["treasury"],
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.treasurer.to_account_info(),
+            &ctx.accounts.fee_treasury.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            slot,
+            &fees,
+        )?;
+
+        set_account_extension::<Treasury, NativeSolTreasury>(
+            &ctx.accounts.treasury.to_account_info(),
+            &NativeSolTreasury::default(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        if let Some(referrer) = referrer {
+            // treasurers can't take a share of their own fees
+            if referrer == ctx.accounts.treasurer.key() {
+                return Err(ErrorCode::InvalidReferrerAccount.into());
+            }
+            set_account_extension::<Treasury, TreasuryReferrer>(
+                &ctx.accounts.treasury.to_account_info(),
+                &TreasuryReferrer {
+                    referrer_address: referrer,
+                },
                 &ctx.accounts.payer.to_account_info(),
-                &ctx.accounts.fee_treasury.to_account_info(),
                 &ctx.accounts.system_program.to_account_info(),
-                CLOSE_STREAM_FLAT_FEE,
             )?;
         }
 
-        #[cfg(feature = "test")]
-        msg!(
-            "stream.total_withdrawals_units: {0}",
-            stream.total_withdrawals_units
-        );
-        #[cfg(feature = "test")]
-        msg!(
-            "beneficiary_closing_amount_after_deducting_fees: {0}",
-            beneficiary_closing_amount_after_deducting_fees
-        );
-        #[cfg(feature = "test")]
-        msg!(
-            "closing_amount_kept_in_treasury: {0}",
-            closing_amount_kept_in_treasury
-        );
-        #[cfg(feature = "test")]
-        msg!("fee_amount: {0}", fee_amount);
+        Ok(())
+    }
+
+    /// Add Funds Native
+    /// Sends `amount` lamports from the contributor to a native SOL treasury
+    pub fn add_funds_native(
+        ctx: Context<AddFundsNativeAccounts>,
+        _idl_file_version: u8,
+        amount: u64,
+    ) -> Result<()> {
+        let fees = Config::load(&ctx.accounts.config)?.fees;
+        let clock = Clock::get()?;
+        let now_ts = clock.unix_timestamp as u64;
+
+        // sol fee
+        transfer_sol_amount(
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.fee_treasury.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            fees.add_funds_flat_fee,
+        )?;
+
+        transfer_sol_amount(
+            &ctx.accounts.contributor.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            amount,
+        )?;
+
+        // update treasury
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.last_known_balance_slot = clock.slot as u64;
+        treasury.last_known_balance_block_time = now_ts;
+        treasury.last_known_balance_units = treasury
+            .last_known_balance_units
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+
         assert!(
-            stream
-                .total_withdrawals_units
-                .checked_add(beneficiary_closing_amount_after_deducting_fees)
-                .unwrap()
-                .checked_add(closing_amount_kept_in_treasury)
-                .unwrap()
-                .checked_add(fee_amount)
-                .ok_or(ErrorCode::Overflow)?
-                == stream.allocation_assigned_units,
-            "stream closing total_withdrawals vs allocation_assigned invariant violated"
+            TreasuryVault::NativeSol.balance(treasury)? >= treasury.last_known_balance_units,
+            "treasury balance units invariant violated"
         );
 
-        mean_emit!(CloseStreamEvent {
-            timestamp: Clock::get()?.unix_timestamp as u64,
-            sol_fee_charged: CLOSE_STREAM_FLAT_FEE,
-            token_fee_charged: fee_amount,
-            token_amount_sent_to_beneficiary: beneficiary_closing_amount_after_deducting_fees,
-            stream_is_token_withdraw_fee_payed_by_treasury: stream.fee_payed_by_treasurer,
-            stream_allocation_before: stream.allocation_assigned_units,
-            stream_total_withdrawals_before: stream.total_withdrawals_units,
+        mean_emit!(TreasuryAddFundsEvent {
+            timestamp: now_ts,
+            sol_fee_charged: fees.add_funds_flat_fee,
+            token_fee_charged: 0,
             treasury_is_sol_fee_payed_by_treasury: treasury.sol_fee_payed_by_treasury,
-            treasury_allocation_after: treasury.allocation_assigned_units,
+            amount: amount,
             treasury_balance_after: treasury.last_known_balance_units,
-            treasury_total_streams_after: treasury.total_streams,
-            stream: stream.key(),
             treasury: treasury.key(),
         });
 
         Ok(())
     }
 
-    /// Close Treasury
-    pub fn close_treasury(
-        ctx: Context<CloseTreasuryAccounts>,
+    /// Create Stream Native
+    /// Creates a stream in a native SOL treasury
+    pub fn create_stream_native(
+        ctx: Context<CreateStreamNativeAccounts>,
         _idl_file_version: u8,
+        name: String,
+        start_utc: u64,
+        rate_amount_units: u64,
+        rate_interval_in_seconds: u64,
+        allocation_assigned_units: u64,
+        cliff_vest_amount_units: u64,
+        fee_payed_by_treasurer: bool,
+        accrual_mode: u8,
+        cliff_utc: u64,
     ) -> Result<()> {
-        let clock = Clock::get()?;
-        let now_ts = clock.unix_timestamp as u64;
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(&ctx.accounts.treasury, &ctx.accounts.referrer, None)?;
+        // native SOL streams have no associated token, the beneficiary gets
+        // the lamports in its own account
+        construct_stream_account(
+            name,
+            start_utc,
+            rate_amount_units,
+            rate_interval_in_seconds,
+            allocation_assigned_units,
+            fee_payed_by_treasurer,
+            cliff_vest_amount_units,
+            accrual_mode,
+            cliff_utc,
+            &mut ctx.accounts.stream,
+            &mut ctx.accounts.treasury,
+            &mut TreasuryVault::NativeSol,
+            &ctx.accounts.beneficiary.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.fee_treasury.to_account_info(),
+            &ctx.accounts.fee_treasury.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            referrer.as_ref(),
+            &fees,
+            fee_tier,
+        )?;
+
+        Ok(())
+    }
+
+    /// Withdraw Native
+    /// Withdraws lamports from a native SOL treasury stream to the
+    /// beneficiary account
+    pub fn withdraw_native<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawNativeAccounts<'info>>,
+        _idl_file_version: u8,
+        amount: u64,
+    ) -> Result<()> {
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(&ctx.accounts.treasury, &ctx.accounts.referrer, None)?;
+        let schedule = load_stream_schedule(
+            ctx.accounts.stream.key(),
+            &ctx.accounts.stream,
+            ctx.remaining_accounts,
+        )?;
+
+        let withdraw_event = stream_withdraw(
+            amount,
+            schedule.as_deref(),
+            &mut ctx.accounts.stream,
+            &mut ctx.accounts.treasury,
+            &mut TreasuryVault::NativeSol,
+            &ctx.accounts.beneficiary.to_account_info(),
+            &ctx.accounts.fee_treasury.to_account_info(),
+            referrer.as_ref(),
+            &fees,
+            fee_tier,
+        )?;
 
+        mean_emit!(withdraw_event);
+
+        Ok(())
+    }
+
+    /// Close Stream Native
+    /// Closes a native SOL treasury stream, sending the vested lamports to
+    /// the beneficiary account
+    pub fn close_stream_native<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseStreamNativeAccounts<'info>>,
+        _idl_file_version: u8,
+    ) -> Result<()> {
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(&ctx.accounts.treasury, &ctx.accounts.referrer, None)?;
+        let now_ts = Clock::get()?.unix_timestamp as u64;
+
+        let schedule = load_stream_schedule(
+            ctx.accounts.stream.key(),
+            &ctx.accounts.stream,
+            ctx.remaining_accounts,
+        )?;
         let treasury = &mut ctx.accounts.treasury;
-        let treasury_token_amount_before = ctx.accounts.treasury_token.amount;
-
-        // if treasury.total_streams > 0 {
-        //     return Err(ErrorCode::TreasuryContainsStreams.into());
-        // }
-        // let treasury_signer_seed: &[&[&[_]]] = &[&[
-        //     treasury.treasurer_address.as_ref(),
-        //     &treasury.slot.to_le_bytes(),
-        //     &treasury.bump.to_le_bytes()
-        // ]];
-
-        if ctx.accounts.treasury_token.amount > 0 {
-            // Approach 1. using Anchor spl wrapper
-            treasury_transfer(
-                &treasury,
-                &ctx.accounts.treasury_token.to_account_info(),
-                &ctx.accounts.destination_token_account.to_account_info(),
-                &ctx.accounts.token_program.to_account_info(),
-                ctx.accounts.treasury_token.amount,
-            )?;
+        let stream = &mut ctx.accounts.stream;
+
+        let beneficiary_closing_amount = match &schedule {
+            Some(schedule) => {
+                if treasury.treasury_type == TREASURY_TYPE_LOCKED
+                    && stream.get_scheduled_status(schedule, now_ts)? == StreamStatus::Running
+                {
+                    return Err(ErrorCode::CloseLockedStreamNotAllowedWhileRunning.into());
+                }
+                stream.get_scheduled_withdrawable_amount(schedule, now_ts)?
+            }
+            None => stream.get_beneficiary_withdrawable_amount(now_ts)?,
+        };
+
+        close_stream_settle(
+            beneficiary_closing_amount,
+            stream,
+            treasury,
+            &mut TreasuryVault::NativeSol,
+            &ctx.accounts.beneficiary.to_account_info(),
+            &ctx.accounts.fee_treasury.to_account_info(),
+            &ctx.accounts.fee_treasury.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            referrer.as_ref(),
+            &fees,
+            fee_tier,
+            true,
+        )?;
 
-            // // Approach 2. using directly the spl token program
-            // // We had to go this way to avoid Solanas weird pre-cpi imbalance check
-            // let mut transfer_account_ix = spl_token::instruction::transfer(
-            //     &ctx.accounts.token_program.key(),
-            //     &ctx.accounts.treasury_token.key(),
-            //     &ctx.accounts.destination_token_account.key(),
-            //     &treasury.key(),
-            //     &[],
-            //     ctx.accounts.treasury_token.amount,
-            // )?;
-            // transfer_account_ix
-            //     .accounts
-            //     .push(anchor_lang::solana_program::instruction::AccountMeta {
-            //         pubkey: ctx.accounts.fee_treasury.key(),
-            //         is_signer: false,
-            //         is_writable: false,
-            //     });
-
-            // anchor_lang::solana_program::program::invoke_signed(
-            //     &transfer_account_ix,
-            //     &[
-            //         ctx.accounts.treasury_token.to_account_info(),
-            //         ctx.accounts.destination_token_account.to_account_info(),
-            //         treasury.to_account_info(),
-            //         ctx.accounts.fee_treasury.to_account_info(),
-            //     ],
-            //     treasury_signer_seed
-            // )?;
+        // the schedule is closed along with the stream
+        if let Some(schedule) = schedule {
+            schedule.close(ctx.accounts.payer.to_account_info())?;
         }
 
-        // CLOSE THE TREASURY TOKEN ACCOUNT
-        // Treasury seeds
-        let treasury_signer_seed: &[&[_]] = &[&[
-            treasury.treasurer_address.as_ref(),
-            &treasury.slot.to_le_bytes(),
-            &treasury.bump.to_le_bytes(),
-        ]];
-        // Approach 1. using Anchor spl wrapper
-        // Close treasury token account
-        let close_cpi_accounts = CloseAccount {
-            account: ctx.accounts.treasury_token.to_account_info(),
-            destination: ctx.accounts.destination_authority.to_account_info(),
-            authority: treasury.to_account_info().clone(),
-        };
+        Ok(())
+    }
 
-        let close_cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            close_cpi_accounts,
-            treasury_signer_seed,
-        );
+    /// Treasury Withdraw Native
+    /// Withdraws unallocated lamports from a native SOL treasury
+    pub fn treasury_withdraw_native<'info>(
+        ctx: Context<'_, '_, '_, 'info, TreasuryWithdrawNativeAccounts<'info>>,
+        _idl_file_version: u8,
+        amount: u64,
+    ) -> Result<()> {
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(&ctx.accounts.treasury, &ctx.accounts.referrer, None)?;
+        let clock = Clock::get()?;
+        let now_ts = clock.unix_timestamp as u64;
 
-        close_account(close_cpi_ctx)?;
-
-        // // Approach 2. using directly the spl token program
-        // // We had to go this way to avoid Solanas weird pre-cpi imbalance check
-        // let mut close_account_ix = spl_token::instruction::close_account(
-        //     &ctx.accounts.token_program.key(),
-        //     &ctx.accounts.treasury_token.key(),
-        //     ctx.accounts.destination_authority.key,
-        //     &treasury.key(),
-        //     &[],
-        // )?;
-        // // adding the missing account here as a crazy hack to avoid
-        // // issues with Solanas weird pre-cpi imbalance check
-        // close_account_ix
-        //     .accounts
-        //     .push(anchor_lang::solana_program::instruction::AccountMeta {
-        //         pubkey: ctx.accounts.fee_treasury.key(),
-        //         is_signer: false,
-        //         is_writable: false,
-        //     });
-
-        // anchor_lang::solana_program::program::invoke_signed(
-        //     &close_account_ix,
-        //     &[
-        //         ctx.accounts.treasury_token.to_account_info(),
-        //         ctx.accounts.destination_authority.to_account_info(),
-        //         treasury.to_account_info(),
-        //         ctx.accounts.fee_treasury.to_account_info(),
-        //     ],
-        //     treasury_signer_seed
-        // )?;
+        let treasury = &mut ctx.accounts.treasury;
 
-        // sol fee
-        // this is done at the end to avoid pre-CPI imbalance check error
-        if treasury.sol_fee_payed_by_treasury {
-            // Since the treasury is being closed, there is no need to check if
-            // the treasury is rent exempt after transferring the fee amount.
-            // Also it can inconvenience users as they may have to fund the
-            // treasury with lamports in order to close it.
-            // Warning! We DO NEED this check in any other operation that
-            // transfers lamports out of the treasury.
-            let treasury_account_info = &treasury.to_account_info();
-            let fee_account_info = &ctx.accounts.fee_treasury.to_account_info();
-
-            if CLOSE_TREASURY_FLAT_FEE > treasury_account_info.lamports() {
-                return Err(ErrorCode::InsufficientLamports.into());
-            }
+        let fee_amount = u64::try_from(
+            (fees.treasury_withdraw_percent_fee as u128)
+                .checked_mul(amount as u128)
+                .ok_or(ErrorCode::Overflow)?
+                .checked_div(PERCENT_DENOMINATOR as u128)
+                .ok_or(ErrorCode::Overflow)?,
+        )
+        .unwrap();
 
-            **treasury_account_info.try_borrow_mut_lamports()? = treasury_account_info
-                .lamports()
-                .checked_sub(CLOSE_TREASURY_FLAT_FEE)
-                .ok_or(ErrorCode::Overflow)?;
+        let destination_amount = amount.checked_sub(fee_amount).ok_or(ErrorCode::Overflow)?;
 
-            **fee_account_info.try_borrow_mut_lamports()? = fee_account_info
-                .lamports()
-                .checked_add(CLOSE_TREASURY_FLAT_FEE)
-                .ok_or(ErrorCode::Overflow)?;
-        } else {
-            transfer_sol_amount(
-                &ctx.accounts.payer.to_account_info(),
+        // the lamports never go below the treasury rent exempt minimum
+        if fee_amount > 0 {
+            TreasuryVault::NativeSol.transfer_fee(
+                treasury,
                 &ctx.accounts.fee_treasury.to_account_info(),
-                &ctx.accounts.system_program.to_account_info(),
-                CLOSE_TREASURY_FLAT_FEE,
+                referrer.as_ref(),
+                fee_amount,
+                &fees,
             )?;
         }
 
+        TreasuryVault::NativeSol.transfer(
+            treasury,
+            &ctx.accounts.destination_authority.to_account_info(),
+            destination_amount,
+        )?;
+
+        // update treasury
+        treasury.last_known_balance_slot = clock.slot as u64;
+        treasury.last_known_balance_block_time = now_ts;
+        treasury.last_known_balance_units = treasury
+            .last_known_balance_units
+            .checked_sub(amount)
+            .ok_or(ErrorCode::Overflow)?;
+
+        mean_emit!(TreasuryWithdrawEvent {
+            timestamp: now_ts,
+            sol_fee_charged: 0,
+            token_fee_charged: fee_amount,
+            fee_tier,
+            amount: amount,
+            token_amount_sent_to_destination: destination_amount,
+            treasury_is_sol_fee_payed_by_treasury: treasury.sol_fee_payed_by_treasury,
+            treasury_balance_after: treasury.last_known_balance_units,
+            treasury: treasury.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Close Treasury Native
+    /// Closes a native SOL treasury without streams, sending all its lamports
+    /// to the destination authority
+    pub fn close_treasury_native(
+        ctx: Context<CloseTreasuryNativeAccounts>,
+        _idl_file_version: u8,
+    ) -> Result<()> {
+        let fees = Config::load(&ctx.accounts.config)?.fees;
+        let now_ts = Clock::get()?.unix_timestamp as u64;
+        let treasury = &ctx.accounts.treasury;
+        let treasury_balance = TreasuryVault::NativeSol.balance(treasury)?;
+
+        // program fees for native SOL treasuries are payed by the payer
+        transfer_sol_amount(
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.fee_treasury.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            fees.close_treasury_flat_fee,
+        )?;
+
+        // the treasury lamports are sent to the destination when the account
+        // is closed
         mean_emit!(CloseTreasuryEvent {
             timestamp: now_ts,
-            sol_fee_charged: CLOSE_TREASURY_FLAT_FEE,
+            sol_fee_charged: fees.close_treasury_flat_fee,
             token_fee_charged: 0,
-            token_amount_sent_to_destination: treasury_token_amount_before,
-            treasury_is_sol_fee_payed_by_treasury: treasury.sol_fee_payed_by_treasury,
+            token_amount_sent_to_destination: treasury_balance,
+            treasury_is_sol_fee_payed_by_treasury: false,
             treasury: treasury.key(),
         });
 
         Ok(())
     }
 
+    /// Init Config
+    /// Creates the config PDA. Until then, the fee constants and the
+    /// `fee_treasury` wallet are used
+    pub fn init_config(
+        ctx: Context<InitConfigAccounts>,
+        _idl_file_version: u8,
+        admin: Pubkey,
+        fee_treasury: Pubkey,
+        fees: FeeSchedule,
+    ) -> Result<()> {
+        fees.validate()?;
+
+        let config = &mut ctx.accounts.config;
+        config.version = 1;
+        config.bump = ctx.bumps["config"];
+        config.admin_address = admin;
+        config.fee_treasury_address = fee_treasury;
+        config.fees = fees;
+
+        mean_emit!(ConfigUpdateEvent {
+            timestamp: Clock::get()?.unix_timestamp as u64,
+            admin: config.admin_address,
+            fee_treasury: config.fee_treasury_address,
+            fees: config.fees,
+        });
+
+        Ok(())
+    }
+
+    /// Update Config
+    /// Replaces the admin, the fee recipient and the fee schedule
+    pub fn update_config(
+        ctx: Context<UpdateConfigAccounts>,
+        _idl_file_version: u8,
+        admin: Pubkey,
+        fee_treasury: Pubkey,
+        fees: FeeSchedule,
+    ) -> Result<()> {
+        fees.validate()?;
+
+        let config = &mut ctx.accounts.config;
+        config.admin_address = admin;
+        config.fee_treasury_address = fee_treasury;
+        config.fees = fees;
+
+        mean_emit!(ConfigUpdateEvent {
+            timestamp: Clock::get()?.unix_timestamp as u64,
+            admin: config.admin_address,
+            fee_treasury: config.fee_treasury_address,
+            fees: config.fees,
+        });
+
+        Ok(())
+    }
+
+    /// Set Fee Override
+    /// Assigns a fee tier to a treasury or to a treasury authority. The tier
+    /// fees replace the config fees when creating, withdrawing from,
+    /// allocating to and closing its streams, and in `treasury_withdraw`
+    pub fn set_fee_override(
+        ctx: Context<SetFeeOverrideAccounts>,
+        _idl_file_version: u8,
+        subject: Pubkey,
+        tier: u8,
+        fees: FeeSchedule,
+    ) -> Result<()> {
+        // tier zero identifies the config fees in the events
+        if tier == 0 {
+            return Err(ErrorCode::InvalidFeeTier.into());
+        }
+        fees.validate()?;
+
+        let fee_override = &mut ctx.accounts.fee_override;
+        fee_override.version = 1;
+        fee_override.bump = ctx.bumps["fee_override"];
+        fee_override.subject_address = subject;
+        fee_override.tier = tier;
+        fee_override.fees = fees;
+
+        mean_emit!(FeeOverrideUpdateEvent {
+            timestamp: Clock::get()?.unix_timestamp as u64,
+            tier: fee_override.tier,
+            fees: fee_override.fees,
+            subject: fee_override.subject_address,
+        });
+
+        Ok(())
+    }
+
+    /// Remove Fee Override
+    /// Closes the fee override. The subject is charged the config fees again
+    pub fn remove_fee_override(
+        ctx: Context<RemoveFeeOverrideAccounts>,
+        _idl_file_version: u8,
+    ) -> Result<()> {
+        mean_emit!(FeeOverrideUpdateEvent {
+            timestamp: Clock::get()?.unix_timestamp as u64,
+            tier: 0,
+            fees: ctx.accounts.config.fees,
+            subject: ctx.accounts.fee_override.subject_address,
+        });
+
+        Ok(())
+    }
+
     // /// UPDATE TREASURY DATA
     // pub fn update_treasury_data(
     //     ctx: Context<UpdateTreasuryDataAccounts>,
@@ -1289,18 +2445,28 @@ pub mod msp {
     // }
 
     /// Withdraw unallocated funds from treasury
-    pub fn treasury_withdraw(
-        ctx: Context<TreasuryWithdrawAccounts>,
+    pub fn treasury_withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, TreasuryWithdrawAccounts<'info>>,
         _idl_file_version: u8,
         amount: u64,
     ) -> Result<()> {
+        let (fees, fee_tier) = FeeOverride::resolve(
+            &ctx.accounts.fee_override,
+            &Config::load(&ctx.accounts.config)?,
+            &ctx.accounts.treasury,
+        )?;
+        let referrer = load_fee_referrer(
+            &ctx.accounts.treasury,
+            &ctx.accounts.referrer,
+            Some(&ctx.accounts.referrer_token),
+        )?;
         let clock = Clock::get()?;
         let now_ts = clock.unix_timestamp as u64;
 
         let treasury = &mut ctx.accounts.treasury;
 
         let fee_amount = u64::try_from(
-            (TREASURY_WITHDRAW_PERCENT_FEE as u128)
+            (fees.treasury_withdraw_percent_fee as u128)
                 .checked_mul(amount as u128)
                 .ok_or(ErrorCode::Overflow)?
                 .checked_div(PERCENT_DENOMINATOR as u128)
@@ -1312,12 +2478,16 @@ pub mod msp {
 
         // transfer token % fee to fee account
         if fee_amount > 0 {
-            treasury_transfer(
-                &treasury,
-                &ctx.accounts.treasury_token.to_account_info(),
+            TreasuryVault::Token {
+                treasury_token: &mut ctx.accounts.treasury_token,
+                token_program: ctx.accounts.token_program.to_account_info(),
+            }
+            .transfer_fee(
+                treasury,
                 &ctx.accounts.fee_treasury_token.to_account_info(),
-                &ctx.accounts.token_program.to_account_info(),
+                referrer.as_ref(),
                 fee_amount,
+                &fees,
             )?;
         }
 
@@ -1344,6 +2514,7 @@ pub mod msp {
             timestamp: now_ts,
             sol_fee_charged: 0,
             token_fee_charged: fee_amount,
+            fee_tier,
             amount: amount,
             token_amount_sent_to_destination: destination_amount,
             treasury_is_sol_fee_payed_by_treasury: treasury.sol_fee_payed_by_treasury,
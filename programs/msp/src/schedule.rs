@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::errors::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct SchedulePoint {
+    /// Unix timestamp (in seconds) when the units are unlocked
+    pub timestamp: u64,
+    /// The total amount of units unlocked since the stream started (including
+    /// the units unlocked in previous points)
+    pub cumulative_units: u64,
+}
+
+#[account]
+pub struct StreamSchedule {
+    pub version: u8, // offset: 8
+    pub bump: u8,
+    /// The stream that unlocks units following this schedule
+    pub stream_address: Pubkey,
+    /// The unlock points sorted by timestamp
+    pub points: Vec<SchedulePoint>,
+    // total bytes: 46 + 16 * points
+}
+
+impl StreamSchedule {
+    /// Gets the account size needed to store the given number of points
+    pub fn space(number_of_points: usize) -> usize {
+        8 + 1 + 1 + 32 + 4 + number_of_points * 16
+    }
+
+    /// Checks the points are sorted by timestamp, that the unlocked units
+    /// never decrease and that the total unlocked units equals the given
+    /// allocation
+    pub fn validate_points(points: &[SchedulePoint], allocation_assigned_units: u64) -> Result<()> {
+        if points.is_empty() || points.len() > MAX_SCHEDULE_POINTS {
+            return Err(ErrorCode::InvalidSchedule.into());
+        }
+
+        let mut previous: Option<&SchedulePoint> = None;
+        for point in points.iter() {
+            if let Some(previous) = previous {
+                if point.timestamp <= previous.timestamp
+                    || point.cumulative_units < previous.cumulative_units
+                {
+                    return Err(ErrorCode::InvalidSchedule.into());
+                }
+            }
+            previous = Some(point);
+        }
+
+        if points[points.len() - 1].cumulative_units != allocation_assigned_units {
+            return Err(ErrorCode::InvalidScheduleTotal.into());
+        }
+
+        Ok(())
+    }
+
+    /// Gets the total amount of units unlocked in the given blocktime
+    pub fn get_unlocked_units(&self, timestamp: u64) -> u64 {
+        self.points
+            .iter()
+            .take_while(|point| point.timestamp <= timestamp)
+            .last()
+            .map_or(0, |point| point.cumulative_units)
+    }
+
+    /// Gets the blocktime when the last units are unlocked
+    pub fn get_completion_blocktime(&self) -> u64 {
+        self.points.last().map_or(0, |point| point.timestamp)
+    }
+}
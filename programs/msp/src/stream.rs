@@ -1,11 +1,21 @@
 use crate::constants::*;
 use crate::enums::*;
 use crate::errors::ErrorCode;
+use crate::schedule::*;
 use anchor_lang::prelude::*;
+use msp_math::StreamState;
 use std::cmp;
 use std::convert::TryFrom;
+use std::io::Write;
 
-#[account]
+/// Anchor discriminator of the stream accounts (`sha256("account:Stream")[..8]`).
+/// It is the same for v2 and v3 streams, the layout is given by `version`
+pub const STREAM_DISCRIMINATOR: [u8; 8] = [166, 224, 59, 4, 202, 10, 186, 83];
+
+/// A stream with the v3 layout. v2 accounts are decoded into this struct and
+/// written back with the v2 layout until they are migrated with
+/// `migrate_stream` (see `StreamV2Layout`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct Stream {
     pub version: u8, // offset: 8
     pub initialized: bool,
@@ -17,15 +27,10 @@ pub struct Stream {
     pub start_utc: u64,
     /// The amount availaible to withdraw inmidiately (without streaming)
     /// once the money stream starts.
-    /// If both 'cliff_vest_amount_units' and 'cliff_vest_percent' are provided, the former will be used.
     pub cliff_vest_amount_units: u64,
-    /// The percent of the allocation assigned that is availaible to withdraw
-    /// inmidiately (without streaming) once the money stream starts.
-    /// If both 'cliff_vest_amount_units' and 'cliff_vest_percent' are provided, the second (this field) will be used.
-    pub cliff_vest_percent: u64, // deprecated
-    pub beneficiary_address: Pubkey,          // offset: 114
-    pub beneficiary_associated_token: Pubkey, // offset: 146
-    pub treasury_address: Pubkey,             // offset: 178
+    pub beneficiary_address: Pubkey,          // offset: 106
+    pub beneficiary_associated_token: Pubkey, // offset: 138
+    pub treasury_address: Pubkey,             // offset: 170
     /// Amount of tokens allocated to the stream on creation or top up. If the
     /// treasurer decides to close the stream, the vested amount will be sent
     /// to the benefifiary and the unvested amount will be sent to the
@@ -34,14 +39,6 @@ pub struct Stream {
     /// The allocation assigned will be affected by the following instructions:
     /// `addFunds`
     pub allocation_assigned_units: u64,
-    /// Amount of tokens reserved to the stream. If the treasurer decides to
-    /// close the stream, the total amount (vested and unvested) WILL be sent
-    /// to the beneficiary
-    ///
-    /// [deprecated] The allocation reserved will be affected by the following instructions:
-    /// `addFunds`
-    // #[deprecated]
-    pub allocation_reserved_units: u64,
     /// Withdrawal tracking
     /// The total amount that has been withdrawn by the beneficiary
     pub total_withdrawals_units: u64,
@@ -56,12 +53,12 @@ pub struct Stream {
     /// 2) by Running Out Of Funds (not recordable when it happens, needs to be calculated)
     pub last_manual_stop_withdrawable_units_snap: u64,
     pub last_manual_stop_slot: u64,
-    pub last_manual_stop_block_time: u64, // offset: 274
+    pub last_manual_stop_block_time: u64, // offset: 258
     /// The remaining allocation units at the moment of the last manual resume
     /// must be set when calling the Resume Stream
     pub last_manual_resume_remaining_allocation_units_snap: u64,
     pub last_manual_resume_slot: u64,
-    pub last_manual_resume_block_time: u64, // offset: 298
+    pub last_manual_resume_block_time: u64, // offset: 282
     /// The total seconds that have been paused since the start_utc
     /// increment when resume is called manually
     pub last_known_total_seconds_in_paused_status: u64,
@@ -69,8 +66,6 @@ pub struct Stream {
     /// either manually or automaticaly (run out of funds)
     pub last_auto_stop_block_time: u64,
     pub fee_payed_by_treasurer: bool,
-    /// The start timestamp blocktime
-    pub start_utc_in_seconds: u64,
     /// Unix timestamp (in seconds) when the stream was created
     pub created_on_utc: u64,
     /// Indicates the main product category such as `Vesting(1)`
@@ -79,338 +74,467 @@ pub struct Stream {
     /// Indicates the sub product category such as `Advisor(1)`, Development(2)
     /// The default value is set to a `Default(0)` sub_cateogry.
     pub sub_category: u8,
-    // total bytes: 341
+    /// Indicates how the stream accrues units over time. `Linear(0)` streams
+    /// every second, while `Step(1)` unlocks the full `rate_amount_units` at
+    /// the end of each `rate_interval_in_seconds`.
+    pub accrual_mode: u8,
+    /// The `StreamSchedule` account that defines when units are unlocked.
+    /// When set, the rate and cliff fields are not used to calculate the
+    /// vested amount.
+    pub schedule_address: Pubkey,
+    /// The timestamp in seconds before which nothing is withdrawable. Units
+    /// keep accruing from the start and unlock at once at this date.
+    /// Zero means the cliff unlocks at `start_utc`.
+    pub cliff_utc_in_seconds: u64,
+    /// The beneficiary proposed by `propose_stream_transfer`. It has to
+    /// accept the transfer before becoming the stream beneficiary
+    pub pending_beneficiary_address: Pubkey,
+    // total bytes: 390
+    // New fields go into the extensions region that follows (see tlv.rs)
+    /// The deprecated `allocation_reserved_units` of a v2 stream as read. It
+    /// is not part of the v3 layout
+    #[borsh_skip]
+    v2_allocation_reserved_units: u64,
 }
 
-impl Stream {
-    /// Calculates the cliff amount
-    pub fn primitive_get_cliff_units<'info>(&self) -> Result<u64> {
-        // calculate effective cliff units as an absolute amount. We will not store %
-        let cliff_units = if self.cliff_vest_percent > 0 {
-            u64::try_from(
-                (self.cliff_vest_percent as u128)
-                    .checked_mul(self.allocation_assigned_units as u128)
-                    .ok_or(ErrorCode::Overflow)?
-                    .checked_div(PERCENT_DENOMINATOR as u128)
-                    .ok_or(ErrorCode::Overflow)?,
-            )
-            .unwrap()
-        } else {
-            self.cliff_vest_amount_units
+impl AccountSerialize for Stream {
+    fn try_serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer
+            .write_all(&STREAM_DISCRIMINATOR)
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotSerialize)?;
+        let result = match self.version {
+            2 => StreamV2Layout::from_stream(self).serialize(writer),
+            _ => AnchorSerialize::serialize(self, writer),
         };
+        result.map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotSerialize)?;
+        Ok(())
+    }
+}
 
-        Ok(cliff_units)
+impl AccountDeserialize for Stream {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        if buf.len() < STREAM_DISCRIMINATOR.len() {
+            return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorNotFound.into());
+        }
+        if buf[..STREAM_DISCRIMINATOR.len()] != STREAM_DISCRIMINATOR {
+            return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::try_deserialize_unchecked(buf)
     }
 
-    /// calculate effective cliff units as an absolute amount and store it in
-    /// the stream since we will not store the cliff %
-    pub fn save_effective_cliff<'info>(&mut self) {
-        let cliff_units = if self.cliff_vest_percent > 0 {
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        let mut data: &[u8] = buf
+            .get(STREAM_DISCRIMINATOR.len()..)
+            .ok_or(anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?;
+        // the version is the first field in both layouts
+        let stream = match data.first() {
+            Some(2) => StreamV2Layout::deserialize(&mut data)
+                .ok()
+                .and_then(|v2| v2.into_stream().ok()),
+            _ => Stream::deserialize(&mut data).ok(),
+        };
+        Ok(stream.ok_or(anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?)
+    }
+}
+
+impl Owner for Stream {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+/// The v2 layout of a stream account, including the deprecated fields
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct StreamV2Layout {
+    version: u8,
+    initialized: bool,
+    name: [u8; 32],
+    treasurer_address: Pubkey,
+    rate_amount_units: u64,
+    rate_interval_in_seconds: u64,
+    /// The start timestamp in milliseconds when `start_utc_in_seconds` is 0
+    start_utc: u64,
+    cliff_vest_amount_units: u64,
+    /// The percent of the allocation assigned that is availaible to withdraw
+    /// once the stream starts. Used instead of `cliff_vest_amount_units` when
+    /// it is not 0
+    cliff_vest_percent: u64,
+    beneficiary_address: Pubkey,
+    beneficiary_associated_token: Pubkey,
+    treasury_address: Pubkey,
+    allocation_assigned_units: u64,
+    allocation_reserved_units: u64,
+    total_withdrawals_units: u64,
+    last_withdrawal_units: u64,
+    last_withdrawal_slot: u64,
+    last_withdrawal_block_time: u64,
+    last_manual_stop_withdrawable_units_snap: u64,
+    last_manual_stop_slot: u64,
+    last_manual_stop_block_time: u64,
+    last_manual_resume_remaining_allocation_units_snap: u64,
+    last_manual_resume_slot: u64,
+    last_manual_resume_block_time: u64,
+    last_known_total_seconds_in_paused_status: u64,
+    last_auto_stop_block_time: u64,
+    fee_payed_by_treasurer: bool,
+    start_utc_in_seconds: u64,
+    created_on_utc: u64,
+    category: u8,
+    sub_category: u8,
+    accrual_mode: u8,
+    schedule_address: Pubkey,
+    cliff_utc_in_seconds: u64,
+    pending_beneficiary_address: Pubkey,
+    // total bytes: 414
+}
+
+impl StreamV2Layout {
+    /// Decodes the v2 fields into a `Stream`. The cliff percent is replaced
+    /// by the absolute cliff amount and the start is converted to seconds.
+    /// The version is kept so the stream is written back as v2
+    fn into_stream(self) -> Result<Stream> {
+        let cliff_vest_amount_units = if self.cliff_vest_percent > 0 {
             u64::try_from(
                 (self.cliff_vest_percent as u128)
                     .checked_mul(self.allocation_assigned_units as u128)
-                    .unwrap()
+                    .ok_or(ErrorCode::Overflow)?
                     .checked_div(PERCENT_DENOMINATOR as u128)
-                    .unwrap(),
+                    .ok_or(ErrorCode::Overflow)?,
             )
-            .unwrap()
+            .map_err(|_| ErrorCode::Overflow)?
         } else {
             self.cliff_vest_amount_units
         };
-        self.cliff_vest_amount_units = cliff_units;
-        self.cliff_vest_percent = 0;
+        let start_utc = if self.start_utc_in_seconds > 0 {
+            self.start_utc
+        } else {
+            self.start_utc
+                .checked_div(1000u64)
+                .ok_or(ErrorCode::Overflow)?
+        };
+
+        Ok(Stream {
+            version: self.version,
+            initialized: self.initialized,
+            name: self.name,
+            treasurer_address: self.treasurer_address,
+            rate_amount_units: self.rate_amount_units,
+            rate_interval_in_seconds: self.rate_interval_in_seconds,
+            start_utc,
+            cliff_vest_amount_units,
+            beneficiary_address: self.beneficiary_address,
+            beneficiary_associated_token: self.beneficiary_associated_token,
+            treasury_address: self.treasury_address,
+            allocation_assigned_units: self.allocation_assigned_units,
+            total_withdrawals_units: self.total_withdrawals_units,
+            last_withdrawal_units: self.last_withdrawal_units,
+            last_withdrawal_slot: self.last_withdrawal_slot,
+            last_withdrawal_block_time: self.last_withdrawal_block_time,
+            last_manual_stop_withdrawable_units_snap: self
+                .last_manual_stop_withdrawable_units_snap,
+            last_manual_stop_slot: self.last_manual_stop_slot,
+            last_manual_stop_block_time: self.last_manual_stop_block_time,
+            last_manual_resume_remaining_allocation_units_snap: self
+                .last_manual_resume_remaining_allocation_units_snap,
+            last_manual_resume_slot: self.last_manual_resume_slot,
+            last_manual_resume_block_time: self.last_manual_resume_block_time,
+            last_known_total_seconds_in_paused_status: self
+                .last_known_total_seconds_in_paused_status,
+            last_auto_stop_block_time: self.last_auto_stop_block_time,
+            fee_payed_by_treasurer: self.fee_payed_by_treasurer,
+            created_on_utc: self.created_on_utc,
+            category: self.category,
+            sub_category: self.sub_category,
+            accrual_mode: self.accrual_mode,
+            schedule_address: self.schedule_address,
+            cliff_utc_in_seconds: self.cliff_utc_in_seconds,
+            pending_beneficiary_address: self.pending_beneficiary_address,
+            v2_allocation_reserved_units: self.allocation_reserved_units,
+        })
     }
 
-    /// Check is the stream was manually paused
-    pub fn primitive_is_manually_paused<'info>(&self) -> bool {
-        if self.last_manual_stop_block_time == 0 {
-            // @err: probably not needed
-            return false;
+    /// Encodes a `Stream` with the v2 layout. The deprecated fields are
+    /// written with the values of the v3 fields that replace them, or as they
+    /// were read when nothing replaces them
+    fn from_stream(stream: &Stream) -> Self {
+        StreamV2Layout {
+            version: stream.version,
+            initialized: stream.initialized,
+            name: stream.name,
+            treasurer_address: stream.treasurer_address,
+            rate_amount_units: stream.rate_amount_units,
+            rate_interval_in_seconds: stream.rate_interval_in_seconds,
+            start_utc: stream.start_utc,
+            cliff_vest_amount_units: stream.cliff_vest_amount_units,
+            cliff_vest_percent: 0,
+            beneficiary_address: stream.beneficiary_address,
+            beneficiary_associated_token: stream.beneficiary_associated_token,
+            treasury_address: stream.treasury_address,
+            allocation_assigned_units: stream.allocation_assigned_units,
+            allocation_reserved_units: stream.v2_allocation_reserved_units,
+            total_withdrawals_units: stream.total_withdrawals_units,
+            last_withdrawal_units: stream.last_withdrawal_units,
+            last_withdrawal_slot: stream.last_withdrawal_slot,
+            last_withdrawal_block_time: stream.last_withdrawal_block_time,
+            last_manual_stop_withdrawable_units_snap: stream
+                .last_manual_stop_withdrawable_units_snap,
+            last_manual_stop_slot: stream.last_manual_stop_slot,
+            last_manual_stop_block_time: stream.last_manual_stop_block_time,
+            last_manual_resume_remaining_allocation_units_snap: stream
+                .last_manual_resume_remaining_allocation_units_snap,
+            last_manual_resume_slot: stream.last_manual_resume_slot,
+            last_manual_resume_block_time: stream.last_manual_resume_block_time,
+            last_known_total_seconds_in_paused_status: stream
+                .last_known_total_seconds_in_paused_status,
+            last_auto_stop_block_time: stream.last_auto_stop_block_time,
+            fee_payed_by_treasurer: stream.fee_payed_by_treasurer,
+            start_utc_in_seconds: stream.start_utc,
+            created_on_utc: stream.created_on_utc,
+            category: stream.category,
+            sub_category: stream.sub_category,
+            accrual_mode: stream.accrual_mode,
+            schedule_address: stream.schedule_address,
+            cliff_utc_in_seconds: stream.cliff_utc_in_seconds,
+            pending_beneficiary_address: stream.pending_beneficiary_address,
         }
-        return self.last_manual_stop_block_time > self.last_manual_resume_block_time;
     }
+}
 
-    /// Gets the last known blocktime where the stream was paused (auto or manual)
-    pub fn primitive_get_last_known_stop_block_time<'info>(&self) -> u64 {
-        return cmp::max(
-            self.last_auto_stop_block_time,
-            self.last_manual_stop_block_time,
-        );
+impl Stream {
+    /// Gets the fields used by the accrual calculations of `msp_math`
+    pub fn math_state(&self) -> StreamState {
+        StreamState {
+            rate_amount_units: self.rate_amount_units,
+            rate_interval_in_seconds: self.rate_interval_in_seconds,
+            start_utc: self.start_utc,
+            start_utc_in_seconds: self.start_utc,
+            cliff_vest_amount_units: self.cliff_vest_amount_units,
+            cliff_vest_percent: 0,
+            allocation_assigned_units: self.allocation_assigned_units,
+            total_withdrawals_units: self.total_withdrawals_units,
+            last_manual_stop_withdrawable_units_snap: self
+                .last_manual_stop_withdrawable_units_snap,
+            last_manual_stop_block_time: self.last_manual_stop_block_time,
+            last_manual_resume_block_time: self.last_manual_resume_block_time,
+            last_known_total_seconds_in_paused_status: self
+                .last_known_total_seconds_in_paused_status,
+            last_auto_stop_block_time: self.last_auto_stop_block_time,
+            accrual_mode: self.accrual_mode,
+            cliff_utc_in_seconds: self.cliff_utc_in_seconds,
+        }
     }
 
-    /// Calculates the amount of units streamed units during the given seconds
-    ///
-    /// This takes into account if there are enough remaining allocated
-    /// units to fully stream for this number of seconds.
-    /// Also, the returned value does not include cliff.
-    pub fn primitive_get_streamed_units<'info>(&self, seconds: u64) -> Result<u64> {
-        if self.rate_interval_in_seconds == 0 {
-            return Ok(0_u64);
-        }
+    /// Calculates the cliff amount
+    pub fn primitive_get_cliff_units<'info>(&self) -> Result<u64> {
+        Ok(self.math_state().get_cliff_units().map_err(ErrorCode::from)?)
+    }
 
-        let cliff_units = self.primitive_get_cliff_units()?;
-        let streamable_units = self
-            .allocation_assigned_units
-            .checked_sub(cliff_units)
-            .ok_or(ErrorCode::Overflow)?;
-        let streaming_seconds = u64::try_from(
-            (streamable_units as u128)
-                .checked_mul(self.rate_interval_in_seconds as u128)
-                .ok_or(ErrorCode::Overflow)?
-                .checked_div(self.rate_amount_units as u128)
-                .ok_or(ErrorCode::Overflow)?,
-        )
-        .unwrap();
+    /// Check if the stream account layout version is supported (v2 accounts
+    /// are accepted until all of them are migrated to v3)
+    pub fn primitive_is_supported_version(&self) -> bool {
+        self.version == 2 || self.version == 3
+    }
 
-        if seconds >= streaming_seconds {
-            return Ok(streamable_units);
+    /// Check if the stream account size matches its layout version (the
+    /// account can be bigger when it has extensions)
+    pub fn primitive_has_valid_size(&self, data_len: usize) -> bool {
+        match self.version {
+            2 => data_len >= STREAM_V2_SIZE,
+            3 => data_len >= STREAM_V3_SIZE,
+            _ => false,
         }
+    }
 
-        let streamable_units_in_given_seconds = u64::try_from(
-            (self.rate_amount_units as u128)
-                .checked_mul(seconds as u128)
-                .unwrap()
-                .checked_div(self.rate_interval_in_seconds as u128)
-                .ok_or(ErrorCode::Overflow)?,
-        )
-        .unwrap();
-
-        Ok(streamable_units_in_given_seconds)
+    /// Check if the stream still has the v2 layout
+    pub fn primitive_needs_migration(&self) -> bool {
+        self.version == 2
     }
 
-    /// Gets the stream status in the current blocktime
-    pub fn get_status<'info>(&self, timestamp: u64) -> Result<StreamStatus> {
-        let start_utc_seconds = self.get_start_utc()?;
+    /// Sets the v3 version so the stream is written with the v3 layout. The
+    /// deprecated fields were already replaced when the v2 account was
+    /// decoded. The account still needs to be reallocated to `STREAM_V3_SIZE`
+    pub fn migrate_to_v3(&mut self) {
+        self.version = 3;
+    }
 
-        // scheduled
-        if start_utc_seconds > timestamp {
-            return Ok(StreamStatus::Scheduled);
-        }
+    /// Check is the stream was manually paused
+    pub fn primitive_is_manually_paused<'info>(&self) -> bool {
+        self.math_state().is_manually_paused()
+    }
 
-        // manually paused
-        let is_manual_pause = self.primitive_is_manually_paused();
-        if is_manual_pause {
-            return Ok(StreamStatus::Paused);
-        }
+    /// Check if the stream unlocks units in discrete steps at the end of each
+    /// interval instead of every second
+    pub fn primitive_is_step_accrual(&self) -> bool {
+        self.math_state().is_step_accrual()
+    }
 
-        // running or automatically paused (ran out of funds)
-        let cliff_units = self.primitive_get_cliff_units()?;
-        let seconds_since_start = timestamp
-            .checked_sub(start_utc_seconds)
-            .ok_or(ErrorCode::Overflow)?;
+    /// Calculates the number of seconds needed to stream the given units
+    pub fn primitive_get_streaming_seconds(&self, streamable_units: u64) -> Result<u64> {
+        Ok(self
+            .math_state()
+            .get_streaming_seconds(streamable_units)
+            .map_err(ErrorCode::from)?)
+    }
 
-        let not_stop_streamed_units_since_started =
-            self.primitive_get_streamed_units(seconds_since_start)?;
-        let non_stop_earning_units = cliff_units
-            .checked_add(not_stop_streamed_units_since_started)
-            .ok_or(ErrorCode::Overflow)?;
+    /// Gets the seconds streamed in the current interval of a step stream,
+    /// which are not unlocked until the interval ends
+    pub fn get_step_interval_elapsed_seconds(&self, timestamp: u64) -> Result<u64> {
+        Ok(self
+            .math_state()
+            .get_step_interval_elapsed_seconds(timestamp)
+            .map_err(ErrorCode::from)?)
+    }
 
-        let actual_streamed_seconds = seconds_since_start
-            .checked_sub(self.last_known_total_seconds_in_paused_status) // TODO: check
-            .ok_or(ErrorCode::Overflow)?;
-        let actual_streamed_units = self.primitive_get_streamed_units(actual_streamed_seconds)?;
-        let actual_earned_units = cliff_units
-            .checked_add(actual_streamed_units)
-            .ok_or(ErrorCode::Overflow)?;
+    /// Check if the given blocktime is before the stream cliff date
+    pub fn primitive_is_before_cliff_date(&self, timestamp: u64) -> bool {
+        self.math_state().is_before_cliff_date(timestamp)
+    }
 
-        assert!(
-            non_stop_earning_units >= actual_earned_units,
-            "non_stop vs actual earned units invariant violated"
-        );
+    /// Gets the last known blocktime where the stream was paused (auto or manual)
+    pub fn primitive_get_last_known_stop_block_time<'info>(&self) -> u64 {
+        self.math_state().get_last_known_stop_block_time()
+    }
 
-        // running
-        if self.allocation_assigned_units > actual_earned_units {
-            return Ok(StreamStatus::Running);
-        }
+    /// Calculates the amount of units streamed units during the given seconds
+    /// (without cliff)
+    pub fn primitive_get_streamed_units<'info>(&self, seconds: u64) -> Result<u64> {
+        Ok(self
+            .math_state()
+            .get_streamed_units(seconds)
+            .map_err(ErrorCode::from)?)
+    }
 
-        // automatically paused (ran out of funds)
-        Ok(StreamStatus::Paused)
+    /// Gets the stream status in the given blocktime
+    pub fn get_status<'info>(&self, timestamp: u64) -> Result<StreamStatus> {
+        Ok(self
+            .math_state()
+            .get_status(timestamp)
+            .map_err(ErrorCode::from)?)
     }
 
     /// Calculates the stream estimated depletion blocktime. The calculation
     /// has into account the periods of time in which the stream was in
     /// paused status.
-    pub fn get_est_depletion_blocktime(&self) -> Result<u64> {
-        let clock = Clock::get()?;
-        msg!("clock: {0}", clock.unix_timestamp);
-        if self.rate_interval_in_seconds == 0 {
-            return Ok(clock.unix_timestamp as u64); // now
-        }
-        let cliff_units = self.primitive_get_cliff_units()?;
-
-        let streamable_units = self
-            .allocation_assigned_units
-            .checked_sub(cliff_units)
-            .ok_or(ErrorCode::Overflow)?;
-
-        let streaming_seconds = u64::try_from(
-            (streamable_units as u128)
-                .checked_mul(self.rate_interval_in_seconds as u128)
-                .ok_or(ErrorCode::Overflow)?
-                .checked_div(self.rate_amount_units as u128)
-                .ok_or(ErrorCode::Overflow)?,
-        )
-        .unwrap();
-
-        let duration_span_seconds = streaming_seconds
-            .checked_add(self.last_known_total_seconds_in_paused_status)
-            .ok_or(ErrorCode::Overflow)?;
-
-        let start_utc_seconds = self.get_start_utc()?;
-        let est_depletion_time = start_utc_seconds
-            .checked_add(duration_span_seconds)
-            .ok_or(ErrorCode::Overflow)?;
-        Ok(est_depletion_time)
+    pub fn get_est_depletion_blocktime(&self, timestamp: u64) -> Result<u64> {
+        Ok(self
+            .math_state()
+            .get_est_depletion_blocktime(timestamp)
+            .map_err(ErrorCode::from)?)
     }
 
     /// Gets the total funds sent to beneficiary (withdrawable + withdrawn)
     pub fn get_funds_sent_to_beneficiary(&self, timestamp: u64) -> Result<u64> {
-        let withdrawable = self.get_beneficiary_withdrawable_amount(timestamp)?;
-        let funds_sent = self
-            .total_withdrawals_units
-            .checked_add(withdrawable)
-            .ok_or(ErrorCode::Overflow)?;
-        Ok(funds_sent)
+        Ok(self
+            .math_state()
+            .get_funds_sent_to_beneficiary(timestamp)
+            .map_err(ErrorCode::from)?)
     }
 
     /// Gets the funds that have not been withdrew
     pub fn get_funds_left_in_account(&self, timestamp: u64) -> Result<u64> {
         // TODO: Remove if possible
-
-        let withdrawable = self.get_beneficiary_withdrawable_amount(timestamp)?;
-        let funds_left_in_account = self
-            .allocation_assigned_units
-            .checked_sub(self.total_withdrawals_units)
-            .unwrap()
-            .checked_sub(withdrawable)
-            .ok_or(ErrorCode::Overflow)?;
-
-        Ok(funds_left_in_account)
+        Ok(self
+            .math_state()
+            .get_funds_left_in_account(timestamp)
+            .map_err(ErrorCode::from)?)
     }
 
     /// Gets the remaining allocation in the stream
     pub fn get_remaining_allocation(&self) -> Result<u64> {
-        let remaining_allocation = self
-            .allocation_assigned_units
-            .checked_sub(self.total_withdrawals_units)
-            .ok_or(ErrorCode::Overflow)?;
-        Ok(remaining_allocation)
+        Ok(self
+            .math_state()
+            .get_remaining_allocation()
+            .map_err(ErrorCode::from)?)
     }
 
     /// Gets the beneficiary withdrawable amount in the given blocktime
     pub fn get_beneficiary_withdrawable_amount<'info>(&self, timestamp: u64) -> Result<u64> {
+        Ok(self
+            .math_state()
+            .get_beneficiary_withdrawable_amount(timestamp)
+            .map_err(ErrorCode::from)?)
+    }
+
+    /// Gets the amount earned by the beneficiary and not withdrawn yet in the
+    /// given blocktime, regardless of the cliff date
+    pub fn get_beneficiary_vested_amount(&self, timestamp: u64) -> Result<u64> {
+        let withdrawable = self
+            .math_state()
+            .get_beneficiary_vested_amount(timestamp)
+            .map_err(ErrorCode::from)?;
         #[cfg(feature = "test")]
-        msg!("");
-        #[cfg(feature = "test")]
-        msg!("get_beneficiary_withdrawable_amount() started! ******");
+        msg!("withdrawable: {0}", withdrawable);
+        Ok(withdrawable)
+    }
+
+    /// Check if the stream unlocks units following a `StreamSchedule`
+    pub fn primitive_has_schedule(&self) -> bool {
+        self.schedule_address != Pubkey::default()
+    }
 
-        let remaining_allocation = self.get_remaining_allocation()?;
+    /// Gets the status in the given blocktime of a stream that unlocks units
+    /// following the given schedule
+    pub fn get_scheduled_status(
+        &self,
+        schedule: &StreamSchedule,
+        timestamp: u64,
+    ) -> Result<StreamStatus> {
+        let start_utc_seconds = self.get_start_utc()?;
 
-        if remaining_allocation == 0 {
-            return Ok(0);
+        // scheduled
+        if start_utc_seconds > timestamp {
+            return Ok(StreamStatus::Scheduled);
         }
 
-        let status = self.get_status(timestamp)?;
+        // running
+        if self.allocation_assigned_units > schedule.get_unlocked_units(timestamp) {
+            return Ok(StreamStatus::Running);
+        }
 
-        // Check if SCHEDULED
+        // automatically paused (all units unlocked)
+        Ok(StreamStatus::Paused)
+    }
+
+    /// Gets the beneficiary withdrawable amount in the given blocktime of a
+    /// stream that unlocks units following the given schedule
+    pub fn get_scheduled_withdrawable_amount(
+        &self,
+        schedule: &StreamSchedule,
+        timestamp: u64,
+    ) -> Result<u64> {
+        let status = self.get_scheduled_status(schedule, timestamp)?;
         if status == StreamStatus::Scheduled {
-            #[cfg(feature = "test")]
-            msg!("status: Scheduled");
             return Ok(0);
         }
 
-        // Check if PAUSED
-        if status == StreamStatus::Paused {
-            #[cfg(feature = "test")]
-            msg!("status: Paused");
-            let is_manual_pause = self.primitive_is_manually_paused();
-            let withdrawable_while_paused = match is_manual_pause {
-                true => self.last_manual_stop_withdrawable_units_snap,
-                _ => self
-                    .allocation_assigned_units
-                    .checked_sub(self.total_withdrawals_units)
-                    .ok_or(ErrorCode::Overflow)?,
-            };
-            return Ok(withdrawable_while_paused);
-        }
-
-        // Check if RUNNING
-        if self.rate_interval_in_seconds == 0 || self.rate_amount_units == 0 {
-            return Err(ErrorCode::InvalidArgument.into());
+        let unlocked_units = cmp::min(
+            schedule.get_unlocked_units(timestamp),
+            self.allocation_assigned_units,
+        );
+        if unlocked_units <= self.total_withdrawals_units {
+            return Ok(0);
         }
 
-        let cliff_units = self.primitive_get_cliff_units()?;
-        let start_utc_seconds = self.get_start_utc()?;
-        let seconds_since_start = timestamp
-            .checked_sub(start_utc_seconds)
-            .ok_or(ErrorCode::Overflow)?;
-
-        let actual_streamed_seconds = seconds_since_start
-            .checked_sub(self.last_known_total_seconds_in_paused_status) // TODO: check
-            .ok_or(ErrorCode::Overflow)?;
-        let actual_streamed_units = self.primitive_get_streamed_units(actual_streamed_seconds)?;
-        let mut actual_earned_units = cliff_units
-            .checked_add(actual_streamed_units)
-            .ok_or(ErrorCode::Overflow)?;
-
-        #[cfg(feature = "test")]
-        msg!("seconds_since_start: {0}, cliff_units: {1}, start_utc_seconds: {2}, actual_streamed_seconds: {3}, actual_earned_units: {4}, total_withdrawals_units: {5}",
-        seconds_since_start, cliff_units, start_utc_seconds, actual_streamed_seconds, actual_earned_units, self.total_withdrawals_units);
-
-        // assert!(
-        //     actual_earned_units >= self.total_withdrawals_units,
-        //     "entitled_earning vs total_withdrawals invariant violated"
-        // );
-        // TODO: this is a work around the issue of not having a better way for calculating earned units after auto-PAUSED streams
-        actual_earned_units = cmp::max(actual_earned_units, self.total_withdrawals_units);
-
-        #[cfg(feature = "test")]
-        msg!(
-            "stream.total_withdrawals_units: {0}",
-            self.total_withdrawals_units
-        );
-        let withdrawable_units_while_running = actual_earned_units
+        let withdrawable = unlocked_units
             .checked_sub(self.total_withdrawals_units)
             .ok_or(ErrorCode::Overflow)?;
-        #[cfg(feature = "test")]
-        msg!(
-            "withdrawable_units_while_running: {0}",
-            withdrawable_units_while_running
-        );
-
-        let withdrawable = cmp::min(remaining_allocation, withdrawable_units_while_running); // TODO: these two shuold be equal by now
-        #[cfg(feature = "test")]
-        msg!("withdrawable: {0}", withdrawable);
-
-        #[cfg(feature = "test")]
-        msg!("get_beneficiary_withdrawable_amount() finished! ******");
-        #[cfg(feature = "test")]
-        msg!("");
-
         Ok(withdrawable)
     }
 
     /// Gets the start utc seconds amount
     pub fn get_start_utc(&self) -> Result<u64> {
-        if self.start_utc_in_seconds > 0 {
-            return Ok(self.start_utc);
-        }
-        let start_utc_seconds = self
-            .start_utc
-            .checked_div(1000u64)
-            .ok_or(ErrorCode::Overflow)?;
-        Ok(start_utc_seconds)
+        Ok(self.math_state().get_start_utc().map_err(ErrorCode::from)?)
     }
+}
 
-    /// Updates the stream start UTC to seconds if it's necesary
-    pub fn update_start_utc(&mut self) -> Result<()> {
-        let start_utc_seconds = self.get_start_utc()?;
-
-        if self.start_utc_in_seconds == 0 {
-            self.start_utc = start_utc_seconds;
-            self.start_utc_in_seconds = start_utc_seconds;
-        }
-
-        Ok(())
-    }
+/// A stream to be created by `create_streams_batch`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct StreamBatchEntry {
+    pub beneficiary: Pubkey,
+    /// Seed used to derive the stream PDA as [b"stream", treasury, seed]
+    pub stream_pda_seed: Pubkey,
+    pub allocation_assigned_units: u64,
+    pub rate_amount_units: u64,
+    pub rate_interval_in_seconds: u64,
+    pub cliff_vest_amount_units: u64,
 }
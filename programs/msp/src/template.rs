@@ -15,5 +15,11 @@ pub struct StreamTemplate {
     pub duration_number_of_units: u64,
 
     pub fee_payed_by_treasurer: bool,
-    // total bytes: 43
+    /// The accrual mode (`Linear(0)` or `Step(1)`) of the streams created
+    /// with this template
+    pub accrual_mode: u8,
+    /// The cliff date in seconds of the streams created with this template.
+    /// Zero means the cliff unlocks at the stream start
+    pub cliff_utc_in_seconds: u64,
+    // total bytes: 52
 }
@@ -0,0 +1,271 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::errors::ErrorCode;
+use crate::stream::*;
+use crate::treasury::*;
+use crate::utils::resize_account;
+
+/// Size of the type (u16) and length (u16) prefix of each extension
+pub const EXTENSION_HEADER_SIZE: usize = 4;
+
+/// Types of the extensions that can be attached to a `Stream` or `Treasury`.
+/// Values are never reused once assigned
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[repr(u16)]
+pub enum ExtensionType {
+    /// Marks the end of the extensions (the rest of the region is padding)
+    Uninitialized = 0,
+    /// `NativeSolTreasury` (treasury)
+    NativeSolTreasury = 1,
+    /// `TreasuryReferrer` (treasury)
+    TreasuryReferrer = 2,
+}
+
+/// A value stored in the extension region of an account
+pub trait AccountExtension: AnchorSerialize + AnchorDeserialize {
+    const EXTENSION_TYPE: ExtensionType;
+}
+
+/// An account with an extension region right after its fixed fields. In v2
+/// accounts the region starts in the padding left after the last v2 field,
+/// while v3 accounts are reallocated to fit the extensions
+pub trait ExtensibleAccount {
+    /// Offset of the layout `version` in the account data
+    const VERSION_OFFSET: usize;
+
+    /// Gets the offset where the extension region starts for the given
+    /// layout version
+    fn extensions_offset(version: u8) -> usize;
+}
+
+impl ExtensibleAccount for Stream {
+    const VERSION_OFFSET: usize = 8;
+
+    fn extensions_offset(version: u8) -> usize {
+        match version {
+            2 => STREAM_V2_EXTENSIONS_OFFSET,
+            _ => STREAM_V3_SIZE,
+        }
+    }
+}
+
+impl ExtensibleAccount for Treasury {
+    const VERSION_OFFSET: usize = 9;
+
+    fn extensions_offset(version: u8) -> usize {
+        match version {
+            2 => TREASURY_V2_EXTENSIONS_OFFSET,
+            _ => TREASURY_V3_SIZE,
+        }
+    }
+}
+
+/// Gets the offset of the extension region in the account data. Accounts
+/// that are not written yet (version 0) use the v3 offset
+pub fn get_extensions_offset<A: ExtensibleAccount>(data: &[u8]) -> usize {
+    A::extensions_offset(data.get(A::VERSION_OFFSET).copied().unwrap_or_default())
+}
+
+/// Reads the (type, value start, value end) of the extension starting at
+/// `offset` in the region. Returns None at the end of the extensions
+fn read_extension_header(region: &[u8], offset: usize) -> Result<Option<(u16, usize, usize)>> {
+    if offset + EXTENSION_HEADER_SIZE > region.len() {
+        return Ok(None);
+    }
+
+    let extension_type = u16::from_le_bytes([region[offset], region[offset + 1]]);
+    if extension_type == ExtensionType::Uninitialized as u16 {
+        return Ok(None);
+    }
+
+    let length = u16::from_le_bytes([region[offset + 2], region[offset + 3]]) as usize;
+    let value_start = offset + EXTENSION_HEADER_SIZE;
+    let value_end = value_start + length;
+    if value_end > region.len() {
+        return Err(ErrorCode::InvalidAccountExtension.into());
+    }
+
+    Ok(Some((extension_type, value_start, value_end)))
+}
+
+/// Gets the number of bytes used by the extensions in the region
+pub fn get_extensions_len(region: &[u8]) -> Result<usize> {
+    let mut offset = 0;
+    while let Some((_, _, value_end)) = read_extension_header(region, offset)? {
+        offset = value_end;
+    }
+    Ok(offset)
+}
+
+/// Finds the extension of the given type and returns its header start and
+/// value end offsets in the region
+fn find_extension(region: &[u8], extension_type: ExtensionType) -> Result<Option<(usize, usize)>> {
+    let mut offset = 0;
+    while let Some((current_type, _, value_end)) = read_extension_header(region, offset)? {
+        if current_type == extension_type as u16 {
+            return Ok(Some((offset, value_end)));
+        }
+        offset = value_end;
+    }
+    Ok(None)
+}
+
+/// Gets the extension of type `E` from the region, if any
+pub fn get_extension<E: AccountExtension>(region: &[u8]) -> Result<Option<E>> {
+    match find_extension(region, E::EXTENSION_TYPE)? {
+        Some((header_start, value_end)) => {
+            let mut value = &region[header_start + EXTENSION_HEADER_SIZE..value_end];
+            let extension =
+                E::deserialize(&mut value).map_err(|_| ErrorCode::InvalidAccountExtension)?;
+            Ok(Some(extension))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Removes the extension of the given type from the region, moving the
+/// following extensions back. Returns whether the extension was found
+pub fn remove_extension(region: &mut [u8], extension_type: ExtensionType) -> Result<bool> {
+    let (header_start, value_end) = match find_extension(region, extension_type)? {
+        Some(bounds) => bounds,
+        None => return Ok(false),
+    };
+
+    let used_len = get_extensions_len(region)?;
+    region.copy_within(value_end..used_len, header_start);
+    let new_used_len = used_len - (value_end - header_start);
+    region[new_used_len..used_len].fill(0);
+
+    Ok(true)
+}
+
+/// Gets the region size needed to store `extension` (replacing the current
+/// extension of the same type, if any)
+pub fn get_required_region_len<E: AccountExtension>(region: &[u8], extension: &E) -> Result<usize> {
+    let value_len = extension.try_to_vec()?.len();
+    let used_len = get_extensions_len(region)?;
+    let current_len = match find_extension(region, E::EXTENSION_TYPE)? {
+        Some((header_start, value_end)) => value_end - header_start,
+        None => 0,
+    };
+
+    Ok(used_len - current_len + EXTENSION_HEADER_SIZE + value_len)
+}
+
+/// Writes `extension` into the region, replacing the current extension of the
+/// same type. The region must be at least `get_required_region_len` long
+pub fn set_extension<E: AccountExtension>(region: &mut [u8], extension: &E) -> Result<()> {
+    let value = extension.try_to_vec()?;
+    if value.len() > u16::MAX as usize {
+        return Err(ErrorCode::InvalidAccountExtension.into());
+    }
+
+    let offset = match find_extension(region, E::EXTENSION_TYPE)? {
+        // same size, overwrite in place
+        Some((header_start, value_end))
+            if value_end - header_start == EXTENSION_HEADER_SIZE + value.len() =>
+        {
+            header_start
+        }
+        Some(_) => {
+            remove_extension(region, E::EXTENSION_TYPE)?;
+            get_extensions_len(region)?
+        }
+        None => get_extensions_len(region)?,
+    };
+
+    let value_start = offset + EXTENSION_HEADER_SIZE;
+    if value_start + value.len() > region.len() {
+        return Err(ErrorCode::InvalidAccountExtension.into());
+    }
+
+    region[offset..offset + 2].copy_from_slice(&(E::EXTENSION_TYPE as u16).to_le_bytes());
+    region[offset + 2..value_start].copy_from_slice(&(value.len() as u16).to_le_bytes());
+    region[value_start..value_start + value.len()].copy_from_slice(&value);
+
+    Ok(())
+}
+
+/// Gets the extension of type `E` from the account data, if any
+pub fn get_data_extension<A: ExtensibleAccount, E: AccountExtension>(
+    data: &[u8],
+) -> Result<Option<E>> {
+    let offset = get_extensions_offset::<A>(data);
+    if data.len() <= offset {
+        return Ok(None);
+    }
+    get_extension::<E>(&data[offset..])
+}
+
+/// Gets the extension of type `E` attached to the account, if any
+pub fn get_account_extension<A: ExtensibleAccount, E: AccountExtension>(
+    account: &AccountInfo,
+) -> Result<Option<E>> {
+    let data = account.try_borrow_data()?;
+    get_data_extension::<A, E>(&data)
+}
+
+/// Gets the number of bytes used by the extensions attached to the account
+pub fn get_account_extensions_len<A: ExtensibleAccount>(
+    account: &AccountInfo,
+) -> Result<usize> {
+    let data = account.try_borrow_data()?;
+    let offset = get_extensions_offset::<A>(&data);
+    if data.len() <= offset {
+        return Ok(0);
+    }
+    get_extensions_len(&data[offset..])
+}
+
+/// Attaches `extension` to the account (or replaces the current one of the
+/// same type). The account is reallocated when the extension doesn't fit and
+/// the `payer` funds the extra rent
+pub fn set_account_extension<'info, A: ExtensibleAccount, E: AccountExtension>(
+    account: &AccountInfo<'info>,
+    extension: &E,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let (offset, required_len) = {
+        let data = account.try_borrow_data()?;
+        let offset = get_extensions_offset::<A>(&data);
+        let region = data.get(offset..).unwrap_or(&[]);
+        let required_len = offset
+            .checked_add(get_required_region_len(region, extension)?)
+            .ok_or(ErrorCode::Overflow)?;
+        (offset, required_len)
+    };
+
+    if required_len > account.data_len() {
+        resize_account(account, required_len, payer, system_program)?;
+    }
+
+    let mut data = account.try_borrow_mut_data()?;
+    set_extension(&mut data[offset..], extension)
+}
+
+/// Moves the extensions of the account to the offset used by the
+/// `new_version` layout and zeroes the bytes left behind. The fixed fields
+/// are not touched. Returns the number of bytes used by the extensions
+pub fn migrate_account_extensions<A: ExtensibleAccount>(
+    account: &AccountInfo,
+    new_version: u8,
+) -> Result<usize> {
+    let mut data = account.try_borrow_mut_data()?;
+    let offset = get_extensions_offset::<A>(&data);
+    let new_offset = A::extensions_offset(new_version);
+    let extensions_len = match data.get(offset..) {
+        Some(region) => get_extensions_len(region)?,
+        None => 0,
+    };
+    if new_offset + extensions_len > data.len() {
+        return Err(ErrorCode::InvalidAccountExtension.into());
+    }
+
+    data.copy_within(offset..offset + extensions_len, new_offset);
+    let data_len = data.len();
+    data[new_offset + extensions_len..data_len].fill(0);
+
+    Ok(extensions_len)
+}
@@ -1,8 +1,19 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::*;
 use crate::errors::ErrorCode;
+use crate::tlv::*;
+use std::io::Write;
 
-#[account]
+/// Anchor discriminator of the treasury accounts
+/// (`sha256("account:Treasury")[..8]`). It is the same for v2 and v3
+/// treasuries, the layout is given by `version`
+pub const TREASURY_DISCRIMINATOR: [u8; 8] = [238, 239, 123, 238, 89, 1, 168, 253];
+
+/// A treasury with the v3 layout. v2 accounts are decoded into this struct
+/// and written back with the v2 layout until they are migrated with
+/// `migrate_treasury` (see `TreasuryV2Layout`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct Treasury {
     pub initialized: bool,
     pub version: u8,
@@ -11,13 +22,6 @@ pub struct Treasury {
     pub name: [u8; 32],
     pub treasurer_address: Pubkey,
     pub associated_token_address: Pubkey,
-    /// [deprecated] The address of the Mint of the treasury pool
-    // #[deprecated]
-    pub mint_address: Pubkey,
-    /// This field should not be used in its current form because it has a dynamic size
-    ///
-    /// The 4-bytes header can be repurposed in the future
-    pub labels: Vec<String>,
     /// Treasury balance tracking
     /// The last known treasury balance (will be updated in the `refreshTreasuryData` instruction)
     pub last_known_balance_units: u64,
@@ -31,12 +35,6 @@ pub struct Treasury {
     /// The allocation assined will be modified in the following instructions:
     /// `createStream`, `allocate`, `withdraw` and `closeStream`
     pub allocation_assigned_units: u64,
-    /// The allocation reserved accross all the streams that belong to this treasury
-    ///
-    /// [deprecated] The allocation reserved will be modified in the following instructions:
-    /// `createStream`, `withdraw` and `closeStream`
-    // #[deprecated]
-    pub allocation_reserved_units: u64, // deprecated
     /// The total amount withdrawn by all the streams that belong to this treasury
     pub total_withdrawals_units: u64,
     /// The current amount of streams in the treasury (will be updated in the `refreshTreasuryData` instruction)
@@ -56,6 +54,173 @@ pub struct Treasury {
     /// Indicates the sub product category such as `Advisor(1)`, Development(2)
     /// The default value is set to a `Default(0)` sub_cateogry.
     pub sub_category: u8,
+    /// The wallet allowed to manage the treasury and its streams. When not
+    /// set, the `treasurer_address` (used in the treasury PDA seeds) is the
+    /// authority
+    pub authority_address: Pubkey,
+    /// The authority proposed by `propose_treasury_authority`. It has to
+    /// accept the handover before becoming the treasury authority
+    pub pending_authority_address: Pubkey,
+    // total bytes: 240
+    // New fields go into the extensions region that follows (see tlv.rs)
+    /// The deprecated fields of a v2 treasury as read. They are not part of
+    /// the v3 layout
+    #[borsh_skip]
+    v2_deprecated_fields: Option<TreasuryV2DeprecatedFields>,
+}
+
+impl AccountSerialize for Treasury {
+    fn try_serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer
+            .write_all(&TREASURY_DISCRIMINATOR)
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotSerialize)?;
+        let result = match self.version {
+            2 => TreasuryV2Layout::from_treasury(self).serialize(writer),
+            _ => AnchorSerialize::serialize(self, writer),
+        };
+        result.map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotSerialize)?;
+        Ok(())
+    }
+}
+
+impl AccountDeserialize for Treasury {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        if buf.len() < TREASURY_DISCRIMINATOR.len() {
+            return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorNotFound.into());
+        }
+        if buf[..TREASURY_DISCRIMINATOR.len()] != TREASURY_DISCRIMINATOR {
+            return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        let mut data: &[u8] = buf
+            .get(TREASURY_DISCRIMINATOR.len()..)
+            .ok_or(anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?;
+        // the version follows the `initialized` flag in both layouts
+        let treasury = match data.get(1) {
+            Some(2) => {
+                TreasuryV2Layout::deserialize(&mut data).map(TreasuryV2Layout::into_treasury)
+            }
+            _ => Treasury::deserialize(&mut data),
+        };
+        Ok(treasury.map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?)
+    }
+}
+
+impl Owner for Treasury {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+/// The fields of the v2 treasury layout that were dropped in v3. They are
+/// kept as read so writing a v2 treasury back does not change them
+#[derive(Clone, Default)]
+struct TreasuryV2DeprecatedFields {
+    mint_address: Pubkey,
+    labels: Vec<String>,
+    allocation_reserved_units: u64,
+}
+
+/// The v2 layout of a treasury account, including the deprecated fields
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct TreasuryV2Layout {
+    initialized: bool,
+    version: u8,
+    bump: u8,
+    slot: u64,
+    name: [u8; 32],
+    treasurer_address: Pubkey,
+    associated_token_address: Pubkey,
+    /// The address of the Mint of the treasury pool
+    mint_address: Pubkey,
+    /// Always empty since it has a dynamic size
+    labels: Vec<String>,
+    last_known_balance_units: u64,
+    last_known_balance_slot: u64,
+    last_known_balance_block_time: u64,
+    allocation_assigned_units: u64,
+    allocation_reserved_units: u64,
+    total_withdrawals_units: u64,
+    total_streams: u64,
+    created_on_utc: u64,
+    treasury_type: u8,
+    auto_close: bool,
+    sol_fee_payed_by_treasury: bool,
+    category: u8,
+    sub_category: u8,
+    authority_address: Pubkey,
+    pending_authority_address: Pubkey,
+    // total bytes: 284
+}
+
+impl TreasuryV2Layout {
+    /// Decodes the v2 fields into a `Treasury`, setting the deprecated ones
+    /// aside. The version is kept so the treasury is written back as v2
+    fn into_treasury(self) -> Treasury {
+        Treasury {
+            initialized: self.initialized,
+            version: self.version,
+            bump: self.bump,
+            slot: self.slot,
+            name: self.name,
+            treasurer_address: self.treasurer_address,
+            associated_token_address: self.associated_token_address,
+            last_known_balance_units: self.last_known_balance_units,
+            last_known_balance_slot: self.last_known_balance_slot,
+            last_known_balance_block_time: self.last_known_balance_block_time,
+            allocation_assigned_units: self.allocation_assigned_units,
+            total_withdrawals_units: self.total_withdrawals_units,
+            total_streams: self.total_streams,
+            created_on_utc: self.created_on_utc,
+            treasury_type: self.treasury_type,
+            auto_close: self.auto_close,
+            sol_fee_payed_by_treasury: self.sol_fee_payed_by_treasury,
+            category: self.category,
+            sub_category: self.sub_category,
+            authority_address: self.authority_address,
+            pending_authority_address: self.pending_authority_address,
+            v2_deprecated_fields: Some(TreasuryV2DeprecatedFields {
+                mint_address: self.mint_address,
+                labels: self.labels,
+                allocation_reserved_units: self.allocation_reserved_units,
+            }),
+        }
+    }
+
+    /// Encodes a `Treasury` with the v2 layout. The deprecated fields are
+    /// written back as they were read
+    fn from_treasury(treasury: &Treasury) -> Self {
+        let deprecated_fields = treasury.v2_deprecated_fields.clone().unwrap_or_default();
+        TreasuryV2Layout {
+            initialized: treasury.initialized,
+            version: treasury.version,
+            bump: treasury.bump,
+            slot: treasury.slot,
+            name: treasury.name,
+            treasurer_address: treasury.treasurer_address,
+            associated_token_address: treasury.associated_token_address,
+            mint_address: deprecated_fields.mint_address,
+            labels: deprecated_fields.labels,
+            last_known_balance_units: treasury.last_known_balance_units,
+            last_known_balance_slot: treasury.last_known_balance_slot,
+            last_known_balance_block_time: treasury.last_known_balance_block_time,
+            allocation_assigned_units: treasury.allocation_assigned_units,
+            allocation_reserved_units: deprecated_fields.allocation_reserved_units,
+            total_withdrawals_units: treasury.total_withdrawals_units,
+            total_streams: treasury.total_streams,
+            created_on_utc: treasury.created_on_utc,
+            treasury_type: treasury.treasury_type,
+            auto_close: treasury.auto_close,
+            sol_fee_payed_by_treasury: treasury.sol_fee_payed_by_treasury,
+            category: treasury.category,
+            sub_category: treasury.sub_category,
+            authority_address: treasury.authority_address,
+            pending_authority_address: treasury.pending_authority_address,
+        }
+    }
 }
 
 impl Treasury {
@@ -70,4 +235,61 @@ impl Treasury {
         msg!("last_known_unallocated_balance: {0}", result);
         Ok(result)
     }
+
+    /// Check if the treasury account layout version is supported (v2
+    /// accounts are accepted until all of them are migrated to v3)
+    pub fn is_supported_version(&self) -> bool {
+        self.version == 2 || self.version == 3
+    }
+
+    /// Check if the treasury account size matches its layout version (the
+    /// account can be bigger when it has extensions)
+    pub fn has_valid_size(&self, data_len: usize) -> bool {
+        match self.version {
+            2 => data_len >= TREASURY_V2_SIZE,
+            3 => data_len >= TREASURY_V3_SIZE,
+            _ => false,
+        }
+    }
+
+    /// Check if the treasury still has the v2 layout
+    pub fn needs_migration(&self) -> bool {
+        self.version == 2
+    }
+
+    /// Sets the v3 version so the treasury is written with the v3 layout.
+    /// The account still needs to be reallocated to `TREASURY_V3_SIZE`
+    pub fn migrate_to_v3(&mut self) {
+        self.authority_address = self.authority();
+        self.version = 3;
+    }
+
+    /// Gets the current treasury authority
+    pub fn authority(&self) -> Pubkey {
+        if self.authority_address == Pubkey::default() {
+            return self.treasurer_address;
+        }
+        self.authority_address
+    }
+}
+
+/// Extension that marks a treasury whose balance is held as lamports by the
+/// treasury PDA instead of a token account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct NativeSolTreasury {}
+
+impl AccountExtension for NativeSolTreasury {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::NativeSolTreasury;
+}
+
+/// The integrator that referred the treasury. It gets a share of the fees
+/// charged when withdrawing from, allocating to and closing the treasury
+/// streams, and in `treasury_withdraw`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct TreasuryReferrer {
+    pub referrer_address: Pubkey,
+}
+
+impl AccountExtension for TreasuryReferrer {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::TreasuryReferrer;
 }
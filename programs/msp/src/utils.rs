@@ -1,16 +1,23 @@
+use crate::config::FeeSchedule;
 use crate::constants::{
-    CREATE_STREAM_FLAT_FEE, PERCENT_DENOMINATOR, TREASURY_TYPE_LOCKED, WITHDRAW_PERCENT_FEE, CREATE_TREASURY_FLAT_FEE, CREATE_TREASURY_INITIAL_BALANCE_FOR_FEES,
+    PERCENT_DENOMINATOR, TREASURY_TYPE_LOCKED, CREATE_TREASURY_INITIAL_BALANCE_FOR_FEES,
+    STREAM_ACCRUAL_MODE_STEP, STREAM_V3_SIZE,
 };
 use crate::enums::*;
 use crate::errors::ErrorCode;
 use crate::events::*;
+use crate::extensions::*;
 use crate::mean_emit;
+use crate::schedule::*;
 use crate::stream::*;
 use crate::template::*;
+use crate::tlv::*;
 use crate::treasury::*;
 use crate::categories::*;
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
 use anchor_spl::token::*;
+use std::cmp;
 use std::convert::TryFrom;
 
 pub fn transfer_sol_amount<'info>(
@@ -27,11 +34,58 @@ pub fn transfer_sol_amount<'info>(
     .map_err(Into::into)
 }
 
-pub fn treasury_transfer_sol_amount<'info>(
-    treasury: &AccountInfo<'info>,
-    to: &AccountInfo<'info>,
-    amount: u64,
+/// Reallocates a program account to `new_size`. The `payer` funds the rent
+/// for the extra bytes when growing and gets the rent of the removed bytes
+/// back when shrinking. Any other lamports held by the account (e.g. the
+/// treasury balance for fees) are left untouched
+pub fn resize_account<'info>(
+    account: &AccountInfo<'info>,
+    new_size: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
 ) -> Result<()> {
+    let rent = Rent::get()?;
+    let old_min_rent_exempt = rent.minimum_balance(account.data_len());
+    let new_min_rent_exempt = rent.minimum_balance(new_size);
+
+    match new_min_rent_exempt.cmp(&old_min_rent_exempt) {
+        cmp::Ordering::Greater => {
+            transfer_sol_amount(
+                payer,
+                account,
+                system_program,
+                new_min_rent_exempt
+                    .checked_sub(old_min_rent_exempt)
+                    .ok_or(ErrorCode::Overflow)?,
+            )?;
+        }
+        cmp::Ordering::Less => {
+            let refund_lamports = cmp::min(
+                old_min_rent_exempt
+                    .checked_sub(new_min_rent_exempt)
+                    .ok_or(ErrorCode::Overflow)?,
+                account.lamports().saturating_sub(new_min_rent_exempt),
+            );
+            **account.try_borrow_mut_lamports()? = account
+                .lamports()
+                .checked_sub(refund_lamports)
+                .ok_or(ErrorCode::Overflow)?;
+            **payer.try_borrow_mut_lamports()? = payer
+                .lamports()
+                .checked_add(refund_lamports)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+        cmp::Ordering::Equal => {}
+    }
+
+    // zero the new bytes so they read as padding in the extensions region
+    let old_size = account.data_len();
+    account.realloc(new_size, new_size > old_size)?;
+    Ok(())
+}
+
+/// Gets the treasury lamports that can be spent while keeping it rent exempt
+pub fn get_treasury_available_lamports(treasury: &AccountInfo) -> Result<u64> {
     let treasury_lamports = treasury.lamports();
     let treasury_min_rent_exempt = Rent::get()?.minimum_balance(treasury.data_len());
     let treasury_available_lamports = if treasury_lamports > treasury_min_rent_exempt {
@@ -49,6 +103,16 @@ pub fn treasury_transfer_sol_amount<'info>(
         treasury_available_lamports
     );
 
+    Ok(treasury_available_lamports)
+}
+
+pub fn treasury_transfer_sol_amount<'info>(
+    treasury: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let treasury_available_lamports = get_treasury_available_lamports(treasury)?;
+
     if amount > treasury_available_lamports {
         return Err(ErrorCode::InsufficientLamports.into());
     }
@@ -103,6 +167,204 @@ pub fn treasury_transfer<'info>(
     transfer(cpi_ctx, amount)
 }
 
+/// Where the treasury balance is held: the treasury associated token account
+/// or, for native SOL treasuries, the treasury PDA lamports
+pub enum TreasuryVault<'a, 'info> {
+    Token {
+        treasury_token: &'a mut Account<'info, TokenAccount>,
+        token_program: AccountInfo<'info>,
+    },
+    /// Lamports moved out of the treasury before a CPI make the runtime CPI
+    /// imbalance check fail, so sol fees are charged ahead of any CPI
+    NativeSol,
+}
+
+impl<'a, 'info> TreasuryVault<'a, 'info> {
+    pub fn is_native_sol(&self) -> bool {
+        matches!(self, TreasuryVault::NativeSol)
+    }
+
+    /// Sends `amount` units from the treasury to `to`, which is a token
+    /// account of the treasury mint or, for native SOL treasuries, any account
+    pub fn transfer(
+        &self,
+        treasury: &Account<'info, Treasury>,
+        to: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        match self {
+            TreasuryVault::Token {
+                treasury_token,
+                token_program,
+            } => treasury_transfer(
+                treasury,
+                &treasury_token.to_account_info(),
+                to,
+                token_program,
+                amount,
+            ),
+            TreasuryVault::NativeSol => {
+                treasury_transfer_sol_amount(&treasury.to_account_info(), to, amount)
+            }
+        }
+    }
+
+    /// Sends a token fee from the treasury to the fee treasury, minus the
+    /// referrer share which goes to the referrer
+    pub fn transfer_fee(
+        &self,
+        treasury: &Account<'info, Treasury>,
+        fee_treasury_token: &AccountInfo<'info>,
+        referrer: Option<&FeeReferrer<'_, 'info>>,
+        fee_amount: u64,
+        fees: &FeeSchedule,
+    ) -> Result<()> {
+        let referrer_token = referrer.and_then(|referrer| referrer.token_account);
+        let (fee_treasury_amount, referrer_amount) =
+            split_referrer_fee(fee_amount, referrer_token.is_some(), fees)?;
+        if let Some(referrer_token) = referrer_token {
+            if referrer_amount > 0 {
+                self.transfer(treasury, referrer_token, referrer_amount)?;
+            }
+        }
+        if fee_treasury_amount > 0 {
+            self.transfer(treasury, fee_treasury_token, fee_treasury_amount)?;
+        }
+        Ok(())
+    }
+
+    /// Gets the current treasury balance (the lamports over the rent exempt
+    /// minimum for native SOL treasuries)
+    pub fn balance(&mut self, treasury: &Account<'info, Treasury>) -> Result<u64> {
+        match self {
+            TreasuryVault::Token { treasury_token, .. } => {
+                treasury_token.reload()?;
+                Ok(treasury_token.amount)
+            }
+            TreasuryVault::NativeSol => get_treasury_available_lamports(&treasury.to_account_info()),
+        }
+    }
+}
+
+/// Checks if the treasury is a native SOL treasury
+pub fn is_native_sol_treasury(treasury: &AccountInfo) -> Result<bool> {
+    Ok(get_account_extension::<Treasury, NativeSolTreasury>(treasury)?.is_some())
+}
+
+/// Charges a program sol fee from the treasury lamports or from the payer
+/// depending on the treasury settings
+pub fn charge_sol_fee<'info>(
+    treasury: &Account<'info, Treasury>,
+    fee_treasury: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if treasury.sol_fee_payed_by_treasury {
+        treasury_transfer_sol_amount(&treasury.to_account_info(), fee_treasury, amount)
+    } else {
+        transfer_sol_amount(payer, fee_treasury, system_program, amount)
+    }
+}
+
+/// The accounts receiving the referrer share of the fees charged for a
+/// treasury
+pub struct FeeReferrer<'a, 'info> {
+    /// Receives the share of the SOL fees
+    pub wallet: &'a AccountInfo<'info>,
+    /// Receives the share of the token fees. It is the `wallet` for native
+    /// SOL treasuries and None when the referrer has no usable associated
+    /// token account (the share then goes to the fee treasury)
+    pub token_account: Option<&'a AccountInfo<'info>>,
+}
+
+/// Gets the referrer accounts of the treasury from the `referrer` and
+/// `referrer_token` accounts of the instruction. Returns None, so the whole
+/// fee goes to the fee treasury, when the treasury has no referrer or the
+/// `referrer` passed is not its referrer (e.g. the fee treasury is passed).
+/// A missing, closed or frozen referrer token account only sends the token
+/// share to the fee treasury, it never blocks the instruction
+pub fn load_fee_referrer<'a, 'info>(
+    treasury: &Account<'info, Treasury>,
+    referrer: &'a AccountInfo<'info>,
+    referrer_token: Option<&'a AccountInfo<'info>>,
+) -> Result<Option<FeeReferrer<'a, 'info>>> {
+    let treasury_account_info = treasury.to_account_info();
+    let referrer_address =
+        match get_account_extension::<Treasury, TreasuryReferrer>(&treasury_account_info)? {
+            Some(referrer) => referrer.referrer_address,
+            None => return Ok(None),
+        };
+    if referrer.key() != referrer_address || !referrer.is_writable {
+        return Ok(None);
+    }
+
+    let token_account = if is_native_sol_treasury(&treasury_account_info)? {
+        Some(referrer)
+    } else {
+        let referrer_associated_token =
+            get_associated_token_address(&referrer_address, &treasury.associated_token_address);
+        referrer_token.filter(|referrer_token| {
+            referrer_token.key() == referrer_associated_token
+                && referrer_token.is_writable
+                && Account::<TokenAccount>::try_from(referrer_token)
+                    .map_or(false, |token_account| !token_account.is_frozen())
+        })
+    };
+
+    Ok(Some(FeeReferrer {
+        wallet: referrer,
+        token_account,
+    }))
+}
+
+/// Splits a fee into the part sent to the fee treasury and the referrer
+/// share (zero when the fee is not shared with a referrer)
+pub fn split_referrer_fee(
+    fee_amount: u64,
+    is_referred: bool,
+    fees: &FeeSchedule,
+) -> Result<(u64, u64)> {
+    if !is_referred {
+        return Ok((fee_amount, 0));
+    }
+
+    let referrer_amount = u64::try_from(
+        (fees.referrer_fee_share as u128)
+            .checked_mul(fee_amount as u128)
+            .ok_or(ErrorCode::Overflow)?
+            .checked_div(PERCENT_DENOMINATOR as u128)
+            .ok_or(ErrorCode::Overflow)?,
+    )
+    .unwrap();
+    let fee_treasury_amount = fee_amount
+        .checked_sub(referrer_amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    Ok((fee_treasury_amount, referrer_amount))
+}
+
+/// Charges a program sol fee like `charge_sol_fee`, sending the referrer
+/// share to the referrer wallet
+pub fn charge_sol_fee_with_referrer<'info>(
+    treasury: &Account<'info, Treasury>,
+    fee_treasury: &AccountInfo<'info>,
+    referrer: Option<&FeeReferrer<'_, 'info>>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+    fees: &FeeSchedule,
+) -> Result<()> {
+    let (fee_treasury_amount, referrer_amount) =
+        split_referrer_fee(amount, referrer.is_some(), fees)?;
+    if let Some(referrer) = referrer {
+        if referrer_amount > 0 {
+            charge_sol_fee(treasury, referrer.wallet, payer, system_program, referrer_amount)?;
+        }
+    }
+    charge_sol_fee(treasury, fee_treasury, payer, system_program, fee_treasury_amount)
+}
+
 pub fn string_to_bytes<'info>(string: String) -> Result<[u8; 32]> {
     let string_bytes = string.as_bytes();
 
@@ -116,12 +378,179 @@ pub fn string_to_bytes<'info>(string: String) -> Result<[u8; 32]> {
     Ok(string_data)
 }
 
-pub fn get_stream_data_event<'info>(stream: &Stream) -> Result<StreamEvent> {
+pub fn load_stream_schedule<'info>(
+    stream_key: Pubkey,
+    stream: &Stream,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<Option<Account<'info, StreamSchedule>>> {
+    if !stream.primitive_has_schedule() {
+        return Ok(None);
+    }
+
+    let schedule_account_info = remaining_accounts
+        .iter()
+        .find(|account| account.key() == stream.schedule_address)
+        .ok_or(ErrorCode::InvalidScheduleAccount)?;
+    let schedule = Account::<StreamSchedule>::try_from(schedule_account_info)?;
+
+    if schedule.stream_address != stream_key {
+        return Err(ErrorCode::InvalidScheduleAccount.into());
+    }
+
+    Ok(Some(schedule))
+}
+
+/// Loads a treasury passed in the remaining accounts and checks it is the
+/// treasurer PDA of this program
+pub fn load_treasury<'info>(
+    treasury_account_info: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<Account<'info, Treasury>> {
+    if !treasury_account_info.is_writable {
+        return Err(ErrorCode::InvalidTreasury.into());
+    }
+
+    let treasury = Account::<Treasury>::try_from(treasury_account_info)?;
+
+    let treasury_pda = Pubkey::create_program_address(
+        &[
+            treasury.treasurer_address.as_ref(),
+            &treasury.slot.to_le_bytes(),
+            &[treasury.bump],
+        ],
+        program_id,
+    )
+    .map_err(|_| ErrorCode::InvalidTreasury)?;
+    if treasury_pda != treasury.key() {
+        return Err(ErrorCode::InvalidTreasury.into());
+    }
+    if !treasury.is_supported_version() {
+        return Err(ErrorCode::InvalidTreasuryVersion.into());
+    }
+    if !treasury.initialized {
+        return Err(ErrorCode::TreasuryNotInitialized.into());
+    }
+    if !treasury.has_valid_size(treasury_account_info.data_len()) {
+        return Err(ErrorCode::InvalidTreasurySize.into());
+    }
+
+    Ok(treasury)
+}
+
+/// Loads a stream passed in the remaining accounts and checks it belongs to
+/// the given treasury
+pub fn load_treasury_stream<'info>(
+    stream_account_info: &AccountInfo<'info>,
+    treasury: &Account<'info, Treasury>,
+) -> Result<Account<'info, Stream>> {
+    if !stream_account_info.is_writable {
+        return Err(ErrorCode::InvalidStreamAccounts.into());
+    }
+
+    let stream = Account::<Stream>::try_from(stream_account_info)?;
+
+    if stream.treasury_address != treasury.key() {
+        return Err(ErrorCode::InvalidTreasury.into());
+    }
+    if !stream.primitive_is_supported_version() {
+        return Err(ErrorCode::InvalidStreamVersion.into());
+    }
+    if !stream.initialized {
+        return Err(ErrorCode::StreamNotInitialized.into());
+    }
+    if !stream.primitive_has_valid_size(stream_account_info.data_len()) {
+        return Err(ErrorCode::InvalidStreamSize.into());
+    }
+    if stream.treasurer_address != treasury.treasurer_address {
+        return Err(ErrorCode::InvalidTreasurer.into());
+    }
+
+    Ok(stream)
+}
+
+/// Creates the stream PDA [b"stream", treasury, stream_pda_seed] passed in
+/// the remaining accounts. The returned stream is not initialized
+pub fn create_treasury_stream_pda<'info>(
+    stream_account_info: &AccountInfo<'info>,
+    treasury: &Account<'info, Treasury>,
+    stream_pda_seed: Pubkey,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<Account<'info, Stream>> {
+    let treasury_key = treasury.key();
+    let (stream_pda, stream_bump) = Pubkey::find_program_address(
+        &[b"stream", treasury_key.as_ref(), stream_pda_seed.as_ref()],
+        program_id,
+    );
+    if stream_account_info.key() != stream_pda || !stream_account_info.is_writable {
+        return Err(ErrorCode::InvalidStreamAccounts.into());
+    }
+
+    let stream_seeds: &[&[u8]] = &[
+        b"stream",
+        treasury_key.as_ref(),
+        stream_pda_seed.as_ref(),
+        &[stream_bump],
+    ];
+    let minimum_balance = Rent::get()?.minimum_balance(STREAM_V3_SIZE);
+    let current_lamports = stream_account_info.lamports();
+    if current_lamports == 0 {
+        let create_stream_ix = solana_program::system_instruction::create_account(
+            payer.key,
+            stream_account_info.key,
+            minimum_balance,
+            STREAM_V3_SIZE as u64,
+            program_id,
+        );
+        solana_program::program::invoke_signed(
+            &create_stream_ix,
+            &[
+                payer.clone(),
+                stream_account_info.clone(),
+                system_program.clone(),
+            ],
+            &[stream_seeds],
+        )?;
+    } else {
+        // anyone can send lamports to the PDA before it is created, which
+        // makes `create_account` fail, so it is funded, allocated and
+        // assigned instead (as Anchor does for `init`)
+        let required_lamports = minimum_balance.saturating_sub(current_lamports);
+        if required_lamports > 0 {
+            transfer_sol_amount(payer, stream_account_info, system_program, required_lamports)?;
+        }
+        solana_program::program::invoke_signed(
+            &solana_program::system_instruction::allocate(
+                stream_account_info.key,
+                STREAM_V3_SIZE as u64,
+            ),
+            &[stream_account_info.clone(), system_program.clone()],
+            &[stream_seeds],
+        )?;
+        solana_program::program::invoke_signed(
+            &solana_program::system_instruction::assign(stream_account_info.key, program_id),
+            &[stream_account_info.clone(), system_program.clone()],
+            &[stream_seeds],
+        )?;
+    }
+
+    // the account data is all zeros so there is no discriminator to check yet
+    Account::<Stream>::try_from_unchecked(stream_account_info)
+}
+
+pub fn get_stream_data_event(
+    stream: &Stream,
+    schedule: Option<&StreamSchedule>,
+) -> Result<StreamEvent> {
     let now_ts = Clock::get()?.unix_timestamp as u64;
     msg!("clock: {0}", now_ts);
 
     let status_name;
-    let status = stream.get_status(now_ts)?;
+    let status = match schedule {
+        Some(schedule) => stream.get_scheduled_status(schedule, now_ts)?,
+        None => stream.get_status(now_ts)?,
+    };
 
     if StreamStatus::Scheduled == status {
         status_name = "Scheduled";
@@ -157,13 +586,41 @@ pub fn get_stream_data_event<'info>(stream: &Stream) -> Result<StreamEvent> {
 
     let streamed_units_since_started = stream.primitive_get_streamed_units(seconds_since_start)?;
     let cliff_units = stream.primitive_get_cliff_units()?;
-    let non_stop_earning_units = cliff_units
+    let mut non_stop_earning_units = cliff_units
         .checked_add(streamed_units_since_started)
         .ok_or(ErrorCode::Overflow)?;
 
-    let missed_units_while_paused =
+    let mut missed_units_while_paused =
         stream.primitive_get_streamed_units(stream.last_known_total_seconds_in_paused_status)?;
 
+    if stream.primitive_is_step_accrual() {
+        // in step mode the units missed while paused depend on where the
+        // interval boundaries fall, so they are derived from the actual
+        // streamed seconds instead
+        let mut actual_streamed_seconds = 0u64;
+        if seconds_since_start > stream.last_known_total_seconds_in_paused_status {
+            actual_streamed_seconds = seconds_since_start
+                .checked_sub(stream.last_known_total_seconds_in_paused_status)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+        let actual_earned_units = cliff_units
+            .checked_add(stream.primitive_get_streamed_units(actual_streamed_seconds)?)
+            .ok_or(ErrorCode::Overflow)?;
+        missed_units_while_paused = non_stop_earning_units
+            .checked_sub(actual_earned_units)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    if let Some(schedule) = schedule {
+        // scheduled streams can't be paused and unlock units following the
+        // schedule points instead of the rate
+        non_stop_earning_units = cmp::min(
+            schedule.get_unlocked_units(now_ts),
+            stream.allocation_assigned_units,
+        );
+        missed_units_while_paused = 0;
+    }
+
     let mut entitled_earnings_units = 0u64;
 
     if non_stop_earning_units >= missed_units_while_paused {
@@ -181,6 +638,16 @@ pub fn get_stream_data_event<'info>(stream: &Stream) -> Result<StreamEvent> {
     }
 
     let unused_allocation = stream.get_remaining_allocation()?;
+    let (beneficiary_withdrawable_amount, est_depletion_time) = match schedule {
+        Some(schedule) => (
+            stream.get_scheduled_withdrawable_amount(schedule, now_ts)?,
+            schedule.get_completion_blocktime(),
+        ),
+        None => (
+            stream.get_beneficiary_withdrawable_amount(now_ts)?,
+            stream.get_est_depletion_blocktime(now_ts)?,
+        ),
+    };
     // let withdrawable = cmp::min(unused_allocation, withdrawable_units_while_running);
     // let rate_amount = stream.rate_amount_units as f64 / stream.rate_interval_in_seconds as f64;
 
@@ -195,7 +662,7 @@ pub fn get_stream_data_event<'info>(stream: &Stream) -> Result<StreamEvent> {
         rate_interval_in_seconds: stream.rate_interval_in_seconds,
         start_utc: start_utc_seconds,
         cliff_vest_amount_units: stream.cliff_vest_amount_units,
-        cliff_vest_percent: stream.cliff_vest_percent,
+        cliff_vest_percent: 0, // deprecated
         beneficiary_address: stream.beneficiary_address,
         beneficiary_associated_token: stream.beneficiary_associated_token,
         treasury_address: stream.treasury_address,
@@ -222,20 +689,29 @@ pub fn get_stream_data_event<'info>(stream: &Stream) -> Result<StreamEvent> {
         current_block_time: now_ts,
         seconds_since_start: seconds_since_start,
         // streamed_units_per_second: rate_amount,
-        est_depletion_time: stream.get_est_depletion_blocktime()?,
-        funds_left_in_stream: stream.get_funds_left_in_account(now_ts)?,
-        funds_sent_to_beneficiary: stream.get_funds_sent_to_beneficiary(now_ts)?,
+        est_depletion_time,
+        funds_left_in_stream: unused_allocation
+            .checked_sub(beneficiary_withdrawable_amount)
+            .ok_or(ErrorCode::Overflow)?,
+        funds_sent_to_beneficiary: stream
+            .total_withdrawals_units
+            .checked_add(beneficiary_withdrawable_amount)
+            .ok_or(ErrorCode::Overflow)?,
         withdrawable_units_while_paused: withdrawable_while_paused,
         non_stop_earning_units: non_stop_earning_units,
         missed_units_while_paused: missed_units_while_paused,
         entitled_earnings_units: entitled_earnings_units,
         withdrawable_units_while_running: withdrawable_units_while_running,
         beneficiary_remaining_allocation: unused_allocation,
-        beneficiary_withdrawable_amount: stream.get_beneficiary_withdrawable_amount(now_ts)?,
+        beneficiary_withdrawable_amount,
         last_known_stop_block_time: stream.primitive_get_last_known_stop_block_time(),
         created_on_utc: stream.created_on_utc,
         category: stream.category,
         sub_category: stream.sub_category,
+        accrual_mode: stream.accrual_mode,
+        schedule_address: stream.schedule_address,
+        cliff_utc_in_seconds: stream.cliff_utc_in_seconds,
+        pending_beneficiary_address: stream.pending_beneficiary_address,
     };
 
     Ok(data)
@@ -256,18 +732,19 @@ pub fn construct_treasury_account<'info>(
     associated_token: &AccountInfo<'info>,
     system_program: &AccountInfo<'info>,
     slot: u64,
+    fees: &FeeSchedule,
 ) -> Result<()> {
-        treasury.version = 2;
+        treasury.version = 3;
         treasury.bump = treasury_bump;
         treasury.slot = slot;
         treasury.treasurer_address = treasurer.key();
+        treasury.authority_address = treasurer.key();
+        treasury.pending_authority_address = Pubkey::default();
         treasury.associated_token_address = associated_token.key();
         treasury.name = string_to_bytes(name)?;
-        treasury.labels = Vec::new(); // Do not change
         treasury.last_known_balance_units = 0;
         treasury.last_known_balance_slot = 0;
         treasury.last_known_balance_block_time = 0;
-        treasury.allocation_reserved_units = 0; // deprecated
         treasury.allocation_assigned_units = 0;
         treasury.total_withdrawals_units = 0;
         treasury.total_streams = 0;
@@ -284,7 +761,7 @@ pub fn construct_treasury_account<'info>(
             &payer,
             &fee_treasury,
             &system_program,
-            CREATE_TREASURY_FLAT_FEE,
+            fees.create_treasury_flat_fee,
         )?;
 
         if sol_fee_payed_by_treasury {
@@ -298,7 +775,7 @@ pub fn construct_treasury_account<'info>(
 
         mean_emit!(CreateTreasuryEvent {
             timestamp: treasury.created_on_utc,
-            sol_fee_charged: CREATE_TREASURY_FLAT_FEE,
+            sol_fee_charged: fees.create_treasury_flat_fee,
             token_fee_charged: 0,
             sol_deposited_for_fees: CREATE_TREASURY_INITIAL_BALANCE_FOR_FEES,
             treasury_is_sol_fee_payed_by_treasury: treasury.sol_fee_payed_by_treasury,
@@ -316,6 +793,8 @@ pub fn construct_stream_template <'info>(
     duration_number_of_units: u64,
     cliff_vest_percent: u64,
     fee_payed_by_treasurer: bool,
+    accrual_mode: u8,
+    cliff_utc: u64,
     template: &mut Account<'info, StreamTemplate>,
     template_bump: u8,
     template_version: u8,
@@ -323,12 +802,22 @@ pub fn construct_stream_template <'info>(
     let clock = Clock::get()?;
     let now_ts = clock.unix_timestamp as u64;
 
+    if accrual_mode > STREAM_ACCRUAL_MODE_STEP {
+        return Err(ErrorCode::InvalidAccrualMode.into());
+    }
+
+    if cliff_utc > 0 && cliff_utc < start_utc {
+        return Err(ErrorCode::InvalidCliff.into());
+    }
+
     template.version = template_version;
     template.bump = template_bump;
     template.rate_interval_in_seconds = rate_interval_in_seconds;
     template.fee_payed_by_treasurer = fee_payed_by_treasurer;
     template.duration_number_of_units = duration_number_of_units;
     template.cliff_vest_percent = cliff_vest_percent;
+    template.accrual_mode = accrual_mode;
+    template.cliff_utc_in_seconds = cliff_utc;
 
     if start_utc < now_ts {
         template.start_utc_in_seconds = now_ts;
@@ -338,7 +827,33 @@ pub fn construct_stream_template <'info>(
     Ok(())
 }
 
-pub fn construct_stream_account<'info>(
+/// Gets the token fee payed upfront by the treasurer when the stream
+/// beneficiary withdraw fees are payed by the treasurer
+pub fn get_treasurer_fee_amount(
+    fee_payed_by_treasurer: bool,
+    allocation_assigned_units: u64,
+    fees: &FeeSchedule,
+) -> Result<u64> {
+    if !fee_payed_by_treasurer {
+        return Ok(0);
+    }
+
+    // beneficiary fee payed by the treasurer
+    let treasurer_fee_amount = u64::try_from(
+        (fees.withdraw_percent_fee as u128)
+            .checked_mul(allocation_assigned_units as u128)
+            .ok_or(ErrorCode::Overflow)?
+            .checked_div(PERCENT_DENOMINATOR as u128)
+            .ok_or(ErrorCode::Overflow)?,
+    )
+    .unwrap();
+
+    Ok(treasurer_fee_amount)
+}
+
+/// Validates the stream parameters and sets the stream fields. It does not
+/// update the treasury nor charge any fees
+pub fn populate_stream_account(
     name: String,
     start_utc: u64,
     rate_amount_units: u64,
@@ -346,59 +861,36 @@ pub fn construct_stream_account<'info>(
     allocation_assigned_units: u64,
     fee_payed_by_treasurer: bool,
     effective_cliff_units: u64,
-    stream: &mut Account<'info, Stream>,
-    treasury: &mut Account<'info, Treasury>,
-    treasury_token: &mut Account<'info, TokenAccount>,
-    treasurer: &AccountInfo<'info>,
-    beneficiary: &AccountInfo<'info>,
-    beneficiary_associated_token: &AccountInfo<'info>,
-    fee_treasury_token: &AccountInfo<'info>,
-    fee_treasury: &AccountInfo<'info>,
-    payer: &AccountInfo<'info>,
-    token_program: &AccountInfo<'info>,
-    system_program: &AccountInfo<'info>,
+    accrual_mode: u8,
+    cliff_utc: u64,
+    stream: &mut Stream,
+    treasury: &Account<Treasury>,
+    beneficiary: Pubkey,
+    beneficiary_associated_token: Pubkey,
+    now_ts: u64,
 ) -> Result<()> {
-    let clock = Clock::get()?;
-    let now_ts = clock.unix_timestamp as u64;
-
-    let mut treasurer_fee_amount = 0u64;
-    let mut total_treasury_allocation_amount = allocation_assigned_units;
-
-    if fee_payed_by_treasurer {
-        // beneficiary fee payed by the treasurer
-        treasurer_fee_amount = u64::try_from(
-            (WITHDRAW_PERCENT_FEE as u128)
-                .checked_mul(allocation_assigned_units as u128)
-                .ok_or(ErrorCode::Overflow)?
-                .checked_div(PERCENT_DENOMINATOR as u128)
-                .ok_or(ErrorCode::Overflow)?,
-        )
-        .unwrap();
-
-        total_treasury_allocation_amount = allocation_assigned_units
-            .checked_add(treasurer_fee_amount)
-            .ok_or(ErrorCode::Overflow)?;
+    if treasury.treasury_type == TREASURY_TYPE_LOCKED && allocation_assigned_units == 0 {
+        return Err(ErrorCode::InvalidRequestedStreamAllocation.into());
     }
 
-    if total_treasury_allocation_amount > treasury.last_known_unallocated_balance()? {
-        return Err(ErrorCode::InsufficientTreasuryBalance.into());
+    if accrual_mode > STREAM_ACCRUAL_MODE_STEP {
+        return Err(ErrorCode::InvalidAccrualMode.into());
     }
 
-    if treasury.treasury_type == TREASURY_TYPE_LOCKED && allocation_assigned_units == 0 {
-        return Err(ErrorCode::InvalidRequestedStreamAllocation.into());
+    if cliff_utc > 0 && cliff_utc < start_utc {
+        return Err(ErrorCode::InvalidCliff.into());
     }
 
-    // update stream (needs to go before updating the treasury)
-    stream.version = 2;
+    stream.version = 3;
     stream.name = string_to_bytes(name)?;
-    stream.treasurer_address = treasurer.key();
+    // the treasury seed treasurer, the current authority is in the treasury
+    stream.treasurer_address = treasury.treasurer_address;
     stream.rate_amount_units = rate_amount_units;
     stream.rate_interval_in_seconds = rate_interval_in_seconds;
-    stream.beneficiary_address = beneficiary.key();
-    stream.beneficiary_associated_token = beneficiary_associated_token.key();
+    stream.beneficiary_address = beneficiary;
+    stream.beneficiary_associated_token = beneficiary_associated_token;
     stream.treasury_address = treasury.key();
     stream.allocation_assigned_units = allocation_assigned_units;
-    stream.allocation_reserved_units = 0; // deprecated
     stream.total_withdrawals_units = 0;
     stream.last_withdrawal_units = 0;
     stream.last_withdrawal_slot = 0;
@@ -412,22 +904,79 @@ pub fn construct_stream_account<'info>(
     stream.last_manual_resume_block_time = 0;
     stream.last_known_total_seconds_in_paused_status = 0;
     stream.cliff_vest_amount_units = effective_cliff_units;
-    stream.cliff_vest_percent = 0; // deprecated
-    stream.start_utc_in_seconds = 0;
     stream.fee_payed_by_treasurer = fee_payed_by_treasurer;
     stream.initialized = true;
     stream.created_on_utc = now_ts;
     stream.category = treasury.category;
     stream.sub_category = treasury.sub_category;
+    stream.accrual_mode = accrual_mode;
+    stream.schedule_address = Pubkey::default();
+    stream.cliff_utc_in_seconds = cliff_utc;
+    stream.pending_beneficiary_address = Pubkey::default();
 
     if start_utc < now_ts {
         stream.start_utc = now_ts;
-        stream.start_utc_in_seconds = now_ts;
     } else {
         stream.start_utc = start_utc;
-        stream.start_utc_in_seconds = start_utc;
     }
 
+    Ok(())
+}
+
+pub fn construct_stream_account<'info>(
+    name: String,
+    start_utc: u64,
+    rate_amount_units: u64,
+    rate_interval_in_seconds: u64,
+    allocation_assigned_units: u64,
+    fee_payed_by_treasurer: bool,
+    effective_cliff_units: u64,
+    accrual_mode: u8,
+    cliff_utc: u64,
+    stream: &mut Account<'info, Stream>,
+    treasury: &mut Account<'info, Treasury>,
+    vault: &mut TreasuryVault<'_, 'info>,
+    beneficiary: &AccountInfo<'info>,
+    beneficiary_associated_token: &AccountInfo<'info>,
+    fee_treasury_token: &AccountInfo<'info>,
+    fee_treasury: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    referrer: Option<&FeeReferrer<'_, 'info>>,
+    fees: &FeeSchedule,
+    fee_tier: u8,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let now_ts = clock.unix_timestamp as u64;
+
+    let treasurer_fee_amount =
+        get_treasurer_fee_amount(fee_payed_by_treasurer, allocation_assigned_units, fees)?;
+    let total_treasury_allocation_amount = allocation_assigned_units
+        .checked_add(treasurer_fee_amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    if total_treasury_allocation_amount > treasury.last_known_unallocated_balance()? {
+        return Err(ErrorCode::InsufficientTreasuryBalance.into());
+    }
+
+    // update stream (needs to go before updating the treasury)
+    populate_stream_account(
+        name,
+        start_utc,
+        rate_amount_units,
+        rate_interval_in_seconds,
+        allocation_assigned_units,
+        fee_payed_by_treasurer,
+        effective_cliff_units,
+        accrual_mode,
+        cliff_utc,
+        stream,
+        treasury,
+        beneficiary.key(),
+        beneficiary_associated_token.key(),
+        now_ts,
+    )?;
+
     // update treasury (needs to after before updating the stream)
     if stream.allocation_assigned_units > 0 {
         treasury.allocation_assigned_units = treasury
@@ -441,15 +990,22 @@ pub fn construct_stream_account<'info>(
         .checked_add(1u64)
         .ok_or(ErrorCode::Overflow)?;
 
+    // the sol fee goes before any CPI, see `TreasuryVault::NativeSol`
+    if vault.is_native_sol() {
+        charge_sol_fee_with_referrer(
+            treasury,
+            fee_treasury,
+            referrer,
+            payer,
+            system_program,
+            fees.create_stream_flat_fee,
+            fees,
+        )?;
+    }
+
     if treasurer_fee_amount > 0 {
         // beneficiary withdraw fee payed by the treasurer
-        treasury_transfer(
-            &treasury,
-            &treasury_token.to_account_info(),
-            &fee_treasury_token,
-            &token_program,
-            treasurer_fee_amount,
-        )?;
+        vault.transfer_fee(treasury, fee_treasury_token, referrer, treasurer_fee_amount, fees)?;
 
         // update treasury
         treasury.last_known_balance_slot = clock.slot as u64;
@@ -462,25 +1018,20 @@ pub fn construct_stream_account<'info>(
 
     // set categories
     stream.category = treasury.category;
-    if treasury.sol_fee_payed_by_treasury {
-        treasury_transfer_sol_amount(
-            &treasury.to_account_info(),
-            &fee_treasury,
-            CREATE_STREAM_FLAT_FEE,
-        )?;
-    } else {
-        transfer_sol_amount(
-            // Not changing yet to be paid by treasury because of the airdrop streams
-            &payer,
-            &fee_treasury,
-            &system_program,
-            CREATE_STREAM_FLAT_FEE,
+    if !vault.is_native_sol() {
+        charge_sol_fee_with_referrer(
+            treasury,
+            fee_treasury,
+            referrer,
+            payer,
+            system_program,
+            fees.create_stream_flat_fee,
+            fees,
         )?;
     }
 
-    treasury_token.reload()?;
     assert!(
-        treasury_token.amount >= treasury.last_known_balance_units,
+        vault.balance(treasury)? >= treasury.last_known_balance_units,
         "treasury balance units invariant violated"
     );
     assert!(
@@ -490,9 +1041,10 @@ pub fn construct_stream_account<'info>(
 
     mean_emit!(CreateStreamEvent {
         timestamp: now_ts,
-        sol_fee_charged: CREATE_STREAM_FLAT_FEE,
+        sol_fee_charged: fees.create_stream_flat_fee,
         token_fee_charged: treasurer_fee_amount,
-        stream_start_ts: stream.start_utc_in_seconds,
+        fee_tier,
+        stream_start_ts: stream.start_utc,
         stream_rate_amount: stream.rate_amount_units,
         stream_rate_interval: stream.rate_interval_in_seconds,
         stream_allocation: stream.allocation_assigned_units,
@@ -511,7 +1063,549 @@ pub fn construct_stream_account<'info>(
 #[macro_export]
 macro_rules! mean_emit {
     ($e:expr) => {
-        msg!("mean-log-msp2");
+        msg!($crate::constants::EVENT_LOG_MARKER);
         emit!($e);
     };
 }
+
+/// Pays the withdrawable amount to the beneficiary (minus fees), returns the
+/// rest of the stream allocation to the treasury unallocated balance and
+/// charges the close stream fee. With `charge_close_fee` false the sol fee of a
+/// token treasury is left to the caller, which charges it after its own CPIs
+pub fn close_stream_settle<'info>(
+    beneficiary_closing_amount: u64,
+    stream: &Account<'info, Stream>,
+    treasury: &mut Account<'info, Treasury>,
+    vault: &mut TreasuryVault<'_, 'info>,
+    beneficiary_token: &AccountInfo<'info>,
+    fee_treasury_token: &AccountInfo<'info>,
+    fee_treasury: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    referrer: Option<&FeeReferrer<'_, 'info>>,
+    fees: &FeeSchedule,
+    fee_tier: u8,
+    charge_close_fee: bool,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let now_ts = clock.unix_timestamp as u64;
+    let now_slot = clock.slot;
+
+    // the sol fee goes before any CPI, see `TreasuryVault::NativeSol`
+    if vault.is_native_sol() {
+        charge_sol_fee_with_referrer(
+            treasury,
+            fee_treasury,
+            referrer,
+            payer,
+            system_program,
+            fees.close_stream_flat_fee,
+            fees,
+        )?;
+    }
+
+    let closing_amount_kept_in_treasury = stream
+        .allocation_assigned_units
+        .checked_sub(stream.total_withdrawals_units)
+        .unwrap()
+        .checked_sub(beneficiary_closing_amount)
+        .ok_or(ErrorCode::Overflow)?;
+    #[cfg(feature = "test")]
+    msg!(
+        "closing_amount_kept_in_treasury: {0}",
+        closing_amount_kept_in_treasury
+    );
+
+    let mut fee_amount = 0u64;
+    let mut beneficiary_closing_amount_after_deducting_fees = beneficiary_closing_amount;
+
+    if !stream.fee_payed_by_treasurer && beneficiary_closing_amount > 0 {
+        fee_amount = u64::try_from(
+            (fees.close_stream_percent_fee as u128)
+                .checked_mul(beneficiary_closing_amount as u128)
+                .ok_or(ErrorCode::Overflow)?
+                .checked_div(PERCENT_DENOMINATOR as u128)
+                .ok_or(ErrorCode::Overflow)?,
+        )
+        .unwrap();
+
+        beneficiary_closing_amount_after_deducting_fees = beneficiary_closing_amount
+            .checked_sub(fee_amount)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    // Transfer withdrawable amount to beneficiary and deduct fee
+    if beneficiary_closing_amount > 0 {
+        // Transfer withdrawable amount
+        vault.transfer(
+            treasury,
+            beneficiary_token,
+            beneficiary_closing_amount_after_deducting_fees,
+        )?;
+
+        if fee_amount > 0 {
+            // Fee
+            vault.transfer_fee(treasury, fee_treasury_token, referrer, fee_amount, fees)?;
+        }
+    }
+
+    // Update treasury data
+    let deallocated_units = beneficiary_closing_amount
+        .checked_add(closing_amount_kept_in_treasury)
+        .ok_or(ErrorCode::Overflow)?;
+    close_stream_update_treasury(
+        treasury,
+        beneficiary_closing_amount,
+        deallocated_units,
+        now_ts,
+        now_slot,
+    )?;
+
+    // sol fee
+    // #[cfg(feature = "test")]
+    if charge_close_fee && !vault.is_native_sol() {
+        charge_sol_fee_with_referrer(
+            treasury,
+            fee_treasury,
+            referrer,
+            payer,
+            system_program,
+            fees.close_stream_flat_fee,
+            fees,
+        )?;
+    }
+
+    #[cfg(feature = "test")]
+    msg!(
+        "stream.total_withdrawals_units: {0}",
+        stream.total_withdrawals_units
+    );
+    #[cfg(feature = "test")]
+    msg!(
+        "beneficiary_closing_amount_after_deducting_fees: {0}",
+        beneficiary_closing_amount_after_deducting_fees
+    );
+    #[cfg(feature = "test")]
+    msg!(
+        "closing_amount_kept_in_treasury: {0}",
+        closing_amount_kept_in_treasury
+    );
+    #[cfg(feature = "test")]
+    msg!("fee_amount: {0}", fee_amount);
+    assert!(
+        stream
+            .total_withdrawals_units
+            .checked_add(beneficiary_closing_amount_after_deducting_fees)
+            .unwrap()
+            .checked_add(closing_amount_kept_in_treasury)
+            .unwrap()
+            .checked_add(fee_amount)
+            .ok_or(ErrorCode::Overflow)?
+            == stream.allocation_assigned_units,
+        "stream closing total_withdrawals vs allocation_assigned invariant violated"
+    );
+
+    mean_emit!(CloseStreamEvent {
+        timestamp: Clock::get()?.unix_timestamp as u64,
+        sol_fee_charged: fees.close_stream_flat_fee,
+        token_fee_charged: fee_amount,
+        fee_tier,
+        token_amount_sent_to_beneficiary: beneficiary_closing_amount_after_deducting_fees,
+        stream_is_token_withdraw_fee_payed_by_treasury: stream.fee_payed_by_treasurer,
+        stream_allocation_before: stream.allocation_assigned_units,
+        stream_total_withdrawals_before: stream.total_withdrawals_units,
+        treasury_is_sol_fee_payed_by_treasury: treasury.sol_fee_payed_by_treasury,
+        treasury_allocation_after: treasury.allocation_assigned_units,
+        treasury_balance_after: treasury.last_known_balance_units,
+        treasury_total_streams_after: treasury.total_streams,
+        stream: stream.key(),
+        treasury: treasury.key(),
+    });
+
+    Ok(())
+}
+
+/// Sends the treasury token balance to the destination token account, closes
+/// the treasury token account and charges the close treasury fee. The
+/// treasury account itself is closed by the caller
+pub fn close_treasury_settle<'info>(
+    treasury: &Account<'info, Treasury>,
+    treasury_token: &mut Account<'info, TokenAccount>,
+    destination_token: &AccountInfo<'info>,
+    destination_authority: &AccountInfo<'info>,
+    fee_treasury: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    fees: &FeeSchedule,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let now_ts = clock.unix_timestamp as u64;
+
+    treasury_token.reload()?;
+    let treasury_token_amount_before = treasury_token.amount;
+
+    if treasury_token.amount > 0 {
+        treasury_transfer(
+            treasury,
+            &treasury_token.to_account_info(),
+            destination_token,
+            token_program,
+            treasury_token.amount,
+        )?;
+    }
+
+    // CLOSE THE TREASURY TOKEN ACCOUNT
+    // Treasury seeds
+    let treasury_signer_seed: &[&[_]] = &[&[
+        treasury.treasurer_address.as_ref(),
+        &treasury.slot.to_le_bytes(),
+        &treasury.bump.to_le_bytes(),
+    ]];
+    let close_cpi_accounts = CloseAccount {
+        account: treasury_token.to_account_info(),
+        destination: destination_authority.clone(),
+        authority: treasury.to_account_info().clone(),
+    };
+
+    let close_cpi_ctx = CpiContext::new_with_signer(
+        token_program.clone(),
+        close_cpi_accounts,
+        treasury_signer_seed,
+    );
+
+    close_account(close_cpi_ctx)?;
+
+    // sol fee
+    // this is done at the end to avoid pre-CPI imbalance check error
+    if treasury.sol_fee_payed_by_treasury {
+        // Since the treasury is being closed, there is no need to check if
+        // the treasury is rent exempt after transferring the fee amount.
+        // Also it can inconvenience users as they may have to fund the
+        // treasury with lamports in order to close it.
+        // Warning! We DO NEED this check in any other operation that
+        // transfers lamports out of the treasury.
+        let treasury_account_info = &treasury.to_account_info();
+
+        if fees.close_treasury_flat_fee > treasury_account_info.lamports() {
+            return Err(ErrorCode::InsufficientLamports.into());
+        }
+
+        **treasury_account_info.try_borrow_mut_lamports()? = treasury_account_info
+            .lamports()
+            .checked_sub(fees.close_treasury_flat_fee)
+            .ok_or(ErrorCode::Overflow)?;
+
+        **fee_treasury.try_borrow_mut_lamports()? = fee_treasury
+            .lamports()
+            .checked_add(fees.close_treasury_flat_fee)
+            .ok_or(ErrorCode::Overflow)?;
+    } else {
+        transfer_sol_amount(
+            payer,
+            fee_treasury,
+            system_program,
+            fees.close_treasury_flat_fee,
+        )?;
+    }
+
+    mean_emit!(CloseTreasuryEvent {
+        timestamp: now_ts,
+        sol_fee_charged: fees.close_treasury_flat_fee,
+        token_fee_charged: 0,
+        token_amount_sent_to_destination: treasury_token_amount_before,
+        treasury_is_sol_fee_payed_by_treasury: treasury.sol_fee_payed_by_treasury,
+        treasury: treasury.key(),
+    });
+
+    Ok(())
+}
+
+/// Sends up to `amount` withdrawable units from the stream to the given
+/// destination token account (minus fees) and updates the stream and treasury
+/// accounting
+pub fn stream_withdraw<'info>(
+    amount: u64,
+    schedule: Option<&StreamSchedule>,
+    stream: &mut Account<'info, Stream>,
+    treasury: &mut Account<'info, Treasury>,
+    vault: &mut TreasuryVault<'_, 'info>,
+    destination_token: &AccountInfo<'info>,
+    fee_treasury_token: &AccountInfo<'info>,
+    referrer: Option<&FeeReferrer<'_, 'info>>,
+    fees: &FeeSchedule,
+    fee_tier: u8,
+) -> Result<StreamWithdrawEvent> {
+    let clock = Clock::get()?;
+    let now_ts = clock.unix_timestamp as u64;
+
+    let start_utc_seconds = stream.get_start_utc()?;
+    if start_utc_seconds > now_ts {
+        return Err(ErrorCode::StreamIsScheduled.into());
+    }
+
+    let withdrawable_amount = match schedule {
+        Some(schedule) => stream.get_scheduled_withdrawable_amount(schedule, now_ts)?,
+        None => stream.get_beneficiary_withdrawable_amount(now_ts)?,
+    };
+
+    if withdrawable_amount == 0 {
+        return Err(ErrorCode::ZeroWithdrawalAmount.into());
+    }
+
+    let mut user_requested_amount = amount;
+
+    if user_requested_amount > withdrawable_amount {
+        user_requested_amount = withdrawable_amount;
+    }
+
+    let fee_amount = if stream.fee_payed_by_treasurer {
+        0u64
+    } else {
+        u64::try_from(
+            (fees.withdraw_percent_fee as u128)
+                .checked_mul(user_requested_amount as u128)
+                .ok_or(ErrorCode::Overflow)?
+                .checked_div(PERCENT_DENOMINATOR as u128)
+                .ok_or(ErrorCode::Overflow)?,
+        )
+        .unwrap()
+    };
+
+    let transfer_amount = if fee_amount == 0 {
+        user_requested_amount
+    } else {
+        user_requested_amount
+            .checked_sub(fee_amount)
+            .ok_or(ErrorCode::Overflow)?
+    };
+
+    // Transfer from treasury to beneficiary
+    vault.transfer(treasury, destination_token, transfer_amount)?;
+
+    // Transfer fee
+    if fee_amount > 0 {
+        vault.transfer_fee(treasury, fee_treasury_token, referrer, fee_amount, fees)?;
+    }
+
+    stream.last_withdrawal_slot = clock.slot;
+    stream.last_withdrawal_block_time = now_ts;
+    stream.last_withdrawal_units = user_requested_amount;
+    stream.total_withdrawals_units = stream
+        .total_withdrawals_units
+        .checked_add(user_requested_amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    let mut withdraw_event = StreamWithdrawEvent {
+        timestamp: now_ts,
+        sol_fee_charged: 0,
+        token_fee_charged: fee_amount,
+        fee_tier,
+        amount,
+        token_amount_sent_to_beneficiary: transfer_amount,
+        stream_withdrawable_before: withdrawable_amount,
+        stream_is_manually_paused: false,
+        stream_allocation_after: 0,
+        stream_total_withdrawals_after: stream.total_withdrawals_units,
+        stream_is_token_withdraw_fee_payed_by_treasury: stream.fee_payed_by_treasurer,
+        treasury_is_sol_fee_payed_by_treasury: treasury.sol_fee_payed_by_treasury,
+        treasury_allocation_after: 0,
+        treasury_balance_after: 0,
+        treasury_total_withdrawals_after: 0,
+        stream: stream.key(),
+        treasury: treasury.key(),
+        destination_token: destination_token.key(),
+    };
+
+    // if the stream was manually paused then deduct the user requested amount
+    // from the `last_manual_stop_withdrawable_units_snap` to update the
+    // beneficiary withdrawable amount
+    if stream.primitive_is_manually_paused() {
+        stream.last_manual_stop_withdrawable_units_snap = stream
+            .last_manual_stop_withdrawable_units_snap
+            .checked_sub(user_requested_amount)
+            .ok_or(ErrorCode::Overflow)?;
+            withdraw_event.stream_is_manually_paused = true;
+    }
+
+
+    // Update treasury data
+    assert!(
+        treasury.allocation_assigned_units >= user_requested_amount,
+        "treasury allocation_assigned vs withdraw amount invariant violated"
+    );
+    treasury.allocation_assigned_units = treasury
+        .allocation_assigned_units
+        .checked_sub(user_requested_amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    treasury.last_known_balance_slot = clock.slot as u64;
+    treasury.last_known_balance_block_time = now_ts;
+    treasury.last_known_balance_units = treasury
+        .last_known_balance_units
+        .checked_sub(user_requested_amount)
+        .ok_or(ErrorCode::Overflow)?;
+    treasury.total_withdrawals_units = treasury
+        .total_withdrawals_units
+        .checked_add(user_requested_amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    // invariants
+    assert!(
+        vault.balance(treasury)? >= treasury.last_known_balance_units,
+        "treasury balance units invariant violated"
+    );
+
+    withdraw_event.stream_allocation_after = stream.allocation_assigned_units;
+    withdraw_event.treasury_total_withdrawals_after = treasury.total_withdrawals_units;
+    withdraw_event.treasury_allocation_after = treasury.allocation_assigned_units;
+    withdraw_event.treasury_balance_after = treasury.last_known_balance_units;
+
+    Ok(withdraw_event)
+}
+
+/// Allocates `amount` units to the stream from the treasury unallocated
+/// balance (plus the withdraw fee if it is payed by the treasurer). If the
+/// stream was auto-paused because it ran out of funds, it is resumed
+pub fn stream_allocate<'info>(
+    amount: u64,
+    stream: &mut Account<'info, Stream>,
+    treasury: &mut Account<'info, Treasury>,
+    treasury_token: &mut Account<'info, TokenAccount>,
+    fee_treasury_token: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    referrer: Option<&FeeReferrer<'_, 'info>>,
+    fees: &FeeSchedule,
+    fee_tier: u8,
+) -> Result<StreamAllocateEvent> {
+    let clock = Clock::get()?;
+    let now_ts = clock.unix_timestamp as u64;
+    let now_slot = clock.slot;
+
+    let fee_amount = if stream.fee_payed_by_treasurer {
+        u64::try_from(
+            (fees.withdraw_percent_fee as u128)
+                .checked_mul(amount as u128)
+                .ok_or(ErrorCode::Overflow)?
+                .checked_div(PERCENT_DENOMINATOR as u128)
+                .ok_or(ErrorCode::Overflow)?,
+        )
+        .unwrap()
+    } else {
+        0_u64
+    };
+
+    let funding_amount = amount.checked_add(fee_amount).ok_or(ErrorCode::Overflow)?;
+
+    // Added in case we decide not to throw error on inssuficient treasury balance
+    // if funding_amount > treasury.last_known_unallocated_balance()? {
+    //     let stream_max_allocation = treasury.last_known_unallocated_balance()?
+    //         .checked_mul(PERCENT_DENOMINATOR).unwrap()
+    //         .checked_div(
+    //             WITHDRAW_PERCENT_FEE.checked_add(PERCENT_DENOMINATOR).unwrap()
+    //         ).unwrap();
+    //     fee_amount = stream_max_allocation
+    //         .checked_mul(WITHDRAW_PERCENT_FEE).unwrap()
+    //         .checked_div(PERCENT_DENOMINATOR).unwrap();
+
+    //     funding_amount = treasury.last_known_unallocated_balance()?
+    //         .checked_sub(fee_amount).unwrap();
+    // }
+
+    if funding_amount > treasury.last_known_unallocated_balance()? {
+        return Err(ErrorCode::InsufficientTreasuryBalance.into());
+    }
+
+    if fee_amount > 0 {
+        // Transfer fee from Treasury
+        TreasuryVault::Token {
+            treasury_token,
+            token_program: token_program.clone(),
+        }
+        .transfer_fee(treasury, fee_treasury_token, referrer, fee_amount, fees)?;
+    }
+
+    // update stream
+    let status = stream.get_status(now_ts)?;
+    let is_manual_pause = stream.primitive_is_manually_paused();
+
+    let mut allocate_event = StreamAllocateEvent {
+        timestamp: now_ts,
+        sol_fee_charged: 0,
+        token_fee_charged: fee_amount,
+        fee_tier,
+        amount,
+        stream_status_before: status as u32,
+        stream_was_manually_paused_before: is_manual_pause,
+        stream_last_auto_stop_block_time: 0,
+        stream_total_seconds_in_paused_status_after: 0,
+        stream_is_token_withdraw_fee_payed_by_treasury: stream.fee_payed_by_treasurer,
+        stream_allocation_after: 0,
+        treasury_is_sol_fee_payed_by_treasury: treasury.sol_fee_payed_by_treasury,
+        treasury_allocation_after: 0,
+        treasury_balance_after: 0,
+        stream: stream.key(),
+        treasury: treasury.key(),
+    };
+
+    if status == StreamStatus::Paused && !is_manual_pause {
+        let est_depletion_time = stream.get_est_depletion_blocktime(now_ts)?;
+        let remaining_allocation = stream.get_remaining_allocation()?;
+
+        // record the moment the stream stopped for running out of money
+        stream.last_auto_stop_block_time = est_depletion_time;
+
+        let seconds_paused_since_last_auto_stop = now_ts
+            .checked_sub(stream.last_auto_stop_block_time)
+            .ok_or(ErrorCode::Overflow)?;
+
+        // SecondsPaused += S3
+        stream.last_known_total_seconds_in_paused_status = stream
+            .last_known_total_seconds_in_paused_status
+            .checked_add(seconds_paused_since_last_auto_stop)
+            .ok_or(ErrorCode::Overflow)?;
+
+        // Update stream data (Resume the stream)
+        stream.last_manual_resume_remaining_allocation_units_snap = remaining_allocation;
+        stream.last_manual_resume_slot = now_slot;
+        stream.last_manual_resume_block_time = now_ts;
+
+        #[cfg(feature = "test")]
+        msg!("allocate status: auto-paused, est_depletion_time: {0}, remaining_allocation: {1}, last_auto_stop_block_time: {2}, seconds_paused_since_last_auto_stop: {3}, last_known_total_seconds_in_paused_status: {4}",
+            est_depletion_time, remaining_allocation, stream.last_auto_stop_block_time, seconds_paused_since_last_auto_stop, stream.last_known_total_seconds_in_paused_status);
+
+        allocate_event.stream_last_auto_stop_block_time = stream.last_auto_stop_block_time;
+        allocate_event.stream_total_seconds_in_paused_status_after = stream.last_known_total_seconds_in_paused_status;
+    }
+
+    stream.allocation_assigned_units = stream
+        .allocation_assigned_units
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+
+    // update treasury
+    treasury.allocation_assigned_units = treasury
+        .allocation_assigned_units
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    treasury.last_known_balance_slot = now_slot;
+    treasury.last_known_balance_block_time = now_ts;
+    treasury.last_known_balance_units = treasury
+        .last_known_balance_units
+        .checked_sub(fee_amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    treasury_token.reload()?;
+    assert!(
+        treasury_token.amount >= treasury.last_known_balance_units,
+        "treasury balance units invariant violated"
+    );
+
+    allocate_event.stream_allocation_after = stream.allocation_assigned_units;
+    allocate_event.treasury_allocation_after = treasury.allocation_assigned_units;
+    allocate_event.treasury_balance_after = treasury.last_known_balance_units;
+
+    Ok(allocate_event)
+}
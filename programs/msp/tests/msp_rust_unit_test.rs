@@ -6,12 +6,15 @@ use std::println;
 use {
     anchor_client::{
         solana_sdk::{
-            account::Account,
+            account::{Account, AccountSharedData},
             commitment_config::CommitmentConfig,
+            instruction::{Instruction, InstructionError},
             pubkey::Pubkey,
             signature::{Keypair, Signer},
+            system_instruction,
             sysvar::rent,
-            transaction::Transaction,
+            transaction::{Transaction, TransactionError},
+            transport::TransportError,
         },
         Client, Cluster,
     },
@@ -26,16 +29,37 @@ use anchor_spl::associated_token;
 use anchor_spl::token;
 use msp::{
     enums::TreasuryType,
-    instruction::{AddFunds, Allocate, CreateStream, CreateTreasury},
+    errors::ErrorCode,
+    constants::{
+        STREAM_V2_EXTENSIONS_OFFSET, STREAM_V2_SIZE, STREAM_V3_SIZE, TREASURY_V2_EXTENSIONS_OFFSET,
+        TREASURY_V2_SIZE, TREASURY_V3_SIZE,
+    },
+    config::{Config, FeeOverride, FeeSchedule},
+    instruction::{
+        AcceptStreamTransfer, AcceptTreasuryAuthority, AddFunds, AddFundsNative, Allocate,
+        AllocateToAllStreams, CancelStreamTransfer, CloseStream, CloseTreasuryAndStreams,
+        CloseTreasuryNative, CreateNativeTreasury, CreateStream, CreateStreamNative,
+        CreateStreamWithSchedule, CreateStreamsBatch, CreateTreasury, InitConfig, MigrateStream,
+        MigrateTreasury, ProposeStreamTransfer, ProposeTreasuryAuthority, PushWithdraw,
+        RefreshTreasuryData, RemoveFeeOverride, RenounceStream, SetFeeOverride,
+        TreasuryWithdrawNative, UpdateConfig, UpdateStreamRate, Withdraw, WithdrawMany,
+        WithdrawToDestination,
+    },
     instructions::fee_treasury,
-    stream::Stream,
+    schedule::{SchedulePoint, StreamSchedule},
+    stream::{Stream, StreamBatchEntry},
+    tlv::{
+        get_data_extension, get_extension, get_extensions_len, get_extensions_offset,
+        get_required_region_len, remove_extension, set_extension, ExtensionType,
+        EXTENSION_HEADER_SIZE,
+    },
+    treasury::{NativeSolTreasury, Treasury, TreasuryReferrer},
     categories::Category,
     categories::SubCategory,
 };
 use solana_program::program_pack::Pack;
 use solana_program_test::ProgramTestContext;
 use spl_token::native_mint;
-use std::convert::TryInto;
 
 #[tokio::test]
 async fn create_treasury_test_1() {
@@ -75,6 +99,7 @@ async fn create_treasury_test_1() {
             sol_fee_payed_by_treasury: true,
             category: Category::Default,
             sub_category: SubCategory::Default,
+            referrer: None,
         },
         &treasurer,
         &treasury_pubkey,
@@ -114,6 +139,8 @@ async fn create_treasury_test_1() {
             cliff_vest_amount_units: 0,
             cliff_vest_percent: 0,
             fee_payed_by_treasurer: true,
+            accrual_mode: msp::constants::STREAM_ACCRUAL_MODE_LINEAR,
+            cliff_utc: 0,
         },
         &treasurer,
         &treasury_pubkey,
@@ -128,7 +155,7 @@ async fn create_treasury_test_1() {
         "stream:\nversion: {}, treasurer: {}, start_ts: {}",
         stream.version, stream.treasurer_address, stream.start_utc
     );
-    assert_eq!(stream.version, 2, "incorrect version");
+    assert_eq!(stream.version, 3, "incorrect version");
     assert_eq!(stream.rate_amount_units, 5, "incorrect rate amount");
     assert_eq!(
         stream.rate_interval_in_seconds, 2,
@@ -297,6 +324,7 @@ async fn create_treasury_test_2() {
             sol_fee_payed_by_treasury: true,
             category: Category::Default,
             sub_category: SubCategory::Default,
+            referrer: None,
         },
         &treasurer,
         &treasury_pubkey,
@@ -336,6 +364,8 @@ async fn create_treasury_test_2() {
             cliff_vest_amount_units: 0,
             cliff_vest_percent: 0,
             fee_payed_by_treasurer: true,
+            accrual_mode: msp::constants::STREAM_ACCRUAL_MODE_LINEAR,
+            cliff_utc: 0,
         },
         &treasurer,
         &treasury_pubkey,
@@ -350,7 +380,7 @@ async fn create_treasury_test_2() {
         "stream:\nversion: {}, treasurer: {}, start_ts: {}",
         stream.version, stream.treasurer_address, stream.start_utc
     );
-    assert_eq!(stream.version, 2, "incorrect version");
+    assert_eq!(stream.version, 3, "incorrect version");
     assert_eq!(stream.rate_amount_units, 5, "incorrect rate amount");
     assert_eq!(
         stream.rate_interval_in_seconds, 2,
@@ -534,6 +564,7 @@ async fn create_treasury_test_3() {
             sol_fee_payed_by_treasury: true,
             category: Category::Default,
             sub_category: SubCategory::Default,
+            referrer: None,
         },
         &treasurer,
         &treasury_pubkey,
@@ -573,6 +604,8 @@ async fn create_treasury_test_3() {
             cliff_vest_amount_units: 0,
             cliff_vest_percent: 0,
             fee_payed_by_treasurer: true,
+            accrual_mode: msp::constants::STREAM_ACCRUAL_MODE_LINEAR,
+            cliff_utc: 0,
         },
         &treasurer,
         &treasury_pubkey,
@@ -587,7 +620,7 @@ async fn create_treasury_test_3() {
         "stream:\nversion: {}, treasurer: {}, start_ts: {}",
         stream.version, stream.treasurer_address, stream.start_utc
     );
-    assert_eq!(stream.version, 2, "incorrect version");
+    assert_eq!(stream.version, 3, "incorrect version");
     assert_eq!(stream.rate_amount_units, 4, "incorrect rate amount");
     assert_eq!(
         stream.rate_interval_in_seconds, 2,
@@ -732,283 +765,3826 @@ async fn create_treasury_test_3() {
     assert_eq!(status, msp::enums::StreamStatus::Paused, "incorrect status");
 }
 
-async fn create_treasury(
-    context: &mut ProgramTestContext,
-    program: &anchor_client::Program,
-    parameters: CreateTreasury,
-    treasurer: &Keypair,
-    treasury_pubkey: &Pubkey,
-    treasury_token_account: &Pubkey,
-    treasury_token_mint: &Pubkey,
-    fee_pubkey: &Pubkey,
-) {
-    let create_treasury_ix = program
-        .request()
-        .accounts(msp::accounts::CreateTreasuryAccounts {
-            payer: context.payer.pubkey(),
-            treasurer: treasurer.pubkey(),
-            treasury: treasury_pubkey.key(),
-            treasury_token: treasury_token_account.clone(),
-            associated_token: treasury_token_mint.clone(),
-            fee_treasury: fee_pubkey.key(),
-            associated_token_program: associated_token::ID,
-            token_program: token::ID,
-            system_program: system_program::ID,
-            rent: rent::ID,
-        })
-        .args(parameters)
-        .instructions()
-        .unwrap()
-        .pop()
-        .unwrap();
+#[tokio::test]
+async fn create_stream_step_accrual_test() {
+    let mut pt = ProgramTest::new("msp", msp::id(), None);
+    let (treasurer, treasurer_account) = user_account();
+    let (beneficiary, _) = user_account();
 
-    let create_treasury_tx = Transaction::new_signed_with_payer(
-        &[create_treasury_ix],
-        Some(&context.payer.pubkey()),
-        &[&context.payer, &treasurer],
-        context.last_blockhash,
+    let slot = 0_u64;
+    let (treasury_pubkey,treasury_token_mint,treasury_token_account) = treasury_accounts(treasurer.pubkey(), slot);
+    let (treasurer_token, treasurer_token_account) = treasurer_accounts(treasury_token_mint, treasurer.pubkey());
+    let (fee_pubkey, fee_account, fees_token) = fee(&treasury_token_mint);
+
+    pt.add_account(fee_pubkey, fee_account);
+    pt.add_account(treasurer.pubkey(), treasurer_account);
+    pt.add_account(treasurer_token, treasurer_token_account);
+    pt.set_compute_max_units(200_000); // maximum number of instructions allowed
+
+    let mut context = pt.start_with_context().await;
+
+    let client = Client::new_with_options(
+        Cluster::Debug,
+        Rc::new(Keypair::new()),
+        CommitmentConfig::processed(),
     );
+    let program = client.program(msp::id());
 
-    context
-        .banks_client
-        .process_transaction(create_treasury_tx)
-        .await
-        .unwrap();
+    create_treasury(
+        &mut context,
+        &program,
+        CreateTreasury {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            slot,
+            name: "test treasury".to_string(),
+            treasury_type: TreasuryType::Opened as u8,
+            auto_close: false,
+            sol_fee_payed_by_treasury: true,
+            category: Category::Default,
+            sub_category: SubCategory::Default,
+            referrer: None,
+        },
+        &treasurer,
+        &treasury_pubkey,
+        &treasury_token_account,
+        &treasury_token_mint,
+        &fee_pubkey,
+    )
+    .await;
+
+    add_funds(
+        &mut context,
+        &program,
+        AddFunds {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            amount: 1000,
+        },
+        &treasurer,
+        &treasurer_token,
+        &treasury_pubkey,
+        &treasury_token_mint,
+        &treasury_token_account,
+        &fee_pubkey,
+        &fees_token,
+    )
+    .await;
+
+    let (stream, _) = create_stream(
+        &mut context,
+        &program,
+        CreateStream {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            name: "test step stream".to_string(),
+            start_utc: 0,
+            rate_amount_units: 5,
+            rate_interval_in_seconds: 2,
+            allocation_assigned_units: 12,
+            cliff_vest_amount_units: 0,
+            cliff_vest_percent: 0,
+            fee_payed_by_treasurer: true,
+            accrual_mode: msp::constants::STREAM_ACCRUAL_MODE_STEP,
+            cliff_utc: 0,
+        },
+        &treasurer,
+        &treasury_pubkey,
+        &treasury_token_account,
+        &treasury_token_mint,
+        &beneficiary.pubkey(),
+        &fee_pubkey,
+        &fees_token,
+    )
+    .await;
+    assert_eq!(
+        stream.accrual_mode,
+        msp::constants::STREAM_ACCRUAL_MODE_STEP,
+        "incorrect accrual mode"
+    );
+
+    // (seconds since start, expected withdrawable, expected status)
+    let expectations = [
+        (0, 0, msp::enums::StreamStatus::Running),
+        (1, 0, msp::enums::StreamStatus::Running),
+        (2, 5, msp::enums::StreamStatus::Running),
+        (3, 5, msp::enums::StreamStatus::Running),
+        (4, 10, msp::enums::StreamStatus::Running),
+        (5, 10, msp::enums::StreamStatus::Running),
+        // the last (partial) tranche unlocks at the end of its interval
+        (6, 12, msp::enums::StreamStatus::Paused),
+        (7, 12, msp::enums::StreamStatus::Paused),
+    ];
+    for (seconds_since_start, expected_withdrawable, expected_status) in expectations {
+        let timestamp = stream.start_utc.checked_add(seconds_since_start).unwrap();
+        let withdrawable = stream
+            .get_beneficiary_withdrawable_amount(timestamp)
+            .unwrap();
+        assert_eq!(
+            withdrawable, expected_withdrawable,
+            "incorrect withdrawable amount at +{}",
+            seconds_since_start
+        );
+        let status = stream.get_status(timestamp).unwrap();
+        assert_eq!(
+            status, expected_status,
+            "incorrect status at +{}",
+            seconds_since_start
+        );
+    }
 }
 
-async fn create_stream(
-    context: &mut ProgramTestContext,
-    program: &anchor_client::Program,
-    parameters: CreateStream,
-    treasurer: &Keypair,
-    treasury_pubkey: &Pubkey,
-    treasury_token_account: &Pubkey,
-    treasury_token_mint: &Pubkey,
-    beneficiary_pubkey: &Pubkey,
-    fee_pubkey: &Pubkey,
-    fees_token: &Pubkey,
-) -> (Stream, Keypair) {
+#[tokio::test]
+async fn update_stream_rate_step_accrual_test() {
+    let (beneficiary, _) = user_account();
+    let mut fixture = treasury_fixture(vec![], 1000).await;
+    let mut clock = fixture.context.banks_client.get_sysvar::<Clock>().await.unwrap();
+
+    let (stream, stream_key) = create_stream(
+        &mut fixture.context,
+        &fixture.program,
+        CreateStream {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            name: "test step stream".to_string(),
+            start_utc: 0,
+            rate_amount_units: 5,
+            rate_interval_in_seconds: 2,
+            allocation_assigned_units: 40,
+            cliff_vest_amount_units: 0,
+            cliff_vest_percent: 0,
+            fee_payed_by_treasurer: true,
+            accrual_mode: msp::constants::STREAM_ACCRUAL_MODE_STEP,
+            cliff_utc: 0,
+        },
+        &fixture.treasurer,
+        &fixture.treasury_pubkey,
+        &fixture.treasury_token_account,
+        &fixture.treasury_token_mint,
+        &beneficiary.pubkey(),
+        &fixture.fee_pubkey,
+        &fixture.fees_token,
+    )
+    .await;
+    let start_ts = stream.get_start_utc().unwrap();
+
+    // fast forward +3, one interval and half of the next one
+    clock.unix_timestamp = (start_ts as i64).checked_add(3).unwrap();
+    fixture.context.set_sysvar(&clock);
+
+    let update_stream_rate_ix =
+        update_stream_rate_ix(&fixture, &stream_key.pubkey(), 10, 2);
+    process_instructions(
+        &mut fixture.context,
+        &[update_stream_rate_ix],
+        &[&fixture.treasurer],
+    )
+    .await
+    .unwrap();
+
+    // the first interval is checkpointed and the second one keeps the second
+    // already streamed
+    let stream = fetch_stream(&fixture.context, stream_key.pubkey()).await;
+    assert_eq!(stream.cliff_vest_amount_units, 5, "incorrect checkpoint");
+    assert_eq!(stream.get_start_utc().unwrap(), start_ts + 2, "incorrect restart");
+    assert_eq!(stream.rate_amount_units, 10, "incorrect rate amount");
+
+    // (seconds since the original start, expected withdrawable)
+    let expectations = [(3, 5), (4, 15), (5, 15), (6, 25)];
+    for (seconds_since_start, expected_withdrawable) in expectations {
+        let withdrawable = stream
+            .get_beneficiary_withdrawable_amount(start_ts + seconds_since_start)
+            .unwrap();
+        assert_eq!(
+            withdrawable, expected_withdrawable,
+            "incorrect withdrawable amount at +{}",
+            seconds_since_start
+        );
+    }
+}
+
+#[tokio::test]
+async fn update_stream_rate_rejects_scheduled_stream_test() {
+    let (beneficiary, _) = user_account();
+    let mut fixture = treasury_fixture(vec![], 1000).await;
+    let clock = fixture.context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let now_ts = clock.unix_timestamp as u64;
+
     let stream_key = Keypair::new();
-    let create_stream_ix = program
-        .request()
-        .accounts(msp::accounts::CreateStreamAccounts {
-            payer: context.payer.pubkey(),
-            treasurer: treasurer.pubkey(),
-            treasury: treasury_pubkey.key(),
-            treasury_token: treasury_token_account.clone(),
-            associated_token: treasury_token_mint.clone(),
-            beneficiary: beneficiary_pubkey.key(),
-            stream: stream_key.pubkey(),
-            fee_treasury: fee_pubkey.key(),
-            fee_treasury_token: fees_token.clone(),
-            associated_token_program: associated_token::ID,
-            token_program: token::ID,
-            system_program: system_program::ID,
-            rent: rent::ID,
-        })
-        .args(parameters)
-        .instructions()
-        .unwrap()
-        .pop()
-        .unwrap();
+    let instruction = create_stream_with_schedule_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        50,
+        vec![SchedulePoint {
+            timestamp: now_ts + 10,
+            cumulative_units: 50,
+        }],
+    );
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer, &stream_key],
+    )
+    .await
+    .unwrap();
 
-    let crate_stream_tx = Transaction::new_signed_with_payer(
-        &[create_stream_ix],
-        Some(&context.payer.pubkey()),
-        &[&context.payer, &treasurer, &stream_key],
-        context.last_blockhash,
+    // the schedule defines the unlocked units, there is no rate to update
+    let update_stream_rate_ix =
+        update_stream_rate_ix(&fixture, &stream_key.pubkey(), 10, 2);
+    let result = process_instructions(
+        &mut fixture.context,
+        &[update_stream_rate_ix],
+        &[&fixture.treasurer],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::NotAllowedOnScheduledStream);
+}
+
+#[tokio::test]
+async fn push_withdraw_cranker_reward_test() {
+    let (beneficiary, beneficiary_account) = user_account();
+    let mut fixture = treasury_fixture(
+        vec![(beneficiary.pubkey(), beneficiary_account)],
+        1000,
+    )
+    .await;
+    let mut clock = fixture.context.banks_client.get_sysvar::<Clock>().await.unwrap();
+
+    let (_, stream_key) = create_stream(
+        &mut fixture.context,
+        &fixture.program,
+        CreateStream {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            name: "test stream".to_string(),
+            start_utc: 0,
+            rate_amount_units: 10,
+            rate_interval_in_seconds: 3_600,
+            allocation_assigned_units: 500,
+            cliff_vest_amount_units: 0,
+            cliff_vest_percent: 0,
+            fee_payed_by_treasurer: true,
+            accrual_mode: msp::constants::STREAM_ACCRUAL_MODE_LINEAR,
+            cliff_utc: 0,
+        },
+        &fixture.treasurer,
+        &fixture.treasury_pubkey,
+        &fixture.treasury_token_account,
+        &fixture.treasury_token_mint,
+        &beneficiary.pubkey(),
+        &fixture.fee_pubkey,
+        &fixture.fees_token,
+    )
+    .await;
+
+    // the treasury pays the SOL fees, so it needs lamports for the reward
+    let fund_treasury_ix = system_instruction::transfer(
+        &fixture.context.payer.pubkey(),
+        &fixture.treasury_pubkey,
+        1_000_000_000,
     );
-    context
-        .banks_client
-        .process_transaction(crate_stream_tx)
+    process_instructions(&mut fixture.context, &[fund_treasury_ix], &[])
         .await
         .unwrap();
 
-    let stream = fetch_stream(context, stream_key.pubkey()).await;
-    (stream, stream_key)
+    // (seconds to fast forward, expected total withdrawals, rewarded)
+    let expectations = [
+        (3_600, 10, true),
+        // withdrawn an hour ago, the cranker is not rewarded
+        (3_600, 20, false),
+        (msp::constants::PUSH_WITHDRAW_CRANKER_REWARD_MIN_INTERVAL, 260, true),
+    ];
+    for (seconds, expected_total_withdrawals, rewarded) in expectations {
+        clock.unix_timestamp = clock.unix_timestamp.checked_add(seconds as i64).unwrap();
+        fixture.context.set_sysvar(&clock);
+
+        let treasury_lamports_before =
+            fetch_lamports(&fixture.context, fixture.treasury_pubkey).await;
+        let push_withdraw_ix =
+            push_withdraw_ix(&fixture, &stream_key.pubkey(), &beneficiary.pubkey());
+        process_instructions(&mut fixture.context, &[push_withdraw_ix], &[])
+            .await
+            .unwrap();
+        let treasury_lamports_after =
+            fetch_lamports(&fixture.context, fixture.treasury_pubkey).await;
+
+        let stream = fetch_stream(&fixture.context, stream_key.pubkey()).await;
+        assert_eq!(
+            stream.total_withdrawals_units, expected_total_withdrawals,
+            "incorrect withdrawals"
+        );
+        let expected_reward = if rewarded {
+            msp::constants::PUSH_WITHDRAW_CRANKER_REWARD
+        } else {
+            0
+        };
+        assert_eq!(
+            treasury_lamports_before - treasury_lamports_after,
+            expected_reward,
+            "incorrect cranker reward"
+        );
+    }
 }
 
-async fn allocate(
-    context: &mut ProgramTestContext,
-    program: &anchor_client::Program,
-    parameters: Allocate,
-    treasurer: &Keypair,
-    treasury_pubkey: &Pubkey,
-    stream_pubkey: Pubkey,
-    treasury_token_account: &Pubkey,
-    treasury_token_mint: &Pubkey,
-    fee_pubkey: &Pubkey,
-    fees_token: &Pubkey,
-) {
-    let allocate_ix = program
-        .request()
-        .accounts(msp::accounts::AllocateAccounts {
-            payer: context.payer.pubkey(),
-            treasurer: treasurer.pubkey(),
-            treasury: treasury_pubkey.key(),
-            treasury_token: treasury_token_account.key(),
-            associated_token: treasury_token_mint.key(),
-            stream: stream_pubkey.key(),
-            fee_treasury: fee_pubkey.key(),
-            fee_treasury_token: fees_token.key(),
-            associated_token_program: associated_token::ID,
-            token_program: token::ID,
-            system_program: system_program::ID,
-            rent: rent::ID,
+#[tokio::test]
+async fn create_streams_batch_test() {
+    let mut fixture = treasury_fixture(vec![], 1000).await;
+    let entries: Vec<StreamBatchEntry> = [100, 200]
+        .iter()
+        .map(|allocation| StreamBatchEntry {
+            beneficiary: Pubkey::new_unique(),
+            stream_pda_seed: Pubkey::new_unique(),
+            allocation_assigned_units: *allocation,
+            rate_amount_units: 10,
+            rate_interval_in_seconds: 60,
+            cliff_vest_amount_units: 0,
         })
-        .args(parameters)
-        .instructions()
-        .unwrap()
-        .pop()
-        .unwrap();
+        .collect();
+    let stream_pubkeys: Vec<Pubkey> = entries
+        .iter()
+        .map(|entry| stream_pda_pubkey(&fixture.treasury_pubkey, &entry.stream_pda_seed))
+        .collect();
 
-    let allocate_tx = Transaction::new_signed_with_payer(
-        &[allocate_ix],
-        Some(&context.payer.pubkey()),
-        &[&context.payer, &treasurer],
-        context.last_blockhash,
+    // one stream account per entry
+    let instruction = create_streams_batch_ix(&fixture, entries.clone(), &stream_pubkeys[..1]);
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::InvalidStreamAccounts);
+
+    // lamports sent to a stream PDA before it's created don't block it
+    fixture.context.set_account(
+        &stream_pubkeys[1],
+        &AccountSharedData::from(Account {
+            lamports: 1,
+            owner: system_program::ID,
+            ..Account::default()
+        }),
     );
-    context
-        .banks_client
-        .process_transaction(allocate_tx)
+    let instruction = create_streams_batch_ix(&fixture, entries.clone(), &stream_pubkeys);
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer],
+    )
+    .await
+    .unwrap();
+
+    for (entry, stream_pubkey) in entries.iter().zip(stream_pubkeys.iter()) {
+        let stream = fetch_stream(&fixture.context, *stream_pubkey).await;
+        assert_eq!(stream.beneficiary_address, entry.beneficiary, "incorrect beneficiary");
+        assert_eq!(
+            stream.allocation_assigned_units, entry.allocation_assigned_units,
+            "incorrect stream allocation"
+        );
+    }
+    let treasury: Treasury = fetch_account(&fixture.context, fixture.treasury_pubkey).await;
+    assert_eq!(treasury.total_streams, 2, "incorrect total streams");
+    assert_eq!(treasury.allocation_assigned_units, 300, "incorrect treasury allocation");
+}
+
+#[tokio::test]
+async fn migrate_v2_stream_and_treasury_test() {
+    let (beneficiary, _) = user_account();
+    let mut fixture = treasury_fixture(vec![], 1000).await;
+
+    let (stream, stream_key) = create_stream(
+        &mut fixture.context,
+        &fixture.program,
+        CreateStream {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            name: "test v2 stream".to_string(),
+            start_utc: 0,
+            rate_amount_units: 5,
+            rate_interval_in_seconds: 60,
+            allocation_assigned_units: 100,
+            cliff_vest_amount_units: 0,
+            cliff_vest_percent: 0,
+            fee_payed_by_treasurer: true,
+            accrual_mode: 0,
+            cliff_utc: 0,
+        },
+        &fixture.treasurer,
+        &fixture.treasury_pubkey,
+        &fixture.treasury_token_account,
+        &fixture.treasury_token_mint,
+        &beneficiary.pubkey(),
+        &fixture.fee_pubkey,
+        &fixture.fees_token,
+    )
+    .await;
+
+    // new accounts are created with the v3 layout
+    let treasury: Treasury = fetch_account(&fixture.context, fixture.treasury_pubkey).await;
+    assert_eq!(treasury.version, 3, "incorrect treasury version");
+    let instruction = migrate_treasury_ix(&fixture);
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::InvalidTreasuryVersion);
+
+    // rewrite both accounts with the v2 layout and a 10% cliff
+    let mut v2_stream = stream.clone();
+    v2_stream.version = 2;
+    let mut stream_data = Vec::new();
+    v2_stream.try_serialize(&mut stream_data).unwrap();
+    stream_data[98..106].copy_from_slice(&0u64.to_le_bytes()); // cliff_vest_amount_units
+    stream_data[106..114].copy_from_slice(&100_000u64.to_le_bytes()); // cliff_vest_percent
+    set_program_account(&mut fixture.context, &stream_key.pubkey(), stream_data, STREAM_V2_SIZE)
+        .await;
+
+    let mut v2_treasury = treasury.clone();
+    v2_treasury.version = 2;
+    let mut treasury_data = Vec::new();
+    v2_treasury.try_serialize(&mut treasury_data).unwrap();
+    treasury_data[115..147].copy_from_slice(fixture.treasury_token_mint.as_ref()); // mint_address
+    treasury_data[151..159].copy_from_slice(&0u64.to_le_bytes()); // last_known_balance_units
+    treasury_data[183..191].copy_from_slice(&7u64.to_le_bytes()); // allocation_reserved_units
+    set_program_account(
+        &mut fixture.context,
+        &fixture.treasury_pubkey,
+        treasury_data,
+        TREASURY_V2_SIZE,
+    )
+    .await;
+
+    // the deprecated v2 fields are written back as they were read
+    let instruction = refresh_treasury_data_ix(&fixture);
+    process_instructions(&mut fixture.context, &[instruction], &[])
         .await
         .unwrap();
+    let mut bank_copy = fixture.context.banks_client.clone();
+    let treasury_account = bank_copy.get_account(fixture.treasury_pubkey).await.unwrap().unwrap();
+    assert_eq!(&treasury_account.data[115..147], fixture.treasury_token_mint.as_ref());
+    assert_eq!(&treasury_account.data[183..191], &7u64.to_le_bytes());
+    let treasury: Treasury = fetch_account(&fixture.context, fixture.treasury_pubkey).await;
+    assert_eq!(treasury.version, 2, "incorrect treasury version");
+    assert_eq!(treasury.last_known_balance_units, 1000, "incorrect treasury balance");
+
+    // v2 accounts are still accepted
+    let stream = fetch_stream_v2(&fixture.context, stream_key.pubkey()).await;
+    assert_eq!(stream.version, 2, "incorrect stream version");
+    assert_eq!(stream.cliff_vest_amount_units, 10, "incorrect v2 cliff");
+
+    let treasurer_lamports = fetch_lamports(&fixture.context, fixture.treasurer.pubkey()).await;
+    let instruction = migrate_stream_ix(&fixture, &stream_key.pubkey());
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer],
+    )
+    .await
+    .unwrap();
+
+    let mut bank_copy = fixture.context.banks_client.clone();
+    let stream_account = bank_copy.get_account(stream_key.pubkey()).await.unwrap().unwrap();
+    let rent = bank_copy.get_rent().await.unwrap();
+    assert_eq!(stream_account.data.len(), STREAM_V3_SIZE, "incorrect stream size");
+    assert_eq!(
+        stream_account.lamports,
+        rent.minimum_balance(STREAM_V3_SIZE),
+        "incorrect stream rent"
+    );
+    assert!(
+        fetch_lamports(&fixture.context, fixture.treasurer.pubkey()).await > treasurer_lamports,
+        "rent not refunded"
+    );
+    let stream = fetch_stream(&fixture.context, stream_key.pubkey()).await;
+    assert_eq!(stream.version, 3, "incorrect stream version");
+    assert_eq!(stream.cliff_vest_amount_units, 10, "incorrect v3 cliff");
+    assert_eq!(stream.allocation_assigned_units, 100, "incorrect allocation");
+
+    let instruction = migrate_stream_ix(&fixture, &stream_key.pubkey());
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::InvalidStreamVersion);
+
+    let instruction = migrate_treasury_ix(&fixture);
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer],
+    )
+    .await
+    .unwrap();
+    let mut bank_copy = fixture.context.banks_client.clone();
+    let treasury_account = bank_copy.get_account(fixture.treasury_pubkey).await.unwrap().unwrap();
+    assert_eq!(treasury_account.data.len(), TREASURY_V3_SIZE, "incorrect treasury size");
+    let treasury: Treasury = fetch_account(&fixture.context, fixture.treasury_pubkey).await;
+    assert_eq!(treasury.version, 3, "incorrect treasury version");
+    assert_eq!(treasury.allocation_assigned_units, 100, "incorrect treasury allocation");
+    assert_eq!(treasury.authority_address, fixture.treasurer.pubkey(), "incorrect authority");
 }
 
-async fn add_funds(
-    context: &mut ProgramTestContext,
-    program: &anchor_client::Program,
-    parameters: AddFunds,
-    treasurer: &Keypair,
-    treasurer_token: &Pubkey,
-    treasury_pubkey: &Pubkey,
-    treasury_token_mint: &Pubkey,
-    treasury_token_account: &Pubkey,
-    fee_pubkey: &Pubkey,
-    fees_token: &Pubkey,
-) {
-    let add_funds_to_treasury_ix = program
+#[tokio::test]
+async fn native_treasury_withdraw_and_close_test() {
+    let mut fixture = treasury_fixture(vec![], 1000).await;
+    let treasury_pubkey = native_treasury(&mut fixture, 1, 2_000_000_000).await;
+    let treasurer_pubkey = fixture.treasurer.pubkey();
+
+    // only the treasury authority can withdraw
+    let other_treasurer = Keypair::new();
+    let mut ix = treasury_withdraw_native_ix(&fixture, &treasury_pubkey, &treasurer_pubkey, 1_000);
+    ix.accounts[0].pubkey = other_treasurer.pubkey();
+    let result = process_instructions(&mut fixture.context, &[ix], &[&other_treasurer]).await;
+    assert_custom_error(result, ErrorCode::InvalidTreasurer);
+
+    let ix =
+        treasury_withdraw_native_ix(&fixture, &treasury_pubkey, &treasurer_pubkey, 2_000_000_001);
+    let result = process_instructions(&mut fixture.context, &[ix], &[&fixture.treasurer]).await;
+    assert_custom_error(result, ErrorCode::InsufficientTreasuryBalance);
+
+    let treasurer_lamports = fetch_lamports(&fixture.context, treasurer_pubkey).await;
+    let fee_lamports = fetch_lamports(&fixture.context, fixture.fee_pubkey).await;
+    let ix =
+        treasury_withdraw_native_ix(&fixture, &treasury_pubkey, &treasurer_pubkey, 1_000_000_000);
+    process_instructions(&mut fixture.context, &[ix], &[&fixture.treasurer])
+        .await
+        .unwrap();
+
+    let fee_amount = 1_000_000_000 * msp::constants::TREASURY_WITHDRAW_PERCENT_FEE
+        / msp::constants::PERCENT_DENOMINATOR;
+    assert_eq!(
+        fetch_lamports(&fixture.context, treasurer_pubkey).await - treasurer_lamports,
+        1_000_000_000 - fee_amount,
+        "incorrect destination amount"
+    );
+    assert_eq!(
+        fetch_lamports(&fixture.context, fixture.fee_pubkey).await - fee_lamports,
+        fee_amount,
+        "incorrect fee amount"
+    );
+    let treasury: Treasury = fetch_account(&fixture.context, treasury_pubkey).await;
+    assert_eq!(treasury.last_known_balance_units, 1_000_000_000, "incorrect treasury balance");
+
+    // the treasury keeps its rent exempt minimum
+    let ix =
+        treasury_withdraw_native_ix(&fixture, &treasury_pubkey, &treasurer_pubkey, 1_000_000_000);
+    process_instructions(&mut fixture.context, &[ix], &[&fixture.treasurer])
+        .await
+        .unwrap();
+    let mut bank_copy = fixture.context.banks_client.clone();
+    let treasury_account = bank_copy.get_account(treasury_pubkey).await.unwrap().unwrap();
+    let rent = bank_copy.get_rent().await.unwrap();
+    assert_eq!(
+        treasury_account.lamports,
+        rent.minimum_balance(treasury_account.data.len()),
+        "treasury not rent exempt"
+    );
+
+    // only the treasury authority can close it
+    let mut ix = close_treasury_native_ix(&fixture, &treasury_pubkey, &treasurer_pubkey);
+    ix.accounts[1].pubkey = other_treasurer.pubkey();
+    let result = process_instructions(&mut fixture.context, &[ix], &[&other_treasurer]).await;
+    assert_custom_error(result, ErrorCode::InvalidTreasurer);
+
+    let treasurer_lamports = fetch_lamports(&fixture.context, treasurer_pubkey).await;
+    let fee_lamports = fetch_lamports(&fixture.context, fixture.fee_pubkey).await;
+    let ix = close_treasury_native_ix(&fixture, &treasury_pubkey, &treasurer_pubkey);
+    process_instructions(&mut fixture.context, &[ix], &[&fixture.treasurer])
+        .await
+        .unwrap();
+
+    let mut bank_copy = fixture.context.banks_client.clone();
+    assert!(
+        bank_copy.get_account(treasury_pubkey).await.unwrap().is_none(),
+        "treasury not closed"
+    );
+    assert_eq!(
+        fetch_lamports(&fixture.context, treasurer_pubkey).await - treasurer_lamports,
+        treasury_account.lamports,
+        "treasury rent not returned"
+    );
+    assert_eq!(
+        fetch_lamports(&fixture.context, fixture.fee_pubkey).await - fee_lamports,
+        msp::constants::CLOSE_TREASURY_FLAT_FEE,
+        "incorrect close fee"
+    );
+}
+
+#[tokio::test]
+async fn token_instructions_reject_native_treasury_test() {
+    let (beneficiary, beneficiary_account) = user_account();
+    let mut fixture = treasury_fixture(
+        vec![(beneficiary.pubkey(), beneficiary_account)],
+        1000,
+    )
+    .await;
+    let treasury_pubkey = native_treasury(&mut fixture, 1, 1_000_000_000).await;
+    let stream_key = Keypair::new();
+    let instruction = create_stream_native_ix(
+        &fixture,
+        &treasury_pubkey,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        500_000_000,
+    );
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer, &stream_key],
+    )
+    .await
+    .unwrap();
+
+    // a token account of the native treasury, as if someone created it
+    let (treasury_token, treasury_token_account) =
+        token_account(fixture.treasury_token_mint, treasury_pubkey, 1000);
+    fixture.context.set_account(&treasury_token, &AccountSharedData::from(treasury_token_account));
+    // the fixture funds are all in the token treasury
+    let (treasurer_token, treasurer_token_account) =
+        token_account(fixture.treasury_token_mint, fixture.treasurer.pubkey(), 100);
+    fixture.context.set_account(
+        &treasurer_token,
+        &AccountSharedData::from(treasurer_token_account),
+    );
+
+    let add_funds_ix = fixture
+        .program
         .request()
         .accounts(msp::accounts::AddFundsAccounts {
-            payer: context.payer.pubkey(),
-            contributor: treasurer.pubkey(),
-            contributor_token: treasurer_token.clone(),
-            treasury: treasury_pubkey.key(),
-            treasury_token: treasury_token_account.clone(),
-            associated_token: treasury_token_mint.clone(),
-            fee_treasury: fee_pubkey.key(),
-            fee_treasury_token: fees_token.clone(),
+            payer: fixture.context.payer.pubkey(),
+            contributor: fixture.treasurer.pubkey(),
+            contributor_token: fixture.treasurer_token,
+            treasury: treasury_pubkey,
+            treasury_token,
+            associated_token: fixture.treasury_token_mint,
+            config: config_pubkey(),
+            fee_treasury: fixture.fee_pubkey,
+            fee_treasury_token: fixture.fees_token,
             associated_token_program: associated_token::ID,
             token_program: token::ID,
             system_program: system_program::ID,
             rent: rent::ID,
         })
-        .args(parameters)
+        .args(AddFunds {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            amount: 100,
+        })
         .instructions()
         .unwrap()
         .pop()
         .unwrap();
+    let result =
+        process_instructions(&mut fixture.context, &[add_funds_ix], &[&fixture.treasurer]).await;
+    assert_custom_error(result, ErrorCode::NotAllowedOnNativeSolTreasury);
 
-    let transaction = Transaction::new_signed_with_payer(
-        &[add_funds_to_treasury_ix],
-        Some(&context.payer.pubkey()),
-        &[&context.payer, &treasurer],
-        context.last_blockhash,
-    );
+    let mut ix = push_withdraw_ix(&fixture, &stream_key.pubkey(), &beneficiary.pubkey());
+    for account in ix.accounts.iter_mut() {
+        if account.pubkey == fixture.treasury_pubkey {
+            account.pubkey = treasury_pubkey;
+        } else if account.pubkey == fixture.treasury_token_account {
+            account.pubkey = treasury_token;
+        }
+    }
+    let result = process_instructions(&mut fixture.context, &[ix], &[]).await;
+    assert_custom_error(result, ErrorCode::NotAllowedOnNativeSolTreasury);
 
-    context
-        .banks_client
-        .process_transaction(transaction)
-        .await
-        .unwrap();
+    let instruction = withdraw_many_ix(
+        &fixture,
+        &beneficiary.pubkey(),
+        &[
+            AccountMeta::new(treasury_pubkey, false),
+            AccountMeta::new(treasury_token, false),
+            AccountMeta::new(stream_key.pubkey(), false),
+        ],
+    );
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&beneficiary],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::NotAllowedOnNativeSolTreasury);
 }
 
-fn user_account() -> (Keypair, Account) {
-    let user = Keypair::new();
-    let account = {
-        Account {
-            lamports: 1,
-            owner: system_program::ID,
-            ..Account::default()
-        }
+#[tokio::test]
+async fn referred_treasury_fee_split_test() {
+    let (beneficiary, beneficiary_account) = user_account();
+    let referrer = Keypair::new();
+    let referrer_account = Account {
+        lamports: 1_000_000_000,
+        owner: system_program::ID,
+        ..Account::default()
     };
-    (user, account)
-}
+    let mut fixture = treasury_fixture(
+        vec![
+            (beneficiary.pubkey(), beneficiary_account),
+            (referrer.pubkey(), referrer_account),
+        ],
+        1000,
+    )
+    .await;
+    let (treasurer_token, treasurer_token_account) = token_account(
+        fixture.treasury_token_mint,
+        fixture.treasurer.pubkey(),
+        1_000_000_000,
+    );
+    fixture.context.set_account(
+        &treasurer_token,
+        &AccountSharedData::from(treasurer_token_account),
+    );
+    let (referrer_token, referrer_token_account) =
+        token_account(fixture.treasury_token_mint, referrer.pubkey(), 0);
+    fixture.context.set_account(
+        &referrer_token,
+        &AccountSharedData::from(referrer_token_account.clone()),
+    );
 
-fn treasury_accounts(treasurer: Pubkey, slot: u64) -> (Pubkey, Pubkey, Pubkey) {
-    let treasury_token_mint = native_mint::id();
-    let (treasury_pubkey, bump_seed) =
-        Pubkey::find_program_address(&[treasurer.as_ref(), &slot.to_le_bytes()], &msp::id());
-    println!(
-        "treasury_pubkey: {}, bump_seed: {}",
-        treasury_pubkey, bump_seed
+    // treasurers can't take a share of their own fees
+    let instruction = create_treasury_ix(
+        &fixture,
+        1,
+        &fixture.treasury_token_mint,
+        Some(fixture.treasurer.pubkey()),
     );
-    let treasury_token_account =
-        associated_token::get_associated_token_address(&treasury_pubkey, &treasury_token_mint);
-    println!("treasury_token_account: {}", treasury_token_account);
-    (
-        treasury_pubkey,
-        treasury_token_mint,
-        treasury_token_account,
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer],
     )
-}
+    .await;
+    assert_custom_error(result, ErrorCode::InvalidReferrerAccount);
 
-fn treasurer_accounts(treasury_token_mint: Pubkey, treasurer: Pubkey) -> (Pubkey, Account) {
-    let treasurer_token =
-        associated_token::get_associated_token_address(&treasurer, &treasury_token_mint);
-    let dst: &mut [u8] = &mut [0; spl_token::state::Account::LEN];
-    let _ = &spl_token::state::Account {
-        mint: treasury_token_mint,
-        owner: treasurer,
-        amount: 1000,
-        // delegate: COption<Pubkey>,
-        state: spl_token::state::AccountState::Initialized,
-        // If is_some, this is a native token, and the value logs the rent-exempt reserve. An Account
-        // is required to be rent-exempt, so the value is used by the Processor to ensure that wrapped
-        // SOL accounts do not drop below this threshold.
-        // is_native: COption<u64>,
-        // The amount delegated
-        // delegated_amount: u64,
-        // Optional authority to close the account.
-        // close_authority: COption<Pubkey>,
-        ..spl_token::state::Account::default()
-    }
-    .pack_into_slice(dst);
-    let treasurer_token_account = {
-        let mut data = Vec::new();
-        data.extend_from_slice(dst);
-        Account {
-            lamports: 1,
-            data: data,
-            owner: token::ID,
-            ..Account::default()
+    // the fixture instructions go to a treasury referred by `referrer` from now on
+    let instruction = create_treasury_ix(
+        &fixture,
+        1,
+        &fixture.treasury_token_mint,
+        Some(referrer.pubkey()),
+    );
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer],
+    )
+    .await
+    .unwrap();
+    let (treasury_pubkey, _, treasury_token_account) =
+        treasury_accounts(fixture.treasurer.pubkey(), 1);
+    fixture.treasury_pubkey = treasury_pubkey;
+    fixture.treasury_token_account = treasury_token_account;
+    add_funds(
+        &mut fixture.context,
+        &fixture.program,
+        AddFunds {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            amount: 200_000_000,
+        },
+        &fixture.treasurer,
+        &fixture.treasurer_token,
+        &fixture.treasury_pubkey,
+        &fixture.treasury_token_mint,
+        &fixture.treasury_token_account,
+        &fixture.fee_pubkey,
+        &fixture.fees_token,
+    )
+    .await;
+    // the treasury pays the SOL fees, and the wrapped SOL added to its token
+    // account gets the lamports behind it like in the fixture
+    let fund_treasury_ixs = [
+        system_instruction::transfer(
+            &fixture.context.payer.pubkey(),
+            &fixture.treasury_pubkey,
+            1_000_000_000,
+        ),
+        system_instruction::transfer(
+            &fixture.context.payer.pubkey(),
+            &fixture.treasury_token_account,
+            200_000_000,
+        ),
+    ];
+    process_instructions(&mut fixture.context, &fund_treasury_ixs, &[])
+        .await
+        .unwrap();
+
+    // the create stream SOL fee is split
+    let stream_key = Keypair::new();
+    let mut ix = create_stream_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        120_000_000,
+        120_000_000,
+        false,
+    );
+    set_referrer_accounts(&mut ix, &fixture, &referrer.pubkey());
+    let referrer_lamports_before = fetch_lamports(&fixture.context, referrer.pubkey()).await;
+    let fee_lamports_before = fetch_lamports(&fixture.context, fixture.fee_pubkey).await;
+    process_instructions(&mut fixture.context, &[ix], &[&fixture.treasurer, &stream_key])
+        .await
+        .unwrap();
+    assert_eq!(
+        fetch_lamports(&fixture.context, referrer.pubkey()).await - referrer_lamports_before,
+        1_000,
+        "incorrect referrer SOL fee"
+    );
+    assert_eq!(
+        fetch_lamports(&fixture.context, fixture.fee_pubkey).await - fee_lamports_before,
+        9_000,
+        "incorrect fee treasury SOL fee"
+    );
+
+    let other_stream_key = Keypair::new();
+    let mut create_instruction = create_stream_ix(
+        &fixture,
+        &other_stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        40_000_000,
+        0,
+        true,
+    );
+    set_referrer_accounts(&mut create_instruction, &fixture, &referrer.pubkey());
+    let mut withdraw_instruction = withdraw_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        40_000_000,
+        &[],
+    );
+    set_referrer_accounts(&mut withdraw_instruction, &fixture, &referrer.pubkey());
+    let mut withdraw_many_instruction = withdraw_many_ix(
+        &fixture,
+        &beneficiary.pubkey(),
+        &[
+            AccountMeta::new(fixture.treasury_pubkey, false),
+            AccountMeta::new(fixture.treasury_token_account, false),
+            AccountMeta::new(stream_key.pubkey(), false),
+        ],
+    );
+    set_referrer_accounts(&mut withdraw_many_instruction, &fixture, &referrer.pubkey());
+
+    // (instruction, signers, referrer token fee, fee treasury token fee)
+    let expectations = vec![
+        // the treasurer fee of a stream whose withdraw fees are payed by the
+        // treasurer
+        (
+            create_instruction,
+            vec![&fixture.treasurer, &other_stream_key],
+            10_000,
+            90_000,
+        ),
+        (withdraw_instruction.clone(), vec![&beneficiary], 10_000, 90_000),
+        // a closed referrer token account doesn't block the withdrawal, the
+        // whole fee goes to the fee treasury
+        (withdraw_instruction, vec![&beneficiary], 0, 100_000),
+        (withdraw_many_instruction, vec![&beneficiary], 10_000, 90_000),
+    ];
+    for (index, (ix, signers, referrer_fee, fee_treasury_fee)) in
+        expectations.into_iter().enumerate()
+    {
+        if index == 2 {
+            fixture.context.set_account(&referrer_token, &AccountSharedData::default());
+        } else {
+            fixture.context.set_account(
+                &referrer_token,
+                &AccountSharedData::from(referrer_token_account.clone()),
+            );
         }
-    };
-    (treasurer_token, treasurer_token_account)
+        let fees_token_before = fetch_token_amount(&fixture.context, fixture.fees_token).await;
+        process_instructions(&mut fixture.context, &[ix], &signers)
+            .await
+            .unwrap();
+        if referrer_fee > 0 {
+            assert_eq!(
+                fetch_token_amount(&fixture.context, referrer_token).await,
+                referrer_fee,
+                "incorrect referrer token fee"
+            );
+        }
+        assert_eq!(
+            fetch_token_amount(&fixture.context, fixture.fees_token).await - fees_token_before,
+            fee_treasury_fee,
+            "incorrect fee treasury token fee"
+        );
+    }
 }
 
-fn fee(treasury_token_mint: &Pubkey) -> (Pubkey, Account, Pubkey) {
-    let fee_pubkey = fee_treasury::ID;
-    let fee_account = {
-        let fee_data = Vec::new();
-        Account {
-            lamports: 1,
-            data: fee_data,
-            owner: system_program::ID,
-            ..Account::default()
-        }
-    };
-    let fees_token =
-        associated_token::get_associated_token_address(&fee_pubkey, &treasury_token_mint);
+#[test]
+fn stream_schedule_validate_points_test() {
+    let points = [
+        SchedulePoint { timestamp: 10, cumulative_units: 20 },
+        SchedulePoint { timestamp: 20, cumulative_units: 20 },
+        SchedulePoint { timestamp: 30, cumulative_units: 50 },
+    ];
+    assert!(StreamSchedule::validate_points(&points, 50).is_ok());
 
-    (fee_pubkey, fee_account, fees_token)
+    // the last point has to unlock the whole allocation
+    let error = StreamSchedule::validate_points(&points, 60).unwrap_err();
+    assert_eq!(
+        anchor_error_code(error),
+        u32::from(ErrorCode::InvalidScheduleTotal),
+        "incorrect error"
+    );
+
+    // the points have to be sorted by timestamp
+    let unsorted = [points[1], points[0], points[2]];
+    let error = StreamSchedule::validate_points(&unsorted, 50).unwrap_err();
+    assert_eq!(
+        anchor_error_code(error),
+        u32::from(ErrorCode::InvalidSchedule),
+        "incorrect error"
+    );
+
+    // the unlocked units can't decrease
+    let decreasing = [
+        SchedulePoint { timestamp: 10, cumulative_units: 30 },
+        SchedulePoint { timestamp: 20, cumulative_units: 20 },
+    ];
+    let error = StreamSchedule::validate_points(&decreasing, 20).unwrap_err();
+    assert_eq!(
+        anchor_error_code(error),
+        u32::from(ErrorCode::InvalidSchedule),
+        "incorrect error"
+    );
+
+    let schedule = StreamSchedule {
+        version: 1,
+        bump: 0,
+        stream_address: Pubkey::default(),
+        points: points.to_vec(),
+    };
+    assert_eq!(schedule.get_unlocked_units(9), 0, "incorrect unlocked units");
+    assert_eq!(schedule.get_unlocked_units(10), 20, "incorrect unlocked units");
+    assert_eq!(schedule.get_unlocked_units(29), 20, "incorrect unlocked units");
+    assert_eq!(schedule.get_unlocked_units(30), 50, "incorrect unlocked units");
+    assert_eq!(schedule.get_completion_blocktime(), 30, "incorrect completion");
 }
 
-async fn fetch_stream(context: &ProgramTestContext, stream_pubkey: Pubkey) -> msp::stream::Stream {
-    let mut bank_copy = context.banks_client.clone();
-    let stream_account = bank_copy.get_account(stream_pubkey).await.unwrap().unwrap();
-    let stream_data_u8: [u8; 500] = stream_account.data.try_into().unwrap();
-    let mut slice: &[u8] = &stream_data_u8;
+#[tokio::test]
+async fn create_stream_with_schedule_test() {
+    let (beneficiary, beneficiary_account) = user_account();
+    let mut fixture = treasury_fixture(
+        vec![(beneficiary.pubkey(), beneficiary_account)],
+        1000,
+    )
+    .await;
+    let clock = fixture.context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let now_ts = clock.unix_timestamp as u64;
+
+    // the schedule total has to match the stream allocation
+    let stream_key = Keypair::new();
+    let instruction = create_stream_with_schedule_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        60,
+        vec![
+            SchedulePoint {
+                timestamp: now_ts + 10,
+                cumulative_units: 20,
+            },
+            SchedulePoint {
+                timestamp: now_ts + 20,
+                cumulative_units: 50,
+            },
+        ],
+    );
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer, &stream_key],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::InvalidScheduleTotal);
+
+    let stream_key = Keypair::new();
+    let instruction = create_stream_with_schedule_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        50,
+        vec![
+            SchedulePoint {
+                timestamp: now_ts + 10,
+                cumulative_units: 20,
+            },
+            SchedulePoint {
+                timestamp: now_ts + 20,
+                cumulative_units: 50,
+            },
+        ],
+    );
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer, &stream_key],
+    )
+    .await
+    .unwrap();
+
+    let schedule_pubkey = schedule_pubkey(&stream_key.pubkey());
+    let stream = fetch_stream(&fixture.context, stream_key.pubkey()).await;
+    assert_eq!(stream.schedule_address, schedule_pubkey, "incorrect schedule");
+    assert_eq!(stream.allocation_assigned_units, 50, "incorrect stream allocation");
+    assert_eq!(stream.get_start_utc().unwrap(), now_ts + 10, "incorrect start");
+
+    let schedule: StreamSchedule = fetch_account(&fixture.context, schedule_pubkey).await;
+    assert_eq!(schedule.stream_address, stream_key.pubkey(), "incorrect stream");
+    assert_eq!(schedule.points.len(), 2, "incorrect number of points");
+    assert_eq!(
+        stream.get_scheduled_status(&schedule, now_ts).unwrap(),
+        msp::enums::StreamStatus::Scheduled,
+        "incorrect status"
+    );
+    assert_eq!(
+        stream.get_scheduled_withdrawable_amount(&schedule, now_ts + 15).unwrap(),
+        20,
+        "incorrect withdrawable amount"
+    );
+}
+
+#[tokio::test]
+async fn withdraw_and_close_scheduled_stream_test() {
+    let (beneficiary, beneficiary_account) = user_account();
+    let mut fixture = treasury_fixture(
+        vec![(beneficiary.pubkey(), beneficiary_account)],
+        1000,
+    )
+    .await;
+    let mut clock = fixture.context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let now_ts = clock.unix_timestamp as u64;
+
+    let stream_key = Keypair::new();
+    let instruction = create_stream_with_schedule_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        50,
+        vec![
+            SchedulePoint {
+                timestamp: now_ts + 10,
+                cumulative_units: 20,
+            },
+            SchedulePoint {
+                timestamp: now_ts + 20,
+                cumulative_units: 30,
+            },
+            SchedulePoint {
+                timestamp: now_ts + 30,
+                cumulative_units: 50,
+            },
+        ],
+    );
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer, &stream_key],
+    )
+    .await
+    .unwrap();
+    let schedule_pubkey = schedule_pubkey(&stream_key.pubkey());
+
+    // fast forward to the first point
+    clock.unix_timestamp = clock.unix_timestamp.checked_add(10).unwrap();
+    fixture.context.set_sysvar(&clock);
+
+    // the schedule has to be passed to withdraw from a scheduled stream
+    let instruction = withdraw_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        u64::MAX,
+        &[],
+    );
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&beneficiary],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::InvalidScheduleAccount);
+
+    let instruction = withdraw_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        u64::MAX,
+        &[AccountMeta::new(schedule_pubkey, false)],
+    );
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&beneficiary],
+    )
+    .await
+    .unwrap();
+    let stream = fetch_stream(&fixture.context, stream_key.pubkey()).await;
+    assert_eq!(stream.total_withdrawals_units, 20, "incorrect withdrawals");
+
+    // withdraw_many takes the schedule right after the stream triple
+    clock.unix_timestamp = clock.unix_timestamp.checked_add(10).unwrap();
+    fixture.context.set_sysvar(&clock);
+    let instruction = withdraw_many_ix(
+        &fixture,
+        &beneficiary.pubkey(),
+        &[
+            AccountMeta::new(fixture.treasury_pubkey, false),
+            AccountMeta::new(fixture.treasury_token_account, false),
+            AccountMeta::new(stream_key.pubkey(), false),
+            AccountMeta::new(schedule_pubkey, false),
+        ],
+    );
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&beneficiary],
+    )
+    .await
+    .unwrap();
+    let stream = fetch_stream(&fixture.context, stream_key.pubkey()).await;
+    assert_eq!(stream.total_withdrawals_units, 30, "incorrect withdrawals");
+
+    // the schedule is closed along with the stream
+    let instruction = close_stream_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        &[AccountMeta::new(schedule_pubkey, false)],
+    );
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer],
+    )
+    .await
+    .unwrap();
+    let mut bank_copy = fixture.context.banks_client.clone();
+    assert!(bank_copy.get_account(stream_key.pubkey()).await.unwrap().is_none());
+    assert!(bank_copy.get_account(schedule_pubkey).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn renounce_stream_test() {
+    let (beneficiary, beneficiary_account) = user_account();
+    let (other_user, other_user_account) = user_account();
+    let mut fixture = treasury_fixture(
+        vec![
+            (beneficiary.pubkey(), beneficiary_account),
+            (other_user.pubkey(), other_user_account),
+        ],
+        1000,
+    )
+    .await;
+    let (_, stream_key) = create_stream(
+        &mut fixture.context,
+        &fixture.program,
+        CreateStream {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            name: "test stream".to_string(),
+            start_utc: 0,
+            rate_amount_units: 10,
+            rate_interval_in_seconds: 3_600,
+            allocation_assigned_units: 500,
+            cliff_vest_amount_units: 100,
+            cliff_vest_percent: 0,
+            fee_payed_by_treasurer: false,
+            accrual_mode: msp::constants::STREAM_ACCRUAL_MODE_LINEAR,
+            cliff_utc: 0,
+        },
+        &fixture.treasurer,
+        &fixture.treasury_pubkey,
+        &fixture.treasury_token_account,
+        &fixture.treasury_token_mint,
+        &beneficiary.pubkey(),
+        &fixture.fee_pubkey,
+        &fixture.fees_token,
+    )
+    .await;
+
+    // only the beneficiary can renounce the stream
+    let instruction = renounce_stream_ix(&fixture, &stream_key.pubkey(), &other_user.pubkey());
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&other_user],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::InvalidBeneficiary);
+
+    let instruction = renounce_stream_ix(&fixture, &stream_key.pubkey(), &beneficiary.pubkey());
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&beneficiary],
+    )
+    .await
+    .unwrap();
+
+    // the beneficiary gets the vested cliff and the rest goes back to the
+    // treasury unallocated balance
+    let mut bank_copy = fixture.context.banks_client.clone();
+    assert!(bank_copy.get_account(stream_key.pubkey()).await.unwrap().is_none());
+    let beneficiary_token = associated_token::get_associated_token_address(
+        &beneficiary.pubkey(),
+        &fixture.treasury_token_mint,
+    );
+    assert_eq!(
+        fetch_token_amount(&fixture.context, beneficiary_token).await,
+        100,
+        "incorrect beneficiary amount"
+    );
+    let treasury: Treasury = fetch_account(&fixture.context, fixture.treasury_pubkey).await;
+    assert_eq!(treasury.total_streams, 0, "incorrect total streams");
+    assert_eq!(treasury.allocation_assigned_units, 0, "incorrect treasury allocation");
+    assert_eq!(
+        fetch_token_amount(&fixture.context, fixture.treasury_token_account).await,
+        900,
+        "incorrect treasury balance"
+    );
+}
+
+#[tokio::test]
+async fn withdraw_to_destination_test() {
+    let (beneficiary, beneficiary_account) = user_account();
+    let (other_user, other_user_account) = user_account();
+    let mut fixture = treasury_fixture(
+        vec![
+            (beneficiary.pubkey(), beneficiary_account),
+            (other_user.pubkey(), other_user_account),
+        ],
+        1000,
+    )
+    .await;
+    let stream_key = Keypair::new();
+    let instruction = create_stream_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        500,
+        500,
+        false,
+    );
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer, &stream_key],
+    )
+    .await
+    .unwrap();
+
+    // e.g. a multisig vault
+    let (destination_token, destination_token_account) =
+        token_account(fixture.treasury_token_mint, other_user.pubkey(), 0);
+    fixture.context.set_account(
+        &destination_token,
+        &AccountSharedData::from(destination_token_account),
+    );
+    let (other_mint_token, other_mint_token_account) =
+        token_account(Pubkey::new_unique(), beneficiary.pubkey(), 0);
+    fixture.context.set_account(
+        &other_mint_token,
+        &AccountSharedData::from(other_mint_token_account),
+    );
+
+    // (signer, destination, amount, error)
+    let rejections = [
+        (&beneficiary, other_mint_token, 400, ErrorCode::InvalidAssociatedToken),
+        (&other_user, destination_token, 400, ErrorCode::InvalidBeneficiary),
+        (&beneficiary, destination_token, 0, ErrorCode::ZeroWithdrawalAmount),
+    ];
+    for (signer, destination, amount, error) in rejections {
+        let instruction = withdraw_to_destination_ix(
+            &fixture,
+            &stream_key.pubkey(),
+            &signer.pubkey(),
+            &destination,
+            amount,
+        );
+        let result = process_instructions(
+            &mut fixture.context,
+            &[instruction],
+            &[signer],
+        )
+        .await;
+        assert_custom_error(result, error);
+    }
+
+    let fees_before = fetch_token_amount(&fixture.context, fixture.fees_token).await;
+    let instruction = withdraw_to_destination_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        &destination_token,
+        400,
+    );
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&beneficiary],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        fetch_token_amount(&fixture.context, destination_token).await,
+        399,
+        "incorrect destination amount"
+    );
+    assert_eq!(
+        fetch_token_amount(&fixture.context, fixture.fees_token).await - fees_before,
+        1,
+        "incorrect fee"
+    );
+    let stream = fetch_stream(&fixture.context, stream_key.pubkey()).await;
+    assert_eq!(stream.total_withdrawals_units, 400, "incorrect withdrawals");
+}
+
+#[tokio::test]
+async fn stream_transfer_test() {
+    let beneficiary = Keypair::new();
+    let beneficiary_account = Account {
+        lamports: 1_000_000_000,
+        owner: system_program::ID,
+        ..Account::default()
+    };
+    let (new_beneficiary, new_beneficiary_account) = user_account();
+    let (other_user, other_user_account) = user_account();
+    let mut fixture = treasury_fixture(
+        vec![
+            (beneficiary.pubkey(), beneficiary_account),
+            (new_beneficiary.pubkey(), new_beneficiary_account),
+            (other_user.pubkey(), other_user_account),
+        ],
+        1000,
+    )
+    .await;
+    let stream_key = Keypair::new();
+    let instruction = create_stream_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        500,
+        500,
+        false,
+    );
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer, &stream_key],
+    )
+    .await
+    .unwrap();
+
+    // only the beneficiary can propose a transfer
+    let instruction = propose_stream_transfer_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &other_user.pubkey(),
+        &other_user.pubkey(),
+    );
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&other_user],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::NotAuthorized);
+
+    // nothing to cancel yet
+    let instruction =
+        cancel_stream_transfer_ix(&fixture, &stream_key.pubkey(), &beneficiary.pubkey());
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&beneficiary],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::NoPendingStreamTransfer);
+
+    let fee_lamports_before = fetch_lamports(&fixture.context, fixture.fee_pubkey).await;
+    let instruction = propose_stream_transfer_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        &new_beneficiary.pubkey(),
+    );
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&beneficiary],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        fetch_lamports(&fixture.context, fixture.fee_pubkey).await - fee_lamports_before,
+        msp::constants::TRANSFER_STREAM_FLAT_FEE,
+        "incorrect transfer fee"
+    );
+    let stream = fetch_stream(&fixture.context, stream_key.pubkey()).await;
+    assert_eq!(stream.beneficiary_address, beneficiary.pubkey(), "beneficiary changed on propose");
+    assert_eq!(stream.pending_beneficiary_address, new_beneficiary.pubkey());
+
+    // only the proposed beneficiary can accept it
+    let instruction =
+        accept_stream_transfer_ix(&fixture, &stream_key.pubkey(), &other_user.pubkey());
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&other_user],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::NotAuthorized);
+
+    // a cancelled transfer can't be accepted
+    let instruction =
+        cancel_stream_transfer_ix(&fixture, &stream_key.pubkey(), &beneficiary.pubkey());
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&beneficiary],
+    )
+    .await
+    .unwrap();
+    let stream = fetch_stream(&fixture.context, stream_key.pubkey()).await;
+    assert_eq!(stream.pending_beneficiary_address, Pubkey::default());
+    let instruction =
+        accept_stream_transfer_ix(&fixture, &stream_key.pubkey(), &new_beneficiary.pubkey());
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&new_beneficiary],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::NotAuthorized);
+
+    let instruction = propose_stream_transfer_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        &new_beneficiary.pubkey(),
+    );
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&beneficiary],
+    )
+    .await
+    .unwrap();
+    let instruction =
+        accept_stream_transfer_ix(&fixture, &stream_key.pubkey(), &new_beneficiary.pubkey());
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&new_beneficiary],
+    )
+    .await
+    .unwrap();
+    let stream = fetch_stream(&fixture.context, stream_key.pubkey()).await;
+    assert_eq!(stream.beneficiary_address, new_beneficiary.pubkey(), "incorrect beneficiary");
+    assert_eq!(stream.pending_beneficiary_address, Pubkey::default());
+
+    // the previous beneficiary can't withdraw anymore
+    let instruction = withdraw_to_destination_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        &fixture.fees_token,
+        100,
+    );
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&beneficiary],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::InvalidBeneficiary);
+}
+
+#[tokio::test]
+async fn treasury_authority_handover_test() {
+    let (beneficiary, beneficiary_account) = user_account();
+    let (new_authority, new_authority_account) = user_account();
+    let (other_user, other_user_account) = user_account();
+    let mut fixture = treasury_fixture(
+        vec![
+            (beneficiary.pubkey(), beneficiary_account),
+            (new_authority.pubkey(), new_authority_account),
+            (other_user.pubkey(), other_user_account),
+        ],
+        1000,
+    )
+    .await;
+    let treasurer = fixture.treasurer.pubkey();
+
+    // (signer, proposed authority, error)
+    let rejections = [
+        (&other_user, new_authority.pubkey(), ErrorCode::NotAuthorized),
+        (&fixture.treasurer, treasurer, ErrorCode::InvalidTreasuryAuthority),
+    ];
+    for (signer, proposed_authority, error) in rejections {
+        let instruction =
+            propose_treasury_authority_ix(&fixture, &signer.pubkey(), &proposed_authority);
+        let result = process_instructions(
+            &mut fixture.context,
+            &[instruction],
+            &[signer],
+        )
+        .await;
+        assert_custom_error(result, error);
+    }
+
+    let instruction = propose_treasury_authority_ix(&fixture, &treasurer, &new_authority.pubkey());
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer],
+    )
+    .await
+    .unwrap();
+    let treasury: Treasury = fetch_account(&fixture.context, fixture.treasury_pubkey).await;
+    assert_eq!(treasury.authority(), treasurer, "authority changed on propose");
+    assert_eq!(treasury.pending_authority_address, new_authority.pubkey());
+
+    // only the proposed authority can accept it
+    let instruction = accept_treasury_authority_ix(&fixture, &other_user.pubkey());
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&other_user],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::NotAuthorized);
+
+    let instruction = accept_treasury_authority_ix(&fixture, &new_authority.pubkey());
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&new_authority],
+    )
+    .await
+    .unwrap();
+    let treasury: Treasury = fetch_account(&fixture.context, fixture.treasury_pubkey).await;
+    assert_eq!(treasury.authority(), new_authority.pubkey(), "incorrect authority");
+    assert_eq!(treasury.treasurer_address, treasurer, "treasurer address changed");
+    assert_eq!(treasury.pending_authority_address, Pubkey::default());
+
+    // the handover can't be accepted twice
+    let instruction = accept_treasury_authority_ix(&fixture, &new_authority.pubkey());
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&new_authority],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::NotAuthorized);
+
+    // the previous treasurer can't create streams anymore
+    let stream_key = Keypair::new();
+    let instruction = create_stream_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        500,
+        500,
+        false,
+    );
+    let result = process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer, &stream_key],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::NotAuthorized);
+
+    // the new authority signs in the treasurer place
+    let mut create_instruction =
+        create_stream_ix(&fixture, &stream_key.pubkey(), &beneficiary.pubkey(), 500, 500, false);
+    create_instruction.accounts[1].pubkey = new_authority.pubkey();
+    process_instructions(
+        &mut fixture.context,
+        &[create_instruction],
+        &[&new_authority, &stream_key],
+    )
+    .await
+    .unwrap();
+    let treasury: Treasury = fetch_account(&fixture.context, fixture.treasury_pubkey).await;
+    assert_eq!(treasury.total_streams, 1, "incorrect total streams");
+}
+
+#[tokio::test]
+async fn allocate_to_all_streams_test() {
+    let (beneficiary, beneficiary_account) = user_account();
+    let (other_user, other_user_account) = user_account();
+    let mut fixture = treasury_fixture(
+        vec![
+            (beneficiary.pubkey(), beneficiary_account),
+            (other_user.pubkey(), other_user_account),
+        ],
+        1000,
+    )
+    .await;
+    // rates of 10 and 30 units per 1000 seconds so the pro-rata split is exact
+    let mut stream_pubkeys = vec![];
+    for rate_amount_units in [10, 30] {
+        let (_, stream_key) = create_stream(
+            &mut fixture.context,
+            &fixture.program,
+            CreateStream {
+                _idl_file_version: msp::constants::IDL_FILE_VERSION,
+                name: "test stream".to_string(),
+                start_utc: 0,
+                rate_amount_units,
+                rate_interval_in_seconds: 1_000,
+                allocation_assigned_units: 100,
+                cliff_vest_amount_units: 0,
+                cliff_vest_percent: 0,
+                fee_payed_by_treasurer: false,
+                accrual_mode: msp::constants::STREAM_ACCRUAL_MODE_LINEAR,
+                cliff_utc: 0,
+            },
+            &fixture.treasurer,
+            &fixture.treasury_pubkey,
+            &fixture.treasury_token_account,
+            &fixture.treasury_token_mint,
+            &beneficiary.pubkey(),
+            &fixture.fee_pubkey,
+            &fixture.fees_token,
+        )
+        .await;
+        stream_pubkeys.push(stream_key.pubkey());
+    }
+    let streams: Vec<AccountMeta> = stream_pubkeys
+        .iter()
+        .map(|stream_pubkey| AccountMeta::new(*stream_pubkey, false))
+        .collect();
+    let pro_rata = msp::constants::ALLOCATION_SPLIT_MODE_PRO_RATA;
+    let even = msp::constants::ALLOCATION_SPLIT_MODE_EVEN;
+
+    // (signer, amount, split mode, streams, error)
+    let treasurer = &fixture.treasurer;
+    let duplicated_streams = vec![streams[0].clone(); 2];
+    let rejections = [
+        (
+            &other_user,
+            400,
+            pro_rata,
+            streams.clone(),
+            ErrorCode::InvalidTreasurer,
+        ),
+        (
+            treasurer,
+            0,
+            pro_rata,
+            streams.clone(),
+            ErrorCode::ZeroContributionAmount,
+        ),
+        (
+            treasurer,
+            400,
+            2,
+            streams.clone(),
+            ErrorCode::InvalidAllocationSplitMode,
+        ),
+        (
+            treasurer,
+            400,
+            pro_rata,
+            vec![],
+            ErrorCode::InvalidStreamAccounts,
+        ),
+        (
+            treasurer,
+            400,
+            pro_rata,
+            duplicated_streams,
+            ErrorCode::InvalidStreamAccounts,
+        ),
+        // 225 and 675 while the treasury has 800 unallocated
+        (
+            treasurer,
+            900,
+            pro_rata,
+            streams.clone(),
+            ErrorCode::InsufficientTreasuryBalance,
+        ),
+    ];
+    for (signer, amount, split_mode, remaining_accounts, error) in rejections {
+        let instruction = allocate_to_all_streams_ix(
+            &fixture,
+            &signer.pubkey(),
+            amount,
+            split_mode,
+            &remaining_accounts,
+        );
+        let result = process_instructions(&mut fixture.context, &[instruction], &[signer]).await;
+        assert_custom_error(result, error);
+    }
+
+    // (amount, split mode, expected stream allocations)
+    let allocations = [(400, pro_rata, [200, 400]), (200, even, [300, 500])];
+    for (amount, split_mode, expected_allocations) in allocations {
+        let instruction = allocate_to_all_streams_ix(
+            &fixture,
+            &fixture.treasurer.pubkey(),
+            amount,
+            split_mode,
+            &streams,
+        );
+        process_instructions(&mut fixture.context, &[instruction], &[&fixture.treasurer])
+            .await
+            .unwrap();
+        for (stream_pubkey, expected_allocation) in stream_pubkeys.iter().zip(expected_allocations)
+        {
+            let stream = fetch_stream(&fixture.context, *stream_pubkey).await;
+            assert_eq!(
+                stream.allocation_assigned_units, expected_allocation,
+                "incorrect stream allocation"
+            );
+        }
+    }
+    let treasury: Treasury = fetch_account(&fixture.context, fixture.treasury_pubkey).await;
+    assert_eq!(
+        treasury.allocation_assigned_units, 800,
+        "incorrect treasury allocation"
+    );
+}
+
+#[tokio::test]
+async fn withdraw_many_test() {
+    let (beneficiary, beneficiary_account) = user_account();
+    let (other_user, other_user_account) = user_account();
+    let mut fixture = treasury_fixture(
+        vec![
+            (beneficiary.pubkey(), beneficiary_account),
+            (other_user.pubkey(), other_user_account),
+        ],
+        1000,
+    )
+    .await;
+    // fully vested at the cliff so the withdrawable amounts don't depend on
+    // the clock
+    let mut remaining_accounts = vec![];
+    for _ in 0..2 {
+        let stream_key = Keypair::new();
+        let instruction = create_stream_ix(
+            &fixture,
+            &stream_key.pubkey(),
+            &beneficiary.pubkey(),
+            400,
+            400,
+            false,
+        );
+        process_instructions(
+            &mut fixture.context,
+            &[instruction],
+            &[&fixture.treasurer, &stream_key],
+        )
+        .await
+        .unwrap();
+        remaining_accounts.extend_from_slice(&[
+            AccountMeta::new(fixture.treasury_pubkey, false),
+            AccountMeta::new(fixture.treasury_token_account, false),
+            AccountMeta::new(stream_key.pubkey(), false),
+        ]);
+    }
+
+    // (signer, remaining accounts, error)
+    let mut wrong_treasury_token = remaining_accounts[..3].to_vec();
+    wrong_treasury_token[1].pubkey = fixture.fees_token;
+    let rejections = [
+        (&beneficiary, vec![], ErrorCode::InvalidStreamAccounts),
+        (
+            &beneficiary,
+            remaining_accounts[..2].to_vec(),
+            ErrorCode::InvalidStreamAccounts,
+        ),
+        (
+            &beneficiary,
+            wrong_treasury_token,
+            ErrorCode::InvalidAssociatedToken,
+        ),
+        (
+            &other_user,
+            remaining_accounts.clone(),
+            ErrorCode::InvalidBeneficiary,
+        ),
+    ];
+    for (signer, remaining_accounts, error) in rejections {
+        let instruction = withdraw_many_ix(&fixture, &signer.pubkey(), &remaining_accounts);
+        let result = process_instructions(&mut fixture.context, &[instruction], &[signer]).await;
+        assert_custom_error(result, error);
+    }
+
+    // both streams of the same treasury in one instruction
+    let fees_before = fetch_token_amount(&fixture.context, fixture.fees_token).await;
+    let instruction = withdraw_many_ix(&fixture, &beneficiary.pubkey(), &remaining_accounts);
+    process_instructions(&mut fixture.context, &[instruction], &[&beneficiary])
+        .await
+        .unwrap();
+    let beneficiary_token = associated_token::get_associated_token_address(
+        &beneficiary.pubkey(),
+        &fixture.treasury_token_mint,
+    );
+    assert_eq!(
+        fetch_token_amount(&fixture.context, beneficiary_token).await,
+        798,
+        "incorrect beneficiary amount"
+    );
+    assert_eq!(
+        fetch_token_amount(&fixture.context, fixture.fees_token).await - fees_before,
+        2,
+        "incorrect fee"
+    );
+    for stream_meta in remaining_accounts.iter().skip(2).step_by(3) {
+        let stream = fetch_stream(&fixture.context, stream_meta.pubkey).await;
+        assert_eq!(stream.total_withdrawals_units, 400, "incorrect withdrawals");
+    }
+    assert_eq!(
+        fetch_token_amount(&fixture.context, fixture.treasury_token_account).await,
+        200,
+        "incorrect treasury balance"
+    );
+}
+
+#[tokio::test]
+async fn close_treasury_and_streams_test() {
+    let (beneficiary, beneficiary_account) = user_account();
+    let (other_user, other_user_account) = user_account();
+    let mut fixture = treasury_fixture(
+        vec![
+            (beneficiary.pubkey(), beneficiary_account),
+            (other_user.pubkey(), other_user_account),
+        ],
+        1000,
+    )
+    .await;
+    let (beneficiary_token, beneficiary_token_account) =
+        token_account(fixture.treasury_token_mint, beneficiary.pubkey(), 0);
+    fixture.context.set_account(
+        &beneficiary_token,
+        &AccountSharedData::from(beneficiary_token_account),
+    );
+    let mut stream_pubkeys = vec![];
+    let mut remaining_accounts = vec![];
+    for _ in 0..2 {
+        let stream_key = Keypair::new();
+        let instruction = create_stream_ix(
+            &fixture,
+            &stream_key.pubkey(),
+            &beneficiary.pubkey(),
+            400,
+            400,
+            false,
+        );
+        process_instructions(
+            &mut fixture.context,
+            &[instruction],
+            &[&fixture.treasurer, &stream_key],
+        )
+        .await
+        .unwrap();
+        stream_pubkeys.push(stream_key.pubkey());
+        remaining_accounts.extend_from_slice(&[
+            AccountMeta::new(stream_key.pubkey(), false),
+            AccountMeta::new(beneficiary_token, false),
+        ]);
+    }
+
+    // (signer, remaining accounts, error)
+    let treasurer = &fixture.treasurer;
+    let mut wrong_beneficiary_token = remaining_accounts.clone();
+    wrong_beneficiary_token[1].pubkey = fixture.fees_token;
+    let rejections = [
+        (
+            &other_user,
+            remaining_accounts.clone(),
+            ErrorCode::InvalidTreasurer,
+        ),
+        (
+            treasurer,
+            remaining_accounts[..1].to_vec(),
+            ErrorCode::InvalidStreamAccounts,
+        ),
+        (
+            treasurer,
+            wrong_beneficiary_token,
+            ErrorCode::InvalidAssociatedToken,
+        ),
+        // one of the streams is left open
+        (
+            treasurer,
+            remaining_accounts[..2].to_vec(),
+            ErrorCode::TreasuryContainsStreams,
+        ),
+    ];
+    for (signer, remaining_accounts, error) in rejections {
+        let instruction =
+            close_treasury_and_streams_ix(&fixture, &signer.pubkey(), &remaining_accounts);
+        let result = process_instructions(&mut fixture.context, &[instruction], &[signer]).await;
+        assert_custom_error(result, error);
+    }
+
+    let treasurer_token_before =
+        fetch_token_amount(&fixture.context, fixture.treasurer_token).await;
+    let instruction =
+        close_treasury_and_streams_ix(&fixture, &fixture.treasurer.pubkey(), &remaining_accounts);
+    process_instructions(&mut fixture.context, &[instruction], &[&fixture.treasurer])
+        .await
+        .unwrap();
+
+    // the beneficiary gets the vested amounts and the treasurer the rest
+    assert_eq!(
+        fetch_token_amount(&fixture.context, beneficiary_token).await,
+        798,
+        "incorrect beneficiary amount"
+    );
+    assert_eq!(
+        fetch_token_amount(&fixture.context, fixture.treasurer_token).await
+            - treasurer_token_before,
+        200,
+        "incorrect treasurer amount"
+    );
+    let mut bank_copy = fixture.context.banks_client.clone();
+    for pubkey in [
+        fixture.treasury_pubkey,
+        fixture.treasury_token_account,
+        stream_pubkeys[0],
+        stream_pubkeys[1],
+    ] {
+        assert!(bank_copy.get_account(pubkey).await.unwrap().is_none());
+    }
+}
+
+#[test]
+fn tlv_extension_round_trip_test() {
+    let referrer = TreasuryReferrer {
+        referrer_address: Pubkey::new_unique(),
+    };
+    let mut region = [0u8; 64];
+    assert!(get_extension::<TreasuryReferrer>(&region)
+        .unwrap()
+        .is_none());
+    assert_eq!(get_extensions_len(&region).unwrap(), 0);
+    assert_eq!(
+        get_required_region_len(&region, &referrer).unwrap(),
+        EXTENSION_HEADER_SIZE + 32
+    );
+
+    set_extension(&mut region, &referrer).unwrap();
+    set_extension(&mut region, &NativeSolTreasury {}).unwrap();
+    assert_eq!(
+        get_extension::<TreasuryReferrer>(&region)
+            .unwrap()
+            .unwrap()
+            .referrer_address,
+        referrer.referrer_address
+    );
+    assert!(get_extension::<NativeSolTreasury>(&region)
+        .unwrap()
+        .is_some());
+    assert_eq!(
+        get_extensions_len(&region).unwrap(),
+        2 * EXTENSION_HEADER_SIZE + 32
+    );
+
+    // replacing an extension keeps the others
+    let new_referrer = TreasuryReferrer {
+        referrer_address: Pubkey::new_unique(),
+    };
+    assert_eq!(
+        get_required_region_len(&region, &new_referrer).unwrap(),
+        2 * EXTENSION_HEADER_SIZE + 32
+    );
+    set_extension(&mut region, &new_referrer).unwrap();
+    assert_eq!(
+        get_extension::<TreasuryReferrer>(&region)
+            .unwrap()
+            .unwrap()
+            .referrer_address,
+        new_referrer.referrer_address
+    );
+    assert!(get_extension::<NativeSolTreasury>(&region)
+        .unwrap()
+        .is_some());
+
+    // removing an extension moves the following ones back
+    assert!(remove_extension(&mut region, ExtensionType::TreasuryReferrer).unwrap());
+    assert!(!remove_extension(&mut region, ExtensionType::TreasuryReferrer).unwrap());
+    assert!(get_extension::<TreasuryReferrer>(&region)
+        .unwrap()
+        .is_none());
+    assert!(get_extension::<NativeSolTreasury>(&region)
+        .unwrap()
+        .is_some());
+    assert_eq!(get_extensions_len(&region).unwrap(), EXTENSION_HEADER_SIZE);
+    assert!(region[EXTENSION_HEADER_SIZE..]
+        .iter()
+        .all(|byte| *byte == 0));
+
+    // the region has to fit the extension
+    let error = set_extension(&mut [0u8; EXTENSION_HEADER_SIZE + 31], &referrer).unwrap_err();
+    assert_eq!(
+        anchor_error_code(error),
+        u32::from(ErrorCode::InvalidAccountExtension),
+        "incorrect error"
+    );
+
+    // a length past the end of the region
+    let mut corrupted = [0u8; 16];
+    corrupted[..4].copy_from_slice(&[ExtensionType::TreasuryReferrer as u8, 0, 32, 0]);
+    let error = get_extension::<TreasuryReferrer>(&corrupted).err().unwrap();
+    assert_eq!(
+        anchor_error_code(error),
+        u32::from(ErrorCode::InvalidAccountExtension),
+        "incorrect error"
+    );
+
+    // the region starts after the fixed fields of the account layout version
+    for (version, offset) in [(2, TREASURY_V2_EXTENSIONS_OFFSET), (3, TREASURY_V3_SIZE)] {
+        let mut data = vec![0u8; offset + 36];
+        data[9] = version;
+        set_extension(&mut data[offset..], &referrer).unwrap();
+        let extension = get_data_extension::<Treasury, TreasuryReferrer>(&data).unwrap();
+        assert_eq!(
+            extension.unwrap().referrer_address,
+            referrer.referrer_address
+        );
+    }
+}
+
+#[test]
+fn tlv_extension_region_test() {
+    // an empty region has no extensions
+    let mut region = [0u8; 16];
+    assert_eq!(get_extensions_len(&region).unwrap(), 0);
+
+    // the extensions end at the first uninitialized header
+    region[..4].copy_from_slice(&[1, 0, 2, 0]);
+    assert_eq!(get_extensions_len(&region).unwrap(), EXTENSION_HEADER_SIZE + 2);
+
+    // a length past the end of the region
+    region[..4].copy_from_slice(&[1, 0, 32, 0]);
+    let error = get_extensions_len(&region).unwrap_err();
+    assert_eq!(
+        anchor_error_code(error),
+        u32::from(ErrorCode::InvalidAccountExtension),
+        "incorrect error"
+    );
+
+    // the region starts after the fixed fields of the account layout version
+    for (version, offset) in [(2, TREASURY_V2_EXTENSIONS_OFFSET), (3, TREASURY_V3_SIZE)] {
+        let mut data = vec![0u8; offset + EXTENSION_HEADER_SIZE];
+        data[9] = version;
+        assert_eq!(get_extensions_offset::<Treasury>(&data), offset);
+    }
+    for (version, offset) in [(2, STREAM_V2_EXTENSIONS_OFFSET), (3, STREAM_V3_SIZE)] {
+        let mut data = vec![0u8; offset + EXTENSION_HEADER_SIZE];
+        data[8] = version;
+        assert_eq!(get_extensions_offset::<Stream>(&data), offset);
+    }
+}
+
+#[tokio::test]
+async fn config_test() {
+    let (admin, admin_account) = user_account();
+    let (new_admin, new_admin_account) = user_account();
+    let (beneficiary, beneficiary_account) = user_account();
+    let mut fixture = treasury_fixture(
+        vec![
+            (admin.pubkey(), admin_account),
+            (new_admin.pubkey(), new_admin_account),
+            (beneficiary.pubkey(), beneficiary_account),
+        ],
+        1000,
+    )
+    .await;
+
+    // only the maintenance authority can initialize the config
+    let instruction = init_config_ix(&fixture, &admin.pubkey(), FeeSchedule::default());
+    let result = process_instructions(&mut fixture.context, &[instruction], &[&admin]).await;
+    assert_custom_error(result, ErrorCode::NotAuthorized);
+    set_config(&mut fixture, &admin.pubkey()).await;
+
+    let mut fees = FeeSchedule {
+        withdraw_percent_fee: 10_000,
+        ..FeeSchedule::default()
+    };
+    let mut invalid_fees = fees;
+    invalid_fees.withdraw_percent_fee = 1_000_001;
+    // (signer, fees, error)
+    let rejections = [
+        (&beneficiary, fees, ErrorCode::NotAuthorized),
+        (&admin, invalid_fees, ErrorCode::InvalidFeeSchedule),
+    ];
+    for (signer, fees, error) in rejections {
+        let instruction = update_config_ix(&fixture, &signer.pubkey(), &admin.pubkey(), fees);
+        let result = process_instructions(&mut fixture.context, &[instruction], &[signer]).await;
+        assert_custom_error(result, error);
+    }
+
+    let instruction = update_config_ix(&fixture, &admin.pubkey(), &admin.pubkey(), fees);
+    process_instructions(&mut fixture.context, &[instruction], &[&admin])
+        .await
+        .unwrap();
+    let config = fetch_config(&fixture.context).await;
+    assert_eq!(config.admin_address, admin.pubkey(), "incorrect admin");
+    assert_eq!(
+        config.fee_treasury_address, fixture.fee_pubkey,
+        "incorrect fee treasury"
+    );
+    assert_eq!(config.fees, fees, "incorrect fees");
+
+    // the new withdraw fee (1%) is charged
+    let stream_key = Keypair::new();
+    let instruction = create_stream_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        400,
+        400,
+        false,
+    );
+    process_instructions(
+        &mut fixture.context,
+        &[instruction],
+        &[&fixture.treasurer, &stream_key],
+    )
+    .await
+    .unwrap();
+    let fees_before = fetch_token_amount(&fixture.context, fixture.fees_token).await;
+    let instruction = withdraw_ix(
+        &fixture,
+        &stream_key.pubkey(),
+        &beneficiary.pubkey(),
+        400,
+        &[],
+    );
+    process_instructions(&mut fixture.context, &[instruction], &[&beneficiary])
+        .await
+        .unwrap();
+    let beneficiary_token = associated_token::get_associated_token_address(
+        &beneficiary.pubkey(),
+        &fixture.treasury_token_mint,
+    );
+    assert_eq!(
+        fetch_token_amount(&fixture.context, beneficiary_token).await,
+        396,
+        "incorrect beneficiary amount"
+    );
+    assert_eq!(
+        fetch_token_amount(&fixture.context, fixture.fees_token).await - fees_before,
+        4,
+        "incorrect fee"
+    );
+
+    // handing over the config to a new admin
+    fees.withdraw_percent_fee = 5_000;
+    let instruction = update_config_ix(&fixture, &admin.pubkey(), &new_admin.pubkey(), fees);
+    process_instructions(&mut fixture.context, &[instruction], &[&admin])
+        .await
+        .unwrap();
+    let instruction = update_config_ix(&fixture, &admin.pubkey(), &admin.pubkey(), fees);
+    let result = process_instructions(&mut fixture.context, &[instruction], &[&admin]).await;
+    assert_custom_error(result, ErrorCode::NotAuthorized);
+    let instruction = update_config_ix(&fixture, &new_admin.pubkey(), &new_admin.pubkey(), fees);
+    process_instructions(&mut fixture.context, &[instruction], &[&new_admin])
+        .await
+        .unwrap();
+    let config = fetch_config(&fixture.context).await;
+    assert_eq!(config.admin_address, new_admin.pubkey(), "incorrect admin");
+    assert_eq!(
+        config.fees.withdraw_percent_fee, 5_000,
+        "incorrect withdraw fee"
+    );
+}
+
+#[tokio::test]
+async fn fee_override_test() {
+    let (admin, admin_account) = user_account();
+    let (other_user, other_user_account) = user_account();
+    let (beneficiary, beneficiary_account) = user_account();
+    let mut fixture = treasury_fixture(
+        vec![
+            (admin.pubkey(), admin_account),
+            (other_user.pubkey(), other_user_account),
+            (beneficiary.pubkey(), beneficiary_account),
+        ],
+        1200,
+    )
+    .await;
+    set_config(&mut fixture, &admin.pubkey()).await;
+    let treasurer_pubkey = fixture.treasurer.pubkey();
+
+    // (signer, tier, error)
+    let rejections = [
+        (&other_user, 1, ErrorCode::NotAuthorized),
+        (&admin, 0, ErrorCode::InvalidFeeTier),
+    ];
+    for (signer, tier, error) in rejections {
+        let instruction = set_fee_override_ix(
+            &fixture,
+            &signer.pubkey(),
+            &treasurer_pubkey,
+            tier,
+            FeeSchedule::default(),
+        );
+        let result = process_instructions(&mut fixture.context, &[instruction], &[signer]).await;
+        assert_custom_error(result, error);
+    }
+
+    // (subject, tier, withdraw fee, charged fee on 400). An override for a
+    // wallet other than the treasury or its authority is ignored
+    let tiers = [
+        (treasurer_pubkey, 1, 10_000, 4),
+        (fixture.treasury_pubkey, 2, 20_000, 8),
+        (other_user.pubkey(), 3, 50_000, 1),
+    ];
+    for (subject, tier, withdraw_percent_fee, fee) in tiers {
+        let fees = FeeSchedule {
+            withdraw_percent_fee,
+            ..FeeSchedule::default()
+        };
+        let instruction = set_fee_override_ix(&fixture, &admin.pubkey(), &subject, tier, fees);
+        process_instructions(&mut fixture.context, &[instruction], &[&admin])
+            .await
+            .unwrap();
+        let fee_override = fetch_fee_override(&fixture.context, &subject).await;
+        assert_eq!(fee_override.subject_address, subject, "incorrect subject");
+        assert_eq!(fee_override.tier, tier, "incorrect tier");
+        assert_eq!(fee_override.fees, fees, "incorrect fees");
+
+        let stream_key = Keypair::new();
+        let instruction = create_stream_ix(
+            &fixture,
+            &stream_key.pubkey(),
+            &beneficiary.pubkey(),
+            400,
+            400,
+            false,
+        );
+        process_instructions(
+            &mut fixture.context,
+            &[instruction],
+            &[&fixture.treasurer, &stream_key],
+        )
+        .await
+        .unwrap();
+        let fees_before = fetch_token_amount(&fixture.context, fixture.fees_token).await;
+        let mut instruction = withdraw_ix(
+            &fixture,
+            &stream_key.pubkey(),
+            &beneficiary.pubkey(),
+            400,
+            &[],
+        );
+        instruction.accounts[8].pubkey = fee_override_pubkey(&subject); // fee_override
+        process_instructions(&mut fixture.context, &[instruction], &[&beneficiary])
+            .await
+            .unwrap();
+        assert_eq!(
+            fetch_token_amount(&fixture.context, fixture.fees_token).await - fees_before,
+            fee,
+            "incorrect fee"
+        );
+    }
+
+    let instruction = remove_fee_override_ix(&fixture, &other_user.pubkey(), &treasurer_pubkey);
+    let result = process_instructions(&mut fixture.context, &[instruction], &[&other_user]).await;
+    assert_custom_error(result, ErrorCode::NotAuthorized);
+
+    // the rent goes back to the admin
+    let admin_lamports_before = fetch_lamports(&fixture.context, admin.pubkey()).await;
+    let instruction = remove_fee_override_ix(&fixture, &admin.pubkey(), &treasurer_pubkey);
+    process_instructions(&mut fixture.context, &[instruction], &[&admin])
+        .await
+        .unwrap();
+    let rent = fixture.context.banks_client.get_rent().await.unwrap();
+    assert_eq!(
+        fetch_lamports(&fixture.context, admin.pubkey()).await - admin_lamports_before,
+        rent.minimum_balance(FeeOverride::SIZE),
+        "incorrect admin lamports"
+    );
+    assert!(
+        fixture
+            .context
+            .banks_client
+            .get_account(fee_override_pubkey(&treasurer_pubkey))
+            .await
+            .unwrap()
+            .is_none(),
+        "fee override not closed"
+    );
+}
+
+async fn create_treasury(
+    context: &mut ProgramTestContext,
+    program: &anchor_client::Program,
+    parameters: CreateTreasury,
+    treasurer: &Keypair,
+    treasury_pubkey: &Pubkey,
+    treasury_token_account: &Pubkey,
+    treasury_token_mint: &Pubkey,
+    fee_pubkey: &Pubkey,
+) {
+    let create_treasury_ix = program
+        .request()
+        .accounts(msp::accounts::CreateTreasuryAccounts {
+            payer: context.payer.pubkey(),
+            treasurer: treasurer.pubkey(),
+            treasury: treasury_pubkey.key(),
+            treasury_token: treasury_token_account.clone(),
+            associated_token: treasury_token_mint.clone(),
+            config: config_pubkey(),
+            fee_treasury: fee_pubkey.key(),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        })
+        .args(parameters)
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap();
+
+    let create_treasury_tx = Transaction::new_signed_with_payer(
+        &[create_treasury_ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &treasurer],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(create_treasury_tx)
+        .await
+        .unwrap();
+}
+
+async fn create_stream(
+    context: &mut ProgramTestContext,
+    program: &anchor_client::Program,
+    parameters: CreateStream,
+    treasurer: &Keypair,
+    treasury_pubkey: &Pubkey,
+    treasury_token_account: &Pubkey,
+    treasury_token_mint: &Pubkey,
+    beneficiary_pubkey: &Pubkey,
+    fee_pubkey: &Pubkey,
+    fees_token: &Pubkey,
+) -> (Stream, Keypair) {
+    let stream_key = Keypair::new();
+    let create_stream_ix = program
+        .request()
+        .accounts(msp::accounts::CreateStreamAccounts {
+            payer: context.payer.pubkey(),
+            treasurer: treasurer.pubkey(),
+            treasury: treasury_pubkey.key(),
+            treasury_token: treasury_token_account.clone(),
+            associated_token: treasury_token_mint.clone(),
+            beneficiary: beneficiary_pubkey.key(),
+            stream: stream_key.pubkey(),
+            config: config_pubkey(),
+            fee_override: system_program::ID,
+            referrer: fee_pubkey.key(),
+            referrer_token: *fees_token,
+            fee_treasury: fee_pubkey.key(),
+            fee_treasury_token: fees_token.clone(),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        })
+        .args(parameters)
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap();
+
+    let crate_stream_tx = Transaction::new_signed_with_payer(
+        &[create_stream_ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &treasurer, &stream_key],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(crate_stream_tx)
+        .await
+        .unwrap();
+
+    let stream = fetch_stream(context, stream_key.pubkey()).await;
+    (stream, stream_key)
+}
+
+async fn allocate(
+    context: &mut ProgramTestContext,
+    program: &anchor_client::Program,
+    parameters: Allocate,
+    treasurer: &Keypair,
+    treasury_pubkey: &Pubkey,
+    stream_pubkey: Pubkey,
+    treasury_token_account: &Pubkey,
+    treasury_token_mint: &Pubkey,
+    fee_pubkey: &Pubkey,
+    fees_token: &Pubkey,
+) {
+    let allocate_ix = program
+        .request()
+        .accounts(msp::accounts::AllocateAccounts {
+            payer: context.payer.pubkey(),
+            treasurer: treasurer.pubkey(),
+            treasury: treasury_pubkey.key(),
+            treasury_token: treasury_token_account.key(),
+            associated_token: treasury_token_mint.key(),
+            stream: stream_pubkey.key(),
+            config: config_pubkey(),
+            fee_override: system_program::ID,
+            referrer: fee_pubkey.key(),
+            referrer_token: fees_token.key(),
+            fee_treasury: fee_pubkey.key(),
+            fee_treasury_token: fees_token.key(),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        })
+        .args(parameters)
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap();
+
+    let allocate_tx = Transaction::new_signed_with_payer(
+        &[allocate_ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &treasurer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(allocate_tx)
+        .await
+        .unwrap();
+}
+
+async fn add_funds(
+    context: &mut ProgramTestContext,
+    program: &anchor_client::Program,
+    parameters: AddFunds,
+    treasurer: &Keypair,
+    treasurer_token: &Pubkey,
+    treasury_pubkey: &Pubkey,
+    treasury_token_mint: &Pubkey,
+    treasury_token_account: &Pubkey,
+    fee_pubkey: &Pubkey,
+    fees_token: &Pubkey,
+) {
+    let add_funds_to_treasury_ix = program
+        .request()
+        .accounts(msp::accounts::AddFundsAccounts {
+            payer: context.payer.pubkey(),
+            contributor: treasurer.pubkey(),
+            contributor_token: treasurer_token.clone(),
+            treasury: treasury_pubkey.key(),
+            treasury_token: treasury_token_account.clone(),
+            associated_token: treasury_token_mint.clone(),
+            config: config_pubkey(),
+            fee_treasury: fee_pubkey.key(),
+            fee_treasury_token: fees_token.clone(),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        })
+        .args(parameters)
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[add_funds_to_treasury_ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &treasurer],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+fn user_account() -> (Keypair, Account) {
+    let user = Keypair::new();
+    let account = {
+        Account {
+            lamports: 1,
+            owner: system_program::ID,
+            ..Account::default()
+        }
+    };
+    (user, account)
+}
+
+fn treasury_accounts(treasurer: Pubkey, slot: u64) -> (Pubkey, Pubkey, Pubkey) {
+    let treasury_token_mint = native_mint::id();
+    let (treasury_pubkey, bump_seed) =
+        Pubkey::find_program_address(&[treasurer.as_ref(), &slot.to_le_bytes()], &msp::id());
+    println!(
+        "treasury_pubkey: {}, bump_seed: {}",
+        treasury_pubkey, bump_seed
+    );
+    let treasury_token_account =
+        associated_token::get_associated_token_address(&treasury_pubkey, &treasury_token_mint);
+    println!("treasury_token_account: {}", treasury_token_account);
+    (
+        treasury_pubkey,
+        treasury_token_mint,
+        treasury_token_account,
+    )
+}
+
+fn treasurer_accounts(treasury_token_mint: Pubkey, treasurer: Pubkey) -> (Pubkey, Account) {
+    let treasurer_token =
+        associated_token::get_associated_token_address(&treasurer, &treasury_token_mint);
+    let dst: &mut [u8] = &mut [0; spl_token::state::Account::LEN];
+    let _ = &spl_token::state::Account {
+        mint: treasury_token_mint,
+        owner: treasurer,
+        amount: 1000,
+        // delegate: COption<Pubkey>,
+        state: spl_token::state::AccountState::Initialized,
+        // If is_some, this is a native token, and the value logs the rent-exempt reserve. An Account
+        // is required to be rent-exempt, so the value is used by the Processor to ensure that wrapped
+        // SOL accounts do not drop below this threshold.
+        // is_native: COption<u64>,
+        // The amount delegated
+        // delegated_amount: u64,
+        // Optional authority to close the account.
+        // close_authority: COption<Pubkey>,
+        ..spl_token::state::Account::default()
+    }
+    .pack_into_slice(dst);
+    let treasurer_token_account = {
+        let mut data = Vec::new();
+        data.extend_from_slice(dst);
+        Account {
+            lamports: 1,
+            data: data,
+            owner: token::ID,
+            ..Account::default()
+        }
+    };
+    (treasurer_token, treasurer_token_account)
+}
+
+fn fee(treasury_token_mint: &Pubkey) -> (Pubkey, Account, Pubkey) {
+    let fee_pubkey = fee_treasury::ID;
+    let fee_account = {
+        let fee_data = Vec::new();
+        Account {
+            lamports: 1,
+            data: fee_data,
+            owner: system_program::ID,
+            ..Account::default()
+        }
+    };
+    let fees_token =
+        associated_token::get_associated_token_address(&fee_pubkey, &treasury_token_mint);
+
+    (fee_pubkey, fee_account, fees_token)
+}
+
+fn config_pubkey() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &msp::id()).0
+}
+
+async fn fetch_stream(context: &ProgramTestContext, stream_pubkey: Pubkey) -> msp::stream::Stream {
+    let mut bank_copy = context.banks_client.clone();
+    let stream_account = bank_copy.get_account(stream_pubkey).await.unwrap().unwrap();
+    assert_eq!(stream_account.data.len(), STREAM_V3_SIZE);
+    let mut slice: &[u8] = &stream_account.data;
     let stream_des = msp::stream::Stream::try_deserialize(&mut slice).unwrap();
     stream_des
 }
+
+/// A started test validator with a treasury created by `treasurer` and funded
+/// with the treasurer tokens
+struct TreasuryFixture {
+    context: ProgramTestContext,
+    program: anchor_client::Program,
+    treasurer: Keypair,
+    treasury_pubkey: Pubkey,
+    treasury_token_mint: Pubkey,
+    treasury_token_account: Pubkey,
+    treasurer_token: Pubkey,
+    fee_pubkey: Pubkey,
+    fees_token: Pubkey,
+}
+
+async fn treasury_fixture(accounts: Vec<(Pubkey, Account)>, funds: u64) -> TreasuryFixture {
+    let mut pt = ProgramTest::new("msp", msp::id(), None);
+    let (treasurer, treasurer_account) = user_account();
+
+    let slot = 0_u64;
+    let (treasury_pubkey, treasury_token_mint, treasury_token_account) =
+        treasury_accounts(treasurer.pubkey(), slot);
+    let (treasurer_token, treasurer_token_account) =
+        token_account(treasury_token_mint, treasurer.pubkey(), funds);
+    let (fee_pubkey, fee_account, fees_token) = fee(&treasury_token_mint);
+
+    pt.add_account(fee_pubkey, fee_account);
+    pt.add_account(treasurer.pubkey(), treasurer_account);
+    pt.add_account(treasurer_token, treasurer_token_account);
+    for (pubkey, account) in accounts {
+        pt.add_account(pubkey, account);
+    }
+    pt.set_compute_max_units(200_000); // maximum number of instructions allowed
+
+    let mut context = pt.start_with_context().await;
+
+    let client = Client::new_with_options(
+        Cluster::Debug,
+        Rc::new(Keypair::new()),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(msp::id());
+
+    create_treasury(
+        &mut context,
+        &program,
+        CreateTreasury {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            slot,
+            name: "test treasury".to_string(),
+            treasury_type: TreasuryType::Opened as u8,
+            auto_close: false,
+            sol_fee_payed_by_treasury: true,
+            category: Category::Default,
+            sub_category: SubCategory::Default,
+            referrer: None,
+        },
+        &treasurer,
+        &treasury_pubkey,
+        &treasury_token_account,
+        &treasury_token_mint,
+        &fee_pubkey,
+    )
+    .await;
+
+    add_funds(
+        &mut context,
+        &program,
+        AddFunds {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            amount: funds,
+        },
+        &treasurer,
+        &treasurer_token,
+        &treasury_pubkey,
+        &treasury_token_mint,
+        &treasury_token_account,
+        &fee_pubkey,
+        &fees_token,
+    )
+    .await;
+
+    // the treasurer token account is not a native one, so the wrapped SOL
+    // added to the treasury token account has no lamports behind it and the
+    // token transfers out of the treasury would leave it rent paying
+    let back_funds_ix =
+        system_instruction::transfer(&context.payer.pubkey(), &treasury_token_account, funds);
+    process_instructions(&mut context, &[back_funds_ix], &[])
+        .await
+        .unwrap();
+
+    TreasuryFixture {
+        context,
+        program,
+        treasurer,
+        treasury_pubkey,
+        treasury_token_mint,
+        treasury_token_account,
+        treasurer_token,
+        fee_pubkey,
+        fees_token,
+    }
+}
+
+/// Signs the instructions with the payer and the given signers and processes
+/// them in a single transaction
+async fn process_instructions(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> std::result::Result<(), TransportError> {
+    // a new blockhash for each transaction, otherwise sending the same
+    // instructions again would be rejected as already processed
+    loop {
+        let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+        if blockhash != context.last_blockhash {
+            context.last_blockhash = blockhash;
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+/// Asserts the transaction failed with the given program error
+fn assert_custom_error(result: std::result::Result<(), TransportError>, error: ErrorCode) {
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(u32::from(error))),
+        "incorrect error"
+    );
+}
+
+fn anchor_error_code(error: anchor_lang::error::Error) -> u32 {
+    match error {
+        anchor_lang::error::Error::AnchorError(error) => error.error_code_number,
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
+
+fn create_stream_with_schedule_ix(
+    fixture: &TreasuryFixture,
+    stream_pubkey: &Pubkey,
+    beneficiary_pubkey: &Pubkey,
+    allocation_assigned_units: u64,
+    points: Vec<SchedulePoint>,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::CreateStreamWithScheduleAccounts {
+            payer: fixture.context.payer.pubkey(),
+            treasurer: fixture.treasurer.pubkey(),
+            treasury: fixture.treasury_pubkey,
+            treasury_token: fixture.treasury_token_account,
+            associated_token: fixture.treasury_token_mint,
+            beneficiary: *beneficiary_pubkey,
+            stream: *stream_pubkey,
+            schedule: schedule_pubkey(stream_pubkey),
+            config: config_pubkey(),
+            fee_override: system_program::ID,
+            referrer: fixture.fee_pubkey,
+            referrer_token: fixture.fees_token,
+            fee_treasury: fixture.fee_pubkey,
+            fee_treasury_token: fixture.fees_token,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        })
+        .args(CreateStreamWithSchedule {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            name: "test scheduled stream".to_string(),
+            allocation_assigned_units,
+            fee_payed_by_treasurer: false,
+            points,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn withdraw_ix(
+    fixture: &TreasuryFixture,
+    stream_pubkey: &Pubkey,
+    beneficiary_pubkey: &Pubkey,
+    amount: u64,
+    remaining_accounts: &[AccountMeta],
+) -> Instruction {
+    let mut instruction = fixture
+        .program
+        .request()
+        .accounts(msp::accounts::WithdrawAccounts {
+            payer: fixture.context.payer.pubkey(),
+            beneficiary: *beneficiary_pubkey,
+            beneficiary_token: associated_token::get_associated_token_address(
+                beneficiary_pubkey,
+                &fixture.treasury_token_mint,
+            ),
+            associated_token: fixture.treasury_token_mint,
+            treasury: fixture.treasury_pubkey,
+            treasury_token: fixture.treasury_token_account,
+            stream: *stream_pubkey,
+            config: config_pubkey(),
+            fee_override: system_program::ID,
+            referrer: fixture.fee_pubkey,
+            referrer_token: fixture.fees_token,
+            fee_treasury: fixture.fee_pubkey,
+            fee_treasury_token: fixture.fees_token,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        })
+        .args(Withdraw {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            amount,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap();
+    instruction.accounts.extend_from_slice(remaining_accounts);
+    instruction
+}
+
+fn withdraw_many_ix(
+    fixture: &TreasuryFixture,
+    beneficiary_pubkey: &Pubkey,
+    remaining_accounts: &[AccountMeta],
+) -> Instruction {
+    let mut instruction = fixture
+        .program
+        .request()
+        .accounts(msp::accounts::WithdrawManyAccounts {
+            payer: fixture.context.payer.pubkey(),
+            beneficiary: *beneficiary_pubkey,
+            beneficiary_token: associated_token::get_associated_token_address(
+                beneficiary_pubkey,
+                &fixture.treasury_token_mint,
+            ),
+            associated_token: fixture.treasury_token_mint,
+            config: config_pubkey(),
+            fee_override: system_program::ID,
+            referrer: fixture.fee_pubkey,
+            referrer_token: fixture.fees_token,
+            fee_treasury: fixture.fee_pubkey,
+            fee_treasury_token: fixture.fees_token,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        })
+        .args(WithdrawMany {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap();
+    instruction.accounts.extend_from_slice(remaining_accounts);
+    instruction
+}
+
+fn close_stream_ix(
+    fixture: &TreasuryFixture,
+    stream_pubkey: &Pubkey,
+    beneficiary_pubkey: &Pubkey,
+    remaining_accounts: &[AccountMeta],
+) -> Instruction {
+    let mut instruction = fixture
+        .program
+        .request()
+        .accounts(msp::accounts::CloseStreamAccounts {
+            payer: fixture.context.payer.pubkey(),
+            treasurer: fixture.treasurer.pubkey(),
+            beneficiary: *beneficiary_pubkey,
+            beneficiary_token: associated_token::get_associated_token_address(
+                beneficiary_pubkey,
+                &fixture.treasury_token_mint,
+            ),
+            associated_token: fixture.treasury_token_mint,
+            treasury: fixture.treasury_pubkey,
+            treasury_token: fixture.treasury_token_account,
+            stream: *stream_pubkey,
+            config: config_pubkey(),
+            fee_override: system_program::ID,
+            referrer: fixture.fee_pubkey,
+            referrer_token: fixture.fees_token,
+            fee_treasury: fixture.fee_pubkey,
+            fee_treasury_token: fixture.fees_token,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        })
+        .args(CloseStream {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap();
+    instruction.accounts.extend_from_slice(remaining_accounts);
+    instruction
+}
+
+fn schedule_pubkey(stream_pubkey: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"schedule", stream_pubkey.as_ref()], &msp::id()).0
+}
+
+async fn fetch_account<T: AccountDeserialize>(context: &ProgramTestContext, pubkey: Pubkey) -> T {
+    let mut bank_copy = context.banks_client.clone();
+    let account = bank_copy.get_account(pubkey).await.unwrap().unwrap();
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+fn update_stream_rate_ix(
+    fixture: &TreasuryFixture,
+    stream_pubkey: &Pubkey,
+    rate_amount_units: u64,
+    rate_interval_in_seconds: u64,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::UpdateStreamRateAccounts {
+            treasurer: fixture.treasurer.pubkey(),
+            treasury: fixture.treasury_pubkey,
+            stream: *stream_pubkey,
+        })
+        .args(UpdateStreamRate {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            rate_amount_units,
+            rate_interval_in_seconds,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn push_withdraw_ix(
+    fixture: &TreasuryFixture,
+    stream_pubkey: &Pubkey,
+    beneficiary_pubkey: &Pubkey,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::PushWithdrawAccounts {
+            payer: fixture.context.payer.pubkey(),
+            beneficiary: *beneficiary_pubkey,
+            beneficiary_token: associated_token::get_associated_token_address(
+                beneficiary_pubkey,
+                &fixture.treasury_token_mint,
+            ),
+            associated_token: fixture.treasury_token_mint,
+            treasury: fixture.treasury_pubkey,
+            treasury_token: fixture.treasury_token_account,
+            stream: *stream_pubkey,
+            config: config_pubkey(),
+            fee_override: system_program::ID,
+            referrer: fixture.fee_pubkey,
+            referrer_token: fixture.fees_token,
+            fee_treasury: fixture.fee_pubkey,
+            fee_treasury_token: fixture.fees_token,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        })
+        .args(PushWithdraw {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+async fn fetch_lamports(context: &ProgramTestContext, pubkey: Pubkey) -> u64 {
+    let mut bank_copy = context.banks_client.clone();
+    bank_copy.get_balance(pubkey).await.unwrap()
+}
+
+fn create_streams_batch_ix(
+    fixture: &TreasuryFixture,
+    entries: Vec<StreamBatchEntry>,
+    stream_pubkeys: &[Pubkey],
+) -> Instruction {
+    let mut instruction = fixture
+        .program
+        .request()
+        .accounts(msp::accounts::CreateStreamsBatchAccounts {
+            payer: fixture.context.payer.pubkey(),
+            treasurer: fixture.treasurer.pubkey(),
+            treasury: fixture.treasury_pubkey,
+            treasury_token: fixture.treasury_token_account,
+            associated_token: fixture.treasury_token_mint,
+            config: config_pubkey(),
+            fee_override: system_program::ID,
+            referrer: fixture.fee_pubkey,
+            referrer_token: fixture.fees_token,
+            fee_treasury: fixture.fee_pubkey,
+            fee_treasury_token: fixture.fees_token,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        })
+        .args(CreateStreamsBatch {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            name: "test batch stream".to_string(),
+            start_utc: 0,
+            fee_payed_by_treasurer: false,
+            accrual_mode: msp::constants::STREAM_ACCRUAL_MODE_LINEAR,
+            cliff_utc: 0,
+            entries,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap();
+    instruction.accounts.extend(
+        stream_pubkeys
+            .iter()
+            .map(|stream_pubkey| AccountMeta::new(*stream_pubkey, false)),
+    );
+    instruction
+}
+
+fn stream_pda_pubkey(treasury_pubkey: &Pubkey, stream_pda_seed: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"stream", treasury_pubkey.as_ref(), stream_pda_seed.as_ref()],
+        &msp::id(),
+    )
+    .0
+}
+
+/// Replaces the data of a program account, padded with zeros to `size`
+/// bytes and funded with the rent for that size
+async fn set_program_account(
+    context: &mut ProgramTestContext,
+    pubkey: &Pubkey,
+    mut data: Vec<u8>,
+    size: usize,
+) {
+    let rent = context.banks_client.get_rent().await.unwrap();
+    data.resize(size, 0);
+    context.set_account(
+        pubkey,
+        &AccountSharedData::from(Account {
+            lamports: rent.minimum_balance(size),
+            data,
+            owner: msp::id(),
+            ..Account::default()
+        }),
+    );
+}
+
+async fn fetch_stream_v2(context: &ProgramTestContext, stream_pubkey: Pubkey) -> Stream {
+    let mut bank_copy = context.banks_client.clone();
+    let stream_account = bank_copy.get_account(stream_pubkey).await.unwrap().unwrap();
+    assert_eq!(stream_account.data.len(), STREAM_V2_SIZE);
+    Stream::try_deserialize(&mut stream_account.data.as_slice()).unwrap()
+}
+
+fn migrate_stream_ix(fixture: &TreasuryFixture, stream_pubkey: &Pubkey) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::MigrateStreamAccounts {
+            authority: fixture.treasurer.pubkey(),
+            treasury: fixture.treasury_pubkey,
+            stream: *stream_pubkey,
+            system_program: system_program::ID,
+        })
+        .args(MigrateStream {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn refresh_treasury_data_ix(fixture: &TreasuryFixture) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::RefreshTreasuryDataAccounts {
+            associated_token: fixture.treasury_token_mint,
+            treasury: fixture.treasury_pubkey,
+            treasury_token: fixture.treasury_token_account,
+        })
+        .args(RefreshTreasuryData {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn migrate_treasury_ix(fixture: &TreasuryFixture) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::MigrateTreasuryAccounts {
+            authority: fixture.treasurer.pubkey(),
+            treasury: fixture.treasury_pubkey,
+            system_program: system_program::ID,
+        })
+        .args(MigrateTreasury {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn create_treasury_ix(
+    fixture: &TreasuryFixture,
+    slot: u64,
+    mint: &Pubkey,
+    referrer: Option<Pubkey>,
+) -> Instruction {
+    let treasury_pubkey = Pubkey::find_program_address(
+        &[fixture.treasurer.pubkey().as_ref(), &slot.to_le_bytes()],
+        &msp::id(),
+    )
+    .0;
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::CreateTreasuryAccounts {
+            payer: fixture.context.payer.pubkey(),
+            treasurer: fixture.treasurer.pubkey(),
+            treasury: treasury_pubkey,
+            treasury_token: associated_token::get_associated_token_address(&treasury_pubkey, mint),
+            associated_token: *mint,
+            config: config_pubkey(),
+            fee_treasury: fixture.fee_pubkey,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        })
+        .args(CreateTreasury {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            slot,
+            name: "test treasury".to_string(),
+            treasury_type: TreasuryType::Opened as u8,
+            auto_close: false,
+            sol_fee_payed_by_treasury: true,
+            category: Category::Default,
+            sub_category: SubCategory::Default,
+            referrer,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+/// Creates a native SOL treasury of the fixture treasurer with the given slot
+/// and funds it with the payer lamports
+async fn native_treasury(fixture: &mut TreasuryFixture, slot: u64, funds: u64) -> Pubkey {
+    let treasury_pubkey = Pubkey::find_program_address(
+        &[fixture.treasurer.pubkey().as_ref(), &slot.to_le_bytes()],
+        &msp::id(),
+    )
+    .0;
+    let create_native_treasury_ix = fixture
+        .program
+        .request()
+        .accounts(msp::accounts::CreateNativeTreasuryAccounts {
+            payer: fixture.context.payer.pubkey(),
+            treasurer: fixture.treasurer.pubkey(),
+            treasury: treasury_pubkey,
+            config: config_pubkey(),
+            fee_treasury: fixture.fee_pubkey,
+            system_program: system_program::ID,
+        })
+        .args(CreateNativeTreasury {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            slot,
+            name: "test native treasury".to_string(),
+            treasury_type: TreasuryType::Opened as u8,
+            auto_close: false,
+            category: Category::Default,
+            sub_category: SubCategory::Default,
+            referrer: None,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap();
+    let add_funds_native_ix = fixture
+        .program
+        .request()
+        .accounts(msp::accounts::AddFundsNativeAccounts {
+            payer: fixture.context.payer.pubkey(),
+            contributor: fixture.context.payer.pubkey(),
+            treasury: treasury_pubkey,
+            config: config_pubkey(),
+            fee_treasury: fixture.fee_pubkey,
+            system_program: system_program::ID,
+        })
+        .args(AddFundsNative {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            amount: funds,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap();
+    process_instructions(
+        &mut fixture.context,
+        &[create_native_treasury_ix, add_funds_native_ix],
+        &[&fixture.treasurer],
+    )
+    .await
+    .unwrap();
+    treasury_pubkey
+}
+
+fn create_stream_native_ix(
+    fixture: &TreasuryFixture,
+    treasury_pubkey: &Pubkey,
+    stream_pubkey: &Pubkey,
+    beneficiary_pubkey: &Pubkey,
+    allocation_assigned_units: u64,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::CreateStreamNativeAccounts {
+            payer: fixture.context.payer.pubkey(),
+            treasurer: fixture.treasurer.pubkey(),
+            treasury: *treasury_pubkey,
+            beneficiary: *beneficiary_pubkey,
+            stream: *stream_pubkey,
+            config: config_pubkey(),
+            fee_override: system_program::ID,
+            referrer: fixture.fee_pubkey,
+            fee_treasury: fixture.fee_pubkey,
+            system_program: system_program::ID,
+        })
+        .args(CreateStreamNative {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            name: "test native stream".to_string(),
+            start_utc: 0,
+            rate_amount_units: 1_000,
+            rate_interval_in_seconds: 60,
+            allocation_assigned_units,
+            cliff_vest_amount_units: 0,
+            fee_payed_by_treasurer: false,
+            accrual_mode: msp::constants::STREAM_ACCRUAL_MODE_LINEAR,
+            cliff_utc: 0,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn treasury_withdraw_native_ix(
+    fixture: &TreasuryFixture,
+    treasury_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::TreasuryWithdrawNativeAccounts {
+            treasurer: fixture.treasurer.pubkey(),
+            destination_authority: *destination_pubkey,
+            treasury: *treasury_pubkey,
+            config: config_pubkey(),
+            fee_override: system_program::ID,
+            referrer: fixture.fee_pubkey,
+            fee_treasury: fixture.fee_pubkey,
+            system_program: system_program::ID,
+        })
+        .args(TreasuryWithdrawNative {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            amount,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn close_treasury_native_ix(
+    fixture: &TreasuryFixture,
+    treasury_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::CloseTreasuryNativeAccounts {
+            payer: fixture.context.payer.pubkey(),
+            treasurer: fixture.treasurer.pubkey(),
+            destination_authority: *destination_pubkey,
+            treasury: *treasury_pubkey,
+            config: config_pubkey(),
+            fee_treasury: fixture.fee_pubkey,
+            system_program: system_program::ID,
+        })
+        .args(CloseTreasuryNative {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+/// Gets a token account of the owner with the given amount
+fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> (Pubkey, Account) {
+    let pubkey = associated_token::get_associated_token_address(&owner, &mint);
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint,
+        owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..spl_token::state::Account::default()
+    }
+    .pack_into_slice(&mut data);
+    let account = Account {
+        lamports: Rent::default().minimum_balance(spl_token::state::Account::LEN),
+        data,
+        owner: token::ID,
+        ..Account::default()
+    };
+    (pubkey, account)
+}
+
+async fn fetch_token_amount(context: &ProgramTestContext, pubkey: Pubkey) -> u64 {
+    let mut bank_copy = context.banks_client.clone();
+    let account = bank_copy.get_account(pubkey).await.unwrap().unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+/// Passes the referrer accounts to an instruction built with the fee treasury
+/// ones as the `referrer` and `referrer_token` accounts
+fn set_referrer_accounts(
+    instruction: &mut Instruction,
+    fixture: &TreasuryFixture,
+    referrer: &Pubkey,
+) {
+    // the referrer accounts go before the fee treasury ones
+    let index = instruction
+        .accounts
+        .iter()
+        .position(|account| account.pubkey == fixture.fee_pubkey)
+        .unwrap();
+    instruction.accounts[index].pubkey = *referrer;
+    if instruction.accounts[index + 1].pubkey == fixture.fees_token {
+        instruction.accounts[index + 1].pubkey =
+            associated_token::get_associated_token_address(referrer, &fixture.treasury_token_mint);
+    }
+}
+
+fn create_stream_ix(
+    fixture: &TreasuryFixture,
+    stream_pubkey: &Pubkey,
+    beneficiary_pubkey: &Pubkey,
+    allocation_assigned_units: u64,
+    cliff_vest_amount_units: u64,
+    fee_payed_by_treasurer: bool,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::CreateStreamAccounts {
+            payer: fixture.context.payer.pubkey(),
+            treasurer: fixture.treasurer.pubkey(),
+            treasury: fixture.treasury_pubkey,
+            treasury_token: fixture.treasury_token_account,
+            associated_token: fixture.treasury_token_mint,
+            beneficiary: *beneficiary_pubkey,
+            stream: *stream_pubkey,
+            config: config_pubkey(),
+            fee_override: system_program::ID,
+            referrer: fixture.fee_pubkey,
+            referrer_token: fixture.fees_token,
+            fee_treasury: fixture.fee_pubkey,
+            fee_treasury_token: fixture.fees_token,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        })
+        .args(CreateStream {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            name: "test stream".to_string(),
+            start_utc: 0,
+            rate_amount_units: 1_000,
+            rate_interval_in_seconds: 60,
+            allocation_assigned_units,
+            cliff_vest_amount_units,
+            cliff_vest_percent: 0,
+            fee_payed_by_treasurer,
+            accrual_mode: msp::constants::STREAM_ACCRUAL_MODE_LINEAR,
+            cliff_utc: 0,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn renounce_stream_ix(
+    fixture: &TreasuryFixture,
+    stream_pubkey: &Pubkey,
+    beneficiary_pubkey: &Pubkey,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::RenounceStreamAccounts {
+            payer: fixture.context.payer.pubkey(),
+            beneficiary: *beneficiary_pubkey,
+            beneficiary_token: associated_token::get_associated_token_address(
+                beneficiary_pubkey,
+                &fixture.treasury_token_mint,
+            ),
+            associated_token: fixture.treasury_token_mint,
+            treasurer: fixture.treasurer.pubkey(),
+            treasury: fixture.treasury_pubkey,
+            treasury_token: fixture.treasury_token_account,
+            stream: *stream_pubkey,
+            config: config_pubkey(),
+            fee_override: system_program::ID,
+            referrer: fixture.fee_pubkey,
+            referrer_token: fixture.fees_token,
+            fee_treasury: fixture.fee_pubkey,
+            fee_treasury_token: fixture.fees_token,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        })
+        .args(RenounceStream {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn withdraw_to_destination_ix(
+    fixture: &TreasuryFixture,
+    stream_pubkey: &Pubkey,
+    beneficiary_pubkey: &Pubkey,
+    destination_token: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::WithdrawToDestinationAccounts {
+            beneficiary: *beneficiary_pubkey,
+            destination_token: *destination_token,
+            associated_token: fixture.treasury_token_mint,
+            treasury: fixture.treasury_pubkey,
+            treasury_token: fixture.treasury_token_account,
+            stream: *stream_pubkey,
+            config: config_pubkey(),
+            fee_override: system_program::ID,
+            referrer: fixture.fee_pubkey,
+            referrer_token: fixture.fees_token,
+            fee_treasury: fixture.fee_pubkey,
+            fee_treasury_token: fixture.fees_token,
+            token_program: token::ID,
+        })
+        .args(WithdrawToDestination {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            amount,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn propose_stream_transfer_ix(
+    fixture: &TreasuryFixture,
+    stream_pubkey: &Pubkey,
+    beneficiary_pubkey: &Pubkey,
+    new_beneficiary: &Pubkey,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::TransferStreamAccounts {
+            beneficiary: *beneficiary_pubkey,
+            stream: *stream_pubkey,
+            config: config_pubkey(),
+            fee_treasury: fixture.fee_pubkey,
+            system_program: system_program::ID,
+        })
+        .args(ProposeStreamTransfer {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            new_beneficiary: *new_beneficiary,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn cancel_stream_transfer_ix(
+    fixture: &TreasuryFixture,
+    stream_pubkey: &Pubkey,
+    beneficiary_pubkey: &Pubkey,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::CancelStreamTransferAccounts {
+            beneficiary: *beneficiary_pubkey,
+            stream: *stream_pubkey,
+        })
+        .args(CancelStreamTransfer {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn accept_stream_transfer_ix(
+    fixture: &TreasuryFixture,
+    stream_pubkey: &Pubkey,
+    new_beneficiary: &Pubkey,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::AcceptStreamTransferAccounts {
+            new_beneficiary: *new_beneficiary,
+            stream: *stream_pubkey,
+        })
+        .args(AcceptStreamTransfer {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn propose_treasury_authority_ix(
+    fixture: &TreasuryFixture,
+    authority: &Pubkey,
+    new_authority: &Pubkey,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::ProposeTreasuryAuthorityAccounts {
+            authority: *authority,
+            treasury: fixture.treasury_pubkey,
+        })
+        .args(ProposeTreasuryAuthority {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            new_authority: *new_authority,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn accept_treasury_authority_ix(fixture: &TreasuryFixture, new_authority: &Pubkey) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::AcceptTreasuryAuthorityAccounts {
+            new_authority: *new_authority,
+            treasury: fixture.treasury_pubkey,
+        })
+        .args(AcceptTreasuryAuthority {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+fn allocate_to_all_streams_ix(
+    fixture: &TreasuryFixture,
+    treasurer: &Pubkey,
+    amount: u64,
+    split_mode: u8,
+    remaining_accounts: &[AccountMeta],
+) -> Instruction {
+    let mut instruction = fixture
+        .program
+        .request()
+        .accounts(msp::accounts::AllocateToAllStreamsAccounts {
+            payer: fixture.context.payer.pubkey(),
+            treasurer: *treasurer,
+            treasury: fixture.treasury_pubkey,
+            treasury_token: fixture.treasury_token_account,
+            associated_token: fixture.treasury_token_mint,
+            config: config_pubkey(),
+            fee_override: system_program::ID,
+            referrer: fixture.fee_pubkey,
+            referrer_token: fixture.fees_token,
+            fee_treasury: fixture.fee_pubkey,
+            fee_treasury_token: fixture.fees_token,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        })
+        .args(AllocateToAllStreams {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            amount,
+            split_mode,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap();
+    instruction.accounts.extend_from_slice(remaining_accounts);
+    instruction
+}
+
+fn close_treasury_and_streams_ix(
+    fixture: &TreasuryFixture,
+    treasurer: &Pubkey,
+    remaining_accounts: &[AccountMeta],
+) -> Instruction {
+    let mut instruction = fixture
+        .program
+        .request()
+        .accounts(msp::accounts::CloseTreasuryAndStreamsAccounts {
+            payer: fixture.context.payer.pubkey(),
+            treasurer: *treasurer,
+            destination_authority: fixture.treasurer.pubkey(),
+            destination_token_account: fixture.treasurer_token,
+            associated_token: fixture.treasury_token_mint,
+            treasury: fixture.treasury_pubkey,
+            treasury_token: fixture.treasury_token_account,
+            config: config_pubkey(),
+            fee_override: system_program::ID,
+            referrer: fixture.fee_pubkey,
+            referrer_token: fixture.fees_token,
+            fee_treasury: fixture.fee_pubkey,
+            fee_treasury_token: fixture.fees_token,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        })
+        .args(CloseTreasuryAndStreams {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap();
+    instruction.accounts.extend_from_slice(remaining_accounts);
+    instruction
+}
+fn init_config_ix(fixture: &TreasuryFixture, authority: &Pubkey, fees: FeeSchedule) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::InitConfigAccounts {
+            payer: fixture.context.payer.pubkey(),
+            authority: *authority,
+            config: config_pubkey(),
+            system_program: system_program::ID,
+        })
+        .args(InitConfig {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            admin: *authority,
+            fee_treasury: fixture.fee_pubkey,
+            fees,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn update_config_ix(
+    fixture: &TreasuryFixture,
+    admin: &Pubkey,
+    new_admin: &Pubkey,
+    fees: FeeSchedule,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::UpdateConfigAccounts {
+            admin: *admin,
+            config: config_pubkey(),
+        })
+        .args(UpdateConfig {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            admin: *new_admin,
+            fee_treasury: fixture.fee_pubkey,
+            fees,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+/// Writes an initialized config with the default fees and the given admin
+/// (`init_config` needs the maintenance authority key)
+async fn set_config(fixture: &mut TreasuryFixture, admin: &Pubkey) {
+    let config = Config {
+        version: 1,
+        bump: Pubkey::find_program_address(&[b"config"], &msp::id()).1,
+        admin_address: *admin,
+        fee_treasury_address: fixture.fee_pubkey,
+        fees: FeeSchedule::default(),
+    };
+    let mut data = Vec::new();
+    config.try_serialize(&mut data).unwrap();
+    set_program_account(&mut fixture.context, &config_pubkey(), data, Config::SIZE).await;
+}
+
+async fn fetch_config(context: &ProgramTestContext) -> Config {
+    let mut bank_copy = context.banks_client.clone();
+    let config_account = bank_copy
+        .get_account(config_pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    Config::try_deserialize(&mut config_account.data.as_slice()).unwrap()
+}
+fn fee_override_pubkey(subject: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"fee_override", subject.as_ref()], &msp::id()).0
+}
+
+fn set_fee_override_ix(
+    fixture: &TreasuryFixture,
+    admin: &Pubkey,
+    subject: &Pubkey,
+    tier: u8,
+    fees: FeeSchedule,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::SetFeeOverrideAccounts {
+            payer: fixture.context.payer.pubkey(),
+            admin: *admin,
+            config: config_pubkey(),
+            fee_override: fee_override_pubkey(subject),
+            system_program: system_program::ID,
+        })
+        .args(SetFeeOverride {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+            subject: *subject,
+            tier,
+            fees,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+fn remove_fee_override_ix(
+    fixture: &TreasuryFixture,
+    admin: &Pubkey,
+    subject: &Pubkey,
+) -> Instruction {
+    fixture
+        .program
+        .request()
+        .accounts(msp::accounts::RemoveFeeOverrideAccounts {
+            admin: *admin,
+            config: config_pubkey(),
+            fee_override: fee_override_pubkey(subject),
+        })
+        .args(RemoveFeeOverride {
+            _idl_file_version: msp::constants::IDL_FILE_VERSION,
+        })
+        .instructions()
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+async fn fetch_fee_override(context: &ProgramTestContext, subject: &Pubkey) -> FeeOverride {
+    let mut bank_copy = context.banks_client.clone();
+    let fee_override_account = bank_copy
+        .get_account(fee_override_pubkey(subject))
+        .await
+        .unwrap()
+        .unwrap();
+    FeeOverride::try_deserialize(&mut fee_override_account.data.as_slice()).unwrap()
+}
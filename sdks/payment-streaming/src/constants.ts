@@ -42,7 +42,7 @@ export const NATIVE_WSOL_MINT = NATIVE_MINT;
 
 /** Current version number that needs to be set as argument when creating any
  * transaction of the Payment Streaming program */
-export const LATEST_IDL_FILE_VERSION = 5;
+export const LATEST_IDL_FILE_VERSION = 10;
 
 export enum WARNING_TYPES {
   NO_WARNING = 0,
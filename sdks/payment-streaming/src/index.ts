@@ -2,4 +2,4 @@ export * from './payment-streaming';
 export * from './utils';
 export * from './types';
 export * from './constants';
-export { Msp, Msp as Ps } from './msp_idl_005';
+export { Msp, Msp as Ps } from './msp_idl_010';
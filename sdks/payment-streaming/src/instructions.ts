@@ -15,8 +15,9 @@ import {
   TOKEN_PROGRAM_ID,
 } from './constants';
 
-import { Msp as Ps } from './msp_idl_005';
-import { Category, AccountType, SubCategory } from './types';
+import { Msp as Ps } from './msp_idl_010';
+import { AccrualMode, Category, AccountType, SubCategory } from './types';
+import { findConfigAddress, findFeeOverrideAddress } from './utils';
 
 export type CreateAccountInstructionAccounts = {
   /**
@@ -55,6 +56,8 @@ export type CreateAccountInstructionResult = {
  * from the newly created account, otherwise from the {@link feePayer} account
  * @param category - Category of the new account
  * @param subCategory - Subcategory of the new account
+ * @param referrer - Wallet of the integrator that referred the new account.
+ * It gets a share of the protocol fees charged to the account streams
  */
 export async function buildCreateAccountInstruction(
   program: Program<Ps>,
@@ -65,6 +68,7 @@ export async function buildCreateAccountInstruction(
   solFeePayedFromAccount: boolean,
   category: Category = Category.default,
   subCategory: SubCategory = SubCategory.default,
+  referrer: PublicKey | null = null,
 ): Promise<CreateAccountInstructionResult> {
   const [slotBn, psAccountSeeds] = await getAccountSeeds(
     program.provider.connection,
@@ -84,6 +88,8 @@ export async function buildCreateAccountInstruction(
     true,
   );
 
+  const [config] = findConfigAddress(program.programId);
+
   const instruction = await program.methods
     .createTreasury(
       LATEST_IDL_FILE_VERSION,
@@ -94,6 +100,7 @@ export async function buildCreateAccountInstruction(
       solFeePayedFromAccount,
       { [Category[category]]: {} },
       { [SubCategory[subCategory]]: {} },
+      referrer,
     )
     .accounts({
       payer: feePayer,
@@ -101,6 +108,7 @@ export async function buildCreateAccountInstruction(
       treasury: psAccount,
       treasuryToken: psAccountToken,
       associatedToken: mint,
+      config: config,
       feeTreasury: FEE_ACCOUNT,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       tokenProgram: TOKEN_PROGRAM_ID,
@@ -198,6 +206,8 @@ export async function buildAddFundsInstruction(
     FEE_ACCOUNT,
   );
 
+  const [config] = findConfigAddress(program.programId);
+
   const instruction = await program.methods
     .addFunds(LATEST_IDL_FILE_VERSION, amount)
     .accounts({
@@ -207,6 +217,7 @@ export async function buildAddFundsInstruction(
       treasury: psAccount,
       treasuryToken: psAccountToken,
       associatedToken: psAccountMint,
+      config: config,
       feeTreasury: FEE_ACCOUNT,
       feeTreasuryToken: feeAccountToken,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -259,6 +270,19 @@ export type CreateStreamInstructionAccounts = {
    *  The fee account ATA
    */
   feeAccountToken?: PublicKey;
+
+  /**
+   * Fee override of the PS account or of its authority. Defaults to the
+   * PS account fee override address, the program fees apply when it does
+   * not exist
+   */
+  feeOverride?: PublicKey;
+
+  /**
+   * Referrer of the PS account, if any. Defaults to the fee account, the
+   * whole fee goes to the fee account when it is not the PS account referrer
+   */
+  referrer?: PublicKey;
 };
 
 export type CreateStreamInstructionResult = {
@@ -297,6 +321,11 @@ export type CreateStreamInstructionResult = {
  * at withdraw time
  * @param usePda - If true, the new stream will be created at an address
  * derived from the program
+ * @param accrualMode - Whether the rate amount is streamed second by second
+ * or unlocked at the end of each interval
+ * @param cliffTs - Unix timestamp before which nothing is withdrawable. The
+ * cliff and the units accrued until then unlock at once at this date. Zero
+ * means the cliff unlocks at {@link startTs}
  */
 export async function buildCreateStreamInstruction(
   program: Program<Ps>,
@@ -308,6 +337,8 @@ export async function buildCreateStreamInstruction(
     beneficiary,
     feePayer,
     feeAccountToken,
+    feeOverride,
+    referrer,
   }: CreateStreamInstructionAccounts,
   name: string,
   rateAmount: BN,
@@ -318,6 +349,8 @@ export async function buildCreateStreamInstruction(
   cliffVestPercent: BN,
   tokenFeePayedFromAccount: boolean,
   usePda: boolean,
+  accrualMode: AccrualMode = AccrualMode.Linear,
+  cliffTs: BN = new BN(0),
 ): Promise<CreateStreamInstructionResult> {
   psAccountToken = await ensureAssociatedTokenAddress(
     psAccountToken,
@@ -331,6 +364,16 @@ export async function buildCreateStreamInstruction(
     FEE_ACCOUNT,
   );
 
+  const [config] = findConfigAddress(program.programId);
+  feeOverride =
+    feeOverride ?? findFeeOverrideAddress(psAccount, program.programId)[0];
+  referrer = referrer ?? FEE_ACCOUNT;
+  const referrerToken = await ensureAssociatedTokenAddress(
+    undefined,
+    psAccountMint,
+    referrer,
+  );
+
   if (usePda) {
     const streamPdaSeed = Keypair.generate().publicKey;
     const [streamPda] = PublicKey.findProgramAddressSync(
@@ -350,6 +393,8 @@ export async function buildCreateStreamInstruction(
         cliffVestPercent,
         tokenFeePayedFromAccount,
         streamPdaSeed,
+        accrualMode,
+        cliffTs,
       )
       .accounts({
         payer: feePayer,
@@ -359,6 +404,10 @@ export async function buildCreateStreamInstruction(
         associatedToken: psAccountMint,
         beneficiary: beneficiary,
         stream: streamPda,
+        config: config,
+        feeOverride: feeOverride,
+        referrer: referrer,
+        referrerToken: referrerToken,
         feeTreasury: FEE_ACCOUNT,
         feeTreasuryToken: feeAccountToken,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -390,6 +439,8 @@ export async function buildCreateStreamInstruction(
       cliffVestAmount,
       cliffVestPercent,
       tokenFeePayedFromAccount,
+      accrualMode,
+      cliffTs,
     )
     .accounts({
       payer: feePayer,
@@ -399,6 +450,10 @@ export async function buildCreateStreamInstruction(
       associatedToken: psAccountMint,
       beneficiary: beneficiary,
       stream: streamKey.publicKey,
+      config: config,
+      feeOverride: feeOverride,
+      referrer: referrer,
+      referrerToken: referrerToken,
       feeTreasury: FEE_ACCOUNT,
       feeTreasuryToken: feeAccountToken,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -467,6 +522,19 @@ export type StreamTemplateOptions = {
    * the beneficiary will pay for token fees at withdraw time
    */
   tokenFeePayedFromAccount: boolean;
+
+  /**
+   * Whether the rate amount is streamed second by second or unlocked at the
+   * end of each interval. Defaults to {@link AccrualMode.Linear}
+   */
+  accrualMode?: AccrualMode;
+
+  /**
+   * Unix timestamp before which nothing is withdrawable from streams created
+   * with this template. Zero (the default) means the cliff unlocks at
+   * {@link startTs}
+   */
+  cliffTs?: BN;
 };
 
 export type CreateAccountAndTemplateInstructionResult = {
@@ -503,6 +571,8 @@ export async function buildCreateAccountAndTemplateInstruction(
     startTs,
     cliffVestPercent,
     tokenFeePayedFromAccount,
+    accrualMode = AccrualMode.Linear,
+    cliffTs = new BN(0),
   }: StreamTemplateOptions,
   category: Category = Category.default,
   subCategory: SubCategory = SubCategory.default,
@@ -531,6 +601,8 @@ export async function buildCreateAccountAndTemplateInstruction(
     program.programId,
   );
 
+  const [config] = findConfigAddress(program.programId);
+
   const instruction = await program.methods
     .createTreasuryAndTemplate(
       LATEST_IDL_FILE_VERSION,
@@ -546,6 +618,8 @@ export async function buildCreateAccountAndTemplateInstruction(
       cliffVestPercent,
       tokenFeePayedFromAccount,
       slotBn,
+      accrualMode,
+      cliffTs,
     )
     .accounts({
       payer: feePayer,
@@ -554,6 +628,7 @@ export async function buildCreateAccountAndTemplateInstruction(
       treasuryToken: psAccountToken,
       template,
       associatedToken: mint,
+      config: config,
       feeTreasury: FEE_ACCOUNT,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       tokenProgram: TOKEN_PROGRAM_ID,
@@ -606,6 +681,8 @@ export type UpdateStreamTemplateInstructionResult = {
  * @param newStartTs
  * @param newCliffVestPercent
  * @param newTokenFeePayedFromAccount
+ * @param newAccrualMode
+ * @param newCliffTs
  */
 export async function buildUpdateStreamTemplateInstruction(
   program: Program<Ps>,
@@ -620,6 +697,8 @@ export async function buildUpdateStreamTemplateInstruction(
   newStartTs: BN,
   newCliffVestPercent: BN,
   newTokenFeePayedFromAccount: boolean,
+  newAccrualMode: AccrualMode = AccrualMode.Linear,
+  newCliffTs: BN = new BN(0),
 ): Promise<UpdateStreamTemplateInstructionResult> {
   const instruction = await program.methods
     .modifyStreamTemplate(
@@ -629,6 +708,8 @@ export async function buildUpdateStreamTemplateInstruction(
       newNumberOfIntervals,
       newCliffVestPercent,
       newTokenFeePayedFromAccount,
+      newAccrualMode,
+      newCliffTs,
     )
     .accounts({
       payer: feePayer,
@@ -676,6 +757,8 @@ export async function buildCreateStreamWithTemplateInstruction(
     feePayer,
     beneficiary,
     feeAccountToken,
+    feeOverride,
+    referrer,
   }: CreateStreamWithTemplateInstructionAccounts,
   allocationAssigned: BN,
   name: string,
@@ -693,6 +776,16 @@ export async function buildCreateStreamWithTemplateInstruction(
     FEE_ACCOUNT,
   );
 
+  const [config] = findConfigAddress(program.programId);
+  feeOverride =
+    feeOverride ?? findFeeOverrideAddress(psAccount, program.programId)[0];
+  referrer = referrer ?? FEE_ACCOUNT;
+  const referrerToken = await ensureAssociatedTokenAddress(
+    undefined,
+    psAccountMint,
+    referrer,
+  );
+
   if (usePda) {
     const streamPdaSeed = Keypair.generate().publicKey;
     const [streamPda] = PublicKey.findProgramAddressSync(
@@ -716,6 +809,10 @@ export async function buildCreateStreamWithTemplateInstruction(
         beneficiary: beneficiary,
         template: template,
         stream: streamPda,
+        config: config,
+        feeOverride: feeOverride,
+        referrer: referrer,
+        referrerToken: referrerToken,
         feeTreasury: FEE_ACCOUNT,
         feeTreasuryToken: feeAccountToken,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -751,6 +848,10 @@ export async function buildCreateStreamWithTemplateInstruction(
       beneficiary: beneficiary,
       template: template,
       stream: streamKey.publicKey,
+      config: config,
+      feeOverride: feeOverride,
+      referrer: referrer,
+      referrerToken: referrerToken,
       feeTreasury: FEE_ACCOUNT,
       feeTreasuryToken: feeAccountToken,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -778,6 +879,8 @@ export type AllocateFundsToStreamInstructionAccounts = {
   stream: PublicKey;
   psAccountToken?: PublicKey;
   feeAccountToken?: PublicKey;
+  feeOverride?: PublicKey;
+  referrer?: PublicKey;
 };
 
 export type AllocateFundsToStreamInstructionResult = {
@@ -802,6 +905,8 @@ export async function buildAllocateFundsToStreamInstruction(
     stream,
     psAccountToken,
     feeAccountToken,
+    feeOverride,
+    referrer,
   }: AllocateFundsToStreamInstructionAccounts,
   amount: BN,
 ): Promise<AllocateFundsToStreamInstructionResult> {
@@ -817,6 +922,16 @@ export async function buildAllocateFundsToStreamInstruction(
     FEE_ACCOUNT,
   );
 
+  const [config] = findConfigAddress(program.programId);
+  feeOverride =
+    feeOverride ?? findFeeOverrideAddress(psAccount, program.programId)[0];
+  referrer = referrer ?? FEE_ACCOUNT;
+  const referrerToken = await ensureAssociatedTokenAddress(
+    undefined,
+    psAccountMint,
+    referrer,
+  );
+
   const instruction = await program.methods
     .allocate(LATEST_IDL_FILE_VERSION, amount)
     .accounts({
@@ -826,6 +941,10 @@ export async function buildAllocateFundsToStreamInstruction(
       treasuryToken: psAccountToken,
       associatedToken: psAccountMint,
       stream: stream,
+      config: config,
+      feeOverride: feeOverride,
+      referrer: referrer,
+      referrerToken: referrerToken,
       feeTreasury: FEE_ACCOUNT,
       feeTreasuryToken: feeAccountToken,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -849,6 +968,8 @@ export type WithdrawFromAccountInstructionAccounts = {
   destinationToken?: PublicKey;
   psAccountToken?: PublicKey;
   feeAccountToken?: PublicKey;
+  feeOverride?: PublicKey;
+  referrer?: PublicKey;
 };
 
 export type WithdrawFromAccountInstructionResult = {
@@ -876,6 +997,8 @@ export async function buildWithdrawFromAccountInstruction(
     destinationToken,
     psAccountToken,
     feeAccountToken,
+    feeOverride,
+    referrer,
   }: WithdrawFromAccountInstructionAccounts,
   amount: BN,
 ): Promise<WithdrawFromAccountInstructionResult> {
@@ -897,6 +1020,16 @@ export async function buildWithdrawFromAccountInstruction(
     FEE_ACCOUNT,
   );
 
+  const [config] = findConfigAddress(program.programId);
+  feeOverride =
+    feeOverride ?? findFeeOverrideAddress(psAccount, program.programId)[0];
+  referrer = referrer ?? FEE_ACCOUNT;
+  const referrerToken = await ensureAssociatedTokenAddress(
+    undefined,
+    psAccountMint,
+    referrer,
+  );
+
   const instruction = await program.methods
     .treasuryWithdraw(LATEST_IDL_FILE_VERSION, amount)
     .accounts({
@@ -907,6 +1040,10 @@ export async function buildWithdrawFromAccountInstruction(
       associatedToken: psAccountMint,
       treasury: psAccount,
       treasuryToken: psAccountToken,
+      config: config,
+      feeOverride: feeOverride,
+      referrer: referrer,
+      referrerToken: referrerToken,
       feeTreasury: FEE_ACCOUNT,
       feeTreasuryToken: feeAccountToken,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -1027,6 +1164,8 @@ export async function buildCloseAccountInstruction(
     FEE_ACCOUNT,
   );
 
+  const [config] = findConfigAddress(program.programId);
+
   const instruction = await program.methods
     .closeTreasury(LATEST_IDL_FILE_VERSION)
     .accounts({
@@ -1037,6 +1176,7 @@ export async function buildCloseAccountInstruction(
       associatedToken: psAccountMint,
       treasury: psAccount,
       treasuryToken: psAccountToken,
+      config: config,
       feeTreasury: FEE_ACCOUNT,
       feeTreasuryToken: feeAccountToken,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -1063,6 +1203,8 @@ export type WithdrawFromStreamInstructionAccounts = {
   beneficiaryToken?: PublicKey;
   psAccountToken?: PublicKey;
   feeAccountToken?: PublicKey;
+  feeOverride?: PublicKey;
+  referrer?: PublicKey;
 };
 
 export type WithdrawFromStreamInstructionResult = {
@@ -1090,6 +1232,8 @@ export async function buildWithdrawFromStreamInstruction(
     beneficiaryToken,
     psAccountToken,
     feeAccountToken,
+    feeOverride,
+    referrer,
   }: WithdrawFromStreamInstructionAccounts,
   amount: BN,
 ): Promise<WithdrawFromStreamInstructionResult> {
@@ -1111,6 +1255,16 @@ export async function buildWithdrawFromStreamInstruction(
     FEE_ACCOUNT,
   );
 
+  const [config] = findConfigAddress(program.programId);
+  feeOverride =
+    feeOverride ?? findFeeOverrideAddress(psAccount, program.programId)[0];
+  referrer = referrer ?? FEE_ACCOUNT;
+  const referrerToken = await ensureAssociatedTokenAddress(
+    undefined,
+    psAccountMint,
+    referrer,
+  );
+
   const instruction = await program.methods
     .withdraw(LATEST_IDL_FILE_VERSION, amount)
     .accounts({
@@ -1121,6 +1275,10 @@ export async function buildWithdrawFromStreamInstruction(
       treasury: psAccount,
       treasuryToken: psAccountToken,
       stream: stream,
+      config: config,
+      feeOverride: feeOverride,
+      referrer: referrer,
+      referrerToken: referrerToken,
       feeTreasury: FEE_ACCOUNT,
       feeTreasuryToken: feeAccountToken,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -1220,11 +1378,14 @@ export async function buildTransferStreamInstruction(
   program: Program<Ps>,
   { stream, beneficiary, newBeneficiary }: TansferStreamInstructionAccounts,
 ): Promise<ResumeStreamInstructionResult> {
+  const [config] = findConfigAddress(program.programId);
+
   const instruction = await program.methods
     .transferStream(LATEST_IDL_FILE_VERSION, newBeneficiary)
     .accounts({
       beneficiary: beneficiary,
       stream: stream,
+      config: config,
       feeTreasury: FEE_ACCOUNT,
       systemProgram: SYSTEM_PROGRAM_ID,
     })
@@ -1245,6 +1406,8 @@ export type CloseStreamInstructionAccounts = {
   beneficiaryToken?: PublicKey;
   psAccountToken?: PublicKey;
   feeAccountToken?: PublicKey;
+  feeOverride?: PublicKey;
+  referrer?: PublicKey;
 };
 
 export type CloseStreamInstructionResult = {
@@ -1272,6 +1435,8 @@ export async function buildCloseStreamInstruction(
     beneficiaryToken,
     psAccountToken,
     feeAccountToken,
+    feeOverride,
+    referrer,
   }: CloseStreamInstructionAccounts,
 ): Promise<CloseStreamInstructionResult> {
   beneficiaryToken = await ensureAssociatedTokenAddress(
@@ -1292,6 +1457,16 @@ export async function buildCloseStreamInstruction(
     FEE_ACCOUNT,
   );
 
+  const [config] = findConfigAddress(program.programId);
+  feeOverride =
+    feeOverride ?? findFeeOverrideAddress(psAccount, program.programId)[0];
+  referrer = referrer ?? FEE_ACCOUNT;
+  const referrerToken = await ensureAssociatedTokenAddress(
+    undefined,
+    psAccountMint,
+    referrer,
+  );
+
   const instruction = await program.methods
     .closeStream(LATEST_IDL_FILE_VERSION)
     .accounts({
@@ -1303,6 +1478,10 @@ export async function buildCloseStreamInstruction(
       treasury: psAccount,
       treasuryToken: psAccountToken,
       stream: stream,
+      config: config,
+      feeOverride: feeOverride,
+      referrer: referrer,
+      referrerToken: referrerToken,
       feeTreasury: FEE_ACCOUNT,
       feeTreasuryToken: feeAccountToken,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
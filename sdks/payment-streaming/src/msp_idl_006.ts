@@ -0,0 +1,8787 @@
+export type Msp = {
+  "version": "2.8.2",
+  "name": "msp",
+  "instructions": [
+    {
+      "name": "createTreasury",
+      "docs": [
+        "Create Treasury"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "slot",
+          "type": "u64"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "treasuryType",
+          "type": "u8"
+        },
+        {
+          "name": "autoClose",
+          "type": "bool"
+        },
+        {
+          "name": "solFeePayedByTreasury",
+          "type": "bool"
+        },
+        {
+          "name": "category",
+          "type": {
+            "defined": "Category"
+          }
+        },
+        {
+          "name": "subCategory",
+          "type": {
+            "defined": "SubCategory"
+          }
+        }
+      ]
+    },
+    {
+      "name": "createStream",
+      "docs": [
+        "Create Stream"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "startUtc",
+          "type": "u64"
+        },
+        {
+          "name": "rateAmountUnits",
+          "type": "u64"
+        },
+        {
+          "name": "rateIntervalInSeconds",
+          "type": "u64"
+        },
+        {
+          "name": "allocationAssignedUnits",
+          "type": "u64"
+        },
+        {
+          "name": "cliffVestAmountUnits",
+          "type": "u64"
+        },
+        {
+          "name": "cliffVestPercent",
+          "type": "u64"
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool"
+        },
+        {
+          "name": "accrualMode",
+          "type": "u8"
+        },
+        {
+          "name": "cliffUtc",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "createStreamPda",
+      "docs": [
+        "Create Stream PDA"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "startUtc",
+          "type": "u64"
+        },
+        {
+          "name": "rateAmountUnits",
+          "type": "u64"
+        },
+        {
+          "name": "rateIntervalInSeconds",
+          "type": "u64"
+        },
+        {
+          "name": "allocationAssignedUnits",
+          "type": "u64"
+        },
+        {
+          "name": "cliffVestAmountUnits",
+          "type": "u64"
+        },
+        {
+          "name": "cliffVestPercent",
+          "type": "u64"
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool"
+        },
+        {
+          "name": "streamPdaSeed",
+          "type": "publicKey"
+        },
+        {
+          "name": "accrualMode",
+          "type": "u8"
+        },
+        {
+          "name": "cliffUtc",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "createStreamsBatch",
+      "docs": [
+        "Create Streams Batch",
+        "Creates one stream per entry. All the streams share the name, start,",
+        "fee payer, accrual mode and cliff date"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "startUtc",
+          "type": "u64"
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool"
+        },
+        {
+          "name": "accrualMode",
+          "type": "u8"
+        },
+        {
+          "name": "cliffUtc",
+          "type": "u64"
+        },
+        {
+          "name": "entries",
+          "type": {
+            "vec": {
+              "defined": "StreamBatchEntry"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "createStreamWithSchedule",
+      "docs": [
+        "Create Stream With Schedule"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "schedule",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "allocationAssignedUnits",
+          "type": "u64"
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool"
+        },
+        {
+          "name": "points",
+          "type": {
+            "vec": {
+              "defined": "SchedulePoint"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "createStreamTemplate",
+      "docs": [
+        "Create template"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "template",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "startUtc",
+          "type": "u64"
+        },
+        {
+          "name": "rateIntervalInSeconds",
+          "type": "u64"
+        },
+        {
+          "name": "durationNumberOfUnits",
+          "type": "u64"
+        },
+        {
+          "name": "cliffVestPercent",
+          "type": "u64"
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool"
+        },
+        {
+          "name": "accrualMode",
+          "type": "u8"
+        },
+        {
+          "name": "cliffUtc",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "modifyStreamTemplate",
+      "docs": [
+        "Edit template"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "template",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "startUtc",
+          "type": "u64"
+        },
+        {
+          "name": "rateIntervalInSeconds",
+          "type": "u64"
+        },
+        {
+          "name": "durationNumberOfUnits",
+          "type": "u64"
+        },
+        {
+          "name": "cliffVestPercent",
+          "type": "u64"
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool"
+        },
+        {
+          "name": "accrualMode",
+          "type": "u8"
+        },
+        {
+          "name": "cliffUtc",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "createTreasuryAndTemplate",
+      "docs": [
+        "Create Treasury"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "template",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "treasuryType",
+          "type": "u8"
+        },
+        {
+          "name": "autoClose",
+          "type": "bool"
+        },
+        {
+          "name": "solFeePayedByTreasury",
+          "type": "bool"
+        },
+        {
+          "name": "category",
+          "type": {
+            "defined": "Category"
+          }
+        },
+        {
+          "name": "subCategory",
+          "type": {
+            "defined": "SubCategory"
+          }
+        },
+        {
+          "name": "startUtc",
+          "type": "u64"
+        },
+        {
+          "name": "rateIntervalInSeconds",
+          "type": "u64"
+        },
+        {
+          "name": "durationNumberOfUnits",
+          "type": "u64"
+        },
+        {
+          "name": "cliffVestPercent",
+          "type": "u64"
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool"
+        },
+        {
+          "name": "slot",
+          "type": "u64"
+        },
+        {
+          "name": "accrualMode",
+          "type": "u8"
+        },
+        {
+          "name": "cliffUtc",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "createStreamWithTemplate",
+      "docs": [
+        "Create stream with template"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "template",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "allocationAssignedUnits",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "createStreamPdaWithTemplate",
+      "docs": [
+        "Create stream with template"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "template",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "allocationAssignedUnits",
+          "type": "u64"
+        },
+        {
+          "name": "streamPdaSeed",
+          "type": "publicKey"
+        }
+      ]
+    },
+    {
+      "name": "withdraw",
+      "docs": [
+        "Withdraw"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "beneficiary",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "beneficiaryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "withdrawToDestination",
+      "docs": [
+        "Withdraw to any token account of the stream mint"
+      ],
+      "accounts": [
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "destinationToken",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "Any token account of the stream mint"
+          ]
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "pushWithdraw",
+      "docs": [
+        "Withdraw the full withdrawable amount on behalf of the beneficiary.",
+        "Anyone can call it and, if the treasury pays the SOL fees, the cranker",
+        "gets a small reward from the treasury at most once a day per stream"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "Anyone can crank the withdrawal on behalf of the beneficiary"
+          ]
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "beneficiaryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "withdrawMany",
+      "docs": [
+        "Withdraw Many",
+        "Withdraws the full withdrawable amount of each stream passed in the",
+        "remaining accounts to the beneficiary token account. Streams with",
+        "nothing to withdraw yet are skipped"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "beneficiaryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "pauseStream",
+      "docs": [
+        "Pause Stream"
+      ],
+      "accounts": [
+        {
+          "name": "initializer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "resumeStream",
+      "docs": [
+        "Resume Stream"
+      ],
+      "accounts": [
+        {
+          "name": "initializer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "updateStreamRate",
+      "docs": [
+        "Update the rate of a stream. Everything earned at the previous rate",
+        "is kept as cliff and the stream continues at the new rate from now"
+      ],
+      "accounts": [
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "rateAmountUnits",
+          "type": "u64"
+        },
+        {
+          "name": "rateIntervalInSeconds",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "proposeTreasuryAuthority",
+      "docs": [
+        "Propose a new treasury authority. The handover takes effect when the",
+        "proposed authority accepts it. Proposing the default pubkey cancels a",
+        "pending handover"
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "newAuthority",
+          "type": "publicKey"
+        }
+      ]
+    },
+    {
+      "name": "acceptTreasuryAuthority",
+      "docs": [
+        "Accept a pending treasury authority handover. Signed by the proposed",
+        "authority"
+      ],
+      "accounts": [
+        {
+          "name": "newAuthority",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "refreshTreasuryData",
+      "docs": [
+        "Refresh Treasury Balance"
+      ],
+      "accounts": [
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "transferStream",
+      "docs": [
+        "Transfer Stream"
+      ],
+      "accounts": [
+        {
+          "name": "beneficiary",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "newBeneficiary",
+          "type": "publicKey"
+        }
+      ]
+    },
+    {
+      "name": "proposeStreamTransfer",
+      "docs": [
+        "Propose a new beneficiary for the stream. The transfer takes effect",
+        "when the proposed beneficiary accepts it"
+      ],
+      "accounts": [
+        {
+          "name": "beneficiary",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "newBeneficiary",
+          "type": "publicKey"
+        }
+      ]
+    },
+    {
+      "name": "cancelStreamTransfer",
+      "docs": [
+        "Cancel a pending stream transfer"
+      ],
+      "accounts": [
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "acceptStreamTransfer",
+      "docs": [
+        "Accept a pending stream transfer. Signed by the proposed beneficiary"
+      ],
+      "accounts": [
+        {
+          "name": "newBeneficiary",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "getStream",
+      "docs": [
+        "Get Stream"
+      ],
+      "accounts": [
+        {
+          "name": "stream",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "addFunds",
+      "docs": [
+        "Adds funds the treasury"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "contributor",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "contributorToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "allocate",
+      "docs": [
+        "Allocate units to a stream"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "allocateToAllStreams",
+      "docs": [
+        "Split `amount` units across the treasury streams passed in the",
+        "remaining accounts, pro-rata to each stream rate or evenly. Any",
+        "rounding remainder is left unallocated and scheduled streams are",
+        "skipped"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        },
+        {
+          "name": "splitMode",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "closeStream",
+      "docs": [
+        "Close Stream"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "beneficiaryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "renounceStream",
+      "docs": [
+        "Close a stream as the beneficiary, giving up the unvested allocation.",
+        "The withdrawable amount is paid out and the rest goes back to the",
+        "treasury unallocated balance"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "beneficiaryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasurer",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "closeTreasury",
+      "docs": [
+        "Close Treasury"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "destinationAuthority",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "destinationTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "closeTreasuryAndStreams",
+      "docs": [
+        "Close Treasury And Streams",
+        "Closes every stream of the treasury (as close_stream does) and then",
+        "closes the treasury. All the treasury streams must be passed"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "destinationAuthority",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "destinationTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "treasuryWithdraw",
+      "docs": [
+        "Withdraw unallocated funds from treasury"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "destinationAuthority",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "destinationTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    }
+  ],
+  "accounts": [
+    {
+      "name": "streamSchedule",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "streamAddress",
+            "docs": [
+              "The stream that unlocks units following this schedule"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "points",
+            "docs": [
+              "The unlock points sorted by timestamp"
+            ],
+            "type": {
+              "vec": {
+                "defined": "SchedulePoint"
+              }
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "stream",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "initialized",
+            "type": "bool"
+          },
+          {
+            "name": "name",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "treasurerAddress",
+            "type": "publicKey"
+          },
+          {
+            "name": "rateAmountUnits",
+            "type": "u64"
+          },
+          {
+            "name": "rateIntervalInSeconds",
+            "type": "u64"
+          },
+          {
+            "name": "startUtc",
+            "docs": [
+              "The start timestamp in seconds"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "cliffVestAmountUnits",
+            "docs": [
+              "The amount availaible to withdraw inmidiately (without streaming)",
+              "once the money stream starts.",
+              "If both 'cliff_vest_amount_units' and 'cliff_vest_percent' are provided, the former will be used."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "cliffVestPercent",
+            "docs": [
+              "The percent of the allocation assigned that is availaible to withdraw",
+              "inmidiately (without streaming) once the money stream starts.",
+              "If both 'cliff_vest_amount_units' and 'cliff_vest_percent' are provided, the second (this field) will be used."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "beneficiaryAddress",
+            "type": "publicKey"
+          },
+          {
+            "name": "beneficiaryAssociatedToken",
+            "type": "publicKey"
+          },
+          {
+            "name": "treasuryAddress",
+            "type": "publicKey"
+          },
+          {
+            "name": "allocationAssignedUnits",
+            "docs": [
+              "Amount of tokens allocated to the stream on creation or top up. If the",
+              "treasurer decides to close the stream, the vested amount will be sent",
+              "to the benefifiary and the unvested amount will be sent to the",
+              "treasurer",
+              "",
+              "The allocation assigned will be affected by the following instructions:",
+              "`addFunds`"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "allocationReservedUnits",
+            "docs": [
+              "Amount of tokens reserved to the stream. If the treasurer decides to",
+              "close the stream, the total amount (vested and unvested) WILL be sent",
+              "to the beneficiary",
+              "",
+              "[deprecated] The allocation reserved will be affected by the following instructions:",
+              "`addFunds`"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "totalWithdrawalsUnits",
+            "docs": [
+              "Withdrawal tracking",
+              "The total amount that has been withdrawn by the beneficiary"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastWithdrawalUnits",
+            "docs": [
+              "The last amount withdrew by the beneficiary"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastWithdrawalSlot",
+            "docs": [
+              "The slot number when the last withdrawal was executed"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastWithdrawalBlockTime",
+            "docs": [
+              "The blocktime value when the last withdrawal was executed"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastManualStopWithdrawableUnitsSnap",
+            "docs": [
+              "How can a stream STOP? -> There are 2 ways:",
+              "1) by a Manual Action (recordable when it happens) or",
+              "2) by Running Out Of Funds (not recordable when it happens, needs to be calculated)"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastManualStopSlot",
+            "type": "u64"
+          },
+          {
+            "name": "lastManualStopBlockTime",
+            "type": "u64"
+          },
+          {
+            "name": "lastManualResumeRemainingAllocationUnitsSnap",
+            "docs": [
+              "The remaining allocation units at the moment of the last manual resume",
+              "must be set when calling the Resume Stream"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastManualResumeSlot",
+            "type": "u64"
+          },
+          {
+            "name": "lastManualResumeBlockTime",
+            "type": "u64"
+          },
+          {
+            "name": "lastKnownTotalSecondsInPausedStatus",
+            "docs": [
+              "The total seconds that have been paused since the start_utc",
+              "increment when resume is called manually"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastAutoStopBlockTime",
+            "docs": [
+              "The last blocktime when the stream was stopped",
+              "either manually or automaticaly (run out of funds)"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "feePayedByTreasurer",
+            "type": "bool"
+          },
+          {
+            "name": "startUtcInSeconds",
+            "docs": [
+              "The start timestamp blocktime"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "createdOnUtc",
+            "docs": [
+              "Unix timestamp (in seconds) when the stream was created"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "category",
+            "docs": [
+              "Indicates the main product category such as `Vesting(1)`",
+              "The default value is set to a `Default(0)` cateogry."
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "subCategory",
+            "docs": [
+              "Indicates the sub product category such as `Advisor(1)`, Development(2)",
+              "The default value is set to a `Default(0)` sub_cateogry."
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "accrualMode",
+            "docs": [
+              "Indicates how the stream accrues units over time. `Linear(0)` streams",
+              "every second, while `Step(1)` unlocks the full `rate_amount_units` at",
+              "the end of each `rate_interval_in_seconds`."
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "scheduleAddress",
+            "docs": [
+              "The `StreamSchedule` account that defines when units are unlocked.",
+              "When set, the rate and cliff fields are not used to calculate the",
+              "vested amount."
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "cliffUtcInSeconds",
+            "docs": [
+              "The timestamp in seconds before which nothing is withdrawable. Units",
+              "keep accruing from the start and unlock at once at this date.",
+              "Zero means the cliff unlocks at `start_utc`."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "pendingBeneficiaryAddress",
+            "docs": [
+              "The beneficiary proposed by `propose_stream_transfer`. It has to",
+              "accept the transfer before becoming the stream beneficiary"
+            ],
+            "type": "publicKey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "streamTemplate",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "startUtcInSeconds",
+            "docs": [
+              "The start timestamp blocktime"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "cliffVestPercent",
+            "docs": [
+              "The percentage availaible to withdraw inmidiately (without streaming)",
+              "once the money stream starts."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "rateIntervalInSeconds",
+            "type": "u64"
+          },
+          {
+            "name": "durationNumberOfUnits",
+            "type": "u64"
+          },
+          {
+            "name": "feePayedByTreasurer",
+            "type": "bool"
+          },
+          {
+            "name": "accrualMode",
+            "docs": [
+              "The accrual mode (`Linear(0)` or `Step(1)`) of the streams created",
+              "with this template"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "cliffUtcInSeconds",
+            "docs": [
+              "The cliff date in seconds of the streams created with this template.",
+              "Zero means the cliff unlocks at the stream start"
+            ],
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "treasury",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "initialized",
+            "type": "bool"
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "slot",
+            "type": "u64"
+          },
+          {
+            "name": "name",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "treasurerAddress",
+            "type": "publicKey"
+          },
+          {
+            "name": "associatedTokenAddress",
+            "type": "publicKey"
+          },
+          {
+            "name": "mintAddress",
+            "docs": [
+              "[deprecated] The address of the Mint of the treasury pool"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "labels",
+            "docs": [
+              "This field should not be used in its current form because it has a dynamic size",
+              "",
+              "The 4-bytes header can be repurposed in the future"
+            ],
+            "type": {
+              "vec": "string"
+            }
+          },
+          {
+            "name": "lastKnownBalanceUnits",
+            "docs": [
+              "Treasury balance tracking",
+              "The last known treasury balance (will be updated in the `refreshTreasuryData` instruction)"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastKnownBalanceSlot",
+            "docs": [
+              "The slot of the last time the treasury balance was updated"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastKnownBalanceBlockTime",
+            "docs": [
+              "The blocktime when the treasury balance was updated"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "allocationAssignedUnits",
+            "docs": [
+              "Treasury allocation tracking",
+              "The allocation assigned accross all the streams that belong to this treasury",
+              "",
+              "The allocation assined will be modified in the following instructions:",
+              "`createStream`, `allocate`, `withdraw` and `closeStream`"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "allocationReservedUnits",
+            "docs": [
+              "The allocation reserved accross all the streams that belong to this treasury",
+              "",
+              "[deprecated] The allocation reserved will be modified in the following instructions:",
+              "`createStream`, `withdraw` and `closeStream`"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "totalWithdrawalsUnits",
+            "docs": [
+              "The total amount withdrawn by all the streams that belong to this treasury"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "totalStreams",
+            "docs": [
+              "The current amount of streams in the treasury (will be updated in the `refreshTreasuryData` instruction)"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "createdOnUtc",
+            "type": "u64"
+          },
+          {
+            "name": "treasuryType",
+            "docs": [
+              "The type of the treasury (Open, Locked)"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "autoClose",
+            "docs": [
+              "only used for filtering in the ui"
+            ],
+            "type": "bool"
+          },
+          {
+            "name": "solFeePayedByTreasury",
+            "docs": [
+              "Indicates whether program sol fees are payed from the `treasury`'s",
+              "lamports balance (when true) or by the `payer` account in the",
+              "transaction (when false)"
+            ],
+            "type": "bool"
+          },
+          {
+            "name": "category",
+            "docs": [
+              "Indicates the main product category such as `Vesting(1)`",
+              "The default value is set to a `Default(0)` cateogry."
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "subCategory",
+            "docs": [
+              "Indicates the sub product category such as `Advisor(1)`, Development(2)",
+              "The default value is set to a `Default(0)` sub_cateogry."
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "authorityAddress",
+            "docs": [
+              "The wallet allowed to manage the treasury and its streams. When not",
+              "set, the `treasurer_address` (used in the treasury PDA seeds) is the",
+              "authority"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "pendingAuthorityAddress",
+            "docs": [
+              "The authority proposed by `propose_treasury_authority`. It has to",
+              "accept the handover before becoming the treasury authority"
+            ],
+            "type": "publicKey"
+          }
+        ]
+      }
+    }
+  ],
+  "types": [
+    {
+      "name": "SchedulePoint",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "timestamp",
+            "docs": [
+              "Unix timestamp (in seconds) when the units are unlocked"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "cumulativeUnits",
+            "docs": [
+              "The total amount of units unlocked since the stream started (including",
+              "the units unlocked in previous points)"
+            ],
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "StreamBatchEntry",
+      "docs": [
+        "A stream to be created by `create_streams_batch`"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "beneficiary",
+            "type": "publicKey"
+          },
+          {
+            "name": "streamPdaSeed",
+            "docs": [
+              "Seed used to derive the stream PDA as [b\"stream\", treasury, seed]"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "allocationAssignedUnits",
+            "type": "u64"
+          },
+          {
+            "name": "rateAmountUnits",
+            "type": "u64"
+          },
+          {
+            "name": "rateIntervalInSeconds",
+            "type": "u64"
+          },
+          {
+            "name": "cliffVestAmountUnits",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "Category",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Default"
+          },
+          {
+            "name": "Vesting"
+          }
+        ]
+      }
+    },
+    {
+      "name": "SubCategory",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Default"
+          },
+          {
+            "name": "Advisor"
+          },
+          {
+            "name": "Development"
+          },
+          {
+            "name": "Foundation"
+          },
+          {
+            "name": "Investor"
+          },
+          {
+            "name": "Marketing"
+          },
+          {
+            "name": "Partnership"
+          },
+          {
+            "name": "Seed"
+          },
+          {
+            "name": "Team"
+          },
+          {
+            "name": "Community"
+          }
+        ]
+      }
+    },
+    {
+      "name": "StreamStatus",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Scheduled"
+          },
+          {
+            "name": "Running"
+          },
+          {
+            "name": "Paused"
+          }
+        ]
+      }
+    },
+    {
+      "name": "TreasuryType",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Opened"
+          },
+          {
+            "name": "Locked"
+          }
+        ]
+      }
+    }
+  ],
+  "events": [
+    {
+      "name": "StreamEvent",
+      "fields": [
+        {
+          "name": "version",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "initialized",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "name",
+          "type": "string",
+          "index": false
+        },
+        {
+          "name": "treasurerAddress",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "rateAmountUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "rateIntervalInSeconds",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "startUtc",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "cliffVestAmountUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "cliffVestPercent",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "beneficiaryAddress",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "beneficiaryAssociatedToken",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "treasuryAddress",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "allocationAssignedUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "allocationReservedUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "totalWithdrawalsUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastWithdrawalUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastWithdrawalSlot",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastWithdrawalBlockTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastManualStopWithdrawableUnitsSnap",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastManualStopSlot",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastManualStopBlockTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastManualResumeRemainingAllocationUnitsSnap",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastManualResumeSlot",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastManualResumeBlockTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastKnownTotalSecondsInPausedStatus",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastAutoStopBlockTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "status",
+          "type": "string",
+          "index": false
+        },
+        {
+          "name": "isManualPause",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "cliffUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "currentBlockTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "secondsSinceStart",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "estDepletionTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "fundsLeftInStream",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "fundsSentToBeneficiary",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "withdrawableUnitsWhilePaused",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "nonStopEarningUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "missedUnitsWhilePaused",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "entitledEarningsUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "withdrawableUnitsWhileRunning",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "beneficiaryRemainingAllocation",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "beneficiaryWithdrawableAmount",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastKnownStopBlockTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "createdOnUtc",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "category",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "subCategory",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "accrualMode",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "scheduleAddress",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "cliffUtcInSeconds",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "pendingBeneficiaryAddress",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "CreateTreasuryEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solDepositedForFees",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryType",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "treasuryIsAutoClose",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "CreateStreamEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamStartTs",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamRateAmount",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamRateInterval",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamAllocation",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamCliff",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamIsTokenWithdrawFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryAllocationAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryBalanceAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "StreamWithdrawEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "amount",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenAmountSentToBeneficiary",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamWithdrawableBefore",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamIsManuallyPaused",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "streamAllocationAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamTotalWithdrawalsAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamIsTokenWithdrawFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryAllocationAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryBalanceAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryTotalWithdrawalsAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "destinationToken",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "StreamPushWithdrawEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenAmountSentToBeneficiary",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamTotalWithdrawalsAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "crankerReward",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "cranker",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "beneficiary",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "StreamPauseEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamLastManualStopWithdrawableAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "StreamResumeEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamTotalSecondsInPausedStatusAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "TreasuryAuthorityProposeEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "proposedAuthority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "TreasuryAuthorityTransferEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "previousAuthority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "newAuthority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "TreasuryRefreshEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryBalanceAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "StreamTransferEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "previousBeneficiary",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "newBeneficiary",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "StreamTransferProposeEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "beneficiary",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "proposedBeneficiary",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "StreamTransferCancelEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "beneficiary",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "cancelledBeneficiary",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TreasuryAddFundsEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "amount",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryBalanceAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "StreamAllocateEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "amount",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamStatusBefore",
+          "type": "u32",
+          "index": false
+        },
+        {
+          "name": "streamWasManuallyPausedBefore",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "streamLastAutoStopBlockTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamTotalSecondsInPausedStatusAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamIsTokenWithdrawFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "streamAllocationAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryAllocationAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryBalanceAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "StreamRateUpdateEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamStatusBefore",
+          "type": "u32",
+          "index": false
+        },
+        {
+          "name": "streamRateAmountBefore",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamRateIntervalBefore",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamRateAmountAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamRateIntervalAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamEarnedUnitsBefore",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamStartTsAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamTotalSecondsInPausedStatusBefore",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "CloseStreamEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenAmountSentToBeneficiary",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamIsTokenWithdrawFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "streamAllocationBefore",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamTotalWithdrawalsBefore",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryAllocationAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryBalanceAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryTotalStreamsAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "CloseTreasuryEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenAmountSentToDestination",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "TreasuryWithdrawEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "amount",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenAmountSentToDestination",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryBalanceAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    }
+  ],
+  "errors": [
+    {
+      "code": 6000,
+      "name": "InvalidProgramId",
+      "msg": "Invalid Money Streaming Program ID"
+    },
+    {
+      "code": 6001,
+      "name": "InvalidOwner",
+      "msg": "Invalid account owner"
+    },
+    {
+      "code": 6002,
+      "name": "NotAuthorized",
+      "msg": "Not Authorized"
+    },
+    {
+      "code": 6003,
+      "name": "Overflow",
+      "msg": "Overflow"
+    },
+    {
+      "code": 6004,
+      "name": "InvalidAssociatedToken",
+      "msg": "Invalid associated token address"
+    },
+    {
+      "code": 6005,
+      "name": "InvalidFeeTreasuryAccount",
+      "msg": "Invalid fee treasury account"
+    },
+    {
+      "code": 6006,
+      "name": "InvalidTreasuryMintDecimals",
+      "msg": "Invalid treasury mint decimals"
+    },
+    {
+      "code": 6007,
+      "name": "TreasuryAlreadyInitialized",
+      "msg": "Treasury is already initialized"
+    },
+    {
+      "code": 6008,
+      "name": "TreasuryNotInitialized",
+      "msg": "Treasury is not initialized"
+    },
+    {
+      "code": 6009,
+      "name": "InvalidTreasuryVersion",
+      "msg": "Invalid treasury version"
+    },
+    {
+      "code": 6010,
+      "name": "InvalidTreasuryMint",
+      "msg": "Invalid treasury mint address"
+    },
+    {
+      "code": 6011,
+      "name": "InvalidTreasury",
+      "msg": "Invalid treasury account"
+    },
+    {
+      "code": 6012,
+      "name": "InvalidTreasurySize",
+      "msg": "Invalid treasury size"
+    },
+    {
+      "code": 6013,
+      "name": "InvalidTreasurer",
+      "msg": "Invalid treasurer"
+    },
+    {
+      "code": 6014,
+      "name": "InvalidBeneficiary",
+      "msg": "Invalid beneficiary"
+    },
+    {
+      "code": 6015,
+      "name": "InvalidArgument",
+      "msg": "Invalid argument"
+    },
+    {
+      "code": 6016,
+      "name": "StreamNotInitialized",
+      "msg": "Stream not initialized"
+    },
+    {
+      "code": 6017,
+      "name": "StreamAlreadyInitialized",
+      "msg": "Stream is already initialized"
+    },
+    {
+      "code": 6018,
+      "name": "InvalidStreamVersion",
+      "msg": "Invalid stream version"
+    },
+    {
+      "code": 6019,
+      "name": "InvalidStreamSize",
+      "msg": "Invalid stream size"
+    },
+    {
+      "code": 6020,
+      "name": "InvalidStream",
+      "msg": "Invalid stream account"
+    },
+    {
+      "code": 6021,
+      "name": "InvalidRequestedStreamAllocation",
+      "msg": "Invalid requested stream allocation"
+    },
+    {
+      "code": 6022,
+      "name": "InvalidWithdrawalAmount",
+      "msg": "Invalid withdrawal amount"
+    },
+    {
+      "code": 6023,
+      "name": "StringTooLong",
+      "msg": "The string length is larger than 32 bytes"
+    },
+    {
+      "code": 6024,
+      "name": "StreamAlreadyRunning",
+      "msg": "The stream is already running"
+    },
+    {
+      "code": 6025,
+      "name": "StreamAlreadyPaused",
+      "msg": "The stream is already paused"
+    },
+    {
+      "code": 6026,
+      "name": "StreamZeroRemainingAllocation",
+      "msg": "Stream allocation assigned is zero"
+    },
+    {
+      "code": 6027,
+      "name": "ZeroContributionAmount",
+      "msg": "Contribution amount is zero"
+    },
+    {
+      "code": 6028,
+      "name": "ZeroWithdrawalAmount",
+      "msg": "Withdrawal amount is zero"
+    },
+    {
+      "code": 6029,
+      "name": "StreamIsScheduled",
+      "msg": "Stream has not started"
+    },
+    {
+      "code": 6030,
+      "name": "CloseLockedStreamNotAllowedWhileRunning",
+      "msg": "Streams in a Locked treasury can not be closed while running"
+    },
+    {
+      "code": 6031,
+      "name": "PauseOrResumeLockedStreamNotAllowed",
+      "msg": "Streams in a Locked treasury can not be paused or resumed"
+    },
+    {
+      "code": 6032,
+      "name": "ReservedAllocationExceedWithdrawableAmount",
+      "msg": "Can not pause a stream if the reserved allocation is greater than the withdrawable amount"
+    },
+    {
+      "code": 6033,
+      "name": "AllocateNotAllowedOnLockedStreams",
+      "msg": "Can not allocate funds to a stream from a locked treasury"
+    },
+    {
+      "code": 6034,
+      "name": "InvalidStreamRate",
+      "msg": "Invalid stream rate"
+    },
+    {
+      "code": 6035,
+      "name": "InvalidCliff",
+      "msg": "Invalid cliff"
+    },
+    {
+      "code": 6036,
+      "name": "InsufficientLamports",
+      "msg": "Insufficient lamports"
+    },
+    {
+      "code": 6037,
+      "name": "TreasuryContainsStreams",
+      "msg": "This treasury contains one or more streams"
+    },
+    {
+      "code": 6038,
+      "name": "InsufficientFunds",
+      "msg": "Insufficient funds"
+    },
+    {
+      "code": 6039,
+      "name": "InsufficientTreasuryBalance",
+      "msg": "Insufficient treasury balance"
+    },
+    {
+      "code": 6040,
+      "name": "CannotResumeAutoPausedStream",
+      "msg": "Stream is auto-paused. To resume use allocate"
+    },
+    {
+      "code": 6041,
+      "name": "CannotPauseAndUnpauseOnSameBlockTime",
+      "msg": "Cannot pause and unpause on the same block time"
+    },
+    {
+      "code": 6042,
+      "name": "InvalidTreasuryRequestedAllocation",
+      "msg": "Treasury allocation can not be greater than treasury balance"
+    },
+    {
+      "code": 6043,
+      "name": "InvalidIdlFileVersion",
+      "msg": "Invalid IDL file version"
+    },
+    {
+      "code": 6044,
+      "name": "InvalidTotalStreamsInTreasury",
+      "msg": "Invalid total streams in treasury"
+    },
+    {
+      "code": 6045,
+      "name": "InvalidTemplateVersion",
+      "msg": "Invalid template version"
+    },
+    {
+      "code": 6046,
+      "name": "InvalidTemplateSize",
+      "msg": "Invalid template size"
+    },
+    {
+      "code": 6047,
+      "name": "CannotModifyTemplate",
+      "msg": "Template cannot be modified after streams have been created"
+    },
+    {
+      "code": 6048,
+      "name": "NumberOfIntervalsMustBePossitive",
+      "msg": "Number of intervals must be greater than zero"
+    },
+    {
+      "code": 6049,
+      "name": "ZeroRateAmountTemplateConfiguration",
+      "msg": "The configuration of this template yields a zero rate amount per interval"
+    },
+    {
+      "code": 6050,
+      "name": "InvalidAccrualMode",
+      "msg": "Invalid stream accrual mode"
+    },
+    {
+      "code": 6051,
+      "name": "InvalidSchedule",
+      "msg": "Invalid unlock schedule"
+    },
+    {
+      "code": 6052,
+      "name": "InvalidScheduleTotal",
+      "msg": "The unlock schedule total does not match the stream allocation"
+    },
+    {
+      "code": 6053,
+      "name": "InvalidScheduleAccount",
+      "msg": "Invalid stream schedule account"
+    },
+    {
+      "code": 6054,
+      "name": "NotAllowedOnScheduledStream",
+      "msg": "This operation is not allowed on streams with an unlock schedule"
+    },
+    {
+      "code": 6055,
+      "name": "UpdateRateNotAllowedOnLockedStreams",
+      "msg": "Can not update the rate of a stream from a locked treasury"
+    },
+    {
+      "code": 6056,
+      "name": "UpdateRateNotAllowedWhilePaused",
+      "msg": "Can not update the rate of a manually paused stream"
+    },
+    {
+      "code": 6057,
+      "name": "NoPendingStreamTransfer",
+      "msg": "The stream has no pending transfer"
+    },
+    {
+      "code": 6058,
+      "name": "InvalidTreasuryAuthority",
+      "msg": "Invalid treasury authority"
+    },
+    {
+      "code": 6059,
+      "name": "NoPendingTreasuryAuthority",
+      "msg": "The treasury has no pending authority handover"
+    },
+    {
+      "code": 6060,
+      "name": "InvalidStreamAccounts",
+      "msg": "Invalid stream accounts"
+    },
+    {
+      "code": 6061,
+      "name": "InvalidAllocationSplitMode",
+      "msg": "Invalid allocation split mode"
+    }
+  ]
+};
+
+export const IDL: Msp = {
+  "version": "2.8.2",
+  "name": "msp",
+  "instructions": [
+    {
+      "name": "createTreasury",
+      "docs": [
+        "Create Treasury"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "slot",
+          "type": "u64"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "treasuryType",
+          "type": "u8"
+        },
+        {
+          "name": "autoClose",
+          "type": "bool"
+        },
+        {
+          "name": "solFeePayedByTreasury",
+          "type": "bool"
+        },
+        {
+          "name": "category",
+          "type": {
+            "defined": "Category"
+          }
+        },
+        {
+          "name": "subCategory",
+          "type": {
+            "defined": "SubCategory"
+          }
+        }
+      ]
+    },
+    {
+      "name": "createStream",
+      "docs": [
+        "Create Stream"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "startUtc",
+          "type": "u64"
+        },
+        {
+          "name": "rateAmountUnits",
+          "type": "u64"
+        },
+        {
+          "name": "rateIntervalInSeconds",
+          "type": "u64"
+        },
+        {
+          "name": "allocationAssignedUnits",
+          "type": "u64"
+        },
+        {
+          "name": "cliffVestAmountUnits",
+          "type": "u64"
+        },
+        {
+          "name": "cliffVestPercent",
+          "type": "u64"
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool"
+        },
+        {
+          "name": "accrualMode",
+          "type": "u8"
+        },
+        {
+          "name": "cliffUtc",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "createStreamPda",
+      "docs": [
+        "Create Stream PDA"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "startUtc",
+          "type": "u64"
+        },
+        {
+          "name": "rateAmountUnits",
+          "type": "u64"
+        },
+        {
+          "name": "rateIntervalInSeconds",
+          "type": "u64"
+        },
+        {
+          "name": "allocationAssignedUnits",
+          "type": "u64"
+        },
+        {
+          "name": "cliffVestAmountUnits",
+          "type": "u64"
+        },
+        {
+          "name": "cliffVestPercent",
+          "type": "u64"
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool"
+        },
+        {
+          "name": "streamPdaSeed",
+          "type": "publicKey"
+        },
+        {
+          "name": "accrualMode",
+          "type": "u8"
+        },
+        {
+          "name": "cliffUtc",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "createStreamsBatch",
+      "docs": [
+        "Create Streams Batch",
+        "Creates one stream per entry. All the streams share the name, start,",
+        "fee payer, accrual mode and cliff date"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "startUtc",
+          "type": "u64"
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool"
+        },
+        {
+          "name": "accrualMode",
+          "type": "u8"
+        },
+        {
+          "name": "cliffUtc",
+          "type": "u64"
+        },
+        {
+          "name": "entries",
+          "type": {
+            "vec": {
+              "defined": "StreamBatchEntry"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "createStreamWithSchedule",
+      "docs": [
+        "Create Stream With Schedule"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "schedule",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "allocationAssignedUnits",
+          "type": "u64"
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool"
+        },
+        {
+          "name": "points",
+          "type": {
+            "vec": {
+              "defined": "SchedulePoint"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "createStreamTemplate",
+      "docs": [
+        "Create template"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "template",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "startUtc",
+          "type": "u64"
+        },
+        {
+          "name": "rateIntervalInSeconds",
+          "type": "u64"
+        },
+        {
+          "name": "durationNumberOfUnits",
+          "type": "u64"
+        },
+        {
+          "name": "cliffVestPercent",
+          "type": "u64"
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool"
+        },
+        {
+          "name": "accrualMode",
+          "type": "u8"
+        },
+        {
+          "name": "cliffUtc",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "modifyStreamTemplate",
+      "docs": [
+        "Edit template"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "template",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "startUtc",
+          "type": "u64"
+        },
+        {
+          "name": "rateIntervalInSeconds",
+          "type": "u64"
+        },
+        {
+          "name": "durationNumberOfUnits",
+          "type": "u64"
+        },
+        {
+          "name": "cliffVestPercent",
+          "type": "u64"
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool"
+        },
+        {
+          "name": "accrualMode",
+          "type": "u8"
+        },
+        {
+          "name": "cliffUtc",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "createTreasuryAndTemplate",
+      "docs": [
+        "Create Treasury"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "template",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "treasuryType",
+          "type": "u8"
+        },
+        {
+          "name": "autoClose",
+          "type": "bool"
+        },
+        {
+          "name": "solFeePayedByTreasury",
+          "type": "bool"
+        },
+        {
+          "name": "category",
+          "type": {
+            "defined": "Category"
+          }
+        },
+        {
+          "name": "subCategory",
+          "type": {
+            "defined": "SubCategory"
+          }
+        },
+        {
+          "name": "startUtc",
+          "type": "u64"
+        },
+        {
+          "name": "rateIntervalInSeconds",
+          "type": "u64"
+        },
+        {
+          "name": "durationNumberOfUnits",
+          "type": "u64"
+        },
+        {
+          "name": "cliffVestPercent",
+          "type": "u64"
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool"
+        },
+        {
+          "name": "slot",
+          "type": "u64"
+        },
+        {
+          "name": "accrualMode",
+          "type": "u8"
+        },
+        {
+          "name": "cliffUtc",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "createStreamWithTemplate",
+      "docs": [
+        "Create stream with template"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "template",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "allocationAssignedUnits",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "createStreamPdaWithTemplate",
+      "docs": [
+        "Create stream with template"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "template",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "allocationAssignedUnits",
+          "type": "u64"
+        },
+        {
+          "name": "streamPdaSeed",
+          "type": "publicKey"
+        }
+      ]
+    },
+    {
+      "name": "withdraw",
+      "docs": [
+        "Withdraw"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "beneficiary",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "beneficiaryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "withdrawToDestination",
+      "docs": [
+        "Withdraw to any token account of the stream mint"
+      ],
+      "accounts": [
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "destinationToken",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "Any token account of the stream mint"
+          ]
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "pushWithdraw",
+      "docs": [
+        "Withdraw the full withdrawable amount on behalf of the beneficiary.",
+        "Anyone can call it and, if the treasury pays the SOL fees, the cranker",
+        "gets a small reward from the treasury at most once a day per stream"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "Anyone can crank the withdrawal on behalf of the beneficiary"
+          ]
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "beneficiaryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "withdrawMany",
+      "docs": [
+        "Withdraw Many",
+        "Withdraws the full withdrawable amount of each stream passed in the",
+        "remaining accounts to the beneficiary token account. Streams with",
+        "nothing to withdraw yet are skipped"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "beneficiaryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "pauseStream",
+      "docs": [
+        "Pause Stream"
+      ],
+      "accounts": [
+        {
+          "name": "initializer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "resumeStream",
+      "docs": [
+        "Resume Stream"
+      ],
+      "accounts": [
+        {
+          "name": "initializer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "updateStreamRate",
+      "docs": [
+        "Update the rate of a stream. Everything earned at the previous rate",
+        "is kept as cliff and the stream continues at the new rate from now"
+      ],
+      "accounts": [
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "rateAmountUnits",
+          "type": "u64"
+        },
+        {
+          "name": "rateIntervalInSeconds",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "proposeTreasuryAuthority",
+      "docs": [
+        "Propose a new treasury authority. The handover takes effect when the",
+        "proposed authority accepts it. Proposing the default pubkey cancels a",
+        "pending handover"
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "newAuthority",
+          "type": "publicKey"
+        }
+      ]
+    },
+    {
+      "name": "acceptTreasuryAuthority",
+      "docs": [
+        "Accept a pending treasury authority handover. Signed by the proposed",
+        "authority"
+      ],
+      "accounts": [
+        {
+          "name": "newAuthority",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "refreshTreasuryData",
+      "docs": [
+        "Refresh Treasury Balance"
+      ],
+      "accounts": [
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "transferStream",
+      "docs": [
+        "Transfer Stream"
+      ],
+      "accounts": [
+        {
+          "name": "beneficiary",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "newBeneficiary",
+          "type": "publicKey"
+        }
+      ]
+    },
+    {
+      "name": "proposeStreamTransfer",
+      "docs": [
+        "Propose a new beneficiary for the stream. The transfer takes effect",
+        "when the proposed beneficiary accepts it"
+      ],
+      "accounts": [
+        {
+          "name": "beneficiary",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "newBeneficiary",
+          "type": "publicKey"
+        }
+      ]
+    },
+    {
+      "name": "cancelStreamTransfer",
+      "docs": [
+        "Cancel a pending stream transfer"
+      ],
+      "accounts": [
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "acceptStreamTransfer",
+      "docs": [
+        "Accept a pending stream transfer. Signed by the proposed beneficiary"
+      ],
+      "accounts": [
+        {
+          "name": "newBeneficiary",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "getStream",
+      "docs": [
+        "Get Stream"
+      ],
+      "accounts": [
+        {
+          "name": "stream",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "addFunds",
+      "docs": [
+        "Adds funds the treasury"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "contributor",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "contributorToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "allocate",
+      "docs": [
+        "Allocate units to a stream"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "allocateToAllStreams",
+      "docs": [
+        "Split `amount` units across the treasury streams passed in the",
+        "remaining accounts, pro-rata to each stream rate or evenly. Any",
+        "rounding remainder is left unallocated and scheduled streams are",
+        "skipped"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        },
+        {
+          "name": "splitMode",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "closeStream",
+      "docs": [
+        "Close Stream"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "beneficiaryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "renounceStream",
+      "docs": [
+        "Close a stream as the beneficiary, giving up the unvested allocation.",
+        "The withdrawable amount is paid out and the rest goes back to the",
+        "treasury unallocated balance"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "beneficiary",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "beneficiaryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasurer",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "stream",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "closeTreasury",
+      "docs": [
+        "Close Treasury"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "destinationAuthority",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "destinationTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "closeTreasuryAndStreams",
+      "docs": [
+        "Close Treasury And Streams",
+        "Closes every stream of the treasury (as close_stream does) and then",
+        "closes the treasury. All the treasury streams must be passed"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "destinationAuthority",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "destinationTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "treasuryWithdraw",
+      "docs": [
+        "Withdraw unallocated funds from treasury"
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "treasurer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "destinationAuthority",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "destinationTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedToken",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "treasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "treasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasury",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "feeTreasuryToken",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "idlFileVersion",
+          "type": "u8"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    }
+  ],
+  "accounts": [
+    {
+      "name": "streamSchedule",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "streamAddress",
+            "docs": [
+              "The stream that unlocks units following this schedule"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "points",
+            "docs": [
+              "The unlock points sorted by timestamp"
+            ],
+            "type": {
+              "vec": {
+                "defined": "SchedulePoint"
+              }
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "stream",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "initialized",
+            "type": "bool"
+          },
+          {
+            "name": "name",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "treasurerAddress",
+            "type": "publicKey"
+          },
+          {
+            "name": "rateAmountUnits",
+            "type": "u64"
+          },
+          {
+            "name": "rateIntervalInSeconds",
+            "type": "u64"
+          },
+          {
+            "name": "startUtc",
+            "docs": [
+              "The start timestamp in seconds"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "cliffVestAmountUnits",
+            "docs": [
+              "The amount availaible to withdraw inmidiately (without streaming)",
+              "once the money stream starts.",
+              "If both 'cliff_vest_amount_units' and 'cliff_vest_percent' are provided, the former will be used."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "cliffVestPercent",
+            "docs": [
+              "The percent of the allocation assigned that is availaible to withdraw",
+              "inmidiately (without streaming) once the money stream starts.",
+              "If both 'cliff_vest_amount_units' and 'cliff_vest_percent' are provided, the second (this field) will be used."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "beneficiaryAddress",
+            "type": "publicKey"
+          },
+          {
+            "name": "beneficiaryAssociatedToken",
+            "type": "publicKey"
+          },
+          {
+            "name": "treasuryAddress",
+            "type": "publicKey"
+          },
+          {
+            "name": "allocationAssignedUnits",
+            "docs": [
+              "Amount of tokens allocated to the stream on creation or top up. If the",
+              "treasurer decides to close the stream, the vested amount will be sent",
+              "to the benefifiary and the unvested amount will be sent to the",
+              "treasurer",
+              "",
+              "The allocation assigned will be affected by the following instructions:",
+              "`addFunds`"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "allocationReservedUnits",
+            "docs": [
+              "Amount of tokens reserved to the stream. If the treasurer decides to",
+              "close the stream, the total amount (vested and unvested) WILL be sent",
+              "to the beneficiary",
+              "",
+              "[deprecated] The allocation reserved will be affected by the following instructions:",
+              "`addFunds`"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "totalWithdrawalsUnits",
+            "docs": [
+              "Withdrawal tracking",
+              "The total amount that has been withdrawn by the beneficiary"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastWithdrawalUnits",
+            "docs": [
+              "The last amount withdrew by the beneficiary"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastWithdrawalSlot",
+            "docs": [
+              "The slot number when the last withdrawal was executed"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastWithdrawalBlockTime",
+            "docs": [
+              "The blocktime value when the last withdrawal was executed"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastManualStopWithdrawableUnitsSnap",
+            "docs": [
+              "How can a stream STOP? -> There are 2 ways:",
+              "1) by a Manual Action (recordable when it happens) or",
+              "2) by Running Out Of Funds (not recordable when it happens, needs to be calculated)"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastManualStopSlot",
+            "type": "u64"
+          },
+          {
+            "name": "lastManualStopBlockTime",
+            "type": "u64"
+          },
+          {
+            "name": "lastManualResumeRemainingAllocationUnitsSnap",
+            "docs": [
+              "The remaining allocation units at the moment of the last manual resume",
+              "must be set when calling the Resume Stream"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastManualResumeSlot",
+            "type": "u64"
+          },
+          {
+            "name": "lastManualResumeBlockTime",
+            "type": "u64"
+          },
+          {
+            "name": "lastKnownTotalSecondsInPausedStatus",
+            "docs": [
+              "The total seconds that have been paused since the start_utc",
+              "increment when resume is called manually"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastAutoStopBlockTime",
+            "docs": [
+              "The last blocktime when the stream was stopped",
+              "either manually or automaticaly (run out of funds)"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "feePayedByTreasurer",
+            "type": "bool"
+          },
+          {
+            "name": "startUtcInSeconds",
+            "docs": [
+              "The start timestamp blocktime"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "createdOnUtc",
+            "docs": [
+              "Unix timestamp (in seconds) when the stream was created"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "category",
+            "docs": [
+              "Indicates the main product category such as `Vesting(1)`",
+              "The default value is set to a `Default(0)` cateogry."
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "subCategory",
+            "docs": [
+              "Indicates the sub product category such as `Advisor(1)`, Development(2)",
+              "The default value is set to a `Default(0)` sub_cateogry."
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "accrualMode",
+            "docs": [
+              "Indicates how the stream accrues units over time. `Linear(0)` streams",
+              "every second, while `Step(1)` unlocks the full `rate_amount_units` at",
+              "the end of each `rate_interval_in_seconds`."
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "scheduleAddress",
+            "docs": [
+              "The `StreamSchedule` account that defines when units are unlocked.",
+              "When set, the rate and cliff fields are not used to calculate the",
+              "vested amount."
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "cliffUtcInSeconds",
+            "docs": [
+              "The timestamp in seconds before which nothing is withdrawable. Units",
+              "keep accruing from the start and unlock at once at this date.",
+              "Zero means the cliff unlocks at `start_utc`."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "pendingBeneficiaryAddress",
+            "docs": [
+              "The beneficiary proposed by `propose_stream_transfer`. It has to",
+              "accept the transfer before becoming the stream beneficiary"
+            ],
+            "type": "publicKey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "streamTemplate",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "startUtcInSeconds",
+            "docs": [
+              "The start timestamp blocktime"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "cliffVestPercent",
+            "docs": [
+              "The percentage availaible to withdraw inmidiately (without streaming)",
+              "once the money stream starts."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "rateIntervalInSeconds",
+            "type": "u64"
+          },
+          {
+            "name": "durationNumberOfUnits",
+            "type": "u64"
+          },
+          {
+            "name": "feePayedByTreasurer",
+            "type": "bool"
+          },
+          {
+            "name": "accrualMode",
+            "docs": [
+              "The accrual mode (`Linear(0)` or `Step(1)`) of the streams created",
+              "with this template"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "cliffUtcInSeconds",
+            "docs": [
+              "The cliff date in seconds of the streams created with this template.",
+              "Zero means the cliff unlocks at the stream start"
+            ],
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "treasury",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "initialized",
+            "type": "bool"
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "slot",
+            "type": "u64"
+          },
+          {
+            "name": "name",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "treasurerAddress",
+            "type": "publicKey"
+          },
+          {
+            "name": "associatedTokenAddress",
+            "type": "publicKey"
+          },
+          {
+            "name": "mintAddress",
+            "docs": [
+              "[deprecated] The address of the Mint of the treasury pool"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "labels",
+            "docs": [
+              "This field should not be used in its current form because it has a dynamic size",
+              "",
+              "The 4-bytes header can be repurposed in the future"
+            ],
+            "type": {
+              "vec": "string"
+            }
+          },
+          {
+            "name": "lastKnownBalanceUnits",
+            "docs": [
+              "Treasury balance tracking",
+              "The last known treasury balance (will be updated in the `refreshTreasuryData` instruction)"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastKnownBalanceSlot",
+            "docs": [
+              "The slot of the last time the treasury balance was updated"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "lastKnownBalanceBlockTime",
+            "docs": [
+              "The blocktime when the treasury balance was updated"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "allocationAssignedUnits",
+            "docs": [
+              "Treasury allocation tracking",
+              "The allocation assigned accross all the streams that belong to this treasury",
+              "",
+              "The allocation assined will be modified in the following instructions:",
+              "`createStream`, `allocate`, `withdraw` and `closeStream`"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "allocationReservedUnits",
+            "docs": [
+              "The allocation reserved accross all the streams that belong to this treasury",
+              "",
+              "[deprecated] The allocation reserved will be modified in the following instructions:",
+              "`createStream`, `withdraw` and `closeStream`"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "totalWithdrawalsUnits",
+            "docs": [
+              "The total amount withdrawn by all the streams that belong to this treasury"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "totalStreams",
+            "docs": [
+              "The current amount of streams in the treasury (will be updated in the `refreshTreasuryData` instruction)"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "createdOnUtc",
+            "type": "u64"
+          },
+          {
+            "name": "treasuryType",
+            "docs": [
+              "The type of the treasury (Open, Locked)"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "autoClose",
+            "docs": [
+              "only used for filtering in the ui"
+            ],
+            "type": "bool"
+          },
+          {
+            "name": "solFeePayedByTreasury",
+            "docs": [
+              "Indicates whether program sol fees are payed from the `treasury`'s",
+              "lamports balance (when true) or by the `payer` account in the",
+              "transaction (when false)"
+            ],
+            "type": "bool"
+          },
+          {
+            "name": "category",
+            "docs": [
+              "Indicates the main product category such as `Vesting(1)`",
+              "The default value is set to a `Default(0)` cateogry."
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "subCategory",
+            "docs": [
+              "Indicates the sub product category such as `Advisor(1)`, Development(2)",
+              "The default value is set to a `Default(0)` sub_cateogry."
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "authorityAddress",
+            "docs": [
+              "The wallet allowed to manage the treasury and its streams. When not",
+              "set, the `treasurer_address` (used in the treasury PDA seeds) is the",
+              "authority"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "pendingAuthorityAddress",
+            "docs": [
+              "The authority proposed by `propose_treasury_authority`. It has to",
+              "accept the handover before becoming the treasury authority"
+            ],
+            "type": "publicKey"
+          }
+        ]
+      }
+    }
+  ],
+  "types": [
+    {
+      "name": "SchedulePoint",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "timestamp",
+            "docs": [
+              "Unix timestamp (in seconds) when the units are unlocked"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "cumulativeUnits",
+            "docs": [
+              "The total amount of units unlocked since the stream started (including",
+              "the units unlocked in previous points)"
+            ],
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "StreamBatchEntry",
+      "docs": [
+        "A stream to be created by `create_streams_batch`"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "beneficiary",
+            "type": "publicKey"
+          },
+          {
+            "name": "streamPdaSeed",
+            "docs": [
+              "Seed used to derive the stream PDA as [b\"stream\", treasury, seed]"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "allocationAssignedUnits",
+            "type": "u64"
+          },
+          {
+            "name": "rateAmountUnits",
+            "type": "u64"
+          },
+          {
+            "name": "rateIntervalInSeconds",
+            "type": "u64"
+          },
+          {
+            "name": "cliffVestAmountUnits",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "Category",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Default"
+          },
+          {
+            "name": "Vesting"
+          }
+        ]
+      }
+    },
+    {
+      "name": "SubCategory",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Default"
+          },
+          {
+            "name": "Advisor"
+          },
+          {
+            "name": "Development"
+          },
+          {
+            "name": "Foundation"
+          },
+          {
+            "name": "Investor"
+          },
+          {
+            "name": "Marketing"
+          },
+          {
+            "name": "Partnership"
+          },
+          {
+            "name": "Seed"
+          },
+          {
+            "name": "Team"
+          },
+          {
+            "name": "Community"
+          }
+        ]
+      }
+    },
+    {
+      "name": "StreamStatus",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Scheduled"
+          },
+          {
+            "name": "Running"
+          },
+          {
+            "name": "Paused"
+          }
+        ]
+      }
+    },
+    {
+      "name": "TreasuryType",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Opened"
+          },
+          {
+            "name": "Locked"
+          }
+        ]
+      }
+    }
+  ],
+  "events": [
+    {
+      "name": "StreamEvent",
+      "fields": [
+        {
+          "name": "version",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "initialized",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "name",
+          "type": "string",
+          "index": false
+        },
+        {
+          "name": "treasurerAddress",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "rateAmountUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "rateIntervalInSeconds",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "startUtc",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "cliffVestAmountUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "cliffVestPercent",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "beneficiaryAddress",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "beneficiaryAssociatedToken",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "treasuryAddress",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "allocationAssignedUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "allocationReservedUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "totalWithdrawalsUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastWithdrawalUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastWithdrawalSlot",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastWithdrawalBlockTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastManualStopWithdrawableUnitsSnap",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastManualStopSlot",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastManualStopBlockTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastManualResumeRemainingAllocationUnitsSnap",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastManualResumeSlot",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastManualResumeBlockTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastKnownTotalSecondsInPausedStatus",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastAutoStopBlockTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "feePayedByTreasurer",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "status",
+          "type": "string",
+          "index": false
+        },
+        {
+          "name": "isManualPause",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "cliffUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "currentBlockTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "secondsSinceStart",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "estDepletionTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "fundsLeftInStream",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "fundsSentToBeneficiary",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "withdrawableUnitsWhilePaused",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "nonStopEarningUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "missedUnitsWhilePaused",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "entitledEarningsUnits",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "withdrawableUnitsWhileRunning",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "beneficiaryRemainingAllocation",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "beneficiaryWithdrawableAmount",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "lastKnownStopBlockTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "createdOnUtc",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "category",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "subCategory",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "accrualMode",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "scheduleAddress",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "cliffUtcInSeconds",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "pendingBeneficiaryAddress",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "CreateTreasuryEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solDepositedForFees",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryType",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "treasuryIsAutoClose",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "CreateStreamEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamStartTs",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamRateAmount",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamRateInterval",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamAllocation",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamCliff",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamIsTokenWithdrawFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryAllocationAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryBalanceAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "StreamWithdrawEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "amount",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenAmountSentToBeneficiary",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamWithdrawableBefore",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamIsManuallyPaused",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "streamAllocationAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamTotalWithdrawalsAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamIsTokenWithdrawFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryAllocationAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryBalanceAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryTotalWithdrawalsAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "destinationToken",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "StreamPushWithdrawEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenAmountSentToBeneficiary",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamTotalWithdrawalsAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "crankerReward",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "cranker",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "beneficiary",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "StreamPauseEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamLastManualStopWithdrawableAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "StreamResumeEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamTotalSecondsInPausedStatusAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "TreasuryAuthorityProposeEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "proposedAuthority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "TreasuryAuthorityTransferEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "previousAuthority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "newAuthority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "TreasuryRefreshEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryBalanceAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "StreamTransferEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "previousBeneficiary",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "newBeneficiary",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "StreamTransferProposeEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "beneficiary",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "proposedBeneficiary",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "StreamTransferCancelEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "beneficiary",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "cancelledBeneficiary",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TreasuryAddFundsEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "amount",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryBalanceAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "StreamAllocateEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "amount",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamStatusBefore",
+          "type": "u32",
+          "index": false
+        },
+        {
+          "name": "streamWasManuallyPausedBefore",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "streamLastAutoStopBlockTime",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamTotalSecondsInPausedStatusAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamIsTokenWithdrawFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "streamAllocationAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryAllocationAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryBalanceAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "StreamRateUpdateEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamStatusBefore",
+          "type": "u32",
+          "index": false
+        },
+        {
+          "name": "streamRateAmountBefore",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamRateIntervalBefore",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamRateAmountAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamRateIntervalAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamEarnedUnitsBefore",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamStartTsAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamTotalSecondsInPausedStatusBefore",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "CloseStreamEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenAmountSentToBeneficiary",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamIsTokenWithdrawFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "streamAllocationBefore",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "streamTotalWithdrawalsBefore",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryAllocationAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryBalanceAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryTotalStreamsAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stream",
+          "type": "publicKey",
+          "index": true
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "CloseTreasuryEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenAmountSentToDestination",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    },
+    {
+      "name": "TreasuryWithdrawEvent",
+      "fields": [
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "solFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenFeeCharged",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "amount",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "tokenAmountSentToDestination",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasuryIsSolFeePayedByTreasury",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "treasuryBalanceAfter",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "treasury",
+          "type": "publicKey",
+          "index": true
+        }
+      ]
+    }
+  ],
+  "errors": [
+    {
+      "code": 6000,
+      "name": "InvalidProgramId",
+      "msg": "Invalid Money Streaming Program ID"
+    },
+    {
+      "code": 6001,
+      "name": "InvalidOwner",
+      "msg": "Invalid account owner"
+    },
+    {
+      "code": 6002,
+      "name": "NotAuthorized",
+      "msg": "Not Authorized"
+    },
+    {
+      "code": 6003,
+      "name": "Overflow",
+      "msg": "Overflow"
+    },
+    {
+      "code": 6004,
+      "name": "InvalidAssociatedToken",
+      "msg": "Invalid associated token address"
+    },
+    {
+      "code": 6005,
+      "name": "InvalidFeeTreasuryAccount",
+      "msg": "Invalid fee treasury account"
+    },
+    {
+      "code": 6006,
+      "name": "InvalidTreasuryMintDecimals",
+      "msg": "Invalid treasury mint decimals"
+    },
+    {
+      "code": 6007,
+      "name": "TreasuryAlreadyInitialized",
+      "msg": "Treasury is already initialized"
+    },
+    {
+      "code": 6008,
+      "name": "TreasuryNotInitialized",
+      "msg": "Treasury is not initialized"
+    },
+    {
+      "code": 6009,
+      "name": "InvalidTreasuryVersion",
+      "msg": "Invalid treasury version"
+    },
+    {
+      "code": 6010,
+      "name": "InvalidTreasuryMint",
+      "msg": "Invalid treasury mint address"
+    },
+    {
+      "code": 6011,
+      "name": "InvalidTreasury",
+      "msg": "Invalid treasury account"
+    },
+    {
+      "code": 6012,
+      "name": "InvalidTreasurySize",
+      "msg": "Invalid treasury size"
+    },
+    {
+      "code": 6013,
+      "name": "InvalidTreasurer",
+      "msg": "Invalid treasurer"
+    },
+    {
+      "code": 6014,
+      "name": "InvalidBeneficiary",
+      "msg": "Invalid beneficiary"
+    },
+    {
+      "code": 6015,
+      "name": "InvalidArgument",
+      "msg": "Invalid argument"
+    },
+    {
+      "code": 6016,
+      "name": "StreamNotInitialized",
+      "msg": "Stream not initialized"
+    },
+    {
+      "code": 6017,
+      "name": "StreamAlreadyInitialized",
+      "msg": "Stream is already initialized"
+    },
+    {
+      "code": 6018,
+      "name": "InvalidStreamVersion",
+      "msg": "Invalid stream version"
+    },
+    {
+      "code": 6019,
+      "name": "InvalidStreamSize",
+      "msg": "Invalid stream size"
+    },
+    {
+      "code": 6020,
+      "name": "InvalidStream",
+      "msg": "Invalid stream account"
+    },
+    {
+      "code": 6021,
+      "name": "InvalidRequestedStreamAllocation",
+      "msg": "Invalid requested stream allocation"
+    },
+    {
+      "code": 6022,
+      "name": "InvalidWithdrawalAmount",
+      "msg": "Invalid withdrawal amount"
+    },
+    {
+      "code": 6023,
+      "name": "StringTooLong",
+      "msg": "The string length is larger than 32 bytes"
+    },
+    {
+      "code": 6024,
+      "name": "StreamAlreadyRunning",
+      "msg": "The stream is already running"
+    },
+    {
+      "code": 6025,
+      "name": "StreamAlreadyPaused",
+      "msg": "The stream is already paused"
+    },
+    {
+      "code": 6026,
+      "name": "StreamZeroRemainingAllocation",
+      "msg": "Stream allocation assigned is zero"
+    },
+    {
+      "code": 6027,
+      "name": "ZeroContributionAmount",
+      "msg": "Contribution amount is zero"
+    },
+    {
+      "code": 6028,
+      "name": "ZeroWithdrawalAmount",
+      "msg": "Withdrawal amount is zero"
+    },
+    {
+      "code": 6029,
+      "name": "StreamIsScheduled",
+      "msg": "Stream has not started"
+    },
+    {
+      "code": 6030,
+      "name": "CloseLockedStreamNotAllowedWhileRunning",
+      "msg": "Streams in a Locked treasury can not be closed while running"
+    },
+    {
+      "code": 6031,
+      "name": "PauseOrResumeLockedStreamNotAllowed",
+      "msg": "Streams in a Locked treasury can not be paused or resumed"
+    },
+    {
+      "code": 6032,
+      "name": "ReservedAllocationExceedWithdrawableAmount",
+      "msg": "Can not pause a stream if the reserved allocation is greater than the withdrawable amount"
+    },
+    {
+      "code": 6033,
+      "name": "AllocateNotAllowedOnLockedStreams",
+      "msg": "Can not allocate funds to a stream from a locked treasury"
+    },
+    {
+      "code": 6034,
+      "name": "InvalidStreamRate",
+      "msg": "Invalid stream rate"
+    },
+    {
+      "code": 6035,
+      "name": "InvalidCliff",
+      "msg": "Invalid cliff"
+    },
+    {
+      "code": 6036,
+      "name": "InsufficientLamports",
+      "msg": "Insufficient lamports"
+    },
+    {
+      "code": 6037,
+      "name": "TreasuryContainsStreams",
+      "msg": "This treasury contains one or more streams"
+    },
+    {
+      "code": 6038,
+      "name": "InsufficientFunds",
+      "msg": "Insufficient funds"
+    },
+    {
+      "code": 6039,
+      "name": "InsufficientTreasuryBalance",
+      "msg": "Insufficient treasury balance"
+    },
+    {
+      "code": 6040,
+      "name": "CannotResumeAutoPausedStream",
+      "msg": "Stream is auto-paused. To resume use allocate"
+    },
+    {
+      "code": 6041,
+      "name": "CannotPauseAndUnpauseOnSameBlockTime",
+      "msg": "Cannot pause and unpause on the same block time"
+    },
+    {
+      "code": 6042,
+      "name": "InvalidTreasuryRequestedAllocation",
+      "msg": "Treasury allocation can not be greater than treasury balance"
+    },
+    {
+      "code": 6043,
+      "name": "InvalidIdlFileVersion",
+      "msg": "Invalid IDL file version"
+    },
+    {
+      "code": 6044,
+      "name": "InvalidTotalStreamsInTreasury",
+      "msg": "Invalid total streams in treasury"
+    },
+    {
+      "code": 6045,
+      "name": "InvalidTemplateVersion",
+      "msg": "Invalid template version"
+    },
+    {
+      "code": 6046,
+      "name": "InvalidTemplateSize",
+      "msg": "Invalid template size"
+    },
+    {
+      "code": 6047,
+      "name": "CannotModifyTemplate",
+      "msg": "Template cannot be modified after streams have been created"
+    },
+    {
+      "code": 6048,
+      "name": "NumberOfIntervalsMustBePossitive",
+      "msg": "Number of intervals must be greater than zero"
+    },
+    {
+      "code": 6049,
+      "name": "ZeroRateAmountTemplateConfiguration",
+      "msg": "The configuration of this template yields a zero rate amount per interval"
+    },
+    {
+      "code": 6050,
+      "name": "InvalidAccrualMode",
+      "msg": "Invalid stream accrual mode"
+    },
+    {
+      "code": 6051,
+      "name": "InvalidSchedule",
+      "msg": "Invalid unlock schedule"
+    },
+    {
+      "code": 6052,
+      "name": "InvalidScheduleTotal",
+      "msg": "The unlock schedule total does not match the stream allocation"
+    },
+    {
+      "code": 6053,
+      "name": "InvalidScheduleAccount",
+      "msg": "Invalid stream schedule account"
+    },
+    {
+      "code": 6054,
+      "name": "NotAllowedOnScheduledStream",
+      "msg": "This operation is not allowed on streams with an unlock schedule"
+    },
+    {
+      "code": 6055,
+      "name": "UpdateRateNotAllowedOnLockedStreams",
+      "msg": "Can not update the rate of a stream from a locked treasury"
+    },
+    {
+      "code": 6056,
+      "name": "UpdateRateNotAllowedWhilePaused",
+      "msg": "Can not update the rate of a manually paused stream"
+    },
+    {
+      "code": 6057,
+      "name": "NoPendingStreamTransfer",
+      "msg": "The stream has no pending transfer"
+    },
+    {
+      "code": 6058,
+      "name": "InvalidTreasuryAuthority",
+      "msg": "Invalid treasury authority"
+    },
+    {
+      "code": 6059,
+      "name": "NoPendingTreasuryAuthority",
+      "msg": "The treasury has no pending authority handover"
+    },
+    {
+      "code": 6060,
+      "name": "InvalidStreamAccounts",
+      "msg": "Invalid stream accounts"
+    },
+    {
+      "code": 6061,
+      "name": "InvalidAllocationSplitMode",
+      "msg": "Invalid allocation split mode"
+    }
+  ]
+};
@@ -21,7 +21,7 @@ import {
 } from '@solana/spl-token';
 import { BN, Program } from '@project-serum/anchor';
 
-import { Msp as Ps } from './msp_idl_005';
+import { Msp as Ps } from './msp_idl_010';
 
 /**
  * MSP
@@ -629,6 +629,7 @@ export class PaymentStreaming {
    * @param solFeePayedFromAccount
    * @param category
    * @param subCategory
+   * @param referrer - Wallet of the integrator that referred the new account
    * @returns
    */
   public async buildCreateAccountTransaction(
@@ -638,6 +639,7 @@ export class PaymentStreaming {
     solFeePayedFromAccount = false,
     category: Category = Category.default,
     subCategory: SubCategory = SubCategory.default,
+    referrer: PublicKey | null = null,
   ): Promise<{
     transaction: Transaction;
     psAccount: PublicKey;
@@ -666,6 +668,7 @@ export class PaymentStreaming {
       solFeePayedFromAccount,
       category,
       subCategory,
+      referrer,
     );
 
     const tx = await this.createTransaction([createAccountIx], feePayer);
@@ -1000,6 +1003,8 @@ export class PaymentStreaming {
         new BN(newStartTs),
         new BN(newClifPercentValue),
         newTokenFeePayedFromVestingAccount,
+        templateInfo.accrualMode,
+        new BN(templateInfo.cliffUtcInSeconds),
       );
 
     const tx = await this.createTransaction(
@@ -1,7 +1,7 @@
 import { IdlAccounts } from '@project-serum/anchor';
 import { Commitment, PublicKey } from '@solana/web3.js';
 import BN from 'bn.js';
-import { Msp } from './msp_idl_005'; // point to the latest IDL
+import { Msp } from './msp_idl_010'; // point to the latest IDL
 // Given an IDL type IDL we can derive Typescript types for its accounts
 // using eg. IdlAccounts<IDL>['ACCOUNT_NAME']
 type RawStream = IdlAccounts<Msp>['stream'];
@@ -198,8 +198,20 @@ export type StreamTemplate = {
   rateIntervalInSeconds: number;
   durationNumberOfUnits: number;
   feePayedByTreasurer: boolean;
+  accrualMode: AccrualMode;
+  cliffUtcInSeconds: number;
 };
 
+/**
+ * How a stream accrues its rate amount
+ */
+export enum AccrualMode {
+  /** The rate amount is streamed progressively second by second */
+  Linear = 0,
+  /** The rate amount is unlocked at once at the end of each interval */
+  Step = 1,
+}
+
 /**
  * Stream status codes.
  */
@@ -362,6 +374,10 @@ export type StreamEventData = {
   createdOnUtc: BN;
   category: number;
   subCategory: number;
+  accrualMode: number;
+  scheduleAddress: PublicKey;
+  cliffUtcInSeconds: BN;
+  pendingBeneficiaryAddress: PublicKey;
 };
 
 type FeepayerAccounts = {
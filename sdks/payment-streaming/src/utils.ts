@@ -16,6 +16,7 @@ import {
 } from '@solana/web3.js';
 import {
   BN,
+  BorshAccountsCoder,
   BorshInstructionCoder,
   Idl,
   IdlAccounts,
@@ -29,6 +30,7 @@ import {
   SIMULATION_PUBKEY,
 } from './constants';
 import {
+  AccrualMode,
   Category,
   ACTION_CODES,
   PaymentStreamingAccount,
@@ -44,12 +46,15 @@ import {
   ActivityActionCode,
   STREAM_STATUS_CODE,
 } from './types';
-import { IDL, Msp as Ps } from './msp_idl_005'; // point to the latest IDL
+import { IDL, Msp as Ps } from './msp_idl_010'; // point to the latest IDL
+// the last IDL with the v2 stream and treasury layouts
+import { IDL as V2_LAYOUT_IDL, Msp as PsV2 } from './msp_idl_006';
 // Given an IDL type IDL we can derive Typescript types for its accounts
 // using eg. IdlAccounts<IDL>['ACCOUNT_NAME']
 type RawStream = IdlAccounts<Ps>['stream'];
 type RawTreasury = IdlAccounts<Ps>['treasury'];
 type RawStreamTemplate = IdlAccounts<Ps>['streamTemplate'];
+type RawStreamV2 = IdlAccounts<PsV2>['stream'];
 // Events are not possible yet.
 // See https://github.com/coral-xyz/anchor/issues/2050
 // See https://github.com/coral-xyz/anchor/pull/2185
@@ -296,7 +301,11 @@ export const getAccount = async (
   program: Program<Ps>,
   address: PublicKey,
 ): Promise<PaymentStreamingAccount> => {
-  const psAccount = await program.account.treasury.fetch(address);
+  const accountInfo = await program.provider.connection.getAccountInfo(address);
+  if (!accountInfo) {
+    throw new Error(`Account does not exist ${address.toBase58()}`);
+  }
+  const psAccount = decodeTreasuryAccount(program, accountInfo.data);
   const parsedAccount = parseAccountData(psAccount, address);
 
   return parsedAccount;
@@ -320,6 +329,177 @@ export const findStreamTemplateAddress = (
   );
 };
 
+export const findConfigAddress = (
+  programId: PublicKey,
+): [PublicKey, number] => {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode('config')],
+    programId,
+  );
+};
+
+export const findFeeOverrideAddress = (
+  subject: PublicKey,
+  programId: PublicKey,
+): [PublicKey, number] => {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode('fee_override'), subject.toBuffer()],
+    programId,
+  );
+};
+
+/** Stream and treasury account layout versions still in use */
+const ACCOUNT_LAYOUT_VERSIONS = [2, 3];
+
+/** Offset of the layout version of a stream account */
+const STREAM_VERSION_OFFSET = 8;
+
+/** Offset of the layout version of a treasury account */
+const TREASURY_VERSION_OFFSET = 8 + 1;
+
+/** Offsets of the stream fields used as filters, by layout version */
+const STREAM_FILTER_OFFSETS: {
+  [version: number]: {
+    treasurer: number;
+    beneficiary: number;
+    treasury: number;
+    category: number;
+    subCategory: number;
+  };
+} = {
+  2: {
+    treasurer: 8 + 34,
+    beneficiary: 8 + 106,
+    treasury: 8 + 170,
+    category: 339,
+    subCategory: 340,
+  },
+  3: {
+    treasurer: 8 + 34,
+    beneficiary: 8 + 98,
+    treasury: 8 + 162,
+    category: 315,
+    subCategory: 316,
+  },
+};
+
+/** Offsets of the treasury fields used as filters, by layout version */
+const TREASURY_FILTER_OFFSETS: {
+  [version: number]: {
+    treasurer: number;
+    autoClose: number;
+    category: number;
+    subCategory: number;
+  };
+} = {
+  2: { treasurer: 8 + 43, autoClose: 216, category: 218, subCategory: 219 },
+  3: { treasurer: 8 + 43, autoClose: 172, category: 174, subCategory: 175 },
+};
+
+const v2AccountsCoder = new BorshAccountsCoder(V2_LAYOUT_IDL);
+
+/**
+ * Decodes a stream account of any layout version. v2 streams are converted
+ * to the v3 fields the same way the program does
+ * @param program
+ * @param data raw account data
+ * @returns RawStream
+ */
+export const decodeStreamAccount = (
+  program: Program<Ps>,
+  data: Buffer,
+): RawStream => {
+  if (data[STREAM_VERSION_OFFSET] !== 2) {
+    return program.coder.accounts.decode('stream', data);
+  }
+
+  const stream: RawStreamV2 = v2AccountsCoder.decode('stream', data);
+  // v3 streams only store the cliff as an absolute amount and the start
+  // timestamp in seconds
+  const cliffVestAmountUnits = stream.cliffVestPercent.gtn(0)
+    ? stream.cliffVestPercent
+        .mul(stream.allocationAssignedUnits)
+        .div(new BN(CLIFF_PERCENT_DENOMINATOR))
+    : stream.cliffVestAmountUnits;
+  const startUtc = stream.startUtcInSeconds.gtn(0)
+    ? stream.startUtcInSeconds
+    : stream.startUtc.toString().length > 10
+    ? stream.startUtc.div(new BN(1000))
+    : stream.startUtc;
+
+  return { ...stream, startUtc, cliffVestAmountUnits };
+};
+
+/**
+ * Decodes a treasury account of any layout version
+ * @param program
+ * @param data raw account data
+ * @returns RawTreasury
+ */
+export const decodeTreasuryAccount = (
+  program: Program<Ps>,
+  data: Buffer,
+): RawTreasury => {
+  if (data[TREASURY_VERSION_OFFSET] !== 2) {
+    return program.coder.accounts.decode('treasury', data);
+  }
+  return v2AccountsCoder.decode('treasury', data);
+};
+
+const getStreamAccounts = async (
+  program: Program<Ps>,
+  version: number,
+  filters: MemcmpFilter[],
+): Promise<ProgramAccount<RawStream>[]> => {
+  const accounts = await program.provider.connection.getProgramAccounts(
+    program.programId,
+    {
+      filters: [
+        { memcmp: program.coder.accounts.memcmp('stream') },
+        {
+          memcmp: {
+            offset: STREAM_VERSION_OFFSET,
+            bytes: bs58.encode([version]),
+          },
+        },
+        ...filters,
+      ],
+    },
+  );
+
+  return accounts.map(({ pubkey, account }) => ({
+    publicKey: pubkey,
+    account: decodeStreamAccount(program, account.data),
+  }));
+};
+
+const getTreasuryAccounts = async (
+  program: Program<Ps>,
+  version: number,
+  filters: MemcmpFilter[],
+): Promise<ProgramAccount<RawTreasury>[]> => {
+  const accounts = await program.provider.connection.getProgramAccounts(
+    program.programId,
+    {
+      filters: [
+        { memcmp: program.coder.accounts.memcmp('treasury') },
+        {
+          memcmp: {
+            offset: TREASURY_VERSION_OFFSET,
+            bytes: bs58.encode([version]),
+          },
+        },
+        ...filters,
+      ],
+    },
+  );
+
+  return accounts.map(({ pubkey, account }) => ({
+    publicKey: pubkey,
+    account: decodeTreasuryAccount(program, account.data),
+  }));
+};
+
 export const listAccounts = async (
   program: Program<Ps>,
   owner?: PublicKey | undefined,
@@ -328,33 +508,43 @@ export const listAccounts = async (
   subCategory?: SubCategory,
 ): Promise<PaymentStreamingAccount[]> => {
   const psAccounts: PaymentStreamingAccount[] = [];
-  const memcmpFilters: GetProgramAccountsFilter[] = [];
+  const accounts: ProgramAccount<RawTreasury>[] = [];
 
-  if (owner) {
-    memcmpFilters.push({
-      memcmp: { offset: 8 + 43, bytes: owner.toBase58() },
-    });
-  }
+  for (const version of ACCOUNT_LAYOUT_VERSIONS) {
+    const offsets = TREASURY_FILTER_OFFSETS[version];
+    const memcmpFilters: MemcmpFilter[] = [];
 
-  if (excludeAutoClose) {
-    memcmpFilters.push({
-      memcmp: { offset: 216, bytes: bs58.encode([0]) },
-    });
-  }
+    if (owner) {
+      memcmpFilters.push({
+        memcmp: { offset: offsets.treasurer, bytes: owner.toBase58() },
+      });
+    }
 
-  if (category !== undefined) {
-    memcmpFilters.push({
-      memcmp: { offset: 218, bytes: bs58.encode([category]) },
-    });
-  }
+    if (excludeAutoClose) {
+      memcmpFilters.push({
+        memcmp: { offset: offsets.autoClose, bytes: bs58.encode([0]) },
+      });
+    }
 
-  if (subCategory !== undefined) {
-    memcmpFilters.push({
-      memcmp: { offset: 219, bytes: bs58.encode([subCategory]) },
-    });
-  }
+    if (category !== undefined) {
+      memcmpFilters.push({
+        memcmp: { offset: offsets.category, bytes: bs58.encode([category]) },
+      });
+    }
 
-  const accounts = await program.account.treasury.all(memcmpFilters);
+    if (subCategory !== undefined) {
+      memcmpFilters.push({
+        memcmp: {
+          offset: offsets.subCategory,
+          bytes: bs58.encode([subCategory]),
+        },
+      });
+    }
+
+    accounts.push(
+      ...(await getTreasuryAccounts(program, version, memcmpFilters)),
+    );
+  }
 
   if (accounts.length) {
     for (const item of accounts) {
@@ -480,59 +670,76 @@ export const getFilteredStreamAccounts = async (
 ) => {
   const accounts: ProgramAccount<RawStream>[] = [];
 
-  // category filters
-  const categoryFilters: MemcmpFilter[] = [];
+  for (const version of ACCOUNT_LAYOUT_VERSIONS) {
+    const offsets = STREAM_FILTER_OFFSETS[version];
 
-  if (category !== undefined) {
-    categoryFilters.push({
-      memcmp: { offset: 339, bytes: bs58.encode([category]) },
-    });
-  }
-
-  if (subCategory !== undefined) {
-    categoryFilters.push({
-      memcmp: { offset: 340, bytes: bs58.encode([subCategory]) },
-    });
-  }
+    // category filters
+    const categoryFilters: MemcmpFilter[] = [];
 
-  if (psAccount) {
-    const memcmpFilters: MemcmpFilter[] = [
-      { memcmp: { offset: 8 + 170, bytes: psAccount.toBase58() } },
-      ...categoryFilters,
-    ];
-    const accs = await program.account.stream.all(memcmpFilters);
+    if (category !== undefined) {
+      categoryFilters.push({
+        memcmp: { offset: offsets.category, bytes: bs58.encode([category]) },
+      });
+    }
 
-    if (accs.length) {
-      accounts.push(...accs);
+    if (subCategory !== undefined) {
+      categoryFilters.push({
+        memcmp: {
+          offset: offsets.subCategory,
+          bytes: bs58.encode([subCategory]),
+        },
+      });
     }
-  } else {
-    if (psAccountOwner) {
+
+    if (psAccount) {
       const memcmpFilters: MemcmpFilter[] = [
-        { memcmp: { offset: 8 + 34, bytes: psAccountOwner.toBase58() } },
+        { memcmp: { offset: offsets.treasury, bytes: psAccount.toBase58() } },
         ...categoryFilters,
       ];
-      const accs = await program.account.stream.all(memcmpFilters);
+      const accs = await getStreamAccounts(program, version, memcmpFilters);
 
       if (accs.length) {
-        for (const acc of accs) {
-          if (accounts.indexOf(acc) === -1) {
-            accounts.push(acc);
+        accounts.push(...accs);
+      }
+    } else {
+      if (psAccountOwner) {
+        const memcmpFilters: MemcmpFilter[] = [
+          {
+            memcmp: {
+              offset: offsets.treasurer,
+              bytes: psAccountOwner.toBase58(),
+            },
+          },
+          ...categoryFilters,
+        ];
+        const accs = await getStreamAccounts(program, version, memcmpFilters);
+
+        if (accs.length) {
+          for (const acc of accs) {
+            if (accounts.indexOf(acc) === -1) {
+              accounts.push(acc);
+            }
           }
         }
       }
-    }
-
-    if (beneficiary) {
-      const memcmpFilters: MemcmpFilter[] = [
-        { memcmp: { offset: 8 + 106, bytes: beneficiary.toBase58() } },
-        ...categoryFilters,
-      ];
-      const accs = await program.account.stream.all(memcmpFilters);
 
-      if (accs.length) {
-        for (const acc of accs) {
-          if (accounts.indexOf(acc) === -1) {
-            accounts.push(acc);
+      if (beneficiary) {
+        const memcmpFilters: MemcmpFilter[] = [
+          {
+            memcmp: {
+              offset: offsets.beneficiary,
+              bytes: beneficiary.toBase58(),
+            },
+          },
+          ...categoryFilters,
+        ];
+        const accs = await getStreamAccounts(program, version, memcmpFilters);
+
+        if (accs.length) {
+          for (const acc of accs) {
+            if (accounts.indexOf(acc) === -1) {
+              accounts.push(acc);
+            }
           }
         }
       }
@@ -568,12 +775,10 @@ const parseStreamEventData = (event: StreamEventData, address: PublicKey) => {
     rateIntervalInSeconds: event.rateIntervalInSeconds,
     startUtc: event.startUtc,
     cliffVestAmountUnits: event.cliffVestAmountUnits,
-    cliffVestPercent: event.cliffVestPercent,
     beneficiaryAddress: event.beneficiaryAddress,
     beneficiaryAssociatedToken: event.beneficiaryAssociatedToken,
     treasuryAddress: event.treasuryAddress,
     allocationAssignedUnits: event.allocationAssignedUnits,
-    allocationReservedUnits: event.allocationReservedUnits,
     totalWithdrawalsUnits: event.totalWithdrawalsUnits,
     lastWithdrawalUnits: event.lastWithdrawalUnits,
     lastWithdrawalSlot: event.lastWithdrawalSlot,
@@ -590,11 +795,13 @@ const parseStreamEventData = (event: StreamEventData, address: PublicKey) => {
       event.lastKnownTotalSecondsInPausedStatus,
     lastAutoStopBlockTime: event.lastAutoStopBlockTime,
     feePayedByTreasurer: event.feePayedByTreasurer,
-    // startUtc is guaranteed to be in seconds for the getStream event
-    startUtcInSeconds: event.startUtc,
     createdOnUtc: event.createdOnUtc,
     category: event.category,
     subCategory: event.subCategory,
+    accrualMode: event.accrualMode,
+    scheduleAddress: event.scheduleAddress,
+    cliffUtcInSeconds: event.cliffUtcInSeconds,
+    pendingBeneficiaryAddress: event.pendingBeneficiaryAddress,
   } as RawStream;
 
   let statusCode = STREAM_STATUS_CODE.Unknown;
@@ -707,8 +914,8 @@ export const parseRawStreamAccount = (
     beneficiary: rawStream.beneficiaryAddress,
     mint: rawStream.beneficiaryAssociatedToken,
     cliffVestAmount: rawStream.cliffVestAmountUnits,
-    cliffVestPercent:
-      rawStream.cliffVestPercent.toNumber() / CLIFF_PERCENT_NUMERATOR,
+    // the cliff is stored as an absolute amount since v3
+    cliffVestPercent: 0,
     allocationAssigned: rawStream.allocationAssignedUnits,
     secondsSinceStart: blockTime - startUtcInSeconds,
     estimatedDepletionDate: depletionDate.toString(),
@@ -835,6 +1042,8 @@ export const parseStreamTemplateData = (
     startUtc: new Date(template.startUtcInSeconds.toNumber() * 1000).toString(),
     cliffVestPercent: template.cliffVestPercent.toNumber(),
     feePayedByTreasurer: template.feePayedByTreasurer,
+    accrualMode: template.accrualMode as AccrualMode,
+    cliffUtcInSeconds: template.cliffUtcInSeconds.toNumber(),
   } as StreamTemplate;
 };
 
@@ -865,18 +1074,9 @@ export const getStreamEstDepletionDate = (stream: RawStream) => {
 };
 
 export const getStreamCliffAmount = (stream: RawStream) => {
-  // Previously, cliff could be provided either as percentage or amount.
-  // Currently, cliff percent is not stored in the stream, when a stream is
-  // created with a cliff percent, it is converted to an absolute amount and
-  // stored in stream.cliffVestAmountUnits. Legacy stream might still use
-  // the percent flavor so we take care of those cases here
-
-  if (stream.cliffVestPercent.gtn(0)) {
-    return stream.cliffVestPercent
-      .mul(stream.allocationAssignedUnits)
-      .div(new BN(CLIFF_PERCENT_DENOMINATOR));
-  }
-
+  // The cliff is always stored as an absolute amount. The cliff percent of
+  // legacy v2 streams is converted when decoding them (see
+  // `decodeStreamAccount`)
   return stream.cliffVestAmountUnits;
 };
 
@@ -1027,14 +1227,8 @@ export const getStreamUnitsPerSecond = (
 };
 
 export const getStreamStartUtcInSeconds = (stream: RawStream): number => {
-  if (stream.startUtcInSeconds.gt(new BN(0))) {
-    return stream.startUtcInSeconds.toNumber();
-  }
-  // Some legacy streams were created with startUtc in miliseconds instead
-  // of seconds. In those cases we need to conver to seconds.
-  if (stream.startUtc.toString().length > 10) {
-    return stream.startUtc.div(new BN(1000)).toNumber();
-  }
+  // Some legacy v2 streams were created with startUtc in miliseconds, they
+  // are converted to seconds when decoding them (see `decodeStreamAccount`)
   return stream.startUtc.toNumber();
 };
 
@@ -1343,6 +1537,21 @@ async function parseProgramInstruction(
       } else if (idlFileVersion === 5) {
         const importedIdl = await import('./msp_idl_005');
         idls[idlFileVersion] = importedIdl.IDL;
+      } else if (idlFileVersion === 6) {
+        const importedIdl = await import('./msp_idl_006');
+        idls[idlFileVersion] = importedIdl.IDL;
+      } else if (idlFileVersion === 7) {
+        const importedIdl = await import('./msp_idl_007');
+        idls[idlFileVersion] = importedIdl.IDL;
+      } else if (idlFileVersion === 8) {
+        const importedIdl = await import('./msp_idl_008');
+        idls[idlFileVersion] = importedIdl.IDL;
+      } else if (idlFileVersion === 9) {
+        const importedIdl = await import('./msp_idl_009');
+        idls[idlFileVersion] = importedIdl.IDL;
+      } else if (idlFileVersion === 10) {
+        const importedIdl = await import('./msp_idl_010');
+        idls[idlFileVersion] = importedIdl.IDL;
       } else {
         return null;
       }
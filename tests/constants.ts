@@ -1,10 +1,15 @@
 import { PublicKey, Keypair, Connection, SystemProgram } from '@solana/web3.js';
 import { AnchorProvider, web3 } from '@project-serum/anchor';
-export const LATEST_IDL_FILE_VERSION = 5;
+export const LATEST_IDL_FILE_VERSION = 10;
 export const DECIMALS = 6;
 export const TREASURY_TYPE_OPEN = 0;
 export const TREASURY_TYPE_LOCKED = 1;
+export const STREAM_ACCRUAL_MODE_LINEAR = 0;
 export const MSP_FEES_PUBKEY = new PublicKey('3TD6SWY9M1mLY2kZWJNavPLhwXvcRsWdnZLRaMzERJBw');
+export const MSP_CONFIG_PUBKEY = PublicKey.findProgramAddressSync(
+    [Buffer.from('config')],
+    new PublicKey('MSPdQo5ZdrPh6rU1LsvUv5nRhAnj1mj6YQEqBUq8YwZ')
+)[0];
 export const SYSTEM_PROGRAM_ID = SystemProgram.programId;
 export const SYSVAR_RENT_PUBKEY = web3.SYSVAR_RENT_PUBKEY;
 
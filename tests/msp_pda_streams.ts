@@ -6,7 +6,7 @@ import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID, Token } from '@solana/sp
 import * as anchor from '@project-serum/anchor';
 import { Program, workspace, BN } from '@project-serum/anchor';
 import { Msp } from '../target/types/msp';
-import { CONFIRM_OPTIONS, DECIMALS, LATEST_IDL_FILE_VERSION, MSP_FEES_PUBKEY, SYSTEM_PROGRAM_ID, SYSVAR_RENT_PUBKEY, TREASURY_TYPE_OPEN, URL } from './constants';
+import { CONFIRM_OPTIONS, DECIMALS, LATEST_IDL_FILE_VERSION, MSP_CONFIG_PUBKEY, MSP_FEES_PUBKEY, STREAM_ACCRUAL_MODE_LINEAR, SYSTEM_PROGRAM_ID, SYSVAR_RENT_PUBKEY, TREASURY_TYPE_OPEN, URL } from './constants';
 
 const Category = {
     Default: { default: {} },
@@ -102,7 +102,8 @@ describe('msp-pda-streams', () => {
                     false,
                     false,
                     Category.Default,
-                    SubCategory.Default
+                    SubCategory.Default,
+                    null
                 )
                 .accounts({
                     payer: treasurerKey.publicKey,
@@ -110,6 +111,7 @@ describe('msp-pda-streams', () => {
                     treasury: treasury,
                     treasuryToken: treasuryFrom,
                     associatedToken: fromTokenClient.publicKey,
+                    config: MSP_CONFIG_PUBKEY,
                     feeTreasury: MSP_FEES_PUBKEY,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                     tokenProgram: TOKEN_PROGRAM_ID,
@@ -130,6 +132,7 @@ describe('msp-pda-streams', () => {
                     treasury: treasury,
                     treasuryToken: treasuryFrom,
                     associatedToken: fromTokenClient.publicKey,
+                    config: MSP_CONFIG_PUBKEY,
                     feeTreasury: MSP_FEES_PUBKEY,
                     feeTreasuryToken: FEES_FROM,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -166,6 +169,8 @@ describe('msp-pda-streams', () => {
                     new BN(0),
                     new BN(0),
                     true,
+                    STREAM_ACCRUAL_MODE_LINEAR,
+                    new BN(0)
                 )
                 .accounts({
                     payer: treasurerKey.publicKey,
@@ -175,6 +180,10 @@ describe('msp-pda-streams', () => {
                     associatedToken: fromTokenClient.publicKey,
                     beneficiary: beneficiaryKey.publicKey,
                     stream: streamKey.publicKey,
+                    config: MSP_CONFIG_PUBKEY,
+                    feeOverride: SYSTEM_PROGRAM_ID,
+                    referrer: MSP_FEES_PUBKEY,
+                    referrerToken: FEES_FROM,
                     feeTreasury: MSP_FEES_PUBKEY,
                     feeTreasuryToken: FEES_FROM,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -230,7 +239,8 @@ describe('msp-pda-streams', () => {
                     false,
                     false,
                     Category.Default,
-                    SubCategory.Default
+                    SubCategory.Default,
+                    null
                 )
                 .accounts({
                     payer: treasurerKey.publicKey,
@@ -238,6 +248,7 @@ describe('msp-pda-streams', () => {
                     treasury: treasury,
                     treasuryToken: treasuryFrom,
                     associatedToken: fromTokenClient.publicKey,
+                    config: MSP_CONFIG_PUBKEY,
                     feeTreasury: MSP_FEES_PUBKEY,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                     tokenProgram: TOKEN_PROGRAM_ID,
@@ -258,6 +269,7 @@ describe('msp-pda-streams', () => {
                     treasury: treasury,
                     treasuryToken: treasuryFrom,
                     associatedToken: fromTokenClient.publicKey,
+                    config: MSP_CONFIG_PUBKEY,
                     feeTreasury: MSP_FEES_PUBKEY,
                     feeTreasuryToken: FEES_FROM,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -298,7 +310,9 @@ describe('msp-pda-streams', () => {
                     new BN(0),
                     new BN(0),
                     true,
-                    streamPdaSeed
+                    streamPdaSeed,
+                    STREAM_ACCRUAL_MODE_LINEAR,
+                    new BN(0)
                 )
                 .accounts({
                     payer: treasurerKey.publicKey,
@@ -308,6 +322,10 @@ describe('msp-pda-streams', () => {
                     associatedToken: fromTokenClient.publicKey,
                     beneficiary: beneficiaryKey.publicKey,
                     stream: stream,
+                    config: MSP_CONFIG_PUBKEY,
+                    feeOverride: SYSTEM_PROGRAM_ID,
+                    referrer: MSP_FEES_PUBKEY,
+                    referrerToken: FEES_FROM,
                     feeTreasury: MSP_FEES_PUBKEY,
                     feeTreasuryToken: FEES_FROM,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -367,7 +385,8 @@ describe('msp-pda-streams', () => {
                     false,
                     false,
                     Category.Default,
-                    SubCategory.Default
+                    SubCategory.Default,
+                    null
                 )
                 .accounts({
                     payer: treasurerKey.publicKey,
@@ -375,6 +394,7 @@ describe('msp-pda-streams', () => {
                     treasury: treasury,
                     treasuryToken: treasuryFrom,
                     associatedToken: fromTokenClient.publicKey,
+                    config: MSP_CONFIG_PUBKEY,
                     feeTreasury: MSP_FEES_PUBKEY,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                     tokenProgram: TOKEN_PROGRAM_ID,
@@ -395,6 +415,7 @@ describe('msp-pda-streams', () => {
                     treasury: treasury,
                     treasuryToken: treasuryFrom,
                     associatedToken: fromTokenClient.publicKey,
+                    config: MSP_CONFIG_PUBKEY,
                     feeTreasury: MSP_FEES_PUBKEY,
                     feeTreasuryToken: FEES_FROM,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -437,7 +458,9 @@ describe('msp-pda-streams', () => {
                         new BN(0),
                         new BN(0),
                         true,
-                        streamPdaSeed
+                        streamPdaSeed,
+                        STREAM_ACCRUAL_MODE_LINEAR,
+                        new BN(0)
                     )
                     .accounts({
                         payer: treasurerKey.publicKey,
@@ -447,6 +470,10 @@ describe('msp-pda-streams', () => {
                         associatedToken: fromTokenClient.publicKey,
                         beneficiary: beneficiaryKey.publicKey,
                         stream: stream,
+                        config: MSP_CONFIG_PUBKEY,
+                        feeOverride: SYSTEM_PROGRAM_ID,
+                        referrer: MSP_FEES_PUBKEY,
+                        referrerToken: FEES_FROM,
                         feeTreasury: MSP_FEES_PUBKEY,
                         feeTreasuryToken: FEES_FROM,
                         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -487,6 +514,10 @@ describe('msp-pda-streams', () => {
                         associatedToken: fromTokenClient.publicKey,
                         beneficiary: beneficiaryKey2.publicKey,
                         stream: stream2,
+                        config: MSP_CONFIG_PUBKEY,
+                        feeOverride: SYSTEM_PROGRAM_ID,
+                        referrer: MSP_FEES_PUBKEY,
+                        referrerToken: FEES_FROM,
                         feeTreasury: MSP_FEES_PUBKEY,
                         feeTreasuryToken: FEES_FROM,
                         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -527,6 +558,10 @@ describe('msp-pda-streams', () => {
                         associatedToken: fromTokenClient.publicKey,
                         beneficiary: beneficiaryKey3.publicKey,
                         stream: stream3,
+                        config: MSP_CONFIG_PUBKEY,
+                        feeOverride: SYSTEM_PROGRAM_ID,
+                        referrer: MSP_FEES_PUBKEY,
+                        referrerToken: FEES_FROM,
                         feeTreasury: MSP_FEES_PUBKEY,
                         feeTreasuryToken: FEES_FROM,
                         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -615,6 +650,7 @@ describe('msp-pda-streams', () => {
                     treasuryToken: treasuryFrom,
                     template: template,
                     associatedToken: fromTokenClient.publicKey,
+                    config: MSP_CONFIG_PUBKEY,
                     feeTreasury: MSP_FEES_PUBKEY,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                     tokenProgram: TOKEN_PROGRAM_ID,
@@ -635,6 +671,7 @@ describe('msp-pda-streams', () => {
                     treasury: treasury,
                     treasuryToken: treasuryFrom,
                     associatedToken: fromTokenClient.publicKey,
+                    config: MSP_CONFIG_PUBKEY,
                     feeTreasury: MSP_FEES_PUBKEY,
                     feeTreasuryToken: FEES_FROM,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -677,6 +714,10 @@ describe('msp-pda-streams', () => {
                     beneficiary: beneficiaryKey.publicKey,
                     template: template,
                     stream: streamKey.publicKey,
+                    config: MSP_CONFIG_PUBKEY,
+                    feeOverride: SYSTEM_PROGRAM_ID,
+                    referrer: MSP_FEES_PUBKEY,
+                    referrerToken: FEES_FROM,
                     feeTreasury: MSP_FEES_PUBKEY,
                     feeTreasuryToken: FEES_FROM,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -755,6 +796,7 @@ describe('msp-pda-streams', () => {
                     treasuryToken: treasuryFrom,
                     template: template,
                     associatedToken: fromTokenClient.publicKey,
+                    config: MSP_CONFIG_PUBKEY,
                     feeTreasury: MSP_FEES_PUBKEY,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                     tokenProgram: TOKEN_PROGRAM_ID,
@@ -775,6 +817,7 @@ describe('msp-pda-streams', () => {
                     treasury: treasury,
                     treasuryToken: treasuryFrom,
                     associatedToken: fromTokenClient.publicKey,
+                    config: MSP_CONFIG_PUBKEY,
                     feeTreasury: MSP_FEES_PUBKEY,
                     feeTreasuryToken: FEES_FROM,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -822,6 +865,10 @@ describe('msp-pda-streams', () => {
                     beneficiary: beneficiaryKey.publicKey,
                     template: template,
                     stream: stream,
+                    config: MSP_CONFIG_PUBKEY,
+                    feeOverride: SYSTEM_PROGRAM_ID,
+                    referrer: MSP_FEES_PUBKEY,
+                    referrerToken: FEES_FROM,
                     feeTreasury: MSP_FEES_PUBKEY,
                     feeTreasuryToken: FEES_FROM,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -904,6 +951,7 @@ describe('msp-pda-streams', () => {
                     treasuryToken: treasuryFrom,
                     template: template,
                     associatedToken: fromTokenClient.publicKey,
+                    config: MSP_CONFIG_PUBKEY,
                     feeTreasury: MSP_FEES_PUBKEY,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                     tokenProgram: TOKEN_PROGRAM_ID,
@@ -924,6 +972,7 @@ describe('msp-pda-streams', () => {
                     treasury: treasury,
                     treasuryToken: treasuryFrom,
                     associatedToken: fromTokenClient.publicKey,
+                    config: MSP_CONFIG_PUBKEY,
                     feeTreasury: MSP_FEES_PUBKEY,
                     feeTreasuryToken: FEES_FROM,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -971,6 +1020,10 @@ describe('msp-pda-streams', () => {
                         associatedToken: fromTokenClient.publicKey,
                         beneficiary: beneficiaryKey.publicKey,
                         stream: stream,
+                        config: MSP_CONFIG_PUBKEY,
+                        feeOverride: SYSTEM_PROGRAM_ID,
+                        referrer: MSP_FEES_PUBKEY,
+                        referrerToken: FEES_FROM,
                         feeTreasury: MSP_FEES_PUBKEY,
                         feeTreasuryToken: FEES_FROM,
                         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -1006,6 +1059,10 @@ describe('msp-pda-streams', () => {
                         associatedToken: fromTokenClient.publicKey,
                         beneficiary: beneficiaryKey2.publicKey,
                         stream: stream2,
+                        config: MSP_CONFIG_PUBKEY,
+                        feeOverride: SYSTEM_PROGRAM_ID,
+                        referrer: MSP_FEES_PUBKEY,
+                        referrerToken: FEES_FROM,
                         feeTreasury: MSP_FEES_PUBKEY,
                         feeTreasuryToken: FEES_FROM,
                         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -1041,6 +1098,10 @@ describe('msp-pda-streams', () => {
                         associatedToken: fromTokenClient.publicKey,
                         beneficiary: beneficiaryKey3.publicKey,
                         stream: stream3,
+                        config: MSP_CONFIG_PUBKEY,
+                        feeOverride: SYSTEM_PROGRAM_ID,
+                        referrer: MSP_FEES_PUBKEY,
+                        referrerToken: FEES_FROM,
                         feeTreasury: MSP_FEES_PUBKEY,
                         feeTreasuryToken: FEES_FROM,
                         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -20,6 +20,10 @@ export const TREASURY_TYPE_LOCKED = 1;
 
 export const TREASURY_ASSOCIATED_MINT_DECIMALS = 6;
 export const MSP_FEES_PUBKEY = new PublicKey('3TD6SWY9M1mLY2kZWJNavPLhwXvcRsWdnZLRaMzERJBw');
+export const MSP_CONFIG_PUBKEY = PublicKey.findProgramAddressSync(
+  [Buffer.from('config')],
+  new PublicKey('MSPdQo5ZdrPh6rU1LsvUv5nRhAnj1mj6YQEqBUq8YwZ')
+)[0];
 export const MSP_TREASURY_ACCOUNT_SIZE_IN_BYTES = 300;
 export const MSP_TEMPLATE_ACCOUNT_SIZE_IN_BYTES = 200;
 export const MSP_CREATE_TREASURY_FEE_IN_LAMPORTS = 10_000;
@@ -33,8 +37,9 @@ export const SYSTEM_PROGRAM_ID = anchor.web3.SystemProgram.programId;
 export const SYSVAR_RENT_PUBKEY = anchor.web3.SYSVAR_RENT_PUBKEY;
 export const SYSVAR_CLOCK_PUBKEY = anchor.web3.SYSVAR_CLOCK_PUBKEY;
 export const ONE_SOL = 1_000_000_000;
+export const STREAM_ACCRUAL_MODE_LINEAR = 0;
 
-export const LATEST_IDL_FILE_VERSION = 5;
+export const LATEST_IDL_FILE_VERSION = 10;
 export const url = process.env.ANCHOR_PROVIDER_URL;
 if (url === undefined) {
   throw new Error('ANCHOR_PROVIDER_URL is not defined');
@@ -278,7 +283,8 @@ export class MspSetup {
         this.autoClose,
         solFeePayedByTreasury,
         { [Category[category]]: {} },
-        { [SubCategory[subCategory]]: {} }
+        { [SubCategory[subCategory]]: {} },
+        null
       )
       .accounts({
         payer: treasurer,
@@ -286,6 +292,7 @@ export class MspSetup {
         treasury: treasury,
         treasuryToken: this.treasuryFrom,
         associatedToken: this.fromMint,
+        config: MSP_CONFIG_PUBKEY,
         feeTreasury: MSP_FEES_PUBKEY,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: tokenProgram,
@@ -712,7 +719,9 @@ export class MspSetup {
         new BN(allocationAssignedUnits),
         new BN(cliffVestAmountUnits),
         new BN(cliffVestPercent),
-        feePayedByTreasurer
+        feePayedByTreasurer,
+        STREAM_ACCRUAL_MODE_LINEAR,
+        new BN(0)
       )
       .accounts({
         payer: payerKeypair.publicKey,
@@ -722,6 +731,10 @@ export class MspSetup {
         associatedToken: this.fromTokenClient.publicKey,
         beneficiary: beneficiary,
         stream: streamKeypair.publicKey,
+        config: MSP_CONFIG_PUBKEY,
+        feeOverride: SYSTEM_PROGRAM_ID,
+        referrer: MSP_FEES_PUBKEY,
+        referrerToken: this.feesFrom,
         feeTreasury: MSP_FEES_PUBKEY,
         feeTreasuryToken: this.feesFrom,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -847,7 +860,9 @@ export class MspSetup {
         new BN(rateIntervalInSeconds),
         new BN(durationNumberOfUnits),
         new BN(cliffVestPercent),
-        feePayedByTreasurer
+        feePayedByTreasurer,
+        STREAM_ACCRUAL_MODE_LINEAR,
+        new BN(0)
       )
       .accounts({
         template: template,
@@ -921,7 +936,9 @@ export class MspSetup {
         new BN(rateIntervalInSeconds),
         new BN(durationNumberOfUnits),
         new BN(cliffVestPercent),
-        feePayedByTreasurer
+        feePayedByTreasurer,
+        STREAM_ACCRUAL_MODE_LINEAR,
+        new BN(0)
       )
       .accounts({
         template: template,
@@ -1029,7 +1046,9 @@ export class MspSetup {
         new BN(durationNumberOfUnits),
         new BN(cliffVestPercent),
         feePayedByTreasurer,
-        this.slot
+        this.slot,
+        STREAM_ACCRUAL_MODE_LINEAR,
+        new BN(0)
       )
       .accounts({
         payer: treasurer,
@@ -1038,6 +1057,7 @@ export class MspSetup {
         treasuryToken: this.treasuryFrom,
         template,
         associatedToken: this.fromMint,
+        config: MSP_CONFIG_PUBKEY,
         feeTreasury: MSP_FEES_PUBKEY,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: tokenProgram,
@@ -1174,6 +1194,10 @@ export class MspSetup {
         beneficiary: beneficiary,
         stream: streamKeypair.publicKey,
         template: template,
+        config: MSP_CONFIG_PUBKEY,
+        feeOverride: SYSTEM_PROGRAM_ID,
+        referrer: MSP_FEES_PUBKEY,
+        referrerToken: this.feesFrom,
         feeTreasury: MSP_FEES_PUBKEY,
         feeTreasuryToken: this.feesFrom,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -1301,6 +1325,10 @@ export class MspSetup {
           associatedToken: this.fromTokenClient.publicKey,
           beneficiary: beneficiary,
           stream: streamKeypair.publicKey,
+          config: MSP_CONFIG_PUBKEY,
+          feeOverride: SYSTEM_PROGRAM_ID,
+          referrer: MSP_FEES_PUBKEY,
+          referrerToken: this.feesFrom,
           feeTreasury: MSP_FEES_PUBKEY,
           feeTreasuryToken: this.feesFrom,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -1378,6 +1406,10 @@ export class MspSetup {
         treasury: treasury,
         treasuryToken: treasuryFrom,
         stream: stream,
+        config: MSP_CONFIG_PUBKEY,
+        feeOverride: SYSTEM_PROGRAM_ID,
+        referrer: MSP_FEES_PUBKEY,
+        referrerToken: this.feesFrom,
         feeTreasury: MSP_FEES_PUBKEY,
         feeTreasuryToken: this.feesFrom,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -1837,6 +1869,7 @@ export class MspSetup {
       .accounts({
         beneficiary: beneficiary,
         stream: stream,
+        config: MSP_CONFIG_PUBKEY,
         feeTreasury: MSP_FEES_PUBKEY,
         systemProgram: SYSTEM_PROGRAM_ID
       })
@@ -2224,6 +2257,7 @@ export class MspSetup {
         treasury: this.treasury,
         treasuryToken: this.treasuryFrom,
         associatedToken: this.fromMint,
+        config: MSP_CONFIG_PUBKEY,
         feeTreasury: MSP_FEES_PUBKEY,
         feeTreasuryToken: this.feesFrom,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -2315,6 +2349,10 @@ export class MspSetup {
         treasuryToken: this.treasuryFrom,
         associatedToken: this.fromMint,
         stream: stream,
+        config: MSP_CONFIG_PUBKEY,
+        feeOverride: SYSTEM_PROGRAM_ID,
+        referrer: MSP_FEES_PUBKEY,
+        referrerToken: this.feesFrom,
         feeTreasury: MSP_FEES_PUBKEY,
         feeTreasuryToken: this.feesFrom,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -2422,6 +2460,7 @@ export class MspSetup {
         associatedToken: this.fromMint,
         treasury: treasury,
         treasuryToken: treasuryFrom,
+        config: MSP_CONFIG_PUBKEY,
         feeTreasury: MSP_FEES_PUBKEY,
         feeTreasuryToken: this.feesFrom,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -2521,6 +2560,10 @@ export class MspSetup {
         treasury: treasury,
         treasuryToken: treasuryFrom,
         stream: stream,
+        config: MSP_CONFIG_PUBKEY,
+        feeOverride: SYSTEM_PROGRAM_ID,
+        referrer: fees,
+        referrerToken: feesFrom,
         feeTreasury: fees,
         feeTreasuryToken: feesFrom,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -2755,6 +2798,10 @@ export class MspSetup {
         associatedToken: this.fromMint,
         treasury: treasury,
         treasuryToken: treasuryFrom,
+        config: MSP_CONFIG_PUBKEY,
+        feeOverride: SYSTEM_PROGRAM_ID,
+        referrer: MSP_FEES_PUBKEY,
+        referrerToken: this.feesFrom,
         feeTreasury: MSP_FEES_PUBKEY,
         feeTreasuryToken: this.feesFrom,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,